@@ -0,0 +1,232 @@
+//! Launch at login: a LaunchAgent on macOS, an XDG autostart entry on Linux
+//! and a `Run` key value on Windows, all pointing at the running executable
+//! (or the AppImage it was started from).
+
+use crate::errors::ConductorError;
+use std::path::{Path, PathBuf};
+
+#[cfg_attr(target_os = "macos", allow(dead_code))]
+const NAME: &str = "Conductor";
+
+/// Registers or unregisters Conductor to start at login.
+pub fn apply(enabled: bool) -> Result<(), ConductorError> {
+    let exe = executable().map_err(|e| ConductorError::Autostart(e.to_string()))?;
+    platform::apply(enabled, &exe).map_err(|e| ConductorError::Autostart(e.to_string()))
+}
+
+/// Called on startup so a moved or updated app keeps starting at login.
+/// Skipped in debug builds, which would register the dev binary.
+pub fn sync(enabled: bool) {
+    if cfg!(debug_assertions) {
+        return;
+    }
+    if let Err(e) = apply(enabled) {
+        eprintln!("Autostart: {}", e);
+    }
+}
+
+fn executable() -> std::io::Result<PathBuf> {
+    // An AppImage runs from a temporary mount; start the image itself.
+    if let Some(appimage) = std::env::var_os("APPIMAGE") {
+        return Ok(PathBuf::from(appimage));
+    }
+    std::env::current_exe()
+}
+
+/// Writes `contents` to `path` unless it already holds them.
+#[cfg_attr(target_os = "windows", allow(dead_code))]
+fn write_if_changed(path: &Path, contents: &str) -> std::io::Result<()> {
+    if std::fs::read_to_string(path).is_ok_and(|current| current == contents) {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, contents)
+}
+
+#[cfg_attr(target_os = "windows", allow(dead_code))]
+fn remove(path: &Path) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+
+    const LABEL: &str = "dev.conductormcp.app";
+
+    pub fn apply(enabled: bool, exe: &Path) -> std::io::Result<()> {
+        let path = dirs::home_dir()
+            .ok_or_else(|| std::io::Error::other("home directory not found"))?
+            .join("Library/LaunchAgents")
+            .join(format!("{}.plist", LABEL));
+        if enabled {
+            write_if_changed(&path, &plist(exe))
+        } else {
+            remove(&path)
+        }
+    }
+
+    fn plist(exe: &Path) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>ProcessType</key>
+    <string>Interactive</string>
+</dict>
+</plist>
+"#,
+            LABEL,
+            xml_escape(&exe.to_string_lossy())
+        )
+    }
+
+    fn xml_escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use super::*;
+
+    pub fn apply(enabled: bool, exe: &Path) -> std::io::Result<()> {
+        let path = dirs::config_dir()
+            .ok_or_else(|| std::io::Error::other("config directory not found"))?
+            .join("autostart")
+            .join("conductor.desktop");
+        if enabled {
+            write_if_changed(&path, &desktop_entry(exe))
+        } else {
+            remove(&path)
+        }
+    }
+
+    fn desktop_entry(exe: &Path) -> String {
+        format!(
+            "[Desktop Entry]\n\
+             Type=Application\n\
+             Name={}\n\
+             Comment=Manage MCP servers across AI clients\n\
+             Exec={}\n\
+             Icon=conductor\n\
+             Terminal=false\n\
+             X-GNOME-Autostart-enabled=true\n",
+            NAME,
+            exec_quote(&exe.to_string_lossy())
+        )
+    }
+
+    /// Quotes a path for an `Exec` key: backslash-escaped inside double
+    /// quotes, with `%` doubled so it isn't read as a field code.
+    fn exec_quote(path: &str) -> String {
+        let mut quoted = String::from("\"");
+        for c in path.chars() {
+            match c {
+                '"' | '`' | '$' | '\\' => {
+                    quoted.push('\\');
+                    quoted.push(c);
+                }
+                '%' => quoted.push_str("%%"),
+                _ => quoted.push(c),
+            }
+        }
+        quoted.push('"');
+        quoted
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+
+    const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    pub fn apply(enabled: bool, exe: &Path) -> std::io::Result<()> {
+        let value = format!("\"{}\"", exe.display());
+        let mut reg = Command::new("reg");
+        if enabled {
+            reg.args([
+                "add", RUN_KEY, "/v", NAME, "/t", "REG_SZ", "/d", &value, "/f",
+            ]);
+        } else {
+            // Nothing to remove unless the value is there.
+            let query = Command::new("reg")
+                .args(["query", RUN_KEY, "/v", NAME])
+                .creation_flags(CREATE_NO_WINDOW)
+                .output()?;
+            if !query.status.success() {
+                return Ok(());
+            }
+            reg.args(["delete", RUN_KEY, "/v", NAME, "/f"]);
+        }
+        let output = reg.creation_flags(CREATE_NO_WINDOW).output()?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(std::io::Error::other(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ))
+        }
+    }
+}
+
+#[cfg(all(test, unix, not(target_os = "macos")))]
+mod tests {
+    use super::*;
+    use crate::test_support::EnvGuard;
+
+    #[test]
+    fn registers_and_unregisters_an_xdg_autostart_entry() {
+        let config_home =
+            std::env::temp_dir().join(format!("conductor-autostart-{}", uuid::Uuid::new_v4()));
+        let mut env = EnvGuard::lock();
+        env.set("XDG_CONFIG_HOME", Some(&config_home));
+        env.set("APPIMAGE", Some("/opt/My Apps/Conductor \"100%\".AppImage"));
+        let entry = config_home.join("autostart").join("conductor.desktop");
+
+        apply(true).unwrap();
+        let contents = std::fs::read_to_string(&entry).unwrap();
+        apply(true).unwrap();
+        let again = std::fs::read_to_string(&entry).unwrap();
+        apply(false).unwrap();
+        let removed = !entry.exists();
+        apply(false).unwrap();
+
+        drop(env);
+        let _ = std::fs::remove_dir_all(&config_home);
+
+        assert_eq!(
+            contents,
+            "[Desktop Entry]\n\
+             Type=Application\n\
+             Name=Conductor\n\
+             Comment=Manage MCP servers across AI clients\n\
+             Exec=\"/opt/My Apps/Conductor \\\"100%%\\\".AppImage\"\n\
+             Icon=conductor\n\
+             Terminal=false\n\
+             X-GNOME-Autostart-enabled=true\n"
+        );
+        assert_eq!(again, contents);
+        assert!(removed);
+    }
+}
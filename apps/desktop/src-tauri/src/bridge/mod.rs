@@ -0,0 +1,1003 @@
+//! `conductor bridge`: a stdio MCP server that relays every message to a
+//! remote SSE or streamable HTTP server, for clients that only run stdio
+//! servers. Credentials come from the keychain at runtime, so they never
+//! appear in the client's config or the process arguments.
+
+use crate::config::variables::{self, VariableStore};
+use crate::config::{self, McpServerConfig, TransportType};
+use crate::errors::ConductorError;
+use anyhow::{bail, Context, Result};
+use reqwest::header::{ACCEPT, AUTHORIZATION};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::{mpsc, oneshot, watch};
+use tokio::time::{sleep, timeout, Duration};
+use url::Url;
+
+const USAGE: &str =
+    "Usage: conductor bridge [--server-id <id>] [--server <name>] [--transport sse|http] <url>";
+
+const SESSION_HEADER: &str = "mcp-session-id";
+const PROTOCOL_HEADER: &str = "mcp-protocol-version";
+const LAST_EVENT_HEADER: &str = "last-event-id";
+
+/// Prefix of request IDs the bridge sends on its own behalf (re-initializing
+/// after a reconnect). Their responses are never forwarded to the client.
+const OWN_ID_PREFIX: &str = "conductor-bridge:";
+
+/// Consecutive failures tolerated before a request or stream gives up.
+const MAX_ATTEMPTS: u32 = 5;
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(60);
+
+struct BridgeArgs {
+    /// The master config entry to take credentials from.
+    server_id: Option<String>,
+    /// The server's name, for messages. Entries written before
+    /// `--server-id` existed only have this, and are looked up by it.
+    server: Option<String>,
+    transport: Option<TransportType>,
+    url: Url,
+}
+
+/// Runs the bridge if `args` (without the program name) start with `bridge`.
+/// Returns the process exit code, or `None` when this isn't a bridge
+/// invocation and the app should start normally.
+pub fn run_from_args(args: &[String]) -> Option<i32> {
+    if args.first().map(String::as_str) != Some("bridge") {
+        return None;
+    }
+
+    let args = match parse_args(&args[1..]) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("conductor bridge: {}", e);
+            eprintln!("{}", USAGE);
+            return Some(2);
+        }
+    };
+
+    let runtime = match tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("conductor bridge: failed to start runtime: {}", e);
+            return Some(1);
+        }
+    };
+
+    match runtime.block_on(run(args)) {
+        Ok(()) => Some(0),
+        Err(e) => {
+            eprintln!("conductor bridge: {:#}", e);
+            Some(1)
+        }
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<BridgeArgs> {
+    let mut server_id = None;
+    let mut server = None;
+    let mut transport = None;
+    let mut url = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--server-id" => {
+                server_id = Some(iter.next().context("--server-id needs a value")?.clone());
+            }
+            "--server" => {
+                server = Some(iter.next().context("--server needs a value")?.clone());
+            }
+            "--transport" => {
+                transport = Some(match iter.next().map(String::as_str) {
+                    Some("sse") => TransportType::Sse,
+                    Some("http") | Some("streamable-http") => TransportType::StreamableHttp,
+                    Some(other) => bail!("Unknown transport '{}'", other),
+                    None => bail!("--transport needs a value"),
+                });
+            }
+            flag if flag.starts_with("--") => bail!("Unknown option '{}'", flag),
+            value if url.is_none() => url = Some(value.to_string()),
+            value => bail!("Unexpected argument '{}'", value),
+        }
+    }
+
+    let url = url.context("Missing server URL")?;
+    let url = Url::parse(&url).with_context(|| format!("Invalid URL '{}'", url))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        bail!("Only http and https URLs can be bridged");
+    }
+    Ok(BridgeArgs {
+        server_id,
+        server,
+        transport,
+        url,
+    })
+}
+
+async fn run(args: BridgeArgs) -> Result<()> {
+    let server = match (&args.server_id, &args.server) {
+        (Some(id), _) => find_server(|s| s.id == *id),
+        (None, Some(name)) => find_server(|s| s.name == *name),
+        (None, None) => None,
+    };
+    if let (Some(label), None) = (args.server.as_ref().or(args.server_id.as_ref()), &server) {
+        eprintln!(
+            "conductor bridge: '{}' is not in Conductor's config; connecting without credentials",
+            label
+        );
+    }
+
+    // The flag wins, then the master config; otherwise guess from the path.
+    let transport = args
+        .transport
+        .or_else(|| server.as_ref().map(|s| s.transport.clone()))
+        .filter(|t| *t != TransportType::Stdio)
+        .unwrap_or_else(|| {
+            if args.url.path().trim_end_matches('/').ends_with("/sse") {
+                TransportType::Sse
+            } else {
+                TransportType::StreamableHttp
+            }
+        });
+
+    let (token, headers) = match &server {
+        Some(server) => (resolve_token(server).await?, resolve_headers(server)?),
+        None => (None, HashMap::new()),
+    };
+
+    let (out_tx, out_rx) = mpsc::unbounded_channel();
+    let writer = tokio::spawn(write_stdout(out_rx));
+
+    let bridge = Arc::new(Bridge::new(args.url, server, transport, token, headers, out_tx));
+    if bridge.transport == TransportType::Sse {
+        tokio::spawn(bridge.clone().run_sse_stream());
+    }
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    while let Some(line) = lines.next_line().await.context("Failed to read stdin")? {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let message: Value = match serde_json::from_str(line) {
+            Ok(message) => message,
+            Err(e) => {
+                bridge.forward(json!({
+                    "jsonrpc": "2.0",
+                    "id": null,
+                    "error": { "code": -32700, "message": format!("Parse error: {}", e) }
+                }));
+                continue;
+            }
+        };
+
+        // The handshake runs in order so later messages carry the session;
+        // everything else is relayed concurrently.
+        match message.get("method").and_then(|m| m.as_str()) {
+            Some("initialize") => {
+                bridge.remember_initialize(&message);
+                bridge.send(message).await;
+            }
+            Some("notifications/initialized") => {
+                if bridge.send(message).await {
+                    bridge.initialized.store(true, Ordering::SeqCst);
+                    if bridge.transport == TransportType::StreamableHttp {
+                        tokio::spawn(bridge.clone().listen_http());
+                    }
+                }
+            }
+            _ => {
+                let bridge = bridge.clone();
+                tokio::spawn(async move {
+                    bridge.send(message).await;
+                });
+            }
+        }
+    }
+
+    bridge.close().await;
+    drop(bridge);
+    let _ = timeout(Duration::from_secs(5), writer).await;
+    Ok(())
+}
+
+fn find_server(matches: impl Fn(&McpServerConfig) -> bool) -> Option<McpServerConfig> {
+    config::read_config()
+        .ok()?
+        .servers
+        .into_iter()
+        .find(|s| matches(s))
+}
+
+/// The server's OAuth token (refreshed when close to expiry), else a static
+/// `OAUTH_TOKEN` from the keychain or its env. Fails if the secret store
+/// can't be read, rather than connecting without credentials.
+async fn resolve_token(server: &McpServerConfig) -> Result<Option<String>> {
+    match crate::oauth::get_valid_oauth_token(&server.id).await {
+        Ok(Some(token)) => Ok(Some(token)),
+        _ => Ok(stored_token(server)?),
+    }
+}
+
+/// The server's headers, with the variables they reference (secret ones
+/// included) filled in.
+fn resolve_headers(server: &McpServerConfig) -> Result<HashMap<String, String>> {
+    let Ok(cfg) = config::read_config() else {
+        return Ok(server.headers.clone());
+    };
+    let store = VariableStore::load_for(&cfg, config::profiles::active(&cfg), false)?;
+    let mut undefined = Vec::new();
+    let headers = server
+        .headers
+        .iter()
+        .map(|(name, value)| (name.clone(), variables::interpolate(value, &store, &mut undefined)))
+        .collect();
+    if !undefined.is_empty() {
+        eprintln!(
+            "conductor bridge: headers reference undefined variables: {}",
+            undefined.join(", ")
+        );
+    }
+    Ok(headers)
+}
+
+fn stored_token(server: &McpServerConfig) -> Result<Option<String>, ConductorError> {
+    let username = format!("{}:OAUTH_TOKEN", server.id);
+    let cfg = config::read_config().ok();
+    let profile = cfg.as_ref().and_then(config::profiles::active);
+    Ok(config::profiles::read_secret(profile, &username)?
+        .or_else(|| server.env.get("OAUTH_TOKEN").cloned())
+        .filter(|t| !t.trim().is_empty()))
+}
+
+async fn write_stdout(mut rx: mpsc::UnboundedReceiver<Value>) {
+    let mut stdout = tokio::io::stdout();
+    while let Some(message) = rx.recv().await {
+        let mut line = message.to_string();
+        line.push('\n');
+        if stdout.write_all(line.as_bytes()).await.is_err() || stdout.flush().await.is_err() {
+            break;
+        }
+    }
+}
+
+fn backoff(attempt: u32) -> Duration {
+    Duration::from_millis(500 * 2u64.pow(attempt.saturating_sub(1).min(6)))
+        .min(Duration::from_secs(30))
+}
+
+fn lock<T>(mutex: &std::sync::Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn is_request(message: &Value) -> bool {
+    message.get("method").is_some() && message.get("id").is_some()
+}
+
+#[derive(Debug, Clone, Default)]
+struct Session {
+    id: Option<String>,
+    protocol_version: Option<String>,
+    /// Bumped on every re-initialization, so concurrent requests that hit an
+    /// expired session only reconnect once.
+    generation: u64,
+}
+
+struct Bridge {
+    http: reqwest::Client,
+    url: Url,
+    server: Option<McpServerConfig>,
+    transport: TransportType,
+    token: std::sync::Mutex<Option<String>>,
+    /// The server's own headers, sent with every request.
+    headers: HashMap<String, String>,
+    out: mpsc::UnboundedSender<Value>,
+    /// The client's `initialize` request (ID, params), replayed on reconnect.
+    initialize: std::sync::Mutex<Option<(Value, Value)>>,
+    initialized: AtomicBool,
+    /// Streamable HTTP session.
+    session: std::sync::Mutex<Session>,
+    reconnect: tokio::sync::Mutex<()>,
+    /// Legacy SSE: the POST endpoint of the current stream, once it's ready.
+    endpoint: watch::Sender<Option<Url>>,
+    /// Legacy SSE: client requests awaiting a response on the stream, so they
+    /// can be failed when it drops.
+    in_flight: std::sync::Mutex<HashMap<String, Value>>,
+    pending: std::sync::Mutex<HashMap<String, oneshot::Sender<Value>>>,
+    next_id: AtomicU64,
+}
+
+impl Bridge {
+    fn new(
+        url: Url,
+        server: Option<McpServerConfig>,
+        transport: TransportType,
+        token: Option<String>,
+        headers: HashMap<String, String>,
+        out: mpsc::UnboundedSender<Value>,
+    ) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            url,
+            server,
+            transport,
+            token: std::sync::Mutex::new(token),
+            headers,
+            out,
+            initialize: std::sync::Mutex::new(None),
+            initialized: AtomicBool::new(false),
+            session: std::sync::Mutex::new(Session::default()),
+            reconnect: tokio::sync::Mutex::new(()),
+            endpoint: watch::Sender::new(None),
+            in_flight: std::sync::Mutex::new(HashMap::new()),
+            pending: std::sync::Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    fn remember_initialize(&self, message: &Value) {
+        if let Some(id) = message.get("id") {
+            let params = message.get("params").cloned().unwrap_or(json!({}));
+            *lock(&self.initialize) = Some((id.clone(), params));
+        }
+    }
+
+    /// Adds the server's headers, and the token unless they carry their own
+    /// `Authorization`.
+    fn authorize(&self, mut request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        if self.headers.keys().any(|name| name.eq_ignore_ascii_case("authorization")) {
+            return request;
+        }
+        match lock(&self.token).as_deref() {
+            Some(token) => request.header(AUTHORIZATION, format!("Bearer {}", token)),
+            None => request,
+        }
+    }
+
+    /// Gets a fresh token after the server rejected the current one. Returns
+    /// whether it changed and the request is worth retrying.
+    async fn refresh_token(&self) -> bool {
+        let Some(server) = &self.server else {
+            return false;
+        };
+        let refreshed = match crate::oauth::refresh_oauth_token(&server.id).await {
+            Ok(token) => Some(token),
+            Err(e) => {
+                eprintln!("conductor bridge: token refresh failed: {:#}", e);
+                // The user may have saved a new token since we started.
+                stored_token(server).unwrap_or_else(|e| {
+                    eprintln!("conductor bridge: {}", e);
+                    None
+                })
+            }
+        };
+        let mut token = lock(&self.token);
+        if refreshed.is_none() || *token == refreshed {
+            return false;
+        }
+        *token = refreshed;
+        true
+    }
+
+    /// Relays one client message. A request that can't be delivered gets an
+    /// error response; returns whether the message was delivered.
+    async fn send(&self, message: Value) -> bool {
+        let result = match self.transport {
+            TransportType::Sse => {
+                if is_request(&message) {
+                    if let Some(id) = message.get("id") {
+                        lock(&self.in_flight).insert(id.to_string(), id.clone());
+                    }
+                }
+                self.post_legacy(&message, None).await
+            }
+            _ => self.post_http(&message).await,
+        };
+        match result {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("conductor bridge: {:#}", e);
+                if let Some(id) = message.get("id").filter(|_| is_request(&message)) {
+                    self.forward(error_response(id, &format!("{:#}", e)));
+                }
+                false
+            }
+        }
+    }
+
+    /// Hands a message from the remote to the client, or to the bridge's own
+    /// waiting request.
+    fn forward(&self, message: Value) {
+        if message.get("method").is_none() {
+            if let Some(id) = message.get("id") {
+                if let Some(own) = id.as_str().filter(|id| id.starts_with(OWN_ID_PREFIX)) {
+                    if let Some(tx) = lock(&self.pending).remove(own) {
+                        let _ = tx.send(message);
+                    }
+                    return;
+                }
+                lock(&self.in_flight).remove(&id.to_string());
+                self.note_protocol_version(id, &message);
+            }
+        }
+        let _ = self.out.send(message);
+    }
+
+    fn forward_body(&self, body: &str) {
+        match serde_json::from_str::<Value>(body) {
+            Ok(Value::Array(batch)) => batch.into_iter().for_each(|m| self.forward(m)),
+            Ok(message) => self.forward(message),
+            Err(e) => eprintln!("conductor bridge: ignoring malformed message: {}", e),
+        }
+    }
+
+    fn forward_event(&self, event: &SseEvent) {
+        if event.event.is_empty() || event.event == "message" {
+            self.forward_body(&event.data);
+        }
+    }
+
+    fn note_protocol_version(&self, id: &Value, response: &Value) {
+        let is_initialize = lock(&self.initialize)
+            .as_ref()
+            .is_some_and(|(init_id, _)| init_id == id);
+        if !is_initialize {
+            return;
+        }
+        if let Some(version) = response
+            .pointer("/result/protocolVersion")
+            .and_then(|v| v.as_str())
+        {
+            lock(&self.session).protocol_version = Some(version.to_string());
+        }
+    }
+
+    fn register_own_request(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> (Value, oneshot::Receiver<Value>) {
+        let id = format!(
+            "{}{}",
+            OWN_ID_PREFIX,
+            self.next_id.fetch_add(1, Ordering::Relaxed)
+        );
+        let (tx, rx) = oneshot::channel();
+        lock(&self.pending).insert(id.clone(), tx);
+        let message = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        (message, rx)
+    }
+
+    async fn await_own_response(rx: oneshot::Receiver<Value>) -> Result<Value> {
+        let response = timeout(RESPONSE_TIMEOUT, rx)
+            .await
+            .context("Timed out waiting for the remote server")?
+            .context("The remote server closed the connection")?;
+        if let Some(error) = response.get("error") {
+            bail!("The remote server refused to re-initialize: {}", error);
+        }
+        Ok(response)
+    }
+
+    fn initialize_params(&self) -> Option<Value> {
+        lock(&self.initialize)
+            .as_ref()
+            .map(|(_, params)| params.clone())
+    }
+
+    // --- Streamable HTTP ---
+
+    async fn post_http(&self, message: &Value) -> Result<()> {
+        let is_initialize = message.get("method").and_then(|m| m.as_str()) == Some("initialize");
+        let mut refreshed = false;
+        let mut reinitialized = false;
+        let mut attempt = 0;
+
+        loop {
+            let session = lock(&self.session).clone();
+            let mut request = self
+                .http
+                .post(self.url.clone())
+                .header(ACCEPT, "application/json, text/event-stream")
+                .json(message);
+            if let Some(id) = &session.id {
+                request = request.header(SESSION_HEADER, id);
+            }
+            if let Some(version) = &session.protocol_version {
+                request = request.header(PROTOCOL_HEADER, version);
+            }
+
+            let response = match self.authorize(request).send().await {
+                Ok(response) => response,
+                Err(e) => {
+                    attempt += 1;
+                    if attempt >= MAX_ATTEMPTS {
+                        return Err(e).context("The remote server is unreachable");
+                    }
+                    eprintln!("conductor bridge: {}; retrying", e);
+                    sleep(backoff(attempt)).await;
+                    continue;
+                }
+            };
+
+            if let Some(id) = response
+                .headers()
+                .get(SESSION_HEADER)
+                .and_then(|v| v.to_str().ok())
+            {
+                lock(&self.session).id = Some(id.to_string());
+            }
+
+            match response.status() {
+                StatusCode::UNAUTHORIZED if !refreshed => {
+                    refreshed = true;
+                    if self.refresh_token().await {
+                        continue;
+                    }
+                    bail!("The remote server rejected the credentials (401)");
+                }
+                // The server forgot our session (restart, timeout): start a
+                // new one and replay the handshake.
+                StatusCode::NOT_FOUND
+                    if session.id.is_some() && !is_initialize && !reinitialized =>
+                {
+                    reinitialized = true;
+                    Box::pin(self.reinitialize_http(session.generation)).await?;
+                    continue;
+                }
+                status if status.is_server_error() && attempt + 1 < MAX_ATTEMPTS => {
+                    attempt += 1;
+                    eprintln!(
+                        "conductor bridge: remote server returned {}; retrying",
+                        status
+                    );
+                    sleep(backoff(attempt)).await;
+                    continue;
+                }
+                status if status.is_success() => return self.read_http_response(response).await,
+                status => {
+                    let body = response.text().await.unwrap_or_default();
+                    bail!("The remote server returned {}: {}", status, body.trim());
+                }
+            }
+        }
+    }
+
+    async fn read_http_response(&self, response: reqwest::Response) -> Result<()> {
+        let is_stream = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/event-stream"));
+        if !is_stream {
+            let body = response
+                .text()
+                .await
+                .context("Failed to read the response")?;
+            if !body.trim().is_empty() {
+                self.forward_body(&body);
+            }
+            return Ok(());
+        }
+
+        // A dropped response stream is resumed with `Last-Event-ID` when the
+        // server numbers its events; otherwise the request fails.
+        let mut last_id: Option<String> = None;
+        let mut next = Some(response);
+        let mut attempt = 0;
+        loop {
+            if let Some(response) = next.take() {
+                let result = read_events(response, |event| {
+                    if let Some(id) = &event.id {
+                        last_id = Some(id.clone());
+                    }
+                    self.forward_event(&event);
+                })
+                .await;
+                match result {
+                    Ok(()) => return Ok(()),
+                    Err(e) if last_id.is_none() => return Err(e),
+                    Err(e) => eprintln!("conductor bridge: {:#}; resuming", e),
+                }
+            }
+            attempt += 1;
+            if attempt > MAX_ATTEMPTS {
+                bail!("Lost the response stream");
+            }
+            sleep(backoff(attempt)).await;
+            match self.open_http_stream(last_id.as_deref()).await {
+                Ok(Some(response)) => next = Some(response),
+                Ok(None) => bail!("Lost the response stream and the server can't resume it"),
+                Err(e) => eprintln!("conductor bridge: {:#}", e),
+            }
+        }
+    }
+
+    async fn reinitialize_http(&self, seen_generation: u64) -> Result<()> {
+        let _guard = self.reconnect.lock().await;
+        if lock(&self.session).generation != seen_generation {
+            // Another request already reconnected.
+            return Ok(());
+        }
+        let Some(params) = self.initialize_params() else {
+            bail!("The session expired before the client initialized it");
+        };
+        eprintln!("conductor bridge: session expired; reconnecting");
+        lock(&self.session).id = None;
+
+        let (request, rx) = self.register_own_request("initialize", params);
+        self.post_http(&request).await?;
+        Self::await_own_response(rx).await?;
+        if self.initialized.load(Ordering::SeqCst) {
+            self.post_http(&json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+                .await?;
+        }
+        lock(&self.session).generation += 1;
+        Ok(())
+    }
+
+    /// Opens the GET stream of the session. `None` when the server doesn't
+    /// offer one (405).
+    async fn open_http_stream(
+        &self,
+        last_event_id: Option<&str>,
+    ) -> Result<Option<reqwest::Response>> {
+        let mut refreshed = false;
+        loop {
+            let session = lock(&self.session).clone();
+            let mut request = self
+                .http
+                .get(self.url.clone())
+                .header(ACCEPT, "text/event-stream");
+            if let Some(id) = &session.id {
+                request = request.header(SESSION_HEADER, id);
+            }
+            if let Some(version) = &session.protocol_version {
+                request = request.header(PROTOCOL_HEADER, version);
+            }
+            if let Some(id) = last_event_id {
+                request = request.header(LAST_EVENT_HEADER, id);
+            }
+            let response = self
+                .authorize(request)
+                .send()
+                .await
+                .context("Failed to reach the remote server")?;
+            match response.status() {
+                StatusCode::METHOD_NOT_ALLOWED => return Ok(None),
+                StatusCode::UNAUTHORIZED if !refreshed => {
+                    refreshed = true;
+                    if !self.refresh_token().await {
+                        bail!("The remote server rejected the credentials (401)");
+                    }
+                }
+                status if status.is_success() => return Ok(Some(response)),
+                status => bail!("The remote server returned {} for the event stream", status),
+            }
+        }
+    }
+
+    /// Keeps the session's GET stream open for server-initiated messages,
+    /// reconnecting when it drops.
+    async fn listen_http(self: Arc<Self>) {
+        let mut last_id: Option<String> = None;
+        let mut failures = 0;
+        loop {
+            match self.open_http_stream(last_id.as_deref()).await {
+                Ok(None) => return,
+                Ok(Some(response)) => {
+                    failures = 0;
+                    let _ = read_events(response, |event| {
+                        if let Some(id) = &event.id {
+                            last_id = Some(id.clone());
+                        }
+                        self.forward_event(&event);
+                    })
+                    .await;
+                }
+                Err(e) => {
+                    failures += 1;
+                    if failures >= MAX_ATTEMPTS {
+                        eprintln!("conductor bridge: giving up on the event stream: {:#}", e);
+                        return;
+                    }
+                }
+            }
+            sleep(backoff(failures.max(1))).await;
+        }
+    }
+
+    /// Ends the HTTP session so the server can free it.
+    async fn close(&self) {
+        if self.transport != TransportType::StreamableHttp {
+            return;
+        }
+        let Some(id) = lock(&self.session).id.clone() else {
+            return;
+        };
+        let request = self
+            .http
+            .delete(self.url.clone())
+            .header(SESSION_HEADER, id);
+        let _ = timeout(Duration::from_secs(5), self.authorize(request).send()).await;
+    }
+
+    // --- Legacy SSE ---
+
+    /// POSTs a message to the stream's endpoint; its response arrives on the
+    /// stream. `endpoint` is set while replaying the handshake, before the
+    /// endpoint is published to everyone else.
+    async fn post_legacy(&self, message: &Value, endpoint: Option<&Url>) -> Result<()> {
+        let mut refreshed = false;
+        let mut attempt = 0;
+        loop {
+            let target = match endpoint {
+                Some(endpoint) => endpoint.clone(),
+                None => self.wait_for_endpoint().await?,
+            };
+            let request = self.http.post(target).json(message);
+            let response = match self.authorize(request).send().await {
+                Ok(response) => response,
+                Err(e) => {
+                    attempt += 1;
+                    if endpoint.is_some() || attempt >= MAX_ATTEMPTS {
+                        return Err(e).context("The remote server is unreachable");
+                    }
+                    // The stream is most likely reconnecting; wait for its
+                    // new endpoint.
+                    sleep(backoff(attempt)).await;
+                    continue;
+                }
+            };
+            match response.status() {
+                StatusCode::UNAUTHORIZED if !refreshed => {
+                    refreshed = true;
+                    if !self.refresh_token().await {
+                        bail!("The remote server rejected the credentials (401)");
+                    }
+                }
+                status if status.is_success() => return Ok(()),
+                status => {
+                    let body = response.text().await.unwrap_or_default();
+                    bail!("The remote server returned {}: {}", status, body.trim());
+                }
+            }
+        }
+    }
+
+    async fn wait_for_endpoint(&self) -> Result<Url> {
+        let mut rx = self.endpoint.subscribe();
+        let endpoint = timeout(RESPONSE_TIMEOUT, rx.wait_for(|e| e.is_some()))
+            .await
+            .context("Timed out connecting to the remote server")?
+            .context("The remote stream closed")?;
+        Ok(endpoint.clone().expect("waited for Some"))
+    }
+
+    /// Keeps the legacy SSE stream open, reconnecting with backoff when it
+    /// drops. Runs until the process exits.
+    async fn run_sse_stream(self: Arc<Self>) {
+        let mut failures = 0;
+        loop {
+            match self.open_sse_stream().await {
+                Ok(response) => {
+                    failures = 0;
+                    let bridge = self.clone();
+                    let result = read_events(response, |event| match event.event.as_str() {
+                        "endpoint" => match bridge.url.join(event.data.trim()) {
+                            Ok(endpoint) => {
+                                tokio::spawn(bridge.clone().resume_legacy(endpoint));
+                            }
+                            Err(e) => eprintln!("conductor bridge: bad endpoint: {}", e),
+                        },
+                        _ => bridge.forward_event(&event),
+                    })
+                    .await;
+                    if let Err(e) = result {
+                        eprintln!("conductor bridge: {:#}", e);
+                    }
+                }
+                Err(e) => {
+                    failures += 1;
+                    eprintln!("conductor bridge: {:#}", e);
+                }
+            }
+
+            self.endpoint.send_replace(None);
+            self.fail_in_flight("The connection to the remote server dropped");
+            sleep(backoff(failures.max(1))).await;
+            eprintln!("conductor bridge: reconnecting to {}", self.url);
+        }
+    }
+
+    async fn open_sse_stream(&self) -> Result<reqwest::Response> {
+        let mut refreshed = false;
+        loop {
+            let request = self
+                .http
+                .get(self.url.clone())
+                .header(ACCEPT, "text/event-stream");
+            let response = self
+                .authorize(request)
+                .send()
+                .await
+                .context("Failed to reach the remote server")?;
+            match response.status() {
+                StatusCode::UNAUTHORIZED if !refreshed => {
+                    refreshed = true;
+                    if !self.refresh_token().await {
+                        bail!("The remote server rejected the credentials (401)");
+                    }
+                }
+                status if status.is_success() => return Ok(response),
+                status => bail!("The remote server returned {}", status),
+            }
+        }
+    }
+
+    /// Replays the handshake on a reconnected stream, then lets queued
+    /// messages through.
+    async fn resume_legacy(self: Arc<Self>, endpoint: Url) {
+        if self.initialized.load(Ordering::SeqCst) {
+            if let Some(params) = self.initialize_params() {
+                let (request, rx) = self.register_own_request("initialize", params);
+                let replay = async {
+                    self.post_legacy(&request, Some(&endpoint)).await?;
+                    Self::await_own_response(rx).await?;
+                    self.post_legacy(
+                        &json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+                        Some(&endpoint),
+                    )
+                    .await
+                };
+                if let Err(e) = replay.await {
+                    eprintln!("conductor bridge: failed to restore the session: {:#}", e);
+                }
+            }
+        }
+        self.endpoint.send_replace(Some(endpoint));
+    }
+
+    fn fail_in_flight(&self, reason: &str) {
+        let ids: Vec<Value> = lock(&self.in_flight).drain().map(|(_, id)| id).collect();
+        for id in ids {
+            let _ = self.out.send(error_response(&id, reason));
+        }
+        lock(&self.pending).clear();
+    }
+}
+
+fn error_response(id: &Value, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": -32000, "message": message }
+    })
+}
+
+async fn read_events(
+    mut response: reqwest::Response,
+    mut on_event: impl FnMut(SseEvent),
+) -> Result<()> {
+    let mut parser = SseParser::default();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .context("The remote stream dropped")?
+    {
+        for event in parser.push(&chunk) {
+            on_event(event);
+        }
+    }
+    Ok(())
+}
+
+struct SseEvent {
+    event: String,
+    data: String,
+    id: Option<String>,
+}
+
+/// Incremental `text/event-stream` parser; chunks may split lines anywhere.
+#[derive(Default)]
+struct SseParser {
+    buffer: Vec<u8>,
+    event: String,
+    data: Vec<String>,
+    id: Option<String>,
+}
+
+impl SseParser {
+    fn push(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|b| *b == b'\n') {
+            let raw: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&raw);
+            let line = line.trim_end_matches(['\n', '\r']);
+
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    events.push(SseEvent {
+                        event: std::mem::take(&mut self.event),
+                        data: self.data.join("\n"),
+                        id: self.id.clone(),
+                    });
+                }
+                self.data.clear();
+                self.event.clear();
+                continue;
+            }
+            if line.starts_with(':') {
+                continue;
+            }
+            let (field, value) = match line.split_once(':') {
+                Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+                None => (line, ""),
+            };
+            match field {
+                "event" => self.event = value.to_string(),
+                "data" => self.data.push(value.to_string()),
+                "id" => self.id = Some(value.to_string()),
+                _ => {}
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_server_id_and_display_name() {
+        let parsed = parse_args(&args(&[
+            "--server-id",
+            "abc-123",
+            "--server",
+            "conductor-github",
+            "https://example.com/mcp",
+        ]))
+        .unwrap();
+        assert_eq!(parsed.server_id.as_deref(), Some("abc-123"));
+        assert_eq!(parsed.server.as_deref(), Some("conductor-github"));
+        assert_eq!(parsed.url.as_str(), "https://example.com/mcp");
+    }
+
+    #[test]
+    fn accepts_entries_written_with_only_a_name() {
+        let parsed = parse_args(&args(&["--server", "github", "https://example.com/sse"])).unwrap();
+        assert_eq!(parsed.server_id, None);
+        assert_eq!(parsed.server.as_deref(), Some("github"));
+    }
+
+    #[test]
+    fn bridge_command_passes_the_server_id() {
+        let server: McpServerConfig = serde_json::from_value(json!({
+            "id": "abc-123",
+            "name": "conductor-github",
+            "transport": "streamableHttp",
+            "url": "https://example.com/mcp",
+        }))
+        .unwrap();
+        let (_, args) = config::serializer::bridge_command(&server).unwrap();
+        let id_at = args.iter().position(|a| a == "--server-id").unwrap();
+        assert_eq!(args[id_at + 1], "abc-123");
+    }
+}
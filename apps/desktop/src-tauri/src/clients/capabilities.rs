@@ -0,0 +1,205 @@
+use crate::clients::spec::{expand_path, AdapterSpec};
+use crate::config::TransportType;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+/// What a client can express natively for remote (URL) servers. Anything it
+/// can't is written as a stdio entry that runs the configured remote fallback.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteCapabilities {
+    pub sse: bool,
+    pub streamable_http: bool,
+    /// Static headers such as `Authorization` can be attached to the entry.
+    pub headers: bool,
+}
+
+impl RemoteCapabilities {
+    pub const NONE: Self = Self {
+        sse: false,
+        streamable_http: false,
+        headers: false,
+    };
+    pub const ALL: Self = Self {
+        sse: true,
+        streamable_http: true,
+        headers: true,
+    };
+
+    pub fn supports(&self, transport: &TransportType) -> bool {
+        match transport {
+            TransportType::Stdio => true,
+            TransportType::Sse => self.sse,
+            TransportType::StreamableHttp => self.streamable_http,
+        }
+    }
+
+    /// Whether `transport` can be written as a native entry, given whether
+    /// the server needs an auth header.
+    pub fn native(&self, transport: &TransportType, needs_header: bool) -> bool {
+        self.supports(transport) && (!needs_header || self.headers)
+    }
+}
+
+/// Clients whose remote support depends on their version: (client ID, first
+/// version with this support, support). The last row a client's version
+/// reaches wins; an unknown version gets the newest row.
+const MATRIX: &[(&str, &str, RemoteCapabilities)] = &[
+    // Remote servers are added as connectors in the app, not in the config file.
+    ("claude-desktop", "0.0.0", RemoteCapabilities::NONE),
+    (
+        "cursor",
+        "0.0.0",
+        RemoteCapabilities {
+            sse: true,
+            streamable_http: false,
+            headers: false,
+        },
+    ),
+    ("cursor", "0.48.0", RemoteCapabilities::ALL),
+    ("windsurf", "0.0.0", RemoteCapabilities::ALL),
+    ("vscode", "0.0.0", RemoteCapabilities::ALL),
+    ("claude-code", "0.0.0", RemoteCapabilities::ALL),
+    ("zed", "0.0.0", RemoteCapabilities::NONE),
+    (
+        "zed",
+        "0.214.0",
+        RemoteCapabilities {
+            sse: false,
+            streamable_http: true,
+            headers: true,
+        },
+    ),
+    // Bearer tokens go through `bearer_token_env_var`, not headers.
+    ("codex", "0.0.0", RemoteCapabilities::NONE),
+    (
+        "codex",
+        "0.46.0",
+        RemoteCapabilities {
+            sse: false,
+            streamable_http: true,
+            headers: false,
+        },
+    ),
+    ("jetbrains", "0.0.0", RemoteCapabilities::NONE),
+];
+
+/// Remote support of a client in the matrix at `version`. `None` for clients
+/// the matrix doesn't cover.
+pub fn from_matrix(client_id: &str, version: Option<&str>) -> Option<RemoteCapabilities> {
+    let version = version.and_then(parse_version);
+    MATRIX
+        .iter()
+        .filter(|(id, _, _)| *id == client_id)
+        .take_while(|(_, since, _)| match &version {
+            Some(v) => parse_version(since).is_some_and(|since| *v >= since),
+            None => true,
+        })
+        .last()
+        .map(|(_, _, caps)| *caps)
+}
+
+/// Remote support of a built-in client, at its detected version.
+pub fn for_client(client_id: &str) -> RemoteCapabilities {
+    from_matrix(client_id, detected_version(client_id).as_deref())
+        .unwrap_or(RemoteCapabilities::ALL)
+}
+
+/// Remote support of a spec-driven client: the matrix for built-ins it
+/// covers, otherwise the spec's own `[transport]` table.
+pub fn for_spec(spec: &AdapterSpec) -> RemoteCapabilities {
+    let version = detected_version(&spec.id);
+    if let Some(caps) = from_matrix(&spec.id, version.as_deref()) {
+        return caps;
+    }
+    let support = &spec.transport;
+    let too_old = match (
+        &support.min_version,
+        version.as_deref().and_then(parse_version),
+    ) {
+        (Some(min), Some(v)) => parse_version(min).is_some_and(|min| v < min),
+        _ => false,
+    };
+    if too_old {
+        return RemoteCapabilities::NONE;
+    }
+    RemoteCapabilities {
+        sse: support.url,
+        streamable_http: support.url,
+        headers: support.headers,
+    }
+}
+
+/// Installed version of a client, looked up once per run.
+pub fn detected_version(client_id: &str) -> Option<String> {
+    static CACHE: OnceLock<Mutex<HashMap<String, Option<String>>>> = OnceLock::new();
+    let cache = CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(cached) = cache.lock().ok().and_then(|c| c.get(client_id).cloned()) {
+        return cached;
+    }
+    let version = crate::clients::get_adapter(client_id).and_then(|a| a.version());
+    if let Ok(mut cache) = cache.lock() {
+        cache.insert(client_id.to_string(), version.clone());
+    }
+    version
+}
+
+/// First `x.y[.z]` in `text`, e.g. "0.46.0" from "codex-cli 0.46.0".
+pub fn version_in(text: &str) -> Option<String> {
+    text.split(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .map(|token| token.trim_matches('.'))
+        .find(|token| token.contains('.') && token.split('.').all(|part| !part.is_empty()))
+        .map(|s| s.to_string())
+}
+
+fn parse_version(version: &str) -> Option<Vec<u32>> {
+    version
+        .trim()
+        .trim_start_matches('v')
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect()
+}
+
+/// Version printed by `<cmd> --version`.
+pub(crate) fn command_version(cmd: &str) -> Option<String> {
+    let output = std::process::Command::new(cmd)
+        .arg("--version")
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    version_in(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// `CFBundleShortVersionString` of a macOS app bundle.
+pub(crate) fn app_bundle_version(app: &Path) -> Option<String> {
+    let plist = std::fs::read_to_string(app.join("Contents").join("Info.plist")).ok()?;
+    let after_key = plist
+        .split("<key>CFBundleShortVersionString</key>")
+        .nth(1)?;
+    let value = after_key
+        .split("<string>")
+        .nth(1)?
+        .split("</string>")
+        .next()?;
+    version_in(value)
+}
+
+/// Version of a spec-driven client from its detect hints: app bundles first,
+/// then `--version` of its commands.
+pub(crate) fn spec_version(spec: &AdapterSpec) -> Option<String> {
+    spec.detect
+        .paths
+        .iter()
+        .filter(|p| p.ends_with(".app"))
+        .filter_map(|p| expand_path(p))
+        .find_map(|app| app_bundle_version(&app))
+        .or_else(|| {
+            spec.detect
+                .commands
+                .iter()
+                .find_map(|cmd| command_version(cmd))
+        })
+}
@@ -1,12 +1,75 @@
-use crate::clients::ClientAdapter;
-use crate::config::McpServerConfig;
+use crate::clients::{capabilities, which_exists, ClientAdapter, SyncTarget};
+use crate::config::{McpServerConfig, PROJECT_TAG_PREFIX};
+use crate::errors::ConductorError;
 use crate::config::{backup, normalizer, serializer};
+use crate::config::serializer::FallbackSettings;
 use anyhow::Result;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub struct ClaudeCodeAdapter;
 
+/// Target ID of the user scope: top-level `mcpServers` in `~/.claude.json`.
+const USER_TARGET: &str = "user";
+/// Prefix of local-scope targets: `projects["<path>"].mcpServers` in `~/.claude.json`.
+const LOCAL_PREFIX: &str = "local:";
+/// Prefix of project-scope targets: the repo's checked-in `<path>/.mcp.json`.
+const PROJECT_PREFIX: &str = "project:";
+
+/// Where a target's servers live.
+enum Scope {
+    User,
+    Local(String),
+    Project(String),
+}
+
+impl Scope {
+    fn parse(target_id: &str) -> Option<Scope> {
+        if target_id == USER_TARGET {
+            Some(Scope::User)
+        } else if let Some(project) = target_id.strip_prefix(LOCAL_PREFIX) {
+            Some(Scope::Local(project.to_string()))
+        } else {
+            target_id
+                .strip_prefix(PROJECT_PREFIX)
+                .map(|project| Scope::Project(project.to_string()))
+        }
+    }
+
+    fn config_path(&self) -> Option<PathBuf> {
+        match self {
+            Scope::User => ClaudeCodeAdapter::get_config_path(),
+            Scope::Local(_) => ClaudeCodeAdapter::claude_json_path(),
+            Scope::Project(project) => Some(Path::new(project).join(".mcp.json")),
+        }
+    }
+
+    fn key_path(&self) -> Vec<&str> {
+        match self {
+            Scope::User | Scope::Project(_) => vec!["mcpServers"],
+            Scope::Local(project) => vec!["projects", project.as_str(), "mcpServers"],
+        }
+    }
+
+    fn project(&self) -> Option<&str> {
+        match self {
+            Scope::User => None,
+            Scope::Local(project) | Scope::Project(project) => Some(project),
+        }
+    }
+
+    /// Tag recorded on imported servers, which keeps them scoped to their
+    /// project once imported.
+    fn project_tag(&self) -> Option<String> {
+        self.project()
+            .map(|project| format!("{}{}", PROJECT_TAG_PREFIX, project))
+    }
+}
+
 impl ClaudeCodeAdapter {
+    fn claude_json_path() -> Option<PathBuf> {
+        Some(dirs::home_dir()?.join(".claude.json"))
+    }
+
     fn get_config_path() -> Option<PathBuf> {
         let home = dirs::home_dir()?;
         // Claude Code stores MCP servers in ~/.claude.json (mcpServers key)
@@ -22,6 +85,58 @@ impl ClaudeCodeAdapter {
         // Default to primary path
         Some(primary)
     }
+
+    /// Project paths Claude Code has been used in, from the `projects` map
+    /// in `~/.claude.json`.
+    fn known_projects() -> Vec<String> {
+        let Some(path) = Self::claude_json_path() else {
+            return Vec::new();
+        };
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return Vec::new();
+        };
+        let Ok(value) = crate::config::jsonc::parse(&content) else {
+            return Vec::new();
+        };
+        let mut projects: Vec<String> = value
+            .get("projects")
+            .and_then(|p| p.as_object())
+            .map(|p| p.keys().cloned().collect())
+            .unwrap_or_default();
+        projects.sort();
+        projects
+    }
+
+    fn scope(&self, target_id: &str) -> Result<Scope> {
+        Scope::parse(target_id).ok_or_else(|| {
+            ConductorError::ClientPathUnknown(format!("{} ({})", self.id(), target_id)).into()
+        })
+    }
+
+    fn read_scope(&self, scope: &Scope) -> Result<Vec<McpServerConfig>> {
+        let path = scope
+            .config_path()
+            .ok_or_else(|| ConductorError::ClientPathUnknown(self.id().to_string()))?;
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        let mut servers =
+            normalizer::parse_json_servers_at(&content, &scope.key_path(), self.id())?;
+        if let Some(tag) = scope.project_tag() {
+            for server in &mut servers {
+                server.tags.push(tag.clone());
+            }
+        }
+        Ok(servers)
+    }
+}
+
+fn project_label(project: &str) -> &str {
+    Path::new(project)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(project)
 }
 
 impl ClientAdapter for ClaudeCodeAdapter {
@@ -51,14 +166,25 @@ impl ClientAdapter for ClaudeCodeAdapter {
         Self::get_config_path()
     }
 
+    fn version(&self) -> Option<String> {
+        capabilities::command_version("claude")
+    }
+
+    /// User-scope servers followed by every project's local and `.mcp.json`
+    /// servers, the latter tagged `project:<path>` so they stay in their
+    /// project when imported.
     fn read_servers(&self) -> Result<Vec<McpServerConfig>> {
-        let path = Self::get_config_path()
-            .ok_or_else(|| anyhow::anyhow!("Cannot determine config path for Claude Code"))?;
-        if !path.exists() {
-            return Ok(Vec::new());
+        let mut servers = self.read_scope(&Scope::User)?;
+        for project in Self::known_projects() {
+            // A broken .mcp.json in one repo shouldn't hide every other scope.
+            for scope in [Scope::Local(project.clone()), Scope::Project(project)] {
+                match self.read_scope(&scope) {
+                    Ok(found) => servers.extend(found),
+                    Err(e) => eprintln!("Warning: skipping Claude Code scope: {:#}", e),
+                }
+            }
         }
-        let content = std::fs::read_to_string(&path)?;
-        normalizer::parse_client_config("claude-code", &content)
+        Ok(servers)
     }
 
     fn write_servers(
@@ -66,9 +192,75 @@ impl ClientAdapter for ClaudeCodeAdapter {
         servers: &[McpServerConfig],
         existing_content: Option<&str>,
         previously_synced_names: &[String],
+        fallback: &FallbackSettings,
     ) -> Result<()> {
-        let path = Self::get_config_path()
-            .ok_or_else(|| anyhow::anyhow!("Cannot determine config path for Claude Code"))?;
+        self.write_target(USER_TARGET, servers, existing_content, previously_synced_names, fallback)
+    }
+
+    /// User scope (synced by default), then each known project's local scope
+    /// and `.mcp.json`, which are only synced when the user picks them.
+    fn targets(&self) -> Vec<SyncTarget> {
+        let mut targets = Vec::new();
+        if let Some(path) = Self::get_config_path() {
+            targets.push(SyncTarget {
+                id: USER_TARGET.to_string(),
+                label: "User".to_string(),
+                exists: path.exists(),
+                config_path: path,
+                selected: true,
+            });
+        }
+        let Some(claude_json) = Self::claude_json_path() else {
+            return targets;
+        };
+        for project in Self::known_projects() {
+            let name = project_label(&project);
+            targets.push(SyncTarget {
+                id: format!("{}{}", LOCAL_PREFIX, project),
+                label: format!("Local · {}", name),
+                config_path: claude_json.clone(),
+                exists: true,
+                selected: false,
+            });
+            if Path::new(&project).is_dir() {
+                let mcp_json = Path::new(&project).join(".mcp.json");
+                targets.push(SyncTarget {
+                    id: format!("{}{}", PROJECT_PREFIX, project),
+                    label: format!("Project · {}", name),
+                    exists: mcp_json.exists(),
+                    config_path: mcp_json,
+                    selected: false,
+                });
+            }
+        }
+        targets
+    }
+
+    fn target_project(&self, target_id: &str) -> Option<String> {
+        Scope::parse(target_id)?.project().map(str::to_string)
+    }
+
+    /// `.mcp.json` is checked in with the repo.
+    fn is_shared_target(&self, target_id: &str) -> bool {
+        matches!(Scope::parse(target_id), Some(Scope::Project(_)))
+    }
+
+    fn read_target(&self, target_id: &str) -> Result<Vec<McpServerConfig>> {
+        self.read_scope(&self.scope(target_id)?)
+    }
+
+    fn write_target(
+        &self,
+        target_id: &str,
+        servers: &[McpServerConfig],
+        existing_content: Option<&str>,
+        previously_synced_names: &[String],
+        fallback: &FallbackSettings,
+    ) -> Result<()> {
+        let scope = self.scope(target_id)?;
+        let path = scope
+            .config_path()
+            .ok_or_else(|| ConductorError::ClientPathUnknown(self.id().to_string()))?;
 
         let current_content = match existing_content {
             Some(c) => Some(c.to_string()),
@@ -81,11 +273,13 @@ impl ClientAdapter for ClaudeCodeAdapter {
             }
         };
 
-        let output = serializer::serialize_to_client_format(
-            "claude-code",
+        let output = serializer::serialize_json_servers_at(
+            self.id(),
+            &scope.key_path(),
             servers,
             current_content.as_deref(),
             previously_synced_names,
+            fallback,
         )?;
 
         if let Some(parent) = path.parent() {
@@ -96,11 +290,3 @@ impl ClientAdapter for ClaudeCodeAdapter {
         Ok(())
     }
 }
-
-fn which_exists(cmd: &str) -> bool {
-    std::process::Command::new("which")
-        .arg(cmd)
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false)
-}
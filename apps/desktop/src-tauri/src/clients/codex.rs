@@ -1,98 +0,0 @@
-use crate::clients::ClientAdapter;
-use crate::config::McpServerConfig;
-use crate::config::{backup, normalizer, serializer};
-use anyhow::Result;
-use std::path::PathBuf;
-
-pub struct CodexAdapter;
-
-impl CodexAdapter {
-    fn get_config_path() -> Option<PathBuf> {
-        let home = dirs::home_dir()?;
-        // Primary path
-        let primary = home.join(".codex").join("config.toml");
-        if primary.exists() {
-            return Some(primary);
-        }
-        // XDG fallback
-        let fallback = home.join(".config").join("codex").join("config.toml");
-        if fallback.exists() {
-            return Some(fallback);
-        }
-        // Default to primary
-        Some(primary)
-    }
-}
-
-impl ClientAdapter for CodexAdapter {
-    fn id(&self) -> &str {
-        "codex"
-    }
-
-    fn display_name(&self) -> &str {
-        "OpenAI Codex"
-    }
-
-    fn icon(&self) -> &str {
-        "codex"
-    }
-
-    fn detect(&self) -> bool {
-        if let Some(path) = Self::get_config_path() {
-            if path.exists() {
-                return true;
-            }
-        }
-        // Check if codex CLI is available
-        std::process::Command::new("which")
-            .arg("codex")
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false)
-    }
-
-    fn config_path(&self) -> Option<PathBuf> {
-        Self::get_config_path()
-    }
-
-    fn read_servers(&self) -> Result<Vec<McpServerConfig>> {
-        let path = Self::get_config_path()
-            .ok_or_else(|| anyhow::anyhow!("Cannot determine config path for Codex"))?;
-        if !path.exists() {
-            return Ok(Vec::new());
-        }
-        let content = std::fs::read_to_string(&path)?;
-        normalizer::parse_client_config("codex", &content)
-    }
-
-    fn write_servers(
-        &self,
-        servers: &[McpServerConfig],
-        existing_content: Option<&str>,
-        previously_synced_names: &[String],
-    ) -> Result<()> {
-        let path = Self::get_config_path()
-            .ok_or_else(|| anyhow::anyhow!("Cannot determine config path for Codex"))?;
-
-        let current_content = match existing_content {
-            Some(c) => Some(c.to_string()),
-            None => {
-                if path.exists() {
-                    Some(std::fs::read_to_string(&path)?)
-                } else {
-                    None
-                }
-            }
-        };
-
-        let output =
-            serializer::serialize_to_client_format("codex", servers, current_content.as_deref(), previously_synced_names)?;
-
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
-
-        backup::atomic_write(&path, &output)?;
-        Ok(())
-    }
-}
@@ -1,82 +0,0 @@
-use crate::clients::ClientAdapter;
-use crate::config::McpServerConfig;
-use crate::config::{backup, normalizer, serializer};
-use anyhow::Result;
-use std::path::PathBuf;
-
-pub struct CursorAdapter;
-
-impl CursorAdapter {
-    fn get_config_path() -> Option<PathBuf> {
-        let home = dirs::home_dir()?;
-        Some(home.join(".cursor").join("mcp.json"))
-    }
-}
-
-impl ClientAdapter for CursorAdapter {
-    fn id(&self) -> &str {
-        "cursor"
-    }
-
-    fn display_name(&self) -> &str {
-        "Cursor"
-    }
-
-    fn icon(&self) -> &str {
-        "cursor"
-    }
-
-    fn detect(&self) -> bool {
-        if let Some(path) = Self::get_config_path() {
-            if path.exists() {
-                return true;
-            }
-        }
-        std::path::Path::new("/Applications/Cursor.app").exists()
-    }
-
-    fn config_path(&self) -> Option<PathBuf> {
-        Self::get_config_path()
-    }
-
-    fn read_servers(&self) -> Result<Vec<McpServerConfig>> {
-        let path = Self::get_config_path()
-            .ok_or_else(|| anyhow::anyhow!("Cannot determine config path for Cursor"))?;
-        if !path.exists() {
-            return Ok(Vec::new());
-        }
-        let content = std::fs::read_to_string(&path)?;
-        normalizer::parse_client_config("cursor", &content)
-    }
-
-    fn write_servers(
-        &self,
-        servers: &[McpServerConfig],
-        existing_content: Option<&str>,
-        previously_synced_names: &[String],
-    ) -> Result<()> {
-        let path = Self::get_config_path()
-            .ok_or_else(|| anyhow::anyhow!("Cannot determine config path for Cursor"))?;
-
-        let current_content = match existing_content {
-            Some(c) => Some(c.to_string()),
-            None => {
-                if path.exists() {
-                    Some(std::fs::read_to_string(&path)?)
-                } else {
-                    None
-                }
-            }
-        };
-
-        let output =
-            serializer::serialize_to_client_format("cursor", servers, current_content.as_deref(), previously_synced_names)?;
-
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
-
-        backup::atomic_write(&path, &output)?;
-        Ok(())
-    }
-}
@@ -0,0 +1,354 @@
+use crate::clients::spec::{expand_path, AdapterSpec};
+use crate::clients::{
+    capabilities, which_exists, ClientAdapter, NativeToolFilter, SyncTarget, DEFAULT_TARGET,
+};
+use crate::config::McpServerConfig;
+use crate::config::{backup, normalizer, serializer};
+use crate::config::serializer::FallbackSettings;
+use crate::errors::ConductorError;
+use anyhow::Result;
+use std::path::PathBuf;
+
+/// Client adapter driven entirely by an `AdapterSpec`: built-in specs shipped
+/// with Conductor and user specs from `~/.conductor/adapters`.
+pub struct SpecAdapter {
+    spec: AdapterSpec,
+}
+
+impl SpecAdapter {
+    pub fn new(spec: AdapterSpec) -> Self {
+        Self { spec }
+    }
+
+    pub fn spec(&self) -> &AdapterSpec {
+        &self.spec
+    }
+
+    /// The spec a target is read and written with: the client's own for the
+    /// default target, with the target's path, pointer and codec otherwise.
+    fn target_spec(&self, target_id: &str) -> Result<AdapterSpec> {
+        if target_id == DEFAULT_TARGET {
+            return Ok(self.spec.clone());
+        }
+        self.spec
+            .all_targets()
+            .iter()
+            .find(|t| t.id == target_id)
+            .map(|t| self.spec.for_target(t))
+            .ok_or_else(|| {
+                ConductorError::ClientPathUnknown(format!("{} ({})", self.id(), target_id)).into()
+            })
+    }
+}
+
+fn read_spec(spec: &AdapterSpec) -> Result<Vec<McpServerConfig>> {
+    let path = spec
+        .resolve_config_path()
+        .ok_or_else(|| ConductorError::ClientPathUnknown(spec.id.clone()))?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(&path)?;
+    match spec.codec_for(&path) {
+        Some(codec) => normalizer::parse_client_config(codec, &content),
+        None => normalizer::parse_with_spec(spec, &content),
+    }
+}
+
+fn write_spec(
+    spec: &AdapterSpec,
+    servers: &[McpServerConfig],
+    existing_content: Option<&str>,
+    previously_synced_names: &[String],
+    fallback: &FallbackSettings,
+) -> Result<()> {
+    let path = spec
+        .resolve_config_path()
+        .ok_or_else(|| ConductorError::ClientPathUnknown(spec.id.clone()))?;
+
+    let current_content = match existing_content {
+        Some(c) => Some(c.to_string()),
+        None => {
+            if path.exists() {
+                Some(std::fs::read_to_string(&path)?)
+            } else {
+                None
+            }
+        }
+    };
+
+    let output = match spec.codec_for(&path) {
+        Some(codec) => serializer::serialize_to_client_format(
+            codec,
+            servers,
+            current_content.as_deref(),
+            previously_synced_names,
+            fallback,
+        )?,
+        None => serializer::serialize_with_spec(
+            spec,
+            servers,
+            current_content.as_deref(),
+            previously_synced_names,
+            fallback,
+        )?,
+    };
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    backup::atomic_write(&path, &output)?;
+    Ok(())
+}
+
+impl ClientAdapter for SpecAdapter {
+    fn id(&self) -> &str {
+        &self.spec.id
+    }
+
+    fn display_name(&self) -> &str {
+        &self.spec.display_name
+    }
+
+    fn icon(&self) -> &str {
+        self.spec.icon()
+    }
+
+    fn detect(&self) -> bool {
+        if let Some(path) = self.spec.resolve_config_path() {
+            if path.exists() {
+                return true;
+            }
+        }
+        self.spec
+            .detect
+            .paths
+            .iter()
+            .filter_map(|p| expand_path(p))
+            .any(|p| p.exists())
+            || self.spec.detect.commands.iter().any(|c| which_exists(c))
+    }
+
+    fn config_path(&self) -> Option<PathBuf> {
+        self.spec.resolve_config_path()
+    }
+
+    fn version(&self) -> Option<String> {
+        capabilities::spec_version(&self.spec)
+    }
+
+    fn native_tool_filter(&self) -> NativeToolFilter {
+        if self.spec.codec.as_deref() == Some("codex") {
+            return NativeToolFilter::EnabledDisabledLists;
+        }
+        match &self.spec.fields.disabled_tools {
+            Some(field) => NativeToolFilter::DisabledField(field.clone()),
+            None => NativeToolFilter::Unsupported,
+        }
+    }
+
+    fn remote_capabilities(&self) -> capabilities::RemoteCapabilities {
+        capabilities::for_spec(&self.spec)
+    }
+
+    /// Servers from every target, de-duplicated by name.
+    fn read_servers(&self) -> Result<Vec<McpServerConfig>> {
+        let mut servers = read_spec(&self.spec)?;
+        let mut seen: std::collections::HashSet<String> =
+            servers.iter().map(|s| s.name.to_lowercase()).collect();
+        for target in &self.spec.all_targets() {
+            for server in read_spec(&self.spec.for_target(target))? {
+                if seen.insert(server.name.to_lowercase()) {
+                    servers.push(server);
+                }
+            }
+        }
+        Ok(servers)
+    }
+
+    fn write_servers(
+        &self,
+        servers: &[McpServerConfig],
+        existing_content: Option<&str>,
+        previously_synced_names: &[String],
+        fallback: &FallbackSettings,
+    ) -> Result<()> {
+        write_spec(&self.spec, servers, existing_content, previously_synced_names, fallback)
+    }
+
+    /// The spec's own path, then each of its `[[targets]]` that resolves on
+    /// this OS and its named profiles.
+    fn targets(&self) -> Vec<SyncTarget> {
+        let mut targets = Vec::new();
+        if let Some(path) = self.config_path() {
+            targets.push(SyncTarget {
+                id: DEFAULT_TARGET.to_string(),
+                label: self.display_name().to_string(),
+                exists: path.exists(),
+                config_path: path,
+                selected: true,
+            });
+        }
+        for target in &self.spec.all_targets() {
+            if let Some(path) = self.spec.for_target(target).resolve_config_path() {
+                targets.push(SyncTarget {
+                    id: target.id.clone(),
+                    label: target.label.clone(),
+                    exists: path.exists(),
+                    config_path: path,
+                    selected: target.selected,
+                });
+            }
+        }
+        targets
+    }
+
+    fn read_target(&self, target_id: &str) -> Result<Vec<McpServerConfig>> {
+        read_spec(&self.target_spec(target_id)?)
+    }
+
+    fn write_target(
+        &self,
+        target_id: &str,
+        servers: &[McpServerConfig],
+        existing_content: Option<&str>,
+        previously_synced_names: &[String],
+        fallback: &FallbackSettings,
+    ) -> Result<()> {
+        write_spec(
+            &self.target_spec(target_id)?,
+            servers,
+            existing_content,
+            previously_synced_names,
+            fallback,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn scratch_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("conductor-spec-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn adapter(source: &str) -> SpecAdapter {
+        SpecAdapter::new(toml_edit::de::from_str(source).unwrap())
+    }
+
+    fn server(name: &str) -> McpServerConfig {
+        serde_json::from_value(serde_json::json!({
+            "id": name,
+            "name": name,
+            "enabled": true,
+            "transport": "stdio",
+            "command": "npx",
+            "args": ["-y", name],
+        }))
+        .unwrap()
+    }
+
+    fn names(servers: &[McpServerConfig]) -> Vec<&str> {
+        servers.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    fn write(adapter: &SpecAdapter, target: &str, servers: &[McpServerConfig], prev: &[&str]) {
+        let prev: Vec<String> = prev.iter().map(|s| s.to_string()).collect();
+        adapter
+            .write_target(target, servers, None, &prev, &FallbackSettings::default())
+            .unwrap();
+    }
+
+    fn read(path: &Path) -> String {
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn yaml_specs_keep_comments_and_other_keys() {
+        let dir = scratch_dir();
+        let path = dir.join("config.yaml");
+        std::fs::write(
+            &path,
+            "# editor settings\ntheme: dark\nservers:\n  # mine\n  local:\n    command: ./run\n  \
+             stale:\n    command: old\nfont: 12 # px\n",
+        )
+        .unwrap();
+        let adapter = adapter(&format!(
+            "id = \"yaml-editor\"\ndisplay_name = \"YAML Editor\"\nformat = \"yaml\"\n\
+             servers_pointer = \"/servers\"\n[paths]\ndefault = ['{}']\n",
+            path.display()
+        ));
+
+        write(&adapter, DEFAULT_TARGET, &[server("github")], &["stale"]);
+
+        let written = read(&path);
+        assert!(written.starts_with("# editor settings\ntheme: dark\nservers:\n  # mine\n"));
+        assert!(written.ends_with("font: 12 # px\n"));
+        assert!(!written.contains("stale"));
+        assert_eq!(names(&adapter.read_servers().unwrap()), ["github", "local"]);
+    }
+
+    #[test]
+    fn extra_targets_are_synced_on_their_own() {
+        let dir = scratch_dir();
+        let main = dir.join("mcp.json");
+        let work = dir.join("work").join("mcp.json");
+        let adapter = adapter(&format!(
+            "id = \"two-files\"\ndisplay_name = \"Two Files\"\nformat = \"json\"\n\
+             [paths]\ndefault = ['{}']\n\
+             [[targets]]\nid = \"work\"\nlabel = \"Work\"\ncodec = \"vscode\"\n\
+             paths = {{ default = ['{}'] }}\n",
+            main.display(),
+            work.display()
+        ));
+
+        let targets = adapter.targets();
+        let ids: Vec<(&str, bool)> = targets.iter().map(|t| (t.id.as_str(), t.selected)).collect();
+        assert_eq!(ids, [(DEFAULT_TARGET, true), ("work", false)]);
+
+        write(&adapter, "work", &[server("github")], &[]);
+        assert!(!main.exists());
+        let written: serde_json::Value = serde_json::from_str(&read(&work)).unwrap();
+        assert_eq!(written["servers"]["github"]["command"], "npx");
+
+        write(&adapter, DEFAULT_TARGET, &[server("linear")], &[]);
+        assert_eq!(names(&adapter.read_target("work").unwrap()), ["github"]);
+        assert_eq!(names(&adapter.read_servers().unwrap()), ["linear", "github"]);
+        assert!(adapter.read_target("missing").is_err());
+    }
+
+    #[test]
+    fn named_profiles_are_targets_in_vscode_format() {
+        let dir = scratch_dir();
+        let user = dir.join("User");
+        std::fs::create_dir_all(user.join("profiles").join("-1a2b")).unwrap();
+        std::fs::create_dir_all(user.join("globalStorage")).unwrap();
+        std::fs::write(
+            user.join("globalStorage").join("storage.json"),
+            r#"{"userDataProfiles": [{"location": "-1a2b", "name": "Work"}]}"#,
+        )
+        .unwrap();
+        let adapter = adapter(&format!(
+            "id = \"fork\"\ndisplay_name = \"Fork\"\nformat = \"json\"\n\
+             [paths]\ndefault = ['{}']\n[profiles]\ndefault = ['{}']\n",
+            dir.join("mcp.json").display(),
+            user.display()
+        ));
+
+        let profile = adapter.targets().pop().unwrap();
+        assert_eq!(profile.id, "profiles/-1a2b");
+        assert_eq!(profile.label, "Profile · Work");
+        assert!(!profile.selected);
+
+        write(&adapter, &profile.id, &[server("github")], &[]);
+        let written: serde_json::Value =
+            serde_json::from_str(&read(&user.join("profiles").join("-1a2b").join("mcp.json")))
+                .unwrap();
+        assert_eq!(written["servers"]["github"]["command"], "npx");
+    }
+}
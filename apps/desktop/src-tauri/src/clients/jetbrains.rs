@@ -1,58 +1,170 @@
-use crate::clients::ClientAdapter;
+use crate::clients::{ClientAdapter, SyncTarget};
 use crate::config::McpServerConfig;
 use crate::config::{backup, normalizer, serializer};
+use crate::config::serializer::FallbackSettings;
+use crate::errors::ConductorError;
 use anyhow::Result;
-use std::path::PathBuf;
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
 
 pub struct JetBrainsAdapter;
 
-impl JetBrainsAdapter {
-    /// JetBrains stores MCP config in the most recent IDE's config directory.
-    /// We search for common JetBrains IDEs on macOS.
-    fn get_config_path() -> Option<PathBuf> {
-        let home = dirs::home_dir()?;
-        let app_support = home
-            .join("Library")
-            .join("Application Support")
-            .join("JetBrains");
-
-        if !app_support.exists() {
-            return None;
-        }
+/// Config directory name prefixes of JetBrains IDEs, with display names.
+/// Longer prefixes come first so `PyCharmCE` isn't read as `PyCharm` + "CE…".
+const PRODUCTS: &[(&str, &str)] = &[
+    ("IntelliJIdea", "IntelliJ IDEA"),
+    ("IdeaIC", "IntelliJ IDEA CE"),
+    ("WebStorm", "WebStorm"),
+    ("PyCharmCE", "PyCharm CE"),
+    ("PyCharm", "PyCharm"),
+    ("GoLand", "GoLand"),
+    ("RustRover", "RustRover"),
+    ("CLion", "CLion"),
+    ("Rider", "Rider"),
+    ("PhpStorm", "PhpStorm"),
+    ("DataGrip", "DataGrip"),
+    ("DataSpell", "DataSpell"),
+    ("RubyMine", "RubyMine"),
+    ("Aqua", "Aqua"),
+    ("Writerside", "Writerside"),
+    ("AndroidStudioPreview", "Android Studio Preview"),
+    ("AndroidStudio", "Android Studio"),
+];
 
-        // Look for the most recent IDE config directory
-        let ide_prefixes = [
-            "IntelliJIdea",
-            "WebStorm",
-            "PyCharm",
-            "GoLand",
-            "RustRover",
-            "CLion",
-            "Rider",
-            "PhpStorm",
-            "DataGrip",
-        ];
-
-        let mut latest_path: Option<PathBuf> = None;
-        let mut latest_version: String = String::new();
-
-        if let Ok(entries) = std::fs::read_dir(&app_support) {
-            for entry in entries.flatten() {
-                let name = entry.file_name().to_string_lossy().to_string();
-                for prefix in &ide_prefixes {
-                    if name.starts_with(prefix) {
-                        let version = name.trim_start_matches(prefix).to_string();
-                        if version > latest_version {
-                            let mcp_path = entry.path().join("options").join("mcp.xml");
-                            latest_version = version;
-                            latest_path = Some(mcp_path);
-                        }
-                    }
-                }
+/// One IDE config directory, e.g. `~/.config/JetBrains/PyCharm2024.3`.
+struct IdeConfigDir {
+    /// Directory name relative to its vendor root, used as the target ID.
+    id: String,
+    product: &'static str,
+    version: Vec<u32>,
+    dir: PathBuf,
+}
+
+impl IdeConfigDir {
+    fn mcp_path(&self) -> PathBuf {
+        self.dir.join("options").join("mcp.xml")
+    }
+
+    fn label(&self) -> String {
+        let version = self
+            .version
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(".");
+        format!("{} {}", self.product, version)
+    }
+}
+
+/// Parses `PyCharm2024.3` into ("PyCharm", [2024, 3]). Versions compare
+/// numerically, so 2024.10 sorts after 2024.3.
+fn parse_dir_name(name: &str) -> Option<(&'static str, Vec<u32>)> {
+    let (prefix, product) = PRODUCTS
+        .iter()
+        .find(|(prefix, _)| name.starts_with(prefix))?;
+    let version: Option<Vec<u32>> = name[prefix.len()..]
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect();
+    let version = version.filter(|v| !v.is_empty())?;
+    Some((product, version))
+}
+
+/// Vendor config roots: JetBrains IDEs (including Toolbox installs, which
+/// share them) and Google for Android Studio.
+fn config_roots() -> Vec<(&'static str, PathBuf)> {
+    let base = if cfg!(target_os = "macos") {
+        dirs::home_dir().map(|h| h.join("Library").join("Application Support"))
+    } else {
+        // %APPDATA% on Windows, $XDG_CONFIG_HOME or ~/.config elsewhere.
+        dirs::config_dir()
+    };
+    match base {
+        Some(base) => vec![
+            ("JetBrains", base.join("JetBrains")),
+            ("Google", base.join("Google")),
+        ],
+        None => Vec::new(),
+    }
+}
+
+/// Lists every IDE config directory, newest version of each product first.
+fn ide_config_dirs() -> Vec<IdeConfigDir> {
+    let mut found = Vec::new();
+    for (vendor, root) in config_roots() {
+        let Ok(entries) = std::fs::read_dir(&root) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if !entry.path().is_dir() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            if let Some((product, version)) = parse_dir_name(&name) {
+                found.push(IdeConfigDir {
+                    id: format!("{}/{}", vendor, name),
+                    product,
+                    version,
+                    dir: entry.path(),
+                });
             }
         }
+    }
+    found.sort_by(|a, b| match a.product.cmp(b.product) {
+        Ordering::Equal => b.version.cmp(&a.version),
+        other => other,
+    });
+    found
+}
+
+/// Install locations used when no config directory exists yet.
+fn install_markers() -> Vec<PathBuf> {
+    let mut markers: Vec<PathBuf> = [
+        "/Applications/IntelliJ IDEA.app",
+        "/Applications/IntelliJ IDEA CE.app",
+        "/Applications/WebStorm.app",
+        "/Applications/PyCharm.app",
+        "/Applications/PyCharm CE.app",
+        "/Applications/GoLand.app",
+        "/Applications/RustRover.app",
+        "/Applications/CLion.app",
+        "/Applications/Rider.app",
+        "/Applications/PhpStorm.app",
+        "/Applications/DataGrip.app",
+        "/Applications/RubyMine.app",
+        "/Applications/Android Studio.app",
+    ]
+    .iter()
+    .map(PathBuf::from)
+    .collect();
+
+    // Toolbox keeps its managed IDEs under its own data directory.
+    if let Some(home) = dirs::home_dir() {
+        markers.push(home.join("Library/Application Support/JetBrains/Toolbox/apps"));
+        markers.push(home.join(".local/share/JetBrains/Toolbox/apps"));
+    }
+    if let Some(local) = dirs::data_local_dir() {
+        markers.push(local.join("JetBrains").join("Toolbox").join("apps"));
+    }
+    markers
+}
 
-        latest_path
+impl JetBrainsAdapter {
+    fn find_target(target_id: &str) -> Result<IdeConfigDir> {
+        ide_config_dirs()
+            .into_iter()
+            .find(|d| d.id == target_id)
+            .ok_or_else(|| {
+                ConductorError::ClientPathUnknown(format!("jetbrains ({})", target_id)).into()
+            })
+    }
+
+    fn read_path(path: &Path) -> Result<Vec<McpServerConfig>> {
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(path)?;
+        normalizer::parse_client_config("jetbrains", &content)
     }
 }
 
@@ -70,45 +182,73 @@ impl ClientAdapter for JetBrainsAdapter {
     }
 
     fn detect(&self) -> bool {
-        if let Some(path) = Self::get_config_path() {
-            return path.exists();
-        }
-        // Check if any JetBrains IDE is installed
-        let apps = [
-            "/Applications/IntelliJ IDEA.app",
-            "/Applications/WebStorm.app",
-            "/Applications/PyCharm.app",
-            "/Applications/GoLand.app",
-            "/Applications/RustRover.app",
-            "/Applications/CLion.app",
-            "/Applications/Rider.app",
-            "/Applications/PhpStorm.app",
-        ];
-        apps.iter().any(|app| std::path::Path::new(app).exists())
+        !ide_config_dirs().is_empty() || install_markers().iter().any(|p| p.exists())
     }
 
+    /// The newest IDE's config, for callers that only show one path.
     fn config_path(&self) -> Option<PathBuf> {
-        Self::get_config_path()
+        let dirs = ide_config_dirs();
+        dirs.iter()
+            .max_by(|a, b| a.version.cmp(&b.version))
+            .map(|d| d.mcp_path())
     }
 
+    /// Servers from every IDE, de-duplicated by name.
     fn read_servers(&self) -> Result<Vec<McpServerConfig>> {
-        let path = Self::get_config_path()
-            .ok_or_else(|| anyhow::anyhow!("Cannot determine config path for JetBrains IDE"))?;
-        if !path.exists() {
-            return Ok(Vec::new());
+        let mut seen = std::collections::HashSet::new();
+        let mut servers = Vec::new();
+        for dir in ide_config_dirs() {
+            for server in Self::read_path(&dir.mcp_path())? {
+                if seen.insert(server.name.to_lowercase()) {
+                    servers.push(server);
+                }
+            }
         }
-        let content = std::fs::read_to_string(&path)?;
-        normalizer::parse_client_config("jetbrains", &content)
+        Ok(servers)
     }
 
+    /// Writes to every IDE. Sync goes through `write_target` instead so each
+    /// IDE keeps its own orphan tracking; this is the fallback for callers
+    /// without target awareness.
     fn write_servers(
         &self,
         servers: &[McpServerConfig],
+        _existing_content: Option<&str>,
+        previously_synced_names: &[String],
+        fallback: &FallbackSettings,
+    ) -> Result<()> {
+        for dir in ide_config_dirs() {
+            self.write_target(&dir.id, servers, None, previously_synced_names, fallback)?;
+        }
+        Ok(())
+    }
+
+    fn targets(&self) -> Vec<SyncTarget> {
+        ide_config_dirs()
+            .into_iter()
+            .map(|d| SyncTarget {
+                label: d.label(),
+                exists: d.mcp_path().exists(),
+                config_path: d.mcp_path(),
+                selected: true,
+                id: d.id,
+            })
+            .collect()
+    }
+
+    fn read_target(&self, target_id: &str) -> Result<Vec<McpServerConfig>> {
+        Self::read_path(&Self::find_target(target_id)?.mcp_path())
+    }
+
+    fn write_target(
+        &self,
+        target_id: &str,
+        servers: &[McpServerConfig],
         existing_content: Option<&str>,
         previously_synced_names: &[String],
+        fallback: &FallbackSettings,
     ) -> Result<()> {
-        let path = Self::get_config_path()
-            .ok_or_else(|| anyhow::anyhow!("Cannot determine config path for JetBrains IDE"))?;
+        let path = Self::find_target(target_id)?.mcp_path();
 
         // Read existing servers to preserve client-specific ones
         let conductor_names_lower: std::collections::HashSet<String> =
@@ -145,7 +285,13 @@ impl ClientAdapter for JetBrainsAdapter {
         // Then add Conductor servers
         all_servers.extend(servers.iter().cloned());
 
-        let output = serializer::serialize_to_client_format("jetbrains", &all_servers, None, previously_synced_names)?;
+        let output = serializer::serialize_to_client_format(
+            "jetbrains",
+            &all_servers,
+            None,
+            previously_synced_names,
+            fallback,
+        )?;
 
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
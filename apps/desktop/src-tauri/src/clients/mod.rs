@@ -1,13 +1,11 @@
-pub mod antigravity;
+pub mod capabilities;
 pub mod claude_code;
-pub mod claude_desktop;
-pub mod codex;
-pub mod cursor;
+pub mod generic;
 pub mod jetbrains;
+pub mod spec;
 pub mod vscode;
-pub mod windsurf;
-pub mod zed;
 
+use crate::config::serializer::FallbackSettings;
 use crate::config::McpServerConfig;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -38,12 +36,119 @@ pub trait ClientAdapter: Send + Sync {
     /// optionally merging with existing content.
     /// `previously_synced_names` is the cumulative set of all server names
     /// Conductor has ever synced to this client — used to remove orphans.
+    /// `fallback` is how remote servers the client can't read natively are
+    /// reached.
     fn write_servers(
         &self,
         servers: &[McpServerConfig],
         existing_content: Option<&str>,
         previously_synced_names: &[String],
+        fallback: &FallbackSettings,
     ) -> Result<()>;
+
+    /// Installed version, if it can be determined. Drives which remote
+    /// transports are written natively (see `capabilities`).
+    fn version(&self) -> Option<String> {
+        None
+    }
+
+    /// Remote transports this client reads natively, at its installed version.
+    fn remote_capabilities(&self) -> capabilities::RemoteCapabilities {
+        capabilities::for_client(self.id())
+    }
+
+    /// How this client hides tools through its own config. Filtered servers
+    /// of clients without support are wrapped in `conductor filter`.
+    fn native_tool_filter(&self) -> NativeToolFilter {
+        NativeToolFilter::Unsupported
+    }
+
+    /// Every config file this client can be synced to. Most clients have a
+    /// single file; JetBrains has one per installed IDE.
+    fn targets(&self) -> Vec<SyncTarget> {
+        self.config_path()
+            .map(|path| {
+                vec![SyncTarget {
+                    id: DEFAULT_TARGET.to_string(),
+                    label: self.display_name().to_string(),
+                    exists: path.exists(),
+                    config_path: path,
+                    selected: true,
+                }]
+            })
+            .unwrap_or_default()
+    }
+
+    /// The project directory a target belongs to, if it's scoped to one.
+    /// Project-scoped servers (see `McpServerConfig::project`) are only
+    /// written to their own project's targets.
+    fn target_project(&self, _target_id: &str) -> Option<String> {
+        None
+    }
+
+    /// Whether a target's file is meant to be committed with its project.
+    /// Secrets go to such targets as `${NAME}` references the client expands
+    /// from its environment, never as values.
+    fn is_shared_target(&self, _target_id: &str) -> bool {
+        false
+    }
+
+    /// Read servers from one target returned by `targets()`.
+    fn read_target(&self, _target_id: &str) -> Result<Vec<McpServerConfig>> {
+        self.read_servers()
+    }
+
+    /// Write servers to one target returned by `targets()`.
+    fn write_target(
+        &self,
+        _target_id: &str,
+        servers: &[McpServerConfig],
+        existing_content: Option<&str>,
+        previously_synced_names: &[String],
+        fallback: &FallbackSettings,
+    ) -> Result<()> {
+        self.write_servers(servers, existing_content, previously_synced_names, fallback)
+    }
+}
+
+/// A client's own way of hiding a server's tools.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NativeToolFilter {
+    Unsupported,
+    /// Codex `enabled_tools` / `disabled_tools`.
+    EnabledDisabledLists,
+    /// An entry field listing hidden tools, e.g. Windsurf `disabledTools`.
+    DisabledField(String),
+}
+
+/// Target ID used by clients with a single config file.
+pub const DEFAULT_TARGET: &str = "default";
+
+/// One config file a client reads MCP servers from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncTarget {
+    /// Stable identifier, unique within the client (e.g. "PyCharm2024.3").
+    pub id: String,
+    pub label: String,
+    pub config_path: PathBuf,
+    /// Whether the config file exists yet.
+    pub exists: bool,
+    /// Whether sync writes to this target. Adapters set their default;
+    /// `apply_target_selection` replaces it with the user's choice.
+    #[serde(default)]
+    pub selected: bool,
+}
+
+/// Marks the targets the user picked as selected. An empty selection keeps
+/// the adapter's defaults.
+pub fn apply_target_selection(targets: &mut [SyncTarget], selection: &[String]) {
+    if selection.is_empty() {
+        return;
+    }
+    for target in targets {
+        target.selected = selection.contains(&target.id);
+    }
 }
 
 /// Information about a detected client.
@@ -70,24 +175,79 @@ pub struct ClientDetection {
     pub last_synced_at: Option<String>,
     #[serde(default)]
     pub config_updated_at: Option<String>,
+    #[serde(default)]
+    pub targets: Vec<SyncTarget>,
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Remote transports written natively; the rest use the remote fallback.
+    pub remote: capabilities::RemoteCapabilities,
 }
 
-/// Returns all available client adapters.
+// Built-in clients described declaratively, in the same format users put
+// in `~/.conductor/adapters`. VS Code, Claude Code and JetBrains stay
+// hand-written: their targets aren't paths a spec can list but are found at
+// runtime (VS Code profiles and remote servers, the projects in
+// `~/.claude.json`, one config directory per installed IDE).
+const CLAUDE_DESKTOP_SPEC: &str = include_str!("specs/claude-desktop.toml");
+const CURSOR_SPEC: &str = include_str!("specs/cursor.toml");
+const WINDSURF_SPEC: &str = include_str!("specs/windsurf.toml");
+const ZED_SPEC: &str = include_str!("specs/zed.toml");
+const CODEX_SPEC: &str = include_str!("specs/codex.toml");
+const ANTIGRAVITY_SPEC: &str = include_str!("specs/antigravity.toml");
+const CLINE_SPEC: &str = include_str!("specs/cline.toml");
+const ROO_CODE_SPEC: &str = include_str!("specs/roo-code.toml");
+const CONTINUE_SPEC: &str = include_str!("specs/continue.toml");
+const GEMINI_SPEC: &str = include_str!("specs/gemini.toml");
+const GOOSE_SPEC: &str = include_str!("specs/goose.toml");
+const OPENCODE_SPEC: &str = include_str!("specs/opencode.toml");
+const AMAZON_Q_SPEC: &str = include_str!("specs/amazon-q.toml");
+
+fn builtin_spec(source: &str) -> Box<dyn ClientAdapter> {
+    let spec = toml_edit::de::from_str(source).expect("built-in adapter spec must be valid");
+    Box::new(generic::SpecAdapter::new(spec))
+}
+
+/// Returns all available client adapters: built-ins first, then any
+/// user-defined specs from `~/.conductor/adapters`.
 pub fn get_all_adapters() -> Vec<Box<dyn ClientAdapter>> {
-    vec![
-        Box::new(claude_desktop::ClaudeDesktopAdapter),
-        Box::new(cursor::CursorAdapter),
+    let mut adapters: Vec<Box<dyn ClientAdapter>> = vec![
+        builtin_spec(CLAUDE_DESKTOP_SPEC),
+        builtin_spec(CURSOR_SPEC),
         Box::new(vscode::VSCodeAdapter),
         Box::new(claude_code::ClaudeCodeAdapter),
-        Box::new(windsurf::WindsurfAdapter),
-        Box::new(zed::ZedAdapter),
+        builtin_spec(WINDSURF_SPEC),
+        builtin_spec(ZED_SPEC),
         Box::new(jetbrains::JetBrainsAdapter),
-        Box::new(codex::CodexAdapter),
-        Box::new(antigravity::AntigravityAdapter),
-    ]
+        builtin_spec(CODEX_SPEC),
+        builtin_spec(ANTIGRAVITY_SPEC),
+        builtin_spec(CLINE_SPEC),
+        builtin_spec(ROO_CODE_SPEC),
+        builtin_spec(CONTINUE_SPEC),
+        builtin_spec(GEMINI_SPEC),
+        builtin_spec(GOOSE_SPEC),
+        builtin_spec(OPENCODE_SPEC),
+        builtin_spec(AMAZON_Q_SPEC),
+    ];
+
+    let builtin_ids: Vec<String> = adapters.iter().map(|a| a.id().to_string()).collect();
+    let builtin_ids: Vec<&str> = builtin_ids.iter().map(|s| s.as_str()).collect();
+    for spec in spec::load_user_specs(&builtin_ids) {
+        adapters.push(Box::new(generic::SpecAdapter::new(spec)));
+    }
+
+    adapters
 }
 
 /// Find a specific adapter by client ID.
 pub fn get_adapter(client_id: &str) -> Option<Box<dyn ClientAdapter>> {
     get_all_adapters().into_iter().find(|a| a.id() == client_id)
 }
+
+/// Whether an executable is reachable on `PATH`.
+pub(crate) fn which_exists(cmd: &str) -> bool {
+    std::process::Command::new("which")
+        .arg(cmd)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
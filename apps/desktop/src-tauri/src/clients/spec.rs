@@ -0,0 +1,614 @@
+use crate::clients::vscode::named_profiles;
+use crate::clients::DEFAULT_TARGET;
+use crate::errors::ConductorError;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// Declarative description of an MCP client, loaded from
+/// `~/.conductor/adapters/*.toml` or `*.json` and executed by `SpecAdapter`.
+///
+/// ```toml
+/// id = "my-editor"
+/// display_name = "My Editor"
+/// format = "json"
+/// servers_pointer = "/mcpServers"
+///
+/// [paths]
+/// default = ["~/.my-editor/mcp.json"]
+/// windows = ["${APPDATA}/MyEditor/mcp.json"]
+///
+/// [transport]
+/// headers = true
+///
+/// [detect]
+/// paths = ["/Applications/My Editor.app"]
+/// commands = ["my-editor"]
+///
+/// [[targets]]
+/// id = "workspace"
+/// label = "Workspace"
+/// paths = { default = ["~/work/.my-editor/mcp.json"] }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AdapterSpec {
+    pub id: String,
+    pub display_name: String,
+    /// Icon identifier for the frontend. Defaults to `id`.
+    #[serde(default)]
+    pub icon: Option<String>,
+    pub format: SpecFormat,
+    pub paths: PathCandidates,
+    /// JSON pointer (RFC 6901) to the object holding servers keyed by name.
+    /// For TOML it addresses nested tables; for XML, nested elements.
+    #[serde(default = "default_servers_pointer")]
+    pub servers_pointer: String,
+    #[serde(default)]
+    pub fields: FieldMap,
+    #[serde(default)]
+    pub transport: TransportSupport,
+    #[serde(default)]
+    pub detect: DetectHints,
+    /// Element and attribute names, only used when `format = "xml"`.
+    #[serde(default)]
+    pub xml: XmlLayout,
+    /// Client-specific keys (e.g. `alwaysAllow`, `timeout`) carried over from
+    /// the existing entry when Conductor rewrites a server it manages, unless
+    /// Conductor sets the key itself.
+    #[serde(default)]
+    pub preserve_fields: Vec<String>,
+    /// Per-server lists of tools the client runs without asking (Cline's
+    /// `alwaysAllow`). Tools a tool filter hides are removed from them.
+    #[serde(default)]
+    pub tool_approval_fields: Vec<String>,
+    /// Built-in entry layout (see `CODECS`) used instead of `servers_pointer`,
+    /// `fields`, `xml`, `preserve_fields` and `tool_approval_fields`, for
+    /// clients whose entries are more than renamed keys.
+    #[serde(default)]
+    pub codec: Option<String>,
+    /// Config files synced in addition to `paths`, each as its own target.
+    #[serde(default)]
+    pub targets: Vec<TargetSpec>,
+    /// User data directory of a VS Code fork (the one holding `settings.json`).
+    /// Each named profile in it is an extra target in VS Code's format.
+    #[serde(default)]
+    pub profiles: PathCandidates,
+}
+
+/// Built-in entry layouts a spec can name in `codec`.
+pub const CODECS: &[&str] = &["codex", "continue", "gemini", "goose", "opencode", "vscode", "zed"];
+
+/// An extra config file of a client, e.g. a second scope.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TargetSpec {
+    /// Stable identifier, unique within the spec.
+    pub id: String,
+    pub label: String,
+    pub paths: PathCandidates,
+    /// Defaults to the spec's own `servers_pointer`.
+    #[serde(default)]
+    pub servers_pointer: Option<String>,
+    /// Defaults to the spec's own `codec`.
+    #[serde(default)]
+    pub codec: Option<String>,
+    /// Whether sync writes here unless the user picks otherwise.
+    #[serde(default)]
+    pub selected: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpecFormat {
+    Json,
+    Jsonc,
+    Toml,
+    Yaml,
+    Xml,
+}
+
+impl SpecFormat {
+    pub fn label(self) -> &'static str {
+        match self {
+            SpecFormat::Json => "JSON",
+            SpecFormat::Jsonc => "JSONC",
+            SpecFormat::Toml => "TOML",
+            SpecFormat::Yaml => "YAML",
+            SpecFormat::Xml => "XML",
+        }
+    }
+}
+
+/// Config path candidates, tried in order. The first one that exists wins;
+/// if none exist the first candidate is used for writing.
+/// The per-OS list replaces `default` when it is non-empty.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PathCandidates {
+    #[serde(default)]
+    pub default: Vec<String>,
+    #[serde(default)]
+    pub macos: Vec<String>,
+    #[serde(default)]
+    pub linux: Vec<String>,
+    #[serde(default)]
+    pub windows: Vec<String>,
+}
+
+impl PathCandidates {
+    pub fn for_current_os(&self) -> &[String] {
+        let os_specific = if cfg!(target_os = "macos") {
+            &self.macos
+        } else if cfg!(target_os = "windows") {
+            &self.windows
+        } else {
+            &self.linux
+        };
+        if os_specific.is_empty() {
+            &self.default
+        } else {
+            os_specific
+        }
+    }
+}
+
+/// Key names inside a single server entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FieldMap {
+    pub command: String,
+    pub args: String,
+    pub env: String,
+    pub url: String,
+    /// Key for HTTP headers; only written when `transport.headers` is set.
+    pub headers: String,
+    /// Key holding the transport type, if the client records one.
+    pub transport: Option<String>,
+    /// Key set to `true` when a server is disabled.
+    pub disabled: Option<String>,
+    /// Key set to `false` when a server is disabled. Used instead of `disabled`.
+    pub enabled: Option<String>,
+    /// Key holding the names of tools the client hides, if it has one.
+    pub disabled_tools: Option<String>,
+}
+
+impl Default for FieldMap {
+    fn default() -> Self {
+        Self {
+            command: "command".to_string(),
+            args: "args".to_string(),
+            env: "env".to_string(),
+            url: "url".to_string(),
+            headers: "headers".to_string(),
+            transport: Some("transport".to_string()),
+            disabled: Some("disabled".to_string()),
+            enabled: None,
+            disabled_tools: None,
+        }
+    }
+}
+
+/// What the client can express natively for remote servers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TransportSupport {
+    /// Client accepts `url` entries. When false, remote servers are written
+    /// as stdio servers running the remote fallback (Conductor's bridge by default).
+    pub url: bool,
+    /// Client accepts a headers map. When false, remote servers with an
+    /// OAuth token go through the remote fallback to carry the header.
+    pub headers: bool,
+    /// Values written to `fields.transport` for each transport. `None` omits the key.
+    pub stdio_value: Option<String>,
+    pub sse_value: Option<String>,
+    pub streamable_http_value: Option<String>,
+    /// First client version that reads remote entries. Older versions get
+    /// the remote fallback.
+    pub min_version: Option<String>,
+}
+
+impl Default for TransportSupport {
+    fn default() -> Self {
+        Self {
+            url: true,
+            headers: false,
+            stdio_value: None,
+            sse_value: None,
+            streamable_http_value: Some("streamable-http".to_string()),
+            min_version: None,
+        }
+    }
+}
+
+/// Extra signals that the client is installed even before it has a config file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DetectHints {
+    /// Files or directories (e.g. an app bundle) whose existence means installed.
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// Executables looked up on `PATH`.
+    #[serde(default)]
+    pub commands: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct XmlLayout {
+    pub server_element: String,
+    pub name_attribute: String,
+    pub enabled_attribute: Option<String>,
+    pub env_container: String,
+    pub env_element: String,
+    /// Attributes written on the outermost element, e.g. `version = "1"`.
+    pub root_attributes: BTreeMap<String, String>,
+}
+
+impl Default for XmlLayout {
+    fn default() -> Self {
+        Self {
+            server_element: "serverConfiguration".to_string(),
+            name_attribute: "name".to_string(),
+            enabled_attribute: Some("enabled".to_string()),
+            env_container: "envs".to_string(),
+            env_element: "env".to_string(),
+            root_attributes: BTreeMap::new(),
+        }
+    }
+}
+
+fn default_servers_pointer() -> String {
+    "/mcpServers".to_string()
+}
+
+impl AdapterSpec {
+    pub fn icon(&self) -> &str {
+        self.icon.as_deref().unwrap_or(&self.id)
+    }
+
+    /// Pointer split into unescaped segments. `""` and `"/"` address the root.
+    pub fn pointer_segments(&self) -> Vec<String> {
+        self.servers_pointer
+            .split('/')
+            .skip(1)
+            .filter(|s| !s.is_empty())
+            .map(|s| s.replace("~1", "/").replace("~0", "~"))
+            .collect()
+    }
+
+    /// The codec used for the file at `path`. `vscode` covers both of VS Code's
+    /// files: the dedicated `mcp.json` and `mcp.servers` in `settings.json`.
+    pub fn codec_for(&self, path: &Path) -> Option<&str> {
+        let codec = self.codec.as_deref()?;
+        if codec == "vscode" && path.file_name().is_some_and(|f| f == "mcp.json") {
+            return Some("vscode-mcp");
+        }
+        Some(codec)
+    }
+
+    /// This spec with a target's paths, pointer and codec in place of its own.
+    pub fn for_target(&self, target: &TargetSpec) -> AdapterSpec {
+        AdapterSpec {
+            paths: target.paths.clone(),
+            servers_pointer: target
+                .servers_pointer
+                .clone()
+                .unwrap_or_else(|| self.servers_pointer.clone()),
+            codec: target.codec.clone().or_else(|| self.codec.clone()),
+            targets: Vec::new(),
+            profiles: PathCandidates::default(),
+            ..self.clone()
+        }
+    }
+
+    /// `targets`, followed by one target per named profile in `profiles`.
+    pub fn all_targets(&self) -> Vec<TargetSpec> {
+        let mut targets = self.targets.clone();
+        let user = self
+            .profiles
+            .for_current_os()
+            .iter()
+            .filter_map(|p| expand_path(p))
+            .find(|p| p.is_dir());
+        for profile in user.map(|u| named_profiles(&u)).unwrap_or_default() {
+            let file = |name: &str| profile.dir.join(name).to_string_lossy().to_string();
+            targets.push(TargetSpec {
+                id: format!("profiles/{}", profile.location),
+                label: format!("Profile · {}", profile.name),
+                paths: PathCandidates {
+                    default: vec![file("mcp.json"), file("settings.json")],
+                    ..Default::default()
+                },
+                servers_pointer: None,
+                codec: Some("vscode".to_string()),
+                selected: false,
+            });
+        }
+        targets
+    }
+
+    /// Resolves the config path: first existing candidate, else the first one.
+    pub fn resolve_config_path(&self) -> Option<PathBuf> {
+        let candidates: Vec<PathBuf> = self
+            .paths
+            .for_current_os()
+            .iter()
+            .filter_map(|p| expand_path(p))
+            .collect();
+        candidates
+            .iter()
+            .find(|p| p.exists())
+            .or_else(|| candidates.first())
+            .cloned()
+    }
+
+    fn validate(&self, builtin_ids: &[&str]) -> Result<()> {
+        let invalid = |msg: String| ConductorError::InvalidInput(msg);
+        if self.id.is_empty()
+            || !self
+                .id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        {
+            return Err(invalid(format!(
+                "adapter id '{}' must be non-empty lowercase letters, digits and '-'",
+                self.id
+            ))
+            .into());
+        }
+        if builtin_ids.contains(&self.id.as_str()) {
+            return Err(invalid(format!(
+                "adapter id '{}' is already used by a built-in client",
+                self.id
+            ))
+            .into());
+        }
+        if self.paths.for_current_os().is_empty() {
+            return Err(invalid(format!(
+                "adapter '{}' has no config path for this OS",
+                self.id
+            ))
+            .into());
+        }
+        let mut target_ids = vec![DEFAULT_TARGET];
+        for target in &self.targets {
+            if target.id.is_empty() || target_ids.contains(&target.id.as_str()) {
+                return Err(invalid(format!(
+                    "adapter '{}': target id '{}' must be non-empty, unique and not '{}'",
+                    self.id, target.id, DEFAULT_TARGET
+                ))
+                .into());
+            }
+            target_ids.push(&target.id);
+            self.for_target(target).validate_layout()?;
+        }
+        self.validate_layout()
+    }
+
+    /// Checks the parts a target can override: pointer and codec.
+    fn validate_layout(&self) -> Result<()> {
+        let invalid = |msg: String| ConductorError::InvalidInput(msg);
+        if let Some(codec) = &self.codec {
+            if !CODECS.contains(&codec.as_str()) {
+                return Err(invalid(format!(
+                    "adapter '{}': unknown codec '{}' (expected one of {})",
+                    self.id,
+                    codec,
+                    CODECS.join(", ")
+                ))
+                .into());
+            }
+            return Ok(());
+        }
+        if !self.servers_pointer.is_empty() && !self.servers_pointer.starts_with('/') {
+            return Err(invalid(format!(
+                "adapter '{}': servers_pointer must start with '/'",
+                self.id
+            ))
+            .into());
+        }
+        if self.format == SpecFormat::Xml && self.pointer_segments().is_empty() {
+            return Err(invalid(format!(
+                "adapter '{}': XML specs need at least one element in servers_pointer",
+                self.id
+            ))
+            .into());
+        }
+        // YAML is edited in place, which works on one top-level mapping.
+        if self.format == SpecFormat::Yaml && self.pointer_segments().len() != 1 {
+            return Err(invalid(format!(
+                "adapter '{}': YAML specs need a servers_pointer with exactly one key",
+                self.id
+            ))
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// Parses a spec from TOML or JSON, chosen by file extension.
+pub fn parse_spec(path: &Path, content: &str) -> Result<AdapterSpec> {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => serde_json::from_str(content)
+            .with_context(|| format!("Invalid adapter spec {}", path.display())),
+        _ => toml_edit::de::from_str(content)
+            .with_context(|| format!("Invalid adapter spec {}", path.display())),
+    }
+}
+
+/// Directory holding user-defined adapter specs.
+pub fn adapters_dir() -> Option<PathBuf> {
+    Some(dirs::home_dir()?.join(".conductor").join("adapters"))
+}
+
+/// Spec files found in the adapters dir, with their modification times.
+/// Used as the cache key so edits, additions and removals are picked up.
+type SpecFiles = Vec<(PathBuf, Option<SystemTime>)>;
+
+static USER_SPECS: Mutex<Option<(SpecFiles, Vec<AdapterSpec>)>> = Mutex::new(None);
+
+fn spec_files(dir: &Path) -> SpecFiles {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut files: SpecFiles = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            matches!(
+                p.extension().and_then(|e| e.to_str()),
+                Some("toml") | Some("json")
+            )
+        })
+        .map(|p| {
+            let modified = std::fs::metadata(&p).and_then(|m| m.modified()).ok();
+            (p, modified)
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Loads every valid spec in `~/.conductor/adapters`, sorted by file name.
+/// Invalid files are skipped with a warning so one bad spec doesn't hide
+/// every other client.
+///
+/// Parsed specs are cached; the directory is only re-read when a spec file
+/// is added, removed or modified.
+pub fn load_user_specs(builtin_ids: &[&str]) -> Vec<AdapterSpec> {
+    let Some(dir) = adapters_dir() else {
+        return Vec::new();
+    };
+    let files = spec_files(&dir);
+
+    let mut cache = USER_SPECS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((cached_files, specs)) = cache.as_ref() {
+        if *cached_files == files {
+            return specs.clone();
+        }
+    }
+
+    let specs = parse_spec_files(&files, builtin_ids);
+    *cache = Some((files, specs.clone()));
+    specs
+}
+
+fn parse_spec_files(files: &SpecFiles, builtin_ids: &[&str]) -> Vec<AdapterSpec> {
+    let mut specs: Vec<AdapterSpec> = Vec::new();
+    for (file, _) in files {
+        let loaded = std::fs::read_to_string(file)
+            .map_err(anyhow::Error::from)
+            .and_then(|content| parse_spec(file, &content))
+            .and_then(|spec| spec.validate(builtin_ids).map(|_| spec));
+        match loaded {
+            Ok(spec) if specs.iter().any(|s| s.id == spec.id) => {
+                eprintln!(
+                    "Warning: skipping adapter spec {}: duplicate id '{}'",
+                    file.display(),
+                    spec.id
+                );
+            }
+            Ok(spec) => specs.push(spec),
+            Err(e) => eprintln!("Warning: skipping adapter spec {}: {:#}", file.display(), e),
+        }
+    }
+    specs
+}
+
+/// Expands `~` and `${VAR}` in a spec path. Returns `None` if a referenced
+/// variable is unset, so the candidate is skipped rather than misresolved.
+pub fn expand_path(raw: &str) -> Option<PathBuf> {
+    let mut expanded = String::new();
+    let mut rest = raw;
+
+    if rest == "~" || rest.starts_with("~/") {
+        expanded.push_str(&dirs::home_dir()?.to_string_lossy());
+        rest = &rest[1..];
+    }
+
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after.find('}')?;
+        let name = &after[..end];
+        let value = match name {
+            "HOME" => dirs::home_dir().map(|p| p.to_string_lossy().to_string()),
+            _ => std::env::var(name).ok(),
+        }?;
+        expanded.push_str(&value);
+        rest = &after[end + 1..];
+    }
+    expanded.push_str(rest);
+
+    Some(PathBuf::from(expanded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clients::*;
+
+    fn spec(source: &str) -> AdapterSpec {
+        parse_spec(Path::new("spec.toml"), source).unwrap()
+    }
+
+    #[test]
+    fn builtin_specs_are_valid() {
+        for source in [
+            CLAUDE_DESKTOP_SPEC,
+            CURSOR_SPEC,
+            WINDSURF_SPEC,
+            ZED_SPEC,
+            CODEX_SPEC,
+            ANTIGRAVITY_SPEC,
+            CLINE_SPEC,
+            ROO_CODE_SPEC,
+            CONTINUE_SPEC,
+            GEMINI_SPEC,
+            GOOSE_SPEC,
+            OPENCODE_SPEC,
+            AMAZON_Q_SPEC,
+        ] {
+            let spec = spec(source);
+            spec.validate(&[]).unwrap_or_else(|e| panic!("{}: {:#}", spec.id, e));
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_codecs_and_clashing_targets() {
+        let base = "id = \"x\"\ndisplay_name = \"X\"\nformat = \"json\"\n\
+                    [paths]\ndefault = [\"~/x.json\"]\n";
+        assert!(spec(base).validate(&[]).is_ok());
+
+        let codec = format!("codec = \"vscode-mcp\"\n{}", base);
+        assert!(spec(&codec).validate(&[]).is_err());
+
+        for id in ["default", ""] {
+            let target = format!(
+                "{}[[targets]]\nid = \"{}\"\nlabel = \"T\"\n\
+                 paths = {{ default = [\"~/t.json\"] }}\n",
+                base, id
+            );
+            assert!(spec(&target).validate(&[]).is_err(), "target id {:?}", id);
+        }
+
+        let nested_yaml = base
+            .replace("\"json\"", "\"yaml\"")
+            .replace("[paths]", "servers_pointer = \"/a/b\"\n[paths]");
+        assert!(spec(&nested_yaml).validate(&[]).is_err());
+    }
+
+    #[test]
+    fn vscode_codec_follows_the_file_name() {
+        let spec = spec(
+            "id = \"fork\"\ndisplay_name = \"Fork\"\nformat = \"jsonc\"\ncodec = \"vscode\"\n\
+             [paths]\ndefault = [\"~/User/mcp.json\"]\n",
+        );
+        assert_eq!(spec.codec_for(Path::new("/User/mcp.json")), Some("vscode-mcp"));
+        assert_eq!(spec.codec_for(Path::new("/User/settings.json")), Some("vscode"));
+    }
+}
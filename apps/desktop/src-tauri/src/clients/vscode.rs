@@ -1,44 +1,234 @@
-use crate::clients::ClientAdapter;
+use crate::clients::{capabilities, which_exists, ClientAdapter, SyncTarget};
 use crate::config::McpServerConfig;
-use crate::config::{backup, normalizer, serializer};
+use crate::config::{self, backup, normalizer, serializer};
+use crate::config::serializer::FallbackSettings;
+use crate::errors::ConductorError;
 use anyhow::Result;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub struct VSCodeAdapter;
 
-impl VSCodeAdapter {
-    fn get_config_path() -> Option<PathBuf> {
-        let home = dirs::home_dir()?;
-        // VS Code uses a dedicated mcp.json file (not settings.json)
-        let mcp_json = home
-            .join("Library")
-            .join("Application Support")
-            .join("Code")
-            .join("User")
-            .join("mcp.json");
+/// Desktop builds: (target ID, label, directory under the user data root).
+const VARIANTS: &[(&str, &str, &str)] = &[
+    ("code", "VS Code", "Code"),
+    ("code-insiders", "VS Code Insiders", "Code - Insiders"),
+    ("vscodium", "VSCodium", "VSCodium"),
+];
+
+/// Remote server installs: under the local home directory, when Conductor
+/// itself runs on the SSH/WSL/container host, and on Windows under each WSL
+/// distribution's home directories (see `wsl_homes`). Servers on SSH hosts
+/// VS Code connects to aren't reachable from here.
+const REMOTE_SERVERS: &[(&str, &str)] = &[
+    (".vscode-server", "VS Code Server"),
+    (".vscode-server-insiders", "VS Code Server Insiders"),
+    (".vscodium-server", "VSCodium Server"),
+];
+
+/// One VS Code `User` directory: a build's default profile, a named profile
+/// or a remote server.
+struct UserDir {
+    id: String,
+    label: String,
+    dir: PathBuf,
+}
+
+impl UserDir {
+    /// VS Code uses a dedicated mcp.json file (not settings.json), but older
+    /// setups keep servers under `mcp.servers` in settings.json.
+    fn config_path(&self) -> PathBuf {
+        let mcp_json = self.dir.join("mcp.json");
         if mcp_json.exists() {
-            return Some(mcp_json);
+            return mcp_json;
         }
-        // Fallback to legacy settings.json location
-        let settings = home
-            .join("Library")
-            .join("Application Support")
-            .join("Code")
-            .join("User")
-            .join("settings.json");
+        let settings = self.dir.join("settings.json");
         if settings.exists() {
-            return Some(settings);
+            return settings;
         }
-        // Default to mcp.json
-        Some(mcp_json)
+        mcp_json
     }
+}
 
+/// `~/Library/Application Support` on macOS, `%APPDATA%` on Windows and
+/// `~/.config` elsewhere.
+fn user_data_root() -> Option<PathBuf> {
+    if cfg!(target_os = "macos") {
+        Some(
+            dirs::home_dir()?
+                .join("Library")
+                .join("Application Support"),
+        )
+    } else {
+        dirs::config_dir()
+    }
+}
+
+/// Profile names from `globalStorage/storage.json`, keyed by profile directory.
+fn profile_names(user_dir: &Path) -> Vec<(String, String)> {
+    let storage = user_dir.join("globalStorage").join("storage.json");
+    let Ok(content) = std::fs::read_to_string(storage) else {
+        return Vec::new();
+    };
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(&content) else {
+        return Vec::new();
+    };
+    value
+        .get("userDataProfiles")
+        .and_then(|p| p.as_array())
+        .map(|profiles| {
+            profiles
+                .iter()
+                .filter_map(|p| {
+                    let location = p.get("location")?.as_str()?;
+                    let name = p.get("name")?.as_str()?;
+                    Some((location.to_string(), name.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// A named profile: its directory under `<user>/profiles` and display name.
+pub(crate) struct NamedProfile {
+    pub location: String,
+    pub name: String,
+    pub dir: PathBuf,
+}
+
+/// Named profiles under `<user>/profiles`, sorted by name. Shared with VS Code
+/// forks described by a spec's `profiles` (see `AdapterSpec`).
+pub(crate) fn named_profiles(user: &Path) -> Vec<NamedProfile> {
+    let names = profile_names(user);
+    let mut profiles: Vec<NamedProfile> = std::fs::read_dir(user.join("profiles"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .map(|e| {
+            let location = e.file_name().to_string_lossy().to_string();
+            let name = names
+                .iter()
+                .find(|(loc, _)| *loc == location)
+                .map(|(_, name)| name.clone())
+                .unwrap_or_else(|| location.clone());
+            NamedProfile {
+                location,
+                name,
+                dir: e.path(),
+            }
+        })
+        .collect();
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+    profiles
+}
+
+/// Home directories inside running WSL distributions, reached through the
+/// `\\wsl$` share: (target ID prefix, label suffix, home). Empty off Windows.
+fn wsl_homes() -> Vec<(String, String, PathBuf)> {
+    if !cfg!(target_os = "windows") {
+        return Vec::new();
+    }
+    let mut homes = Vec::new();
+    for distro in std::fs::read_dir(r"\\wsl$").into_iter().flatten().flatten() {
+        let distro_name = distro.file_name().to_string_lossy().to_string();
+        for user in std::fs::read_dir(distro.path().join("home"))
+            .into_iter()
+            .flatten()
+            .flatten()
+        {
+            let user_name = user.file_name().to_string_lossy().to_string();
+            homes.push((
+                format!("wsl/{}/{}/", distro_name, user_name),
+                format!(" · WSL {} ({})", distro_name, user_name),
+                user.path(),
+            ));
+        }
+    }
+    homes.sort_by(|a, b| a.0.cmp(&b.0));
+    homes
+}
+
+/// Every installed build, its named profiles and any remote-server installs.
+/// Falls back to stable VS Code's default profile so there is always
+/// somewhere to write.
+fn user_dirs() -> Vec<UserDir> {
+    let mut found = Vec::new();
+
+    if let Some(root) = user_data_root() {
+        for (id, label, dir_name) in VARIANTS {
+            let user = root.join(dir_name).join("User");
+            if !user.is_dir() {
+                continue;
+            }
+            let profiles = named_profiles(&user).into_iter().map(|p| UserDir {
+                id: format!("{}/profiles/{}", id, p.location),
+                label: format!("{} · {}", label, p.name),
+                dir: p.dir,
+            });
+
+            found.push(UserDir {
+                id: id.to_string(),
+                label: label.to_string(),
+                dir: user,
+            });
+            found.extend(profiles);
+        }
+    }
+
+    let local_home = dirs::home_dir().map(|home| (String::new(), String::new(), home));
+    for (id_prefix, label_suffix, home) in local_home.into_iter().chain(wsl_homes()) {
+        for (dir_name, label) in REMOTE_SERVERS {
+            let server = home.join(dir_name);
+            if server.is_dir() {
+                found.push(UserDir {
+                    id: format!("remote/{}{}", id_prefix, dir_name.trim_start_matches('.')),
+                    label: format!("{}{}", label, label_suffix),
+                    dir: server.join("data").join("User"),
+                });
+            }
+        }
+    }
+
+    if found.is_empty() {
+        if let Some(root) = user_data_root() {
+            found.push(UserDir {
+                id: VARIANTS[0].0.to_string(),
+                label: VARIANTS[0].1.to_string(),
+                dir: root.join(VARIANTS[0].2).join("User"),
+            });
+        }
+    }
+    found
+}
+
+impl VSCodeAdapter {
     fn is_mcp_json(path: &std::path::Path) -> bool {
         path.file_name()
             .and_then(|f| f.to_str())
             .map(|f| f == "mcp.json")
             .unwrap_or(false)
     }
+
+    fn find_target(&self, target_id: &str) -> Result<UserDir> {
+        user_dirs()
+            .into_iter()
+            .find(|d| d.id == target_id)
+            .ok_or_else(|| {
+                ConductorError::ClientPathUnknown(format!("{} ({})", self.id(), target_id)).into()
+            })
+    }
+
+    fn read_path(path: &Path) -> Result<Vec<McpServerConfig>> {
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(path)?;
+        if Self::is_mcp_json(path) {
+            normalizer::parse_client_config("vscode-mcp", &content)
+        } else {
+            normalizer::parse_client_config("vscode", &content)
+        }
+    }
 }
 
 impl ClientAdapter for VSCodeAdapter {
@@ -55,30 +245,40 @@ impl ClientAdapter for VSCodeAdapter {
     }
 
     fn detect(&self) -> bool {
-        if let Some(path) = Self::get_config_path() {
-            if path.exists() {
-                return true;
-            }
+        if user_dirs().iter().any(|d| d.dir.exists()) {
+            return true;
         }
-        std::path::Path::new("/Applications/Visual Studio Code.app").exists()
+        [
+            "/Applications/Visual Studio Code.app",
+            "/Applications/Visual Studio Code - Insiders.app",
+            "/Applications/VSCodium.app",
+        ]
+        .iter()
+        .any(|app| std::path::Path::new(app).exists())
+            || which_exists("code")
     }
 
     fn config_path(&self) -> Option<PathBuf> {
-        Self::get_config_path()
+        user_dirs().first().map(|d| d.config_path())
+    }
+
+    fn version(&self) -> Option<String> {
+        capabilities::app_bundle_version(Path::new("/Applications/Visual Studio Code.app"))
+            .or_else(|| capabilities::command_version("code"))
     }
 
+    /// Servers from every build, profile and remote server, de-duplicated by name.
     fn read_servers(&self) -> Result<Vec<McpServerConfig>> {
-        let path = Self::get_config_path()
-            .ok_or_else(|| anyhow::anyhow!("Cannot determine config path for VS Code"))?;
-        if !path.exists() {
-            return Ok(Vec::new());
-        }
-        let content = std::fs::read_to_string(&path)?;
-        if Self::is_mcp_json(&path) {
-            normalizer::parse_client_config("vscode-mcp", &content)
-        } else {
-            normalizer::parse_client_config("vscode", &content)
+        let mut seen = std::collections::HashSet::new();
+        let mut servers = Vec::new();
+        for dir in user_dirs() {
+            for server in Self::read_path(&dir.config_path())? {
+                if seen.insert(server.name.to_lowercase()) {
+                    servers.push(server);
+                }
+            }
         }
+        Ok(servers)
     }
 
     fn write_servers(
@@ -86,14 +286,57 @@ impl ClientAdapter for VSCodeAdapter {
         servers: &[McpServerConfig],
         existing_content: Option<&str>,
         previously_synced_names: &[String],
+        fallback: &FallbackSettings,
+    ) -> Result<()> {
+        let Some(first) = user_dirs().into_iter().next() else {
+            return Err(ConductorError::ClientPathUnknown(self.id().to_string()).into());
+        };
+        self.write_target(
+            &first.id,
+            servers,
+            existing_content,
+            previously_synced_names,
+            fallback,
+        )
+    }
+
+    /// The first build's default profile is synced by default; other builds,
+    /// named profiles and remote servers are opt-in.
+    fn targets(&self) -> Vec<SyncTarget> {
+        user_dirs()
+            .into_iter()
+            .enumerate()
+            .map(|(i, d)| {
+                let path = d.config_path();
+                SyncTarget {
+                    id: d.id,
+                    label: d.label,
+                    exists: path.exists(),
+                    config_path: path,
+                    selected: i == 0,
+                }
+            })
+            .collect()
+    }
+
+    fn read_target(&self, target_id: &str) -> Result<Vec<McpServerConfig>> {
+        Self::read_path(&self.find_target(target_id)?.config_path())
+    }
+
+    fn write_target(
+        &self,
+        target_id: &str,
+        servers: &[McpServerConfig],
+        existing_content: Option<&str>,
+        previously_synced_names: &[String],
+        fallback: &FallbackSettings,
     ) -> Result<()> {
-        let path = Self::get_config_path()
-            .ok_or_else(|| anyhow::anyhow!("Cannot determine config path for VS Code"))?;
+        let path = self.find_target(target_id)?.config_path();
 
-        let format = if Self::is_mcp_json(&path) {
-            "vscode-mcp"
+        let (format, inputs_path): (&str, &[&str]) = if Self::is_mcp_json(&path) {
+            ("vscode-mcp", &["inputs"])
         } else {
-            "vscode"
+            ("vscode", &["mcp", "inputs"])
         };
 
         let current_content = match existing_content {
@@ -107,8 +350,25 @@ impl ClientAdapter for VSCodeAdapter {
             }
         };
 
-        let output =
-            serializer::serialize_to_client_format(format, servers, current_content.as_deref(), previously_synced_names)?;
+        // With secret inputs on, VS Code prompts for secrets once and keeps
+        // them in its own secret storage, so they never land in the file.
+        let use_inputs = config::read_config()
+            .map(|c| c.settings.vscode_secret_inputs)
+            .unwrap_or(false);
+        let (servers, inputs) = if use_inputs {
+            serializer::vscode_secret_inputs(servers)
+        } else {
+            (servers.to_vec(), Vec::new())
+        };
+
+        let output = serializer::serialize_to_client_format(
+            format,
+            &servers,
+            current_content.as_deref(),
+            previously_synced_names,
+            fallback,
+        )?;
+        let output = serializer::merge_vscode_inputs(&output, inputs_path, &inputs)?;
 
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
@@ -1,11 +1,12 @@
 use crate::config::{self, ActivityEntry};
+use crate::errors::CommandResult;
 
 #[tauri::command]
-pub async fn get_activity() -> Result<Vec<ActivityEntry>, String> {
-    let mut cfg = config::read_config().map_err(|e| e.to_string())?;
+pub async fn get_activity() -> CommandResult<Vec<ActivityEntry>> {
+    let mut cfg = config::read_config()?;
     let pruned = config::prune_activity_entries(&mut cfg.activity);
     if pruned > 0 {
-        config::write_config(&cfg).map_err(|e| e.to_string())?;
+        config::write_config(&cfg)?;
     }
 
     let mut entries = cfg.activity;
@@ -14,9 +15,9 @@ pub async fn get_activity() -> Result<Vec<ActivityEntry>, String> {
 }
 
 #[tauri::command]
-pub async fn clear_activity() -> Result<(), String> {
-    let mut cfg = config::read_config().map_err(|e| e.to_string())?;
+pub async fn clear_activity() -> CommandResult<()> {
+    let mut cfg = config::read_config()?;
     cfg.activity.clear();
-    config::write_config(&cfg).map_err(|e| e.to_string())?;
+    config::write_config(&cfg)?;
     Ok(())
 }
@@ -1,11 +1,12 @@
-use crate::clients::{get_all_adapters, ClientDetection};
+use crate::clients::{apply_target_selection, capabilities, get_all_adapters, ClientDetection};
 use crate::config::{self, McpConfig};
+use crate::errors::{CommandResult, ConductorError};
 use serde::Serialize;
 
 #[tauri::command]
-pub async fn detect_clients() -> Result<Vec<ClientDetection>, String> {
+pub async fn detect_clients() -> CommandResult<Vec<ClientDetection>> {
     let adapters = get_all_adapters();
-    let cfg = config::read_config().map_err(|e| e.to_string())?;
+    let cfg = config::read_config()?;
     let mut detections = Vec::new();
 
     // Compute config_updated_at: max updated_at across all enabled servers
@@ -18,10 +19,11 @@ pub async fn detect_clients() -> Result<Vec<ClientDetection>, String> {
         .cloned();
 
     // Compute expected server names: names of all enabled servers in Conductor
+    // that aren't scoped to a single project
     let expected_server_names: Vec<String> = cfg
         .servers
         .iter()
-        .filter(|s| s.enabled)
+        .filter(|s| s.enabled && s.project().is_none())
         .map(|s| s.name.clone())
         .collect();
 
@@ -48,6 +50,22 @@ pub async fn detect_clients() -> Result<Vec<ClientDetection>, String> {
         let previously_synced_names = sync_entry
             .map(|s| s.previously_synced_names.clone())
             .unwrap_or_default();
+        let mut targets = adapter.targets();
+        if let Some(entry) = sync_entry {
+            apply_target_selection(&mut targets, &entry.selected_targets);
+        }
+        // Servers renamed or skipped over a name conflict are expected under
+        // the name they were written as, or not at all.
+        let expected_server_names: Vec<String> = match sync_entry {
+            Some(entry) if !entry.resolved_names.is_empty() => expected_server_names
+                .iter()
+                .filter_map(|name| match entry.resolved_names.get(name) {
+                    Some(written) => written.clone(),
+                    None => Some(name.clone()),
+                })
+                .collect(),
+            _ => expected_server_names.clone(),
+        };
 
         detections.push(ClientDetection {
             client_id: adapter.id().to_string(),
@@ -59,12 +77,19 @@ pub async fn detect_clients() -> Result<Vec<ClientDetection>, String> {
                 .map(|p| p.to_string_lossy().to_string()),
             server_count,
             server_names,
-            expected_server_names: expected_server_names.clone(),
+            expected_server_names,
             last_synced_server_names,
             last_synced_server_count,
             previously_synced_names,
             last_synced_at,
             config_updated_at: config_updated_at.clone(),
+            targets,
+            version: if detected {
+                capabilities::detected_version(adapter.id())
+            } else {
+                None
+            },
+            remote: adapter.remote_capabilities(),
         });
     }
 
@@ -72,17 +97,17 @@ pub async fn detect_clients() -> Result<Vec<ClientDetection>, String> {
 }
 
 #[tauri::command]
-pub async fn read_master_config() -> Result<McpConfig, String> {
-    config::read_config().map_err(|e| e.to_string())
+pub async fn read_master_config() -> CommandResult<McpConfig> {
+    Ok(config::read_config()?)
 }
 
 #[tauri::command]
-pub async fn save_master_config(config: McpConfig) -> Result<(), String> {
-    config::write_config(&config).map_err(|e| e.to_string())
+pub async fn save_master_config(config: McpConfig) -> CommandResult<()> {
+    Ok(config::write_config(&config)?)
 }
 
 #[tauri::command]
-pub async fn get_app_version() -> Result<String, String> {
+pub async fn get_app_version() -> CommandResult<String> {
     Ok(env!("CARGO_PKG_VERSION").to_string())
 }
 
@@ -120,28 +145,31 @@ fn version_is_newer(current: &str, latest: &str) -> bool {
 }
 
 #[tauri::command]
-pub async fn check_for_updates() -> Result<UpdateInfo, String> {
+pub async fn check_for_updates() -> CommandResult<UpdateInfo> {
     let current_version = env!("CARGO_PKG_VERSION").to_string();
 
     let client = reqwest::Client::builder()
         .user_agent("Conductor-Desktop")
-        .build()
-        .map_err(|e| e.to_string())?;
+        .build()?;
 
     let resp = client
         .get("https://api.github.com/repos/aryabyte21/conductor/releases/latest")
         .send()
         .await
-        .map_err(|e| format!("Failed to fetch releases: {}", e))?;
+        .map_err(|e| ConductorError::Network(format!("Failed to fetch releases: {}", e)))?;
 
     if !resp.status().is_success() {
-        return Err(format!("GitHub API returned status {}", resp.status()));
+        return Err(ConductorError::Network(format!(
+            "GitHub API returned status {}",
+            resp.status()
+        ))
+        .into());
     }
 
     let release: serde_json::Value = resp
         .json()
         .await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
+        .map_err(|e| ConductorError::Network(format!("Failed to parse response: {}", e)))?;
 
     let tag = release["tag_name"]
         .as_str()
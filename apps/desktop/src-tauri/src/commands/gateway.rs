@@ -0,0 +1,7 @@
+use crate::errors::CommandResult;
+use crate::gateway::{self, GatewayStatus};
+
+#[tauri::command]
+pub async fn get_gateway_status() -> CommandResult<GatewayStatus> {
+    Ok(gateway::status())
+}
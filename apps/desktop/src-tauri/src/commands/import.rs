@@ -1,40 +1,55 @@
 use crate::clients;
-use crate::config::{self, ImportResult};
+use crate::config::{self, variables, ImportResult, McpConfig, McpServerConfig};
+use crate::errors::{CommandError, CommandResult, ConductorError};
 
 #[tauri::command]
-pub async fn import_from_client(client_id: String) -> Result<ImportResult, String> {
-    let adapter =
-        clients::get_adapter(&client_id).ok_or_else(|| format!("Unknown client: {}", client_id))?;
+pub async fn import_from_client(client_id: String) -> CommandResult<ImportResult> {
+    let adapter = clients::get_adapter(&client_id)
+        .ok_or_else(|| ConductorError::ClientNotFound(client_id.clone()))?;
 
     if !adapter.detect() {
-        return Err(format!(
-            "Client '{}' is not installed or not detected",
-            client_id
-        ));
+        return Err(ConductorError::ClientNotDetected(client_id).into());
     }
 
-    let client_servers = adapter.read_servers().map_err(|e| e.to_string())?;
+    let client_servers = adapter.read_servers().map_err(|e| {
+        let err = CommandError::from(e).client(&client_id);
+        match adapter.config_path() {
+            Some(path) => err.path(&path),
+            None => err,
+        }
+    })?;
 
-    let mut cfg = config::read_config().map_err(|e| e.to_string())?;
+    let mut cfg = config::read_config()?;
 
     let mut imported = Vec::new();
     let mut skipped_count = 0usize;
 
-    for server in client_servers {
-        let is_duplicate = cfg
+    for mut server in client_servers {
+        let duplicate = cfg
             .servers
             .iter()
-            .any(|existing| existing.name == server.name && existing.command == server.command);
+            .position(|existing| {
+                existing.name == server.name && existing.command == server.command
+            });
 
-        if is_duplicate {
+        if let Some(index) = duplicate {
+            // Refresh the client-specific fields this client's entry carries.
+            if cfg.servers[index].headers.is_empty() && !server.headers.is_empty() {
+                stash_secret_headers(&mut cfg, &mut server)
+                    .map_err(|e| CommandError::from(e).client(&client_id))?;
+                cfg.servers[index].headers = server.headers;
+            }
+            cfg.servers[index].extra.extend(server.extra);
             skipped_count += 1;
         } else {
+            stash_secret_headers(&mut cfg, &mut server)
+                .map_err(|e| CommandError::from(e).client(&client_id))?;
             cfg.servers.push(server.clone());
             imported.push(server);
         }
     }
 
-    config::write_config(&cfg).map_err(|e| e.to_string())?;
+    config::write_config(&cfg)?;
 
     let added = imported.len();
 
@@ -54,3 +69,66 @@ pub async fn import_from_client(client_id: String) -> Result<ImportResult, Strin
         servers: imported,
     })
 }
+
+/// Moves secret header values (`Authorization`, API keys, cookies) into
+/// secret variables and leaves a `${NAME}` reference in their place, so they
+/// never sit in the master config.
+fn stash_secret_headers(
+    cfg: &mut McpConfig,
+    server: &mut McpServerConfig,
+) -> Result<(), ConductorError> {
+    let mut secret: Vec<String> = server
+        .headers
+        .iter()
+        .filter(|(name, value)| is_secret_header(name) && !value.contains("${"))
+        .map(|(name, _)| name.clone())
+        .collect();
+    if secret.is_empty() {
+        return Ok(());
+    }
+    secret.sort();
+
+    let store = crate::secrets::open(cfg.settings.secret_backend, &cfg.settings);
+    for header in secret {
+        let name = unused_variable_name(cfg, &format!("{}_{}", server.name, header));
+        store.set(&variables::secret_username(&name), &server.headers[&header])?;
+        cfg.secret_variables.push(name.clone());
+        server.headers.insert(header, format!("${{{}}}", name));
+    }
+    Ok(())
+}
+
+fn is_secret_header(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    ["auth", "cookie", "token", "key", "secret", "password", "session"]
+        .iter()
+        .any(|word| name.contains(word))
+}
+
+/// `base` as a variable name (`GITHUB_AUTHORIZATION` for `github` and
+/// `Authorization`), with a number appended if it's taken.
+fn unused_variable_name(cfg: &McpConfig, base: &str) -> String {
+    let mut base: String = base
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if !base.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        base.insert(0, '_');
+    }
+    let taken = |name: &str| {
+        cfg.variables.contains_key(name) || cfg.secret_variables.iter().any(|n| n == name)
+    };
+    let mut name = base.clone();
+    let mut n = 2;
+    while taken(&name) {
+        name = format!("{}_{}", base, n);
+        n += 1;
+    }
+    name
+}
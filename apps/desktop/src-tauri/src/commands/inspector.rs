@@ -0,0 +1,61 @@
+use crate::config;
+use crate::errors::{CommandError, CommandResult, ConductorError};
+use crate::inspector::{self, ReplayResult, TrafficEntry, TrafficQuery};
+
+/// Logged traffic of a server, newest first.
+#[tauri::command]
+pub async fn query_traffic(
+    server_id: String,
+    query: Option<TrafficQuery>,
+) -> CommandResult<Vec<TrafficEntry>> {
+    Ok(inspector::query(&server_id, &query.unwrap_or_default())?)
+}
+
+/// Writes the matching traffic as a HAR file to the Downloads folder and
+/// returns its path.
+#[tauri::command]
+pub async fn export_traffic_har(
+    server_id: String,
+    query: Option<TrafficQuery>,
+) -> CommandResult<String> {
+    let cfg = config::read_config()?;
+    let server = cfg
+        .servers
+        .into_iter()
+        .find(|s| s.id == server_id)
+        .ok_or_else(|| ConductorError::ServerNotFound(server_id.clone()))?;
+    let har = inspector::to_har(&server, &query.unwrap_or_default())?;
+
+    let dir = dirs::download_dir()
+        .or_else(dirs::home_dir)
+        .ok_or_else(|| {
+            ConductorError::Internal("Cannot determine a folder to export to".to_string())
+        })?;
+    let path = dir.join(format!(
+        "conductor-{}-{}.har",
+        server.name,
+        chrono::Local::now().format("%Y%m%d_%H%M%S")
+    ));
+    std::fs::write(&path, serde_json::to_string_pretty(&har)?)
+        .map_err(|e| CommandError::from(ConductorError::IoError(e)).path(&path))?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Re-sends a captured request to the server.
+#[tauri::command]
+pub async fn replay_traffic(server_id: String, entry_id: String) -> CommandResult<ReplayResult> {
+    let cfg = config::read_config()?;
+    let server = cfg
+        .servers
+        .into_iter()
+        .find(|s| s.id == server_id)
+        .ok_or_else(|| ConductorError::ServerNotFound(server_id.clone()))?;
+    inspector::replay(&server, &entry_id)
+        .await
+        .map_err(|e| CommandError::from(e).server(&server_id))
+}
+
+#[tauri::command]
+pub async fn clear_traffic(server_id: String) -> CommandResult<()> {
+    Ok(inspector::clear(&server_id)?)
+}
@@ -1,5 +1,6 @@
+use crate::errors::CommandResult;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
 static CLIENT_ICON_CACHE: std::sync::LazyLock<Mutex<HashMap<String, Option<String>>>> =
@@ -8,7 +9,7 @@ static CLIENT_ICON_CACHE: std::sync::LazyLock<Mutex<HashMap<String, Option<Strin
 /// Load a client app's icon from its macOS .app bundle.
 /// Returns base64-encoded PNG data URI or None.
 #[tauri::command]
-pub async fn get_client_icon(client_id: String) -> Result<Option<String>, String> {
+pub async fn get_client_icon(client_id: String) -> CommandResult<Option<String>> {
     // Check cache
     if let Ok(cache) = CLIENT_ICON_CACHE.lock() {
         if let Some(cached) = cache.get(&client_id) {
@@ -82,7 +83,7 @@ fn load_client_icon_from_macos(client_id: &str) -> Option<String> {
 }
 
 /// Find the app icon .icns file using multiple strategies
-fn find_app_icon(bundle: &PathBuf, resources: &PathBuf) -> Option<PathBuf> {
+fn find_app_icon(bundle: &Path, resources: &Path) -> Option<PathBuf> {
     // Strategy 1: Read CFBundleIconFile from Info.plist
     if let Some(path) = read_icon_from_plist(bundle, resources) {
         return Some(path);
@@ -109,7 +110,7 @@ fn find_app_icon(bundle: &PathBuf, resources: &PathBuf) -> Option<PathBuf> {
 }
 
 /// Read CFBundleIconFile from Info.plist using PlistBuddy
-fn read_icon_from_plist(bundle: &PathBuf, resources: &PathBuf) -> Option<PathBuf> {
+fn read_icon_from_plist(bundle: &Path, resources: &Path) -> Option<PathBuf> {
     let plist_path = bundle.join("Contents/Info.plist");
     if !plist_path.exists() {
         return None;
@@ -150,7 +151,7 @@ fn read_icon_from_plist(bundle: &PathBuf, resources: &PathBuf) -> Option<PathBuf
 }
 
 /// Convert an .icns file to a base64-encoded PNG data URI
-fn convert_icns_to_base64(icns_path: &PathBuf, client_id: &str) -> Option<String> {
+fn convert_icns_to_base64(icns_path: &Path, client_id: &str) -> Option<String> {
     // Validate client_id to prevent path traversal (e.g. "../../etc/passwd")
     if !client_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
         return None;
@@ -190,7 +191,7 @@ fn convert_icns_to_base64(icns_path: &PathBuf, client_id: &str) -> Option<String
 
 fn base64_encode(data: &[u8]) -> String {
     const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
-    let mut result = String::with_capacity((data.len() + 2) / 3 * 4);
+    let mut result = String::with_capacity(data.len().div_ceil(3) * 4);
     for chunk in data.chunks(3) {
         let b0 = chunk[0] as u32;
         let b1 = if chunk.len() > 1 { chunk[1] as u32 } else { 0 };
@@ -222,7 +223,7 @@ pub async fn resolve_server_logo(
     name: String,
     command: Option<String>,
     url: Option<String>,
-) -> Result<Option<String>, String> {
+) -> CommandResult<Option<String>> {
     // Step 1: Hardcoded icons (most reliable, zero network latency)
     if let Some(icon) = get_hardcoded_icon(&name, command.as_deref(), url.as_deref()) {
         return Ok(Some(icon));
@@ -1,12 +1,18 @@
 pub mod activity;
 pub mod detection;
+pub mod gateway;
 pub mod import;
+pub mod inspector;
 pub mod logo;
 pub mod oauth;
+pub mod profiles;
 pub mod registry;
 pub mod secrets;
 pub mod servers;
 pub mod settings;
 pub mod stacks;
+pub mod supervisor;
 pub mod sync;
 pub mod system;
+pub mod tools;
+pub mod variables;
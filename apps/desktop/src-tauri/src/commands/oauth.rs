@@ -1,3 +1,4 @@
+use crate::errors::{CommandError, CommandResult, ConductorError};
 use crate::oauth;
 use serde::{Deserialize, Serialize};
 
@@ -17,14 +18,14 @@ pub async fn start_oauth_flow(
     app_handle: tauri::AppHandle,
     server_id: String,
     provider: String,
-) -> Result<String, String> {
+) -> CommandResult<String> {
     let auth_url = oauth::start_oauth_server(app_handle, &server_id, &provider)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| CommandError::from(e).server(&server_id))?;
 
     // Open the auth URL in the default browser
     if let Err(e) = open::that(&auth_url) {
-        return Err(format!("Failed to open browser: {}", e));
+        return Err(ConductorError::Internal(format!("Failed to open browser: {}", e)).into());
     }
 
     Ok(auth_url)
@@ -32,28 +33,19 @@ pub async fn start_oauth_flow(
 
 /// Check the authentication status for a server.
 #[tauri::command]
-pub async fn check_auth_status(server_id: String) -> Result<OAuthStatus, String> {
+pub async fn check_auth_status(server_id: String) -> CommandResult<OAuthStatus> {
     let authenticated = crate::oauth::get_valid_oauth_token(&server_id)
         .await
         .map(|token| token.is_some())
         .unwrap_or(false);
 
-    let provider = if authenticated {
-        let provider_key = format!("{}:oauth_provider", server_id);
-        keyring::Entry::new("conductor", &provider_key)
-            .ok()
-            .and_then(|e| e.get_password().ok())
+    let (provider, expires_at) = if authenticated {
+        (
+            crate::secrets::get(&format!("{}:oauth_provider", server_id))?,
+            crate::secrets::get(&format!("{}:oauth_expires", server_id))?,
+        )
     } else {
-        None
-    };
-
-    let expires_at = if authenticated {
-        let expires_key = format!("{}:oauth_expires", server_id);
-        keyring::Entry::new("conductor", &expires_key)
-            .ok()
-            .and_then(|e| e.get_password().ok())
-    } else {
-        None
+        (None, None)
     };
 
     Ok(OAuthStatus {
@@ -65,36 +57,14 @@ pub async fn check_auth_status(server_id: String) -> Result<OAuthStatus, String>
 }
 
 /// Revoke OAuth authentication for a server.
-/// Cleans up all keyring entries including dynamically-registered client IDs.
+/// Cleans up all stored OAuth values including dynamically-registered client IDs.
 #[tauri::command]
-pub async fn revoke_auth(server_id: String) -> Result<(), String> {
-    // Core OAuth keys
-    let mut keys = vec![
-        format!("{}:oauth_token", server_id),
-        format!("{}:oauth_provider", server_id),
-        format!("{}:oauth_expires", server_id),
-        format!("{}:oauth_refresh", server_id),
-    ];
-
-    // Also clean up per-provider client credential keys that may have been
-    // stored during dynamic client registration or manual configuration.
-    let providers = ["GITHUB", "GOOGLE", "NOTION", "SLACK", "LINEAR"];
-    for provider in &providers {
-        keys.push(format!("{}:OAUTH_{}_CLIENT_ID", server_id, provider));
-        keys.push(format!("{}:OAUTH_{}_CLIENT_SECRET", server_id, provider));
+pub async fn revoke_auth(server_id: String) -> CommandResult<()> {
+    let store = crate::secrets::store();
+    for username in oauth::stored_keys(&server_id) {
+        store
+            .delete(&username)
+            .map_err(|e| CommandError::from(e).server(&server_id))?;
     }
-    // Generic credential keys
-    keys.push(format!("{}:OAUTH_CLIENT_ID", server_id));
-    keys.push(format!("{}:OAUTH_CLIENT_SECRET", server_id));
-    keys.push(format!("{}:CLIENT_ID", server_id));
-    keys.push(format!("{}:CLIENT_SECRET", server_id));
-
-    for username in &keys {
-        if let Ok(entry) = keyring::Entry::new("conductor", username) {
-            // Ignore errors for keys that don't exist
-            let _ = entry.delete_credential();
-        }
-    }
-
     Ok(())
 }
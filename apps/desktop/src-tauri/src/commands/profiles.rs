@@ -0,0 +1,205 @@
+use crate::commands::sync::sync_to_all_clients;
+use crate::config::profiles::{self, Profile};
+use crate::config::{self, log_activity, SyncResult};
+use crate::errors::{CommandResult, ConductorError};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileList {
+    pub profiles: Vec<Profile>,
+    pub active_profile: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateProfileRequest {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub servers: Option<Vec<String>>,
+    #[serde(default)]
+    pub projects: Option<Vec<String>>,
+}
+
+/// Every profile. The active one's server set is read from the servers'
+/// `enabled` flags, which hold it while it is active.
+#[tauri::command]
+pub async fn list_profiles() -> CommandResult<ProfileList> {
+    let cfg = config::read_config()?;
+    let enabled: Vec<String> = cfg
+        .servers
+        .iter()
+        .filter(|s| s.enabled)
+        .map(|s| s.id.clone())
+        .collect();
+    let profiles = cfg
+        .profiles
+        .iter()
+        .map(|p| match cfg.active_profile.as_deref() {
+            Some(active) if active == p.id => Profile {
+                servers: enabled.clone(),
+                ..p.clone()
+            },
+            _ => p.clone(),
+        })
+        .collect();
+    Ok(ProfileList {
+        profiles,
+        active_profile: cfg.active_profile,
+    })
+}
+
+/// Creates a profile holding the servers enabled right now. It isn't
+/// activated.
+#[tauri::command]
+pub async fn create_profile(name: String) -> CommandResult<Profile> {
+    let mut cfg = config::read_config()?;
+    let name = validate_name(&cfg, None, &name)?;
+    let profile = Profile {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        servers: cfg
+            .servers
+            .iter()
+            .filter(|s| s.enabled)
+            .map(|s| s.id.clone())
+            .collect(),
+        variables: Default::default(),
+        secret_variables: Vec::new(),
+        projects: Vec::new(),
+    };
+    cfg.profiles.push(profile.clone());
+    config::write_config(&cfg)?;
+    Ok(profile)
+}
+
+/// Renames a profile or replaces its server set or project directories.
+/// Changing the active profile's servers enables and disables them now;
+/// the next sync writes them.
+#[tauri::command]
+pub async fn update_profile(
+    profile_id: String,
+    request: UpdateProfileRequest,
+) -> CommandResult<Profile> {
+    let mut cfg = config::read_config()?;
+    let name = match &request.name {
+        Some(name) => Some(validate_name(&cfg, Some(&profile_id), name)?),
+        None => None,
+    };
+    let is_active = cfg.active_profile.as_deref() == Some(profile_id.as_str());
+    let profile = cfg
+        .profiles
+        .iter_mut()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| {
+            ConductorError::InvalidInput(format!("Profile not found: {}", profile_id))
+        })?;
+
+    if let Some(name) = name {
+        profile.name = name;
+    }
+    if let Some(servers) = request.servers {
+        profile.servers = servers;
+    }
+    if let Some(projects) = request.projects {
+        profile.projects = projects
+            .iter()
+            .map(|p| p.trim().trim_end_matches(['/', '\\']).to_string())
+            .filter(|p| !p.is_empty())
+            .collect();
+    }
+    let updated = profile.clone();
+    if is_active {
+        for server in &mut cfg.servers {
+            server.enabled = updated.servers.contains(&server.id);
+        }
+    }
+    config::write_config(&cfg)?;
+    Ok(updated)
+}
+
+/// Deletes a profile along with its secret variables. Deleting the active
+/// profile leaves its servers enabled with no profile active.
+#[tauri::command]
+pub async fn delete_profile(profile_id: String) -> CommandResult<()> {
+    let mut cfg = config::read_config()?;
+    let index = cfg
+        .profiles
+        .iter()
+        .position(|p| p.id == profile_id)
+        .ok_or_else(|| {
+            ConductorError::InvalidInput(format!("Profile not found: {}", profile_id))
+        })?;
+    let profile = cfg.profiles.remove(index);
+    if cfg.active_profile.as_deref() == Some(profile_id.as_str()) {
+        cfg.active_profile = None;
+    }
+
+    // The keychain can't list entries, so only names the config knows of
+    // are removed.
+    let store = crate::secrets::store();
+    let usernames = profile
+        .secret_variables
+        .iter()
+        .map(|name| config::variables::secret_username(name))
+        .chain(cfg.servers.iter().flat_map(|s| {
+            s.secret_env_keys
+                .iter()
+                .map(move |key| format!("{}:{}", s.id, key))
+        }));
+    for username in usernames {
+        let _ = store.delete(&profile.keychain_username(&username));
+    }
+
+    config::write_config(&cfg)?;
+    Ok(())
+}
+
+/// Activates a profile (or none) and re-syncs every client to its servers.
+#[tauri::command]
+pub async fn switch_profile(
+    app: AppHandle,
+    profile_id: Option<String>,
+) -> CommandResult<Vec<SyncResult>> {
+    let mut cfg = config::read_config()?;
+    profiles::activate(&mut cfg, profile_id.as_deref())?;
+    config::write_config(&cfg)?;
+
+    let name = profiles::active(&cfg).map_or("no profile".to_string(), |p| p.name.clone());
+    log_activity(
+        "profile",
+        &format!("Switched to {}", name),
+        None,
+        None,
+        None,
+    );
+    let _ = app.emit("profile-changed", &profile_id);
+
+    sync_to_all_clients().await
+}
+
+fn validate_name(
+    cfg: &config::McpConfig,
+    profile_id: Option<&str>,
+    name: &str,
+) -> Result<String, ConductorError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ConductorError::InvalidInput(
+            "Profile name is required".to_string(),
+        ));
+    }
+    if cfg
+        .profiles
+        .iter()
+        .any(|p| Some(p.id.as_str()) != profile_id && p.name.eq_ignore_ascii_case(name))
+    {
+        return Err(ConductorError::InvalidInput(format!(
+            "A profile named '{}' already exists",
+            name
+        )));
+    }
+    Ok(name.to_string())
+}
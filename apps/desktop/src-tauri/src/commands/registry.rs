@@ -1,4 +1,5 @@
 use crate::config::{self, McpServerConfig, TransportType};
+use crate::errors::{CommandResult, ConductorError};
 use serde::{Deserialize, Serialize};
 
 /// Raw server from the Smithery API — lenient deserialization.
@@ -52,7 +53,7 @@ impl From<RawRegistryServer> for RegistryServer {
         let qn = raw.qualified_name.unwrap_or_default();
         let dn = raw
             .display_name
-            .unwrap_or_else(|| qn.split('/').last().unwrap_or(&qn).to_string());
+            .unwrap_or_else(|| qn.split('/').next_back().unwrap_or(&qn).to_string());
         RegistryServer {
             id: qn.clone(),
             qualified_name: qn,
@@ -88,11 +89,10 @@ struct RegistrySearchResponse {
 
 /// Get popular servers from the Smithery registry (no search query).
 #[tauri::command]
-pub async fn get_popular_servers() -> Result<Vec<RegistryServer>, String> {
+pub async fn get_popular_servers() -> CommandResult<Vec<RegistryServer>> {
     let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(15))
-        .build()
-        .map_err(|e| e.to_string())?;
+        .build()?;
     fetch_servers(&client, "https://registry.smithery.ai/servers?pageSize=20").await
 }
 
@@ -105,14 +105,13 @@ pub async fn get_popular_servers() -> Result<Vec<RegistryServer>, String> {
 /// Results are merged, deduplicated, and re-ranked so that servers whose name
 /// contains the query string appear first.
 #[tauri::command]
-pub async fn search_registry(query: String) -> Result<Vec<RegistryServer>, String> {
+pub async fn search_registry(query: String) -> CommandResult<Vec<RegistryServer>> {
     let encoded_query = urlencoding::encode(&query);
     let query_lower = query.trim().to_lowercase();
 
     let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(15))
-        .build()
-        .map_err(|e| e.to_string())?;
+        .build()?;
 
     // Fire both searches concurrently
     let semantic_url = format!(
@@ -190,25 +189,34 @@ fn name_match_score(qualified_name: &str, display_name: &str, query_lower: &str)
 async fn fetch_servers(
     client: &reqwest::Client,
     url: &str,
-) -> Result<Vec<RegistryServer>, String> {
+) -> CommandResult<Vec<RegistryServer>> {
     let response = client
         .get(url)
         .header("Accept", "application/json")
         .send()
         .await
-        .map_err(|e| format!("Failed to query registry: {}", e))?;
+        .map_err(|e| ConductorError::RegistryFetch(format!("Failed to query registry: {}", e)))?;
 
     if !response.status().is_success() {
-        return Err(format!("Registry returned status {}", response.status()));
+        return Err(ConductorError::RegistryFetch(format!(
+            "Registry returned status {}",
+            response.status()
+        ))
+        .into());
     }
 
-    let body = response.text().await.map_err(|e| e.to_string())?;
+    let body = response.text().await?;
 
     let raw_servers: Vec<RawRegistryServer> =
         match serde_json::from_str::<RegistrySearchResponse>(&body) {
             Ok(resp) => resp.servers,
             Err(_) => serde_json::from_str(&body)
-                .map_err(|e| format!("Failed to parse registry response: {}", e))?,
+                .map_err(|e| {
+                    ConductorError::RegistryFetch(format!(
+                        "Failed to parse registry response: {}",
+                        e
+                    ))
+                })?,
         };
 
     Ok(raw_servers.into_iter().map(RegistryServer::from).collect())
@@ -216,34 +224,38 @@ async fn fetch_servers(
 
 /// Install a server from the Smithery registry by its qualified name.
 #[tauri::command]
-pub async fn install_from_registry(registry_id: String) -> Result<McpServerConfig, String> {
+pub async fn install_from_registry(registry_id: String) -> CommandResult<McpServerConfig> {
     // Fetch server details from registry
     let encoded_id = urlencoding::encode(&registry_id);
     let url = format!("https://registry.smithery.ai/servers/{}", encoded_id);
 
     let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(15))
-        .build()
-        .map_err(|e| e.to_string())?;
+        .build()?;
     let response = client
         .get(&url)
         .header("Accept", "application/json")
         .send()
         .await
-        .map_err(|e| format!("Failed to fetch server details: {}", e))?;
+        .map_err(|e| {
+            ConductorError::RegistryFetch(format!("Failed to fetch server details: {}", e))
+        })?;
 
     if !response.status().is_success() {
-        return Err(format!(
+        return Err(ConductorError::RegistryFetch(format!(
             "Registry returned status {} for server '{}'",
             response.status(),
             registry_id
-        ));
+        ))
+        .into());
     }
 
     let raw_info: RawRegistryServer = response
         .json()
         .await
-        .map_err(|e| format!("Failed to parse server details: {}", e))?;
+        .map_err(|e| {
+            ConductorError::RegistryFetch(format!("Failed to parse server details: {}", e))
+        })?;
 
     let server_info = RegistryServer::from(raw_info);
 
@@ -251,7 +263,7 @@ pub async fn install_from_registry(registry_id: String) -> Result<McpServerConfi
         server_info
             .qualified_name
             .split('/')
-            .last()
+            .next_back()
             .unwrap_or(&registry_id)
             .to_string()
     } else {
@@ -324,7 +336,7 @@ pub async fn install_from_registry(registry_id: String) -> Result<McpServerConfi
     };
 
     // Check for name collision
-    let mut cfg = config::read_config().map_err(|e| e.to_string())?;
+    let mut cfg = config::read_config()?;
 
     let final_name = if cfg.servers.iter().any(|s| s.name == name) {
         format!("{} (registry)", name)
@@ -350,6 +362,7 @@ pub async fn install_from_registry(registry_id: String) -> Result<McpServerConfi
         args,
         env: std::collections::HashMap::new(),
         url: server_url,
+        headers: std::collections::HashMap::new(),
         secret_env_keys: Vec::new(),
         icon_url: server_info.icon_url,
         tags: Vec::new(),
@@ -357,10 +370,15 @@ pub async fn install_from_registry(registry_id: String) -> Result<McpServerConfi
         registry_id: Some(registry_id),
         created_at: Some(ts.clone()),
         updated_at: Some(ts),
+        codex: None,
+        extra: std::collections::HashMap::new(),
+        tool_filters: std::collections::HashMap::new(),
+        inspect: false,
+        managed: false,
     };
 
     cfg.servers.push(server.clone());
-    config::write_config(&cfg).map_err(|e| e.to_string())?;
+    config::write_config(&cfg)?;
 
     Ok(server)
 }
@@ -1,59 +1,109 @@
-/// Save a secret to the system keychain.
-/// Key format: service="conductor", username="{server_id}:{key}"
+use crate::config::{profiles, SecretBackend};
+use crate::secrets::{self, Migration};
+use crate::errors::{CommandError, CommandResult, ConductorError};
+
+/// Save a secret to the secret store.
+/// Key format: "{server_id}:{key}", prefixed with the active profile's
+/// namespace when there is one.
 #[tauri::command]
-pub async fn save_secret(server_id: String, key: String, value: String) -> Result<(), String> {
-    let username = format!("{}:{}", server_id, key);
-    let entry = keyring::Entry::new("conductor", &username).map_err(|e| e.to_string())?;
-    entry.set_password(&value).map_err(|e| e.to_string())?;
+pub async fn save_secret(server_id: String, key: String, value: String) -> CommandResult<()> {
+    let cfg = crate::config::read_config()?;
+    let username = profiles::secret_username(
+        profiles::active(&cfg),
+        &format!("{}:{}", server_id, key),
+    );
+    secrets::open(cfg.settings.secret_backend, &cfg.settings)
+        .set(&username, &value)
+        .map_err(|e| CommandError::from(e).server(&server_id))?;
     Ok(())
 }
 
-/// Get a secret from the system keychain.
+/// Get a secret from the secret store, from the active profile's namespace
+/// or else the shared entry.
 #[tauri::command]
-pub async fn get_secret(server_id: String, key: String) -> Result<Option<String>, String> {
+pub async fn get_secret(server_id: String, key: String) -> CommandResult<Option<String>> {
+    let cfg = crate::config::read_config()?;
+    let store = secrets::open(cfg.settings.secret_backend, &cfg.settings);
     let username = format!("{}:{}", server_id, key);
-    let entry = keyring::Entry::new("conductor", &username).map_err(|e| e.to_string())?;
-    match entry.get_password() {
-        Ok(password) => Ok(Some(password)),
-        Err(keyring::Error::NoEntry) => Ok(None),
-        Err(e) => Err(e.to_string()),
+    if let Some(profile) = profiles::active(&cfg) {
+        let value = store
+            .get(&profile.keychain_username(&username))
+            .map_err(|e| CommandError::from(e).server(&server_id))?;
+        if value.is_some() {
+            return Ok(value);
+        }
     }
+    store
+        .get(&username)
+        .map_err(|e| CommandError::from(e).server(&server_id))
 }
 
-/// Delete a secret from the system keychain.
+/// Delete a secret from the secret store: the active profile's copy when
+/// there is a profile, else the shared one.
 #[tauri::command]
-pub async fn delete_secret(server_id: String, key: String) -> Result<(), String> {
-    let username = format!("{}:{}", server_id, key);
-    let entry = keyring::Entry::new("conductor", &username).map_err(|e| e.to_string())?;
-    match entry.delete_credential() {
-        Ok(()) => Ok(()),
-        Err(keyring::Error::NoEntry) => Ok(()), // Already deleted
-        Err(e) => Err(e.to_string()),
-    }
+pub async fn delete_secret(server_id: String, key: String) -> CommandResult<()> {
+    let cfg = crate::config::read_config()?;
+    let username = profiles::secret_username(
+        profiles::active(&cfg),
+        &format!("{}:{}", server_id, key),
+    );
+    secrets::open(cfg.settings.secret_backend, &cfg.settings)
+        .delete(&username)
+        .map_err(|e| CommandError::from(e).server(&server_id))
 }
 
 /// List all secret keys stored for a given server.
-/// Since keyring doesn't support enumeration, we read from the master config
+/// Since the keychain doesn't support enumeration, we read from the master config
 /// to know which keys exist, then check if they have stored values.
 #[tauri::command]
-pub async fn list_secret_keys(server_id: String) -> Result<Vec<String>, String> {
-    let cfg = crate::config::read_config().map_err(|e| e.to_string())?;
+pub async fn list_secret_keys(server_id: String) -> CommandResult<Vec<String>> {
+    let cfg = crate::config::read_config()?;
 
     let server = cfg
         .servers
         .iter()
         .find(|s| s.id == server_id)
-        .ok_or_else(|| format!("Server '{}' not found", server_id))?;
+        .ok_or_else(|| ConductorError::ServerNotFound(server_id.clone()))?;
 
+    let profile = profiles::active(&cfg);
     let mut stored_keys = Vec::new();
     for key in &server.secret_env_keys {
         let username = format!("{}:{}", server_id, key);
-        if let Ok(entry) = keyring::Entry::new("conductor", &username) {
-            if entry.get_password().is_ok() {
-                stored_keys.push(key.clone());
-            }
+        if profiles::read_secret(profile, &username)?.is_some() {
+            stored_keys.push(key.clone());
         }
     }
 
     Ok(stored_keys)
 }
+
+/// Fails with the reason `backend` can't be used right now: its CLI isn't
+/// installed or signed in, Vault can't be reached, the file is locked.
+#[tauri::command]
+pub async fn check_secret_backend(backend: SecretBackend) -> CommandResult<()> {
+    let cfg = crate::config::read_config()?;
+    secrets::open(backend, &cfg.settings).check()?;
+    Ok(())
+}
+
+/// Unlocks the encrypted secrets file for this session.
+#[tauri::command]
+pub async fn unlock_secret_file(passphrase: String) -> CommandResult<()> {
+    secrets::unlock(&passphrase)?;
+    Ok(())
+}
+
+/// Moves every stored secret to `backend` and makes it the secret store.
+/// Settings for the new backend (1Password vault, Vault address) must be
+/// saved first.
+#[tauri::command]
+pub async fn migrate_secrets(backend: SecretBackend) -> CommandResult<Migration> {
+    let mut cfg = crate::config::read_config()?;
+    if cfg.settings.secret_backend == backend {
+        return Err(
+            ConductorError::InvalidInput("Secrets are already kept there".to_string()).into(),
+        );
+    }
+    let migration = secrets::migrate(&mut cfg, backend)?;
+    Ok(migration)
+}
@@ -1,4 +1,7 @@
+use crate::config::profiles::{self, Profile};
+use crate::config::variables::{self, VariableStore};
 use crate::config::{self, log_activity, McpServerConfig, TransportType};
+use crate::errors::{CommandError, CommandResult, ConductorError};
 use serde::Deserialize;
 use std::collections::{HashMap, HashSet};
 
@@ -35,14 +38,15 @@ pub struct AddServerRequest {
 }
 
 #[tauri::command]
-pub async fn add_server(request: AddServerRequest) -> Result<McpServerConfig, String> {
-    let mut cfg = config::read_config().map_err(|e| e.to_string())?;
+pub async fn add_server(request: AddServerRequest) -> CommandResult<McpServerConfig> {
+    let mut cfg = config::read_config()?;
 
     if cfg.servers.iter().any(|s| s.name == request.name) {
-        return Err(format!(
+        return Err(ConductorError::ServerNameCollision(format!(
             "Server with name '{}' already exists",
             request.name
-        ));
+        ))
+        .into());
     }
 
     let transport = request.transport.unwrap_or_else(|| {
@@ -55,7 +59,13 @@ pub async fn add_server(request: AddServerRequest) -> Result<McpServerConfig, St
 
     let server_id = uuid::Uuid::new_v4().to_string();
     let normalized_secret_keys = normalize_secret_env_keys(&request.secret_env_keys);
-    validate_secret_env_keys(&server_id, &request.env, &normalized_secret_keys)?;
+    validate_secret_env_keys(
+        profiles::active(&cfg),
+        &server_id,
+        &request.env,
+        &normalized_secret_keys,
+    )
+        .map_err(|e| CommandError::from(e).server(&server_id))?;
 
     let ts = now_iso();
     let server = McpServerConfig {
@@ -69,6 +79,7 @@ pub async fn add_server(request: AddServerRequest) -> Result<McpServerConfig, St
         args: request.args,
         env: request.env,
         url: request.url,
+        headers: HashMap::new(),
         secret_env_keys: normalized_secret_keys,
         icon_url: request.icon_url,
         tags: request.tags,
@@ -76,10 +87,16 @@ pub async fn add_server(request: AddServerRequest) -> Result<McpServerConfig, St
         registry_id: request.registry_id,
         created_at: Some(ts.clone()),
         updated_at: Some(ts),
+        codex: None,
+        extra: HashMap::new(),
+        tool_filters: HashMap::new(),
+        inspect: false,
+        managed: false,
     };
+    check_variables(&server, &cfg).map_err(|e| CommandError::from(e).server(&server.id))?;
 
     cfg.servers.push(server.clone());
-    config::write_config(&cfg).map_err(|e| e.to_string())?;
+    config::write_config(&cfg)?;
 
     log_activity(
         "add",
@@ -115,20 +132,25 @@ pub struct UpdateServerRequest {
     pub icon_url: Option<String>,
     #[serde(default)]
     pub enabled: Option<bool>,
+    #[serde(default)]
+    pub inspect: Option<bool>,
+    #[serde(default)]
+    pub managed: Option<bool>,
 }
 
 #[tauri::command]
 pub async fn update_server(
     server_id: String,
     request: UpdateServerRequest,
-) -> Result<McpServerConfig, String> {
-    let mut cfg = config::read_config().map_err(|e| e.to_string())?;
+) -> CommandResult<McpServerConfig> {
+    let mut cfg = config::read_config()?;
+    let profile = profiles::active(&cfg).cloned();
 
     let server = cfg
         .servers
         .iter_mut()
         .find(|s| s.id == server_id)
-        .ok_or_else(|| format!("Server with id '{}' not found", server_id))?;
+        .ok_or_else(|| ConductorError::ServerNotFound(server_id.clone()))?;
 
     // For optional string fields, empty string means "clear the field"
     if let Some(dn) = request.display_name {
@@ -161,34 +183,59 @@ pub async fn update_server(
     if let Some(en) = request.enabled {
         server.enabled = en;
     }
+    if let Some(inspect) = request.inspect {
+        server.inspect = inspect;
+    }
+    if let Some(managed) = request.managed {
+        server.managed = managed;
+    }
+    if server.managed && server.transport != TransportType::Stdio {
+        return Err(CommandError::from(ConductorError::InvalidInput(
+            "Only stdio servers can be managed".to_string(),
+        ))
+        .server(&server_id));
+    }
 
     let normalized_secret_keys = normalize_secret_env_keys(&server.secret_env_keys);
-    validate_secret_env_keys(&server.id, &server.env, &normalized_secret_keys)?;
+    validate_secret_env_keys(
+        profile.as_ref(),
+        &server.id,
+        &server.env,
+        &normalized_secret_keys,
+    )
+        .map_err(|e| CommandError::from(e).server(&server_id))?;
     server.secret_env_keys = normalized_secret_keys;
     server.updated_at = Some(now_iso());
 
     let updated = server.clone();
-    config::write_config(&cfg).map_err(|e| e.to_string())?;
+    check_variables(&updated, &cfg).map_err(|e| CommandError::from(e).server(&server_id))?;
+    config::write_config(&cfg)?;
 
     Ok(updated)
 }
 
 #[tauri::command]
-pub async fn delete_server(server_id: String) -> Result<(), String> {
-    let mut cfg = config::read_config().map_err(|e| e.to_string())?;
+pub async fn delete_server(server_id: String) -> CommandResult<()> {
+    let mut cfg = config::read_config()?;
 
     let original_len = cfg.servers.len();
     cfg.servers.retain(|s| s.id != server_id);
 
     if cfg.servers.len() == original_len {
-        return Err(format!("Server with id '{}' not found", server_id));
+        return Err(ConductorError::ServerNotFound(server_id).into());
     }
 
     for sync_cfg in &mut cfg.sync {
         sync_cfg.server_ids.retain(|sid| *sid != server_id);
     }
+    for profile in &mut cfg.profiles {
+        profile.servers.retain(|sid| *sid != server_id);
+    }
 
-    config::write_config(&cfg).map_err(|e| e.to_string())?;
+    config::write_config(&cfg)?;
+    crate::tools::forget(&server_id);
+    let _ = crate::inspector::clear(&server_id);
+    let _ = crate::supervisor::clear_logs(&server_id);
 
     log_activity(
         "delete",
@@ -202,19 +249,19 @@ pub async fn delete_server(server_id: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn toggle_server(server_id: String, enabled: bool) -> Result<McpServerConfig, String> {
-    let mut cfg = config::read_config().map_err(|e| e.to_string())?;
+pub async fn toggle_server(server_id: String, enabled: bool) -> CommandResult<McpServerConfig> {
+    let mut cfg = config::read_config()?;
 
     let server = cfg
         .servers
         .iter_mut()
         .find(|s| s.id == server_id)
-        .ok_or_else(|| format!("Server with id '{}' not found", server_id))?;
+        .ok_or_else(|| ConductorError::ServerNotFound(server_id.clone()))?;
 
     server.enabled = enabled;
     server.updated_at = Some(now_iso());
     let updated = server.clone();
-    config::write_config(&cfg).map_err(|e| e.to_string())?;
+    config::write_config(&cfg)?;
 
     let action = if enabled { "Enabled" } else { "Disabled" };
     log_activity(
@@ -247,33 +294,48 @@ fn normalize_secret_env_keys(keys: &[String]) -> Vec<String> {
 }
 
 fn validate_secret_env_keys(
+    profile: Option<&Profile>,
     server_id: &str,
     env: &HashMap<String, String>,
     secret_env_keys: &[String],
-) -> Result<(), String> {
-    let missing: Vec<String> = secret_env_keys
-        .iter()
-        .filter(|key| {
-            !env.contains_key((*key).as_str()) && !secret_exists_in_keychain(server_id, key)
-        })
-        .cloned()
-        .collect();
+) -> Result<(), ConductorError> {
+    let mut missing: Vec<String> = Vec::new();
+    for key in secret_env_keys {
+        if !env.contains_key(key.as_str())
+            && !secret_exists_in_keychain(profile, server_id, key)?
+        {
+            missing.push(key.clone());
+        }
+    }
 
     if !missing.is_empty() {
-        return Err(format!(
+        return Err(ConductorError::InvalidInput(format!(
             "secretEnvKeys contains keys without values in env or keychain: {}",
             missing.join(", ")
-        ));
+        )));
     }
 
     Ok(())
 }
 
-fn secret_exists_in_keychain(server_id: &str, key: &str) -> bool {
+/// Every `${NAME}` the server references must have a value. Secret variables
+/// count as defined without reading the keychain.
+fn check_variables(server: &McpServerConfig, cfg: &config::McpConfig) -> Result<(), ConductorError> {
+    let names = variables::undefined_in(server, &VariableStore::load(cfg, true)?);
+    if !names.is_empty() {
+        return Err(ConductorError::UndefinedVariable {
+            server: server.name.clone(),
+            names,
+        });
+    }
+    Ok(())
+}
+
+fn secret_exists_in_keychain(
+    profile: Option<&Profile>,
+    server_id: &str,
+    key: &str,
+) -> Result<bool, ConductorError> {
     let username = format!("{}:{}", server_id, key);
-    keyring::Entry::new("conductor", &username)
-        .ok()
-        .and_then(|entry| entry.get_password().ok())
-        .map(|v| !v.trim().is_empty())
-        .unwrap_or(false)
+    Ok(profiles::read_secret(profile, &username)?.is_some_and(|v| !v.trim().is_empty()))
 }
@@ -1,23 +1,26 @@
 use crate::config::{self, AppSettings};
+use crate::errors::CommandResult;
 
 #[tauri::command]
-pub async fn get_settings() -> Result<AppSettings, String> {
-    let cfg = config::read_config().map_err(|e| e.to_string())?;
+pub async fn get_settings() -> CommandResult<AppSettings> {
+    let cfg = config::read_config()?;
     Ok(cfg.settings)
 }
 
 #[tauri::command]
-pub async fn save_settings(settings: AppSettings) -> Result<(), String> {
-    let mut cfg = config::read_config().map_err(|e| e.to_string())?;
+pub async fn save_settings(settings: AppSettings) -> CommandResult<()> {
+    let mut cfg = config::read_config()?;
     cfg.settings = settings;
-    config::write_config(&cfg).map_err(|e| e.to_string())?;
+    config::write_config(&cfg)?;
+    crate::autostart::apply(cfg.settings.launch_at_login)?;
     Ok(())
 }
 
 #[tauri::command]
-pub async fn reset_settings() -> Result<AppSettings, String> {
-    let mut cfg = config::read_config().map_err(|e| e.to_string())?;
+pub async fn reset_settings() -> CommandResult<AppSettings> {
+    let mut cfg = config::read_config()?;
     cfg.settings = AppSettings::default();
-    config::write_config(&cfg).map_err(|e| e.to_string())?;
+    config::write_config(&cfg)?;
+    crate::autostart::apply(cfg.settings.launch_at_login)?;
     Ok(cfg.settings)
 }
@@ -1,4 +1,5 @@
 use crate::config::{self, McpServerConfig};
+use crate::errors::{CommandResult, ConductorError};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
@@ -20,8 +21,8 @@ pub async fn export_stack(
     description: String,
     server_ids: Vec<String>,
     tags: Vec<String>,
-) -> Result<String, String> {
-    let cfg = config::read_config().map_err(|e| e.to_string())?;
+) -> CommandResult<String> {
+    let cfg = config::read_config()?;
 
     let servers: Vec<McpServerConfig> = cfg
         .servers
@@ -48,6 +49,15 @@ pub async fn export_stack(
             }
             s.secret_env_keys = secret_keys.into_iter().collect();
             s.secret_env_keys.sort();
+            // Secret headers are normally `${NAME}` references already; drop
+            // any credential-like value that isn't.
+            s.headers.retain(|name, value| {
+                value.contains("${")
+                    || !(looks_sensitive_env_key(&name.replace('-', "_"))
+                        || looks_sensitive_env_value(value))
+            });
+            // Client-specific fields can hold headers and local paths.
+            s.extra.clear();
             // Generate fresh IDs for exported servers
             s.id = uuid::Uuid::new_v4().to_string();
             s.source = Some("stack".to_string());
@@ -57,7 +67,9 @@ pub async fn export_stack(
         .collect();
 
     if servers.is_empty() {
-        return Err("No servers found with the given IDs".to_string());
+        return Err(
+            ConductorError::InvalidInput("No servers found with the given IDs".to_string()).into(),
+        );
     }
 
     let stack = McpStack {
@@ -69,16 +81,16 @@ pub async fn export_stack(
         created_at: chrono::Utc::now().to_rfc3339(),
     };
 
-    serde_json::to_string_pretty(&stack).map_err(|e| e.to_string())
+    Ok(serde_json::to_string_pretty(&stack)?)
 }
 
 /// Import a stack from JSON, adding all servers to the master config.
 #[tauri::command]
-pub async fn import_stack(stack_json: String) -> Result<McpStack, String> {
-    let stack: McpStack =
-        serde_json::from_str(&stack_json).map_err(|e| format!("Invalid stack JSON: {}", e))?;
+pub async fn import_stack(stack_json: String) -> CommandResult<McpStack> {
+    let stack: McpStack = serde_json::from_str(&stack_json)
+        .map_err(|e| ConductorError::InvalidInput(format!("Invalid stack JSON: {}", e)))?;
 
-    let mut cfg = config::read_config().map_err(|e| e.to_string())?;
+    let mut cfg = config::read_config()?;
 
     for mut server in stack.servers.clone() {
         // Generate fresh ID to avoid collisions
@@ -96,15 +108,15 @@ pub async fn import_stack(stack_json: String) -> Result<McpStack, String> {
         cfg.servers.push(server);
     }
 
-    config::write_config(&cfg).map_err(|e| e.to_string())?;
+    config::write_config(&cfg)?;
 
     Ok(stack)
 }
 
 /// Save an exported stack JSON to the master config for persistence.
 #[tauri::command]
-pub async fn save_exported_stack(stack_json: String) -> Result<config::SavedStack, String> {
-    let mut cfg = config::read_config().map_err(|e| e.to_string())?;
+pub async fn save_exported_stack(stack_json: String) -> CommandResult<config::SavedStack> {
+    let mut cfg = config::read_config()?;
 
     let saved = config::SavedStack {
         id: uuid::Uuid::new_v4().to_string(),
@@ -113,41 +125,43 @@ pub async fn save_exported_stack(stack_json: String) -> Result<config::SavedStac
     };
 
     cfg.stacks.push(saved.clone());
-    config::write_config(&cfg).map_err(|e| e.to_string())?;
+    config::write_config(&cfg)?;
 
     Ok(saved)
 }
 
 /// Get all saved exported stacks.
 #[tauri::command]
-pub async fn get_saved_stacks() -> Result<Vec<config::SavedStack>, String> {
-    let cfg = config::read_config().map_err(|e| e.to_string())?;
+pub async fn get_saved_stacks() -> CommandResult<Vec<config::SavedStack>> {
+    let cfg = config::read_config()?;
     Ok(cfg.stacks)
 }
 
 /// Delete a saved stack by ID.
 #[tauri::command]
-pub async fn delete_saved_stack(stack_id: String) -> Result<(), String> {
-    let mut cfg = config::read_config().map_err(|e| e.to_string())?;
+pub async fn delete_saved_stack(stack_id: String) -> CommandResult<()> {
+    let mut cfg = config::read_config()?;
     cfg.stacks.retain(|s| s.id != stack_id);
-    config::write_config(&cfg).map_err(|e| e.to_string())?;
+    config::write_config(&cfg)?;
     Ok(())
 }
 
 /// Validate that a URL is safe to fetch (no SSRF to internal networks)
-fn validate_url_safe(url: &str) -> Result<(), String> {
-    let parsed = url::Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+fn validate_url_safe(url: &str) -> Result<(), ConductorError> {
+    let invalid = |msg: &str| ConductorError::InvalidInput(msg.to_string());
+    let parsed = url::Url::parse(url)
+        .map_err(|e| ConductorError::InvalidInput(format!("Invalid URL: {}", e)))?;
 
     // Only allow HTTPS
     if parsed.scheme() != "https" {
-        return Err("Only HTTPS URLs are allowed".to_string());
+        return Err(invalid("Only HTTPS URLs are allowed"));
     }
 
-    let host = parsed.host_str().ok_or("URL has no host")?;
+    let host = parsed.host_str().ok_or_else(|| invalid("URL has no host"))?;
 
     // Reject localhost and loopback
     if host == "localhost" || host == "127.0.0.1" || host == "::1" || host == "[::1]" || host == "0.0.0.0" {
-        return Err("URLs pointing to localhost are not allowed".to_string());
+        return Err(invalid("URLs pointing to localhost are not allowed"));
     }
 
     // Reject private IP ranges
@@ -164,7 +178,9 @@ fn validate_url_safe(url: &str) -> Result<(), String> {
             }
         };
         if is_private {
-            return Err("URLs pointing to private/internal networks are not allowed".to_string());
+            return Err(invalid(
+                "URLs pointing to private/internal networks are not allowed",
+            ));
         }
     }
 
@@ -173,28 +189,31 @@ fn validate_url_safe(url: &str) -> Result<(), String> {
 
 /// Fetch a stack from a URL and return it.
 #[tauri::command]
-pub async fn get_stack_from_url(url: String) -> Result<McpStack, String> {
+pub async fn get_stack_from_url(url: String) -> CommandResult<McpStack> {
     // Validate URL is safe (no SSRF)
     validate_url_safe(&url)?;
 
     let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(15))
-        .build()
-        .map_err(|e| e.to_string())?;
+        .build()?;
     let response = client
         .get(&url)
         .header("Accept", "application/json")
         .send()
         .await
-        .map_err(|e| format!("Failed to fetch stack: {}", e))?;
+        .map_err(|e| ConductorError::Network(format!("Failed to fetch stack: {}", e)))?;
 
     if !response.status().is_success() {
-        return Err(format!("Failed to fetch stack: HTTP {}", response.status()));
+        return Err(ConductorError::Network(format!(
+            "Failed to fetch stack: HTTP {}",
+            response.status()
+        ))
+        .into());
     }
 
-    let body = response.text().await.map_err(|e| e.to_string())?;
-    let stack: McpStack =
-        serde_json::from_str(&body).map_err(|e| format!("Invalid stack JSON: {}", e))?;
+    let body = response.text().await?;
+    let stack: McpStack = serde_json::from_str(&body)
+        .map_err(|e| ConductorError::InvalidInput(format!("Invalid stack JSON: {}", e)))?;
 
     Ok(stack)
 }
@@ -0,0 +1,31 @@
+use crate::errors::{CommandResult, ConductorError};
+use crate::supervisor::{self, ManagedServerStatus};
+
+const DEFAULT_LOG_LINES: usize = 200;
+
+/// Processes the supervisor runs for managed servers, with their stats.
+#[tauri::command]
+pub async fn list_managed_servers() -> CommandResult<Vec<ManagedServerStatus>> {
+    Ok(supervisor::status())
+}
+
+/// The last `lines` lines of a managed server's output log. New lines
+/// arrive as `managed-server-log` events.
+#[tauri::command]
+pub async fn get_managed_server_logs(
+    server_id: String,
+    lines: Option<usize>,
+) -> CommandResult<Vec<String>> {
+    Ok(supervisor::tail_logs(
+        &server_id,
+        lines.unwrap_or(DEFAULT_LOG_LINES),
+    )?)
+}
+
+#[tauri::command]
+pub async fn restart_managed_server(server_id: String) -> CommandResult<()> {
+    if !supervisor::restart(&server_id) {
+        return Err(ConductorError::ServerNotFound(server_id).into());
+    }
+    Ok(())
+}
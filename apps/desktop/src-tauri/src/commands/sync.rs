@@ -1,17 +1,37 @@
-use crate::clients;
-use crate::config::{self, backup, McpServerConfig, SyncResult};
-use std::collections::HashSet;
+use crate::clients::{self, SyncTarget};
+use crate::config::names::{self, InvalidName, NameConflict, Resolution};
+use crate::config::profiles::{self, Profile};
+use crate::config::variables::{self, VariableStore};
+use crate::config::serializer::FallbackSettings;
+use crate::config::{self, backup, McpServerConfig, SyncResult, TargetSyncResult, TargetSyncState};
+use crate::errors::{CommandError, CommandResult, ConductorError};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
 #[tauri::command]
 pub async fn sync_to_client(
     client_id: String,
     server_ids: Option<Vec<String>>,
-) -> Result<SyncResult, String> {
+) -> CommandResult<SyncResult> {
+    let result = write_client(client_id.clone(), server_ids).await;
+    let error = match &result {
+        Ok(r) => r.error.as_ref().map(|e| e.message.clone()),
+        Err(e) => Some(e.message.clone()),
+    };
+    crate::tray::sync_finished(&client_id, error.as_deref());
+    crate::notifications::sync_finished(&client_id, error.as_deref());
+    result
+}
+
+async fn write_client(
+    client_id: String,
+    server_ids: Option<Vec<String>>,
+) -> CommandResult<SyncResult> {
     let adapter =
-        clients::get_adapter(&client_id).ok_or_else(|| format!("Unknown client: {}", client_id))?;
+        clients::get_adapter(&client_id).ok_or_else(|| ConductorError::ClientNotFound(client_id.clone()))?;
 
-    let cfg = config::read_config().map_err(|e| e.to_string())?;
+    let cfg = config::read_config()?;
 
     // If no server_ids provided, sync all enabled servers
     let ids_to_sync = server_ids.unwrap_or_else(|| {
@@ -22,12 +42,17 @@ pub async fn sync_to_client(
             .collect()
     });
 
-    let servers_to_sync: Vec<McpServerConfig> = cfg
-        .servers
-        .iter()
-        .filter(|s| ids_to_sync.contains(&s.id) && s.enabled)
-        .cloned()
-        .collect();
+    // In gateway mode every client gets a single entry for the gateway, which
+    // serves the enabled servers itself.
+    let servers_to_sync: Vec<McpServerConfig> = if cfg.settings.gateway_enabled {
+        vec![crate::gateway::client_entry(cfg.settings.gateway_port, &client_id)]
+    } else {
+        cfg.servers
+            .iter()
+            .filter(|s| ids_to_sync.contains(&s.id) && s.enabled)
+            .cloned()
+            .collect()
+    };
 
     if servers_to_sync.is_empty() {
         return Ok(SyncResult {
@@ -36,160 +61,365 @@ pub async fn sync_to_client(
             servers_written: 0,
             error: None,
             warnings: vec![],
+            targets: vec![],
         });
     }
 
     let mut warnings: Vec<String> = Vec::new();
-
-    // Inject secrets from keychain and OAuth access token into env vars.
-    // Fault-tolerant: if one server's secret injection fails, log a warning
-    // and still include the server (without the failed secret).
-    let mut enriched_servers: Vec<McpServerConfig> = Vec::with_capacity(servers_to_sync.len());
-    for mut server in servers_to_sync {
-        match inject_secrets(&mut server).await {
-            Ok(()) => {}
+    let active = profiles::active(&cfg);
+    let enriched_servers =
+        match prepare_servers(&cfg, &*adapter, servers_to_sync.clone(), active, false, &mut warnings)
+            .await
+        {
+            Ok(servers) => servers,
             Err(e) => {
-                warnings.push(format!("Server '{}': {}", server.name, e));
+                return Ok(SyncResult {
+                    error: Some(CommandError::from(e).client(&client_id)),
+                    client_id,
+                    success: false,
+                    servers_written: 0,
+                    warnings,
+                    targets: vec![],
+                })
             }
-        }
-        enriched_servers.push(server);
-    }
+        };
 
-    let count = enriched_servers.len();
-    let synced_names: Vec<String> = enriched_servers.iter().map(|s| s.name.clone()).collect();
-    let config_path = adapter.config_path();
+    let sync_entry = cfg.sync.iter().find(|s| s.client_id == client_id);
 
-    // Don't swallow file-read errors — capture them as warnings so rollback
-    // knows whether we actually had previous content or just failed to read it.
-    let existing_content = match config_path.as_ref() {
-        Some(path) => match read_existing_content(path) {
-            Ok(content) => content,
-            Err(e) => {
-                warnings.push(format!(
-                    "Could not read {}: {}",
-                    path.display(),
-                    e
-                ));
-                None
-            }
-        },
-        None => None,
-    };
+    let mut targets = adapter.targets();
+    if let Some(entry) = sync_entry {
+        clients::apply_target_selection(&mut targets, &entry.selected_targets);
+    }
+    targets.retain(|t| t.selected);
+    if targets.is_empty() {
+        return Ok(SyncResult {
+            client_id: client_id.clone(),
+            success: false,
+            servers_written: 0,
+            error: Some(ConductorError::ClientPathUnknown(client_id).into()),
+            warnings,
+            targets: vec![],
+        });
+    }
 
-    // Read previously_synced_names from existing sync entry (cumulative tracking).
-    // If empty and a sync entry exists, seed from the client's actual server names
-    // so that pre-existing Conductor-managed servers are recognized as orphans if
-    // later deleted (migration path for existing installs).
-    let prev_synced_names: Vec<String> = {
-        let sync_entry = cfg.sync.iter().find(|s| s.client_id == client_id);
-        match sync_entry {
-            Some(entry) if !entry.previously_synced_names.is_empty() => {
-                entry.previously_synced_names.clone()
+    // Each target is written, verified and rolled back on its own, so one
+    // unwritable IDE config doesn't undo the others.
+    let mut target_results = Vec::with_capacity(targets.len());
+    let mut synced_targets: Vec<(String, Vec<String>, Vec<String>)> = Vec::new();
+    // Targets inside a project bound to another profile get that profile's
+    // servers, and files committed with a project get secrets as references;
+    // each such set is prepared once.
+    let mut other_sets: HashMap<(Option<String>, bool), CommandResult<Vec<McpServerConfig>>> =
+        HashMap::new();
+    let mut resolved_names: HashMap<String, Option<String>> = HashMap::new();
+    let mut written_names: Vec<String> = Vec::new();
+    let fallback = FallbackSettings::from_settings(&cfg.settings);
+    for target in &targets {
+        let bound = profiles::bound_to(&cfg, &target.config_path).filter(|p| {
+            !cfg.settings.gateway_enabled && cfg.active_profile.as_deref() != Some(p.id.as_str())
+        });
+        let shared = adapter.is_shared_target(&target.id);
+        let servers: &[McpServerConfig] = if bound.is_none() && !shared {
+            &enriched_servers
+        } else {
+            let key = (bound.map(|p| p.id.clone()), shared);
+            if !other_sets.contains_key(&key) {
+                let prepared = match bound {
+                    None => {
+                        let set = servers_to_sync.clone();
+                        prepare_servers(&cfg, &*adapter, set, active, true, &mut warnings)
+                            .await
+                            .map_err(|e| CommandError::from(e).client(&client_id))
+                    }
+                    Some(profile) => {
+                        let set = cfg
+                            .servers
+                            .iter()
+                            .filter(|s| profile.servers.contains(&s.id))
+                            .map(|s| McpServerConfig {
+                                enabled: true,
+                                ..s.clone()
+                            })
+                            .collect();
+                        prepare_servers(&cfg, &*adapter, set, Some(profile), shared, &mut warnings)
+                            .await
+                            .map_err(|e| {
+                                let mut error = CommandError::from(e).client(&client_id);
+                                error.message =
+                                    format!("Profile '{}': {}", profile.name, error.message);
+                                error
+                            })
+                    }
+                };
+                other_sets.insert(key.clone(), prepared);
+            }
+            match &other_sets[&key] {
+                Ok(servers) => servers,
+                Err(e) => {
+                    target_results.push(TargetSyncResult {
+                        target_id: target.id.clone(),
+                        label: target.label.clone(),
+                        success: false,
+                        error: Some(e.clone().path(&target.config_path)),
+                    });
+                    continue;
+                }
             }
-            Some(_) => {
-                // Migration seed: use client's current server names as the baseline
-                match adapter.read_servers() {
-                    Ok(client_servers) => client_servers.into_iter().map(|s| s.name).collect(),
-                    Err(_) => Vec::new(),
+        };
+        let project = adapter.target_project(&target.id);
+        let servers: Vec<McpServerConfig> = servers
+            .iter()
+            .filter(|s| in_project(s, project.as_deref()))
+            .cloned()
+            .collect();
+
+        // Read previously_synced_names from existing sync entry (cumulative tracking).
+        // Configs that predate this field are seeded by the v0 -> v1 schema migration.
+        let prev_synced_names: Vec<String> = sync_entry
+            .map(|entry| entry.previously_synced_for(&target.id))
+            .unwrap_or_default();
+
+        // Entries the user wrote by hand keep their name unless the policy
+        // says otherwise.
+        let existing_names: Vec<String> = adapter
+            .read_target(&target.id)
+            .map(|existing| existing.into_iter().map(|s| s.name).collect())
+            .unwrap_or_default();
+        let resolved = names::resolve(
+            adapter.id(),
+            &servers,
+            &existing_names,
+            &prev_synced_names,
+            cfg.settings.name_conflict_policy,
+        );
+        warnings.extend(resolved.warnings(&target.label));
+        let mut owned_names = prev_synced_names;
+        owned_names.extend(resolved.replaced().map(str::to_string));
+
+        let outcome = sync_target(
+            &*adapter,
+            target,
+            &resolved.servers,
+            &owned_names,
+            &fallback,
+            &mut warnings,
+        );
+        if outcome.is_ok() {
+            let names: Vec<String> = resolved.servers.iter().map(|s| s.name.clone()).collect();
+            for conflict in &resolved.conflicts {
+                let written = match &conflict.resolution {
+                    Resolution::Renamed(name) => Some(name.clone()),
+                    Resolution::Skipped => None,
+                    Resolution::Replaced => continue,
+                };
+                resolved_names.insert(conflict.server.clone(), written);
+            }
+            for invalid in &resolved.invalid {
+                resolved_names.insert(invalid.server.clone(), None);
+            }
+            for name in &names {
+                if !written_names.contains(name) {
+                    written_names.push(name.clone());
                 }
             }
-            None => Vec::new(),
+            synced_targets.push((target.id.clone(), owned_names, names));
         }
-    };
+        target_results.push(TargetSyncResult {
+            target_id: target.id.clone(),
+            label: target.label.clone(),
+            success: outcome.is_ok(),
+            error: outcome
+                .err()
+                .map(|e| CommandError::from(e).path(&target.config_path)),
+        });
+    }
 
-    match adapter.write_servers(&enriched_servers, existing_content.as_deref(), &prev_synced_names) {
-        Ok(()) => {
-            if let Err(verify_err) = verify_written_servers(&*adapter, &enriched_servers) {
-                let rollback_err =
-                    rollback_client_config(config_path.as_ref(), existing_content.as_deref());
-                let error = match rollback_err {
-                    Some(rb_err) => format!(
-                        "Sync verification failed: {}. Rollback also failed: {}",
-                        verify_err, rb_err
-                    ),
-                    None => format!(
-                        "Sync verification failed: {}. Rolled back client config.",
-                        verify_err
-                    ),
-                };
+    let error = combined_error(&target_results);
 
-                return Ok(SyncResult {
-                    client_id,
-                    success: false,
-                    servers_written: 0,
-                    error: Some(error),
-                    warnings,
-                });
-            }
+    if synced_targets.is_empty() {
+        return Ok(SyncResult {
+            client_id,
+            success: false,
+            servers_written: 0,
+            error,
+            warnings,
+            targets: target_results,
+        });
+    }
 
-            // Log activity
-            config::log_activity(
-                "sync",
-                &format!("Synced {} servers to {}", count, client_id),
-                None,
-                Some(client_id.clone()),
-                None,
-            );
+    let count = written_names.len();
+    // Log activity
+    config::log_activity(
+        "sync",
+        &format!("Synced {} servers to {}", count, client_id),
+        None,
+        Some(client_id.clone()),
+        None,
+    );
+
+    // Update sync timestamp in master config
+    let mut cfg = config::read_config()?;
+    let timestamp = chrono::Utc::now().to_rfc3339();
+
+    let sync_cfg = match cfg.sync.iter().position(|s| s.client_id == client_id) {
+        Some(i) => &mut cfg.sync[i],
+        None => {
+            cfg.sync.push(config::ClientSyncConfig {
+                client_id: client_id.clone(),
+                enabled: true,
+                server_ids: vec![],
+                synced_server_names: vec![],
+                previously_synced_names: vec![],
+                last_synced: None,
+                targets: vec![],
+                selected_targets: vec![],
+                resolved_names: HashMap::new(),
+            });
+            cfg.sync.last_mut().expect("just pushed")
+        }
+    };
 
-            // Update sync timestamp in master config
-            let mut cfg = config::read_config().map_err(|e| e.to_string())?;
-            let timestamp = chrono::Utc::now().to_rfc3339();
+    // Build cumulative previously_synced_names = previous ∪ current, per
+    // target and client-wide (the fallback for targets seen for the first time).
+    sync_cfg.previously_synced_names = union(&sync_cfg.previously_synced_names, &written_names);
+    for (target_id, prev, names) in synced_targets {
+        let state = TargetSyncState {
+            previously_synced_names: union(&prev, &names),
+            synced_server_names: names,
+            last_synced: Some(timestamp.clone()),
+            target_id,
+        };
+        match sync_cfg.targets.iter_mut().find(|t| t.target_id == state.target_id) {
+            Some(existing) => *existing = state,
+            None => sync_cfg.targets.push(state),
+        }
+    }
+    sync_cfg.last_synced = Some(timestamp);
+    sync_cfg.server_ids = ids_to_sync;
+    sync_cfg.synced_server_names = written_names;
+    sync_cfg.resolved_names = resolved_names;
+
+    config::write_config(&cfg)?;
+
+    Ok(SyncResult {
+        client_id,
+        success: error.is_none(),
+        servers_written: count,
+        error,
+        warnings,
+        targets: target_results,
+    })
+}
 
-            // Build cumulative previously_synced_names = previous ∪ current
-            let mut cumulative: HashSet<String> = prev_synced_names.into_iter().collect();
-            for name in &synced_names {
-                cumulative.insert(name.clone());
-            }
-            let updated_prev: Vec<String> = cumulative.into_iter().collect();
-
-            if let Some(sync_cfg) = cfg.sync.iter_mut().find(|s| s.client_id == client_id) {
-                sync_cfg.last_synced = Some(timestamp);
-                sync_cfg.server_ids = ids_to_sync;
-                sync_cfg.synced_server_names = synced_names;
-                sync_cfg.previously_synced_names = updated_prev;
-            } else {
-                cfg.sync.push(config::ClientSyncConfig {
-                    client_id: client_id.clone(),
-                    enabled: true,
-                    server_ids: ids_to_sync,
-                    synced_server_names: synced_names,
-                    previously_synced_names: updated_prev,
-                    last_synced: Some(timestamp),
-                });
-            }
+/// The error of a sync whose targets ended as `results`: the only target's
+/// own, or the first failure's with every failed target named in the
+/// message.
+fn combined_error(results: &[TargetSyncResult]) -> Option<CommandError> {
+    let failures: Vec<(&TargetSyncResult, &CommandError)> = results
+        .iter()
+        .filter_map(|t| t.error.as_ref().map(|e| (t, e)))
+        .collect();
+    let (_, first) = failures.first()?;
+    let mut error = (*first).clone();
+    if results.len() > 1 {
+        error.message = failures
+            .iter()
+            .map(|(t, e)| format!("{}: {}", t.label, e.message))
+            .collect::<Vec<_>>()
+            .join("; ");
+        if failures.iter().any(|(_, e)| e.path != first.path) {
+            error.path = None;
+        }
+    }
+    Some(error)
+}
 
-            config::write_config(&cfg).map_err(|e| e.to_string())?;
+/// Write, verify and (on failure) roll back a single target.
+fn sync_target(
+    adapter: &dyn clients::ClientAdapter,
+    target: &SyncTarget,
+    servers: &[McpServerConfig],
+    prev_synced_names: &[String],
+    fallback: &FallbackSettings,
+    warnings: &mut Vec<String>,
+) -> Result<(), ConductorError> {
+    // Don't swallow file-read errors — capture them as warnings so rollback
+    // knows whether we actually had previous content or just failed to read it.
+    let existing_content = match read_existing_content(&target.config_path) {
+        Ok(content) => content,
+        Err(e) => {
+            warnings.push(format!(
+                "Could not read {}: {}",
+                target.config_path.display(),
+                e
+            ));
+            None
+        }
+    };
 
-            Ok(SyncResult {
-                client_id,
-                success: true,
-                servers_written: count,
-                error: None,
-                warnings,
-            })
+    let write_failed = |reason: String| ConductorError::ClientConfigWrite {
+        client_id: adapter.id().to_string(),
+        reason,
+        path: Some(target.config_path.clone()),
+    };
+    match adapter.write_target(
+        &target.id,
+        servers,
+        existing_content.as_deref(),
+        prev_synced_names,
+        fallback,
+    ) {
+        Ok(()) => {
+            if let Err(verify_err) = verify_written_servers(adapter, &target.id, servers) {
+                let rollback_err =
+                    rollback_client_config(Some(&target.config_path), existing_content.as_deref());
+                return Err(match rollback_err {
+                    Some(rb_err) => write_failed(format!(
+                        "verification failed: {}. Rollback also failed: {}",
+                        verify_err, rb_err
+                    )),
+                    None => ConductorError::SyncVerification {
+                        client_id: adapter.id().to_string(),
+                        reason: format!("{}. Rolled back client config.", verify_err),
+                    },
+                });
+            }
+            Ok(())
         }
         Err(e) => {
             let rollback_err =
-                rollback_client_config(config_path.as_ref(), existing_content.as_deref());
-            let error = match rollback_err {
-                Some(rb_err) => format!("{} (rollback failed: {})", e, rb_err),
-                None => e.to_string(),
+                rollback_client_config(Some(&target.config_path), existing_content.as_deref());
+            // Errors that already say what went wrong (an unparseable file, no
+            // remote fallback) keep their kind; the rest failed the write.
+            let e = match (ConductorError::from_anyhow(e), rollback_err) {
+                (e, Some(rb_err)) => write_failed(format!("{} (rollback failed: {})", e, rb_err)),
+                (
+                    e @ (ConductorError::IoError(_)
+                    | ConductorError::SerializationError(_)
+                    | ConductorError::Internal(_)),
+                    None,
+                ) => write_failed(e.to_string()),
+                (e, None) => e,
             };
-
-            Ok(SyncResult {
-                client_id,
-                success: false,
-                servers_written: 0,
-                error: Some(error),
-                warnings,
-            })
+            Err(e.with_path(&target.config_path))
         }
     }
 }
 
+/// Whether `server` may be written to a target of `project`. Servers
+/// imported from a project only go back to that project.
+fn in_project(server: &McpServerConfig, project: Option<&str>) -> bool {
+    server.project().is_none_or(|p| project == Some(p))
+}
+
+fn union(previous: &[String], current: &[String]) -> Vec<String> {
+    let cumulative: HashSet<&String> = previous.iter().chain(current).collect();
+    cumulative.into_iter().cloned().collect()
+}
+
 #[tauri::command]
-pub async fn sync_to_all_clients() -> Result<Vec<SyncResult>, String> {
-    let cfg = config::read_config().map_err(|e| e.to_string())?;
+pub async fn sync_to_all_clients() -> CommandResult<Vec<SyncResult>> {
+    let cfg = config::read_config()?;
     let adapters = clients::get_all_adapters();
 
     let enabled_server_ids: Vec<String> = cfg
@@ -214,8 +444,9 @@ pub async fn sync_to_all_clients() -> Result<Vec<SyncResult>, String> {
                 client_id: adapter.id().to_string(),
                 success: false,
                 servers_written: 0,
-                error: Some(e),
+                error: Some(e.client(adapter.id())),
                 warnings: vec![],
+                targets: vec![],
             }),
         }
     }
@@ -223,17 +454,204 @@ pub async fn sync_to_all_clients() -> Result<Vec<SyncResult>, String> {
     Ok(results)
 }
 
-async fn inject_secrets(server: &mut McpServerConfig) -> anyhow::Result<()> {
-    // Inject secret env vars from keychain
+/// Choose which of a client's targets sync writes to. An empty list restores
+/// the adapter's defaults.
+#[tauri::command]
+pub async fn set_sync_targets(client_id: String, target_ids: Vec<String>) -> CommandResult<()> {
+    let adapter =
+        clients::get_adapter(&client_id).ok_or_else(|| ConductorError::ClientNotFound(client_id.clone()))?;
+    let known: HashSet<String> = adapter.targets().into_iter().map(|t| t.id).collect();
+    if let Some(unknown) = target_ids.iter().find(|id| !known.contains(*id)) {
+        return Err(ConductorError::InvalidInput(format!(
+            "Unknown sync target '{}' for {}",
+            unknown, client_id
+        ))
+        .into());
+    }
+
+    let mut cfg = config::read_config()?;
+    match cfg.sync.iter_mut().find(|s| s.client_id == client_id) {
+        Some(entry) => entry.selected_targets = target_ids,
+        None => cfg.sync.push(config::ClientSyncConfig {
+            client_id,
+            enabled: true,
+            server_ids: vec![],
+            synced_server_names: vec![],
+            previously_synced_names: vec![],
+            last_synced: None,
+            targets: vec![],
+            selected_targets: target_ids,
+            resolved_names: HashMap::new(),
+        }),
+    }
+    config::write_config(&cfg)?;
+    Ok(())
+}
+
+/// Name conflicts and invalid names one client file would see.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetNameConflicts {
+    pub client_id: String,
+    pub display_name: String,
+    pub target_id: String,
+    pub target_label: String,
+    pub conflicts: Vec<NameConflict>,
+    pub invalid: Vec<InvalidName>,
+}
+
+/// What the next sync would do about server names in every detected
+/// client's selected files, without writing anything. Files with nothing to
+/// report are left out.
+#[tauri::command]
+pub async fn preview_name_conflicts() -> CommandResult<Vec<TargetNameConflicts>> {
+    let cfg = config::read_config()?;
+    let enabled: Vec<McpServerConfig> = cfg.servers.iter().filter(|s| s.enabled).cloned().collect();
+
+    let mut previews = Vec::new();
+    for adapter in clients::get_all_adapters() {
+        if !adapter.detect() {
+            continue;
+        }
+        let servers = if cfg.settings.gateway_enabled {
+            vec![crate::gateway::client_entry(cfg.settings.gateway_port, adapter.id())]
+        } else {
+            enabled.clone()
+        };
+        let sync_entry = cfg.sync.iter().find(|s| s.client_id == adapter.id());
+        let mut targets = adapter.targets();
+        if let Some(entry) = sync_entry {
+            clients::apply_target_selection(&mut targets, &entry.selected_targets);
+        }
+        for target in targets.into_iter().filter(|t| t.selected) {
+            let project = adapter.target_project(&target.id);
+            let servers: Vec<McpServerConfig> = servers
+                .iter()
+                .filter(|s| in_project(s, project.as_deref()))
+                .cloned()
+                .collect();
+            let existing: Vec<String> = adapter
+                .read_target(&target.id)
+                .map(|existing| existing.into_iter().map(|s| s.name).collect())
+                .unwrap_or_default();
+            let previously_synced = sync_entry
+                .map(|entry| entry.previously_synced_for(&target.id))
+                .unwrap_or_default();
+            let resolved = names::resolve(
+                adapter.id(),
+                &servers,
+                &existing,
+                &previously_synced,
+                cfg.settings.name_conflict_policy,
+            );
+            if resolved.conflicts.is_empty() && resolved.invalid.is_empty() {
+                continue;
+            }
+            previews.push(TargetNameConflicts {
+                client_id: adapter.id().to_string(),
+                display_name: adapter.display_name().to_string(),
+                target_id: target.id,
+                target_label: target.label,
+                conflicts: resolved.conflicts,
+                invalid: resolved.invalid,
+            });
+        }
+    }
+    Ok(previews)
+}
+
+/// Everything sync does to the servers before writing them for `adapter`:
+/// checks that every `${NAME}` resolves under `profile`, injects variables
+/// and secrets, and applies the managed, tool filter and inspector wrappers.
+/// Secret injection failures are warnings; an undefined variable fails the
+/// whole set, since it would reach the client as literal text. For `shared`
+/// targets secrets are written as references instead (see
+/// [`reference_secrets`]).
+async fn prepare_servers(
+    cfg: &config::McpConfig,
+    adapter: &dyn clients::ClientAdapter,
+    servers: Vec<McpServerConfig>,
+    profile: Option<&Profile>,
+    shared: bool,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<McpServerConfig>, ConductorError> {
+    // Shared targets never see secret values, so the store isn't read.
+    let store = VariableStore::load_for(cfg, profile, shared)?;
+    for server in &servers {
+        let names = variables::undefined_in(server, &store);
+        if !names.is_empty() {
+            return Err(ConductorError::UndefinedVariable {
+                server: server.name.clone(),
+                names,
+            });
+        }
+    }
+
+    // Looked up before secrets are injected, which changes the fingerprint.
+    let tool_catalogs = crate::tools::cached_all(&servers);
+    let mut enriched: Vec<McpServerConfig> = Vec::with_capacity(servers.len());
+    for mut server in servers {
+        if shared {
+            let names = reference_secrets(&mut server, &store);
+            if !names.is_empty() {
+                warnings.push(format!(
+                    "Server '{}': secrets are written to {} as references; set {} where it runs",
+                    server.name,
+                    adapter.display_name(),
+                    names.join(", ")
+                ));
+            }
+        } else if let Err(e) = resolve_server(&mut server, profile, &store).await {
+            // Writing the server without the secrets it couldn't read would
+            // leave it with empty credentials, so the target fails instead.
+            match e.downcast::<ConductorError>() {
+                Ok(
+                    e @ (ConductorError::KeychainUnavailable(_)
+                    | ConductorError::SecretBackendUnavailable { .. }
+                    | ConductorError::SecretAccess(_)),
+                ) => return Err(e),
+                Ok(e) => warnings.push(format!("Server '{}': {}", server.name, e)),
+                Err(e) => warnings.push(format!("Server '{}': {}", server.name, e)),
+            }
+        }
+        enriched.push(server);
+    }
+    crate::supervisor::apply_managed(&mut enriched, cfg.settings.gateway_port, warnings);
+    crate::tools::apply_filters(adapter, &mut enriched, &tool_catalogs, warnings);
+    crate::inspector::apply_tap(adapter, &mut enriched, warnings);
+    Ok(enriched)
+}
+
+/// Prepares a server for launch under the active profile: resolves its
+/// `${NAME}` references, then adds keychain secrets and the OAuth token to its
+/// env. References with no value are reported once everything else is in
+/// place.
+pub(crate) async fn inject_secrets(server: &mut McpServerConfig) -> anyhow::Result<()> {
+    let cfg = config::read_config()?;
+    let profile = profiles::active(&cfg);
+    let store = VariableStore::load_for(&cfg, profile, false)?;
+    resolve_server(server, profile, &store).await
+}
+
+async fn resolve_server(
+    server: &mut McpServerConfig,
+    profile: Option<&Profile>,
+    store: &VariableStore,
+) -> anyhow::Result<()> {
+    // Resolved first, so secret values are never scanned for references.
+    let undefined = variables::interpolate_server(server, store);
+
+    // Inject secret env vars from the secret store
     for key in &server.secret_env_keys {
         let username = format!("{}:{}", server.id, key);
-        if let Ok(entry) = keyring::Entry::new("conductor", &username) {
-            if let Ok(secret) = entry.get_password() {
-                server.env.insert(key.clone(), secret);
-            }
+        if let Some(secret) = profiles::read_secret(profile, &username)? {
+            server.env.insert(key.clone(), secret);
         }
     }
 
+    // Values like `op://vault/item/field`, typed in or stored as secrets.
+    let unresolved = crate::secrets::resolve_references(&mut server.env);
+
     // Inject OAuth token if one exists and the server hasn't set OAUTH_TOKEN itself.
     // This avoids silently overwriting user-provided env values.
     if !server.env.contains_key("OAUTH_TOKEN") {
@@ -242,23 +660,63 @@ async fn inject_secrets(server: &mut McpServerConfig) -> anyhow::Result<()> {
         }
     }
 
+    if !undefined.is_empty() {
+        return Err(ConductorError::UndefinedVariable {
+            server: server.name.clone(),
+            names: undefined,
+        }
+        .into());
+    }
+    if !unresolved.is_empty() {
+        return Err(ConductorError::SecretAccess(format!(
+            "couldn't read {}",
+            unresolved.join("; ")
+        ))
+        .into());
+    }
     Ok(())
 }
 
+/// Resolves `server`'s variables for a file committed with its project:
+/// secret variables, stored secret env values and secret URIs become
+/// `${NAME}` references the client expands from its own environment, and
+/// no OAuth token is added. Returns the names that environment must set.
+fn reference_secrets(server: &mut McpServerConfig, store: &VariableStore) -> Vec<String> {
+    // Secret variables are left as written, so they come back undefined.
+    let mut names: Vec<String> = variables::interpolate_server(server, &store.without_secrets())
+        .into_iter()
+        .filter(|name| store.is_secret(name))
+        .collect();
+    for key in &server.secret_env_keys {
+        server.env.insert(key.clone(), format!("${{{}}}", key));
+        names.push(key.clone());
+    }
+    for (key, value) in &mut server.env {
+        if crate::secrets::is_reference(value) {
+            *value = format!("${{{}}}", key);
+            names.push(key.clone());
+        }
+    }
+    names.sort();
+    names.dedup();
+    names
+}
+
 fn verify_written_servers(
     adapter: &dyn crate::clients::ClientAdapter,
+    target_id: &str,
     expected_servers: &[McpServerConfig],
 ) -> anyhow::Result<()> {
-    let actual_servers = adapter.read_servers()?;
+    let actual_servers = adapter.read_target(target_id)?;
     let actual_names: HashSet<&str> = actual_servers.iter().map(|s| s.name.as_str()).collect();
 
     for server in expected_servers {
         if !actual_names.contains(server.name.as_str()) {
-            anyhow::bail!(
-                "Client config verification failed for '{}': missing server '{}'",
-                adapter.id(),
-                server.name
-            );
+            return Err(ConductorError::SyncVerification {
+                client_id: adapter.id().to_string(),
+                reason: format!("missing server '{}'", server.name),
+            }
+            .into());
         }
     }
 
@@ -279,9 +737,7 @@ fn rollback_client_config(
     path: Option<&std::path::PathBuf>,
     previous_content: Option<&str>,
 ) -> Option<String> {
-    let Some(path) = path else {
-        return None;
-    };
+    let path = path?;
 
     if let Some(content) = previous_content {
         backup::atomic_write(path, content)
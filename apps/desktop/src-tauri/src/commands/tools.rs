@@ -0,0 +1,65 @@
+use crate::config::{self, ToolFilter};
+use crate::errors::{CommandError, CommandResult, ConductorError};
+use crate::tools::{self, ToolCatalog};
+
+/// A server's tools: the cached catalog unless it is missing, stale or
+/// `refresh` is set, in which case the server is probed.
+#[tauri::command]
+pub async fn get_server_tools(server_id: String, refresh: bool) -> CommandResult<ToolCatalog> {
+    let cfg = config::read_config()?;
+    let server = cfg
+        .servers
+        .into_iter()
+        .find(|s| s.id == server_id)
+        .ok_or_else(|| ConductorError::ServerNotFound(server_id.clone()))?;
+
+    if !refresh {
+        if let Some(catalog) = tools::cached(&server) {
+            return Ok(catalog);
+        }
+    }
+    tools::discover(&server).await.map_err(|e| {
+        CommandError::from(ConductorError::ToolDiscovery(format!(
+            "{}: {:#}",
+            server.name, e
+        )))
+        .server(&server_id)
+    })
+}
+
+/// Sets the tool filter `client_id` gets for a server (`*` for every client
+/// without its own). An empty filter removes it. Takes effect on next sync.
+#[tauri::command]
+pub async fn set_tool_filter(
+    server_id: String,
+    client_id: String,
+    filter: ToolFilter,
+) -> CommandResult<()> {
+    if let Some(pattern) = filter
+        .allow
+        .iter()
+        .chain(&filter.deny)
+        .find(|p| p.trim().is_empty() || glob::Pattern::new(p).is_err())
+    {
+        return Err(ConductorError::InvalidInput(format!(
+            "'{}' is not a valid tool name pattern",
+            pattern
+        ))
+        .into());
+    }
+
+    let mut cfg = config::read_config()?;
+    let server = cfg
+        .servers
+        .iter_mut()
+        .find(|s| s.id == server_id)
+        .ok_or_else(|| ConductorError::ServerNotFound(server_id.clone()))?;
+    if filter.is_empty() {
+        server.tool_filters.remove(&client_id);
+    } else {
+        server.tool_filters.insert(client_id, filter);
+    }
+    server.updated_at = Some(chrono::Utc::now().to_rfc3339());
+    config::write_config(&cfg)?;
+    Ok(())
+}
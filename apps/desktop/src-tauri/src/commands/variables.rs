@@ -0,0 +1,187 @@
+use crate::config::profiles::{self, Profile};
+use crate::config::variables::{self, VariableSource, VariableStore, MASK};
+use crate::config::{self, McpConfig, McpServerConfig};
+use crate::errors::{CommandResult, ConductorError};
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VariableInfo {
+    pub name: String,
+    pub source: VariableSource,
+    pub secret: bool,
+    /// `None` for secrets.
+    pub value: Option<String>,
+    /// Names of the servers referencing it.
+    pub used_by: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerPreview {
+    /// The server as clients will receive it, secrets masked.
+    pub server: McpServerConfig,
+    pub undefined: Vec<String>,
+}
+
+/// Every variable servers can reference, including allowlisted environment
+/// variables. With `profile_id`, that profile's variables are included.
+#[tauri::command]
+pub async fn get_variables(profile_id: Option<String>) -> CommandResult<Vec<VariableInfo>> {
+    let cfg = config::read_config()?;
+    let profile = find_profile(&cfg, profile_id.as_deref())?;
+    let store = VariableStore::load_for(&cfg, profile, true)?;
+    let references: Vec<(&str, Vec<String>)> = cfg
+        .servers
+        .iter()
+        .map(|s| {
+            let names = variables::undefined_in(s, &VariableStore::default());
+            (s.name.as_str(), names)
+        })
+        .collect();
+
+    Ok(store
+        .entries()
+        .into_iter()
+        .map(|(name, value, source)| {
+            let secret = store.is_secret(name);
+            VariableInfo {
+                name: name.to_string(),
+                source,
+                secret,
+                value: (!secret).then(|| value.to_string()),
+                used_by: references
+                    .iter()
+                    .filter(|(_, names)| names.iter().any(|n| n == name))
+                    .map(|(server, _)| server.to_string())
+                    .collect(),
+            }
+        })
+        .collect())
+}
+
+/// Creates or replaces a variable, globally or in `profile_id`'s set. Secret
+/// values go to the secret store, and a name moves between the plain and secret
+/// lists when `secret` changes.
+#[tauri::command]
+pub async fn set_variable(
+    name: String,
+    value: String,
+    secret: bool,
+    profile_id: Option<String>,
+) -> CommandResult<()> {
+    if !variables::is_valid_name(&name) {
+        return Err(ConductorError::InvalidInput(format!(
+            "'{}' is not a valid variable name; use letters, digits and underscores",
+            name
+        ))
+        .into());
+    }
+
+    let mut cfg = config::read_config()?;
+    let username = keychain_username(&cfg, profile_id.as_deref(), &name)?;
+    let store = crate::secrets::store();
+    let (plain, secrets) = layer_mut(&mut cfg, profile_id.as_deref());
+    if secret {
+        store.set(&username, &value)?;
+        plain.remove(&name);
+        if !secrets.contains(&name) {
+            secrets.push(name);
+        }
+    } else {
+        if secrets.contains(&name) {
+            store.delete(&username)?;
+            secrets.retain(|n| n != &name);
+        }
+        plain.insert(name, value);
+    }
+    config::write_config(&cfg)?;
+    Ok(())
+}
+
+/// Removes a variable, globally or from `profile_id`'s set. Refused while a
+/// server still references it and no other layer would supply a value.
+#[tauri::command]
+pub async fn delete_variable(name: String, profile_id: Option<String>) -> CommandResult<()> {
+    let mut cfg = config::read_config()?;
+    let username = keychain_username(&cfg, profile_id.as_deref(), &name)?;
+    let (plain, secrets) = layer_mut(&mut cfg, profile_id.as_deref());
+    let was_secret = secrets.contains(&name);
+    plain.remove(&name);
+    secrets.retain(|n| n != &name);
+
+    let profile = find_profile(&cfg, profile_id.as_deref())?.or(profiles::active(&cfg));
+    let store = VariableStore::load_for(&cfg, profile, true)?;
+    let users: Vec<&str> = cfg
+        .servers
+        .iter()
+        .filter(|s| variables::undefined_in(s, &store).contains(&name))
+        .map(|s| s.name.as_str())
+        .collect();
+    if !users.is_empty() {
+        return Err(ConductorError::InvalidInput(format!(
+            "'{}' is used by {}",
+            name,
+            users.join(", ")
+        ))
+        .into());
+    }
+
+    if was_secret {
+        crate::secrets::store().delete(&username)?;
+    }
+    config::write_config(&cfg)?;
+    Ok(())
+}
+
+/// How `server` will be written with the current variables. Also works for
+/// unsaved edits, so the editor can preview before saving.
+#[tauri::command]
+pub async fn preview_server(mut server: McpServerConfig) -> CommandResult<ServerPreview> {
+    let cfg = config::read_config()?;
+    let undefined = variables::interpolate_server(&mut server, &VariableStore::load(&cfg, true)?);
+    for key in &server.secret_env_keys {
+        server.env.insert(key.clone(), MASK.to_string());
+    }
+    Ok(ServerPreview { server, undefined })
+}
+
+fn find_profile<'a>(
+    cfg: &'a McpConfig,
+    profile_id: Option<&str>,
+) -> Result<Option<&'a Profile>, ConductorError> {
+    match profile_id {
+        None => Ok(None),
+        Some(id) => cfg
+            .profiles
+            .iter()
+            .find(|p| p.id == id)
+            .map(Some)
+            .ok_or_else(|| ConductorError::InvalidInput(format!("Profile not found: {}", id))),
+    }
+}
+
+fn keychain_username(
+    cfg: &McpConfig,
+    profile_id: Option<&str>,
+    name: &str,
+) -> Result<String, ConductorError> {
+    let username = variables::secret_username(name);
+    Ok(match find_profile(cfg, profile_id)? {
+        Some(profile) => profile.keychain_username(&username),
+        None => username,
+    })
+}
+
+/// The plain and secret variable lists of a profile, or the global ones.
+/// The profile must exist.
+fn layer_mut<'a>(
+    cfg: &'a mut McpConfig,
+    profile_id: Option<&str>,
+) -> (&'a mut HashMap<String, String>, &'a mut Vec<String>) {
+    match profile_id.and_then(|id| cfg.profiles.iter_mut().find(|p| p.id == id)) {
+        Some(profile) => (&mut profile.variables, &mut profile.secret_variables),
+        None => (&mut cfg.variables, &mut cfg.secret_variables),
+    }
+}
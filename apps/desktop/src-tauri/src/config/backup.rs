@@ -1,5 +1,5 @@
 use anyhow::{Context, Result};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Atomically writes content to a file with backup of the existing file.
 ///
@@ -7,12 +7,21 @@ use std::path::Path;
 /// 2. If the target file exists, creates a timestamped .bak backup.
 /// 3. Renames the temporary file to the target path.
 pub fn atomic_write(path: &Path, content: &str) -> Result<()> {
-    // Safety check: if we're writing a JSON file, verify the content is valid
-    // JSON before touching the disk. This prevents writing corrupt data that
-    // could crash clients like Claude Desktop.
-    if path.extension().and_then(|e| e.to_str()) == Some("json") {
-        let _: serde_json::Value = serde_json::from_str(content)
-            .with_context(|| format!("Refusing to write invalid JSON to {}", path.display()))?;
+    // Safety check: if we're writing a JSON or YAML file, verify the content parses
+    // before touching the disk. This prevents writing corrupt data that
+    // could crash clients like Claude Desktop. Comments and trailing commas
+    // are accepted since editors like VS Code and Zed allow them.
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => {
+            super::jsonc::parse(content)
+                .with_context(|| format!("Refusing to write invalid JSON to {}", path.display()))?;
+        }
+        // YAML configs are edited as text, so check the result still parses.
+        Some("yaml") | Some("yml") => {
+            serde_yaml::from_str::<serde_yaml::Value>(content)
+                .with_context(|| format!("Refusing to write invalid YAML to {}", path.display()))?;
+        }
+        _ => {}
     }
 
     let _write_guard = crate::file_guard::acquire_internal_write(path)?;
@@ -28,10 +37,7 @@ pub fn atomic_write(path: &Path, content: &str) -> Result<()> {
     }
 
     // Write to a temporary file in the same directory (same filesystem for rename)
-    let temp_path = parent.join(format!(
-        ".conductor_tmp_{}",
-        uuid::Uuid::new_v4().to_string()
-    ));
+    let temp_path = parent.join(format!(".conductor_tmp_{}", uuid::Uuid::new_v4()));
 
     std::fs::write(&temp_path, content)
         .with_context(|| format!("Failed to write temp file {}", temp_path.display()))?;
@@ -78,6 +84,31 @@ pub fn atomic_write(path: &Path, content: &str) -> Result<()> {
     Ok(())
 }
 
+/// Writes `content` next to `path` as `{stem}.{label}_{timestamp}.{ext}.bak`.
+///
+/// Used for one-off snapshots such as the ones taken before a schema migration.
+/// The `.` after the stem keeps these out of `clean_old_backups`, so they are
+/// never rotated away by later writes.
+pub fn write_backup(path: &Path, content: &str, label: &str) -> Result<PathBuf> {
+    let parent = path.parent().ok_or_else(|| {
+        anyhow::anyhow!("Cannot determine parent directory of {}", path.display())
+    })?;
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let file_stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("config");
+    let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("json");
+    let backup_path = parent.join(format!(
+        "{}.{}_{}.{}.bak",
+        file_stem, label, timestamp, extension
+    ));
+
+    std::fs::write(&backup_path, content)
+        .with_context(|| format!("Failed to write backup {}", backup_path.display()))?;
+    Ok(backup_path)
+}
+
 /// Removes old backup files, keeping only the most recent 5.
 fn clean_old_backups(dir: &Path, stem: &str) {
     let pattern = format!("{}_*.bak", stem);
@@ -0,0 +1,223 @@
+use anyhow::Result;
+use jsonc_parser::cst::{CstInputValue, CstObject, CstRootNode};
+use jsonc_parser::ParseOptions;
+
+/// Parse JSON or JSONC (comments, trailing commas) into a `serde_json::Value`.
+/// Empty documents read as `{}`.
+pub fn parse(raw: &str) -> Result<serde_json::Value, jsonc_parser::errors::ParseError> {
+    if raw.trim().is_empty() {
+        return Ok(serde_json::json!({}));
+    }
+    let value: serde_json::Value =
+        jsonc_parser::parse_to_serde_value(raw, &ParseOptions::default())?;
+    Ok(if value.is_null() {
+        serde_json::json!({})
+    } else {
+        value
+    })
+}
+
+/// Replace the object at `path` with `entries`, editing the document in place.
+///
+/// Only the target object changes: comments, key order, indentation and the
+/// trailing newline elsewhere in the file are kept byte for byte. Inside the
+/// target, entries whose value is unchanged are left untouched (including any
+/// comments on them), changed entries are rewritten in place, removed ones are
+/// dropped and new ones are appended.
+///
+/// Missing parent objects along `path` are created. An empty `path` targets
+/// the document root.
+pub fn replace_object_at(
+    existing_content: Option<&str>,
+    path: &[&str],
+    entries: &serde_json::Map<String, serde_json::Value>,
+) -> Result<String, jsonc_parser::errors::ParseError> {
+    let source = existing_content.filter(|c| !c.trim().is_empty()).unwrap_or("{}\n");
+    let root = CstRootNode::parse(source, &ParseOptions::default())?;
+
+    let mut target: CstObject = root.object_value_or_set();
+    for key in path {
+        target = target.object_value_or_set(key);
+    }
+
+    for prop in target.properties() {
+        let Some(name) = prop.decoded_name() else {
+            continue;
+        };
+        match entries.get(&name) {
+            None => prop.remove(),
+            Some(desired) => {
+                let current = prop.value().and_then(|v| v.to_serde_value());
+                if current.as_ref() != Some(desired) {
+                    prop.set_value(to_input(desired));
+                }
+            }
+        }
+    }
+
+    for (name, value) in entries {
+        if target.get(name).is_none() {
+            target.append(name, to_input(value));
+        }
+    }
+
+    let mut output = root.to_string();
+    if !output.ends_with('\n') && source.ends_with('\n') {
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+/// Set the value at `path` (creating parent objects), editing the document in
+/// place like `replace_object_at`. Leaves the text untouched if the value is
+/// already equal.
+pub fn replace_value_at(
+    existing_content: Option<&str>,
+    path: &[&str],
+    value: &serde_json::Value,
+) -> Result<String, jsonc_parser::errors::ParseError> {
+    let source = existing_content.filter(|c| !c.trim().is_empty()).unwrap_or("{}\n");
+    let root = CstRootNode::parse(source, &ParseOptions::default())?;
+
+    let mut parent: CstObject = root.object_value_or_set();
+    let Some((key, parents)) = path.split_last() else {
+        return Ok(source.to_string());
+    };
+    for segment in parents {
+        parent = parent.object_value_or_set(segment);
+    }
+    match parent.get(key) {
+        Some(prop) => {
+            if prop.value().and_then(|v| v.to_serde_value()).as_ref() != Some(value) {
+                prop.set_value(to_input(value));
+            }
+        }
+        None => {
+            parent.append(key, to_input(value));
+        }
+    }
+
+    let mut output = root.to_string();
+    if !output.ends_with('\n') && source.ends_with('\n') {
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+fn to_input(value: &serde_json::Value) -> CstInputValue {
+    match value {
+        serde_json::Value::Null => CstInputValue::Null,
+        serde_json::Value::Bool(b) => CstInputValue::Bool(*b),
+        serde_json::Value::Number(n) => CstInputValue::Number(n.to_string()),
+        serde_json::Value::String(s) => CstInputValue::String(s.clone()),
+        serde_json::Value::Array(items) => {
+            CstInputValue::Array(items.iter().map(to_input).collect())
+        }
+        serde_json::Value::Object(map) => CstInputValue::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), to_input(v)))
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const SETTINGS: &str = r#"{
+  // Editor
+  "editor.fontSize": 14,
+  /* block
+     comment */
+  "mcp": {
+    "servers": {
+      "github": { "command": "npx" }, // keep me
+      "old": { "command": "old" },
+    },
+  },
+  "zeta": true,
+}
+"#;
+
+    fn entries(value: serde_json::Value) -> serde_json::Map<String, serde_json::Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn replacing_servers_keeps_comments_order_and_trailing_commas() {
+        let servers = entries(json!({
+            "github": {"command": "npx"},
+            "new": {"url": "https://example.com/mcp"}
+        }));
+        let output = replace_object_at(Some(SETTINGS), &["mcp", "servers"], &servers).unwrap();
+        assert_eq!(
+            output,
+            r#"{
+  // Editor
+  "editor.fontSize": 14,
+  /* block
+     comment */
+  "mcp": {
+    "servers": {
+      "github": { "command": "npx" }, // keep me
+      "new": {
+        "url": "https://example.com/mcp",
+      },
+    },
+  },
+  "zeta": true,
+}
+"#
+        );
+        assert_eq!(parse(&output).unwrap()["mcp"]["servers"], json!(servers));
+    }
+
+    #[test]
+    fn replacing_with_the_same_entries_changes_nothing() {
+        let servers = entries(json!({
+            "github": {"command": "npx"},
+            "old": {"command": "old"}
+        }));
+        let output = replace_object_at(Some(SETTINGS), &["mcp", "servers"], &servers).unwrap();
+        assert_eq!(output, SETTINGS);
+    }
+
+    #[test]
+    fn setting_a_value_appends_it_next_to_its_siblings() {
+        let output =
+            replace_value_at(Some(SETTINGS), &["mcp", "inputs"], &json!([{"id": "token"}]))
+                .unwrap();
+        assert!(output.starts_with("{\n  // Editor\n"));
+        assert!(output.contains("\"old\": { \"command\": \"old\" },\n    },\n    \"inputs\": ["));
+        assert!(output.ends_with("  \"zeta\": true,\n}\n"));
+        assert_eq!(parse(&output).unwrap()["mcp"]["inputs"], json!([{"id": "token"}]));
+
+        let unchanged =
+            replace_value_at(Some(SETTINGS), &["editor.fontSize"], &json!(14)).unwrap();
+        assert_eq!(unchanged, SETTINGS);
+    }
+
+    #[test]
+    fn creates_missing_parent_objects() {
+        let servers = entries(json!({"github": {"command": "npx"}}));
+        let source = "{\n  // only\n  \"a\": 1\n}\n";
+        let output = replace_object_at(Some(source), &["x", "y"], &servers).unwrap();
+        assert_eq!(
+            output,
+            "{\n  // only\n  \"a\": 1,\n  \"x\": {\n    \"y\": {\n      \"github\": {\n        \
+             \"command\": \"npx\"\n      }\n    }\n  }\n}\n"
+        );
+
+        let created = replace_object_at(None, &["servers"], &servers).unwrap();
+        assert_eq!(parse(&created).unwrap(), json!({"servers": {"github": {"command": "npx"}}}));
+        assert!(created.ends_with("}\n"));
+    }
+
+    #[test]
+    fn keeps_a_missing_trailing_newline_missing() {
+        let output = replace_object_at(Some("{\"a\": 1}"), &["s"], &Default::default()).unwrap();
+        assert_eq!(output, "{\n  \"a\": 1,\n  \"s\": {}\n}");
+    }
+}
@@ -0,0 +1,273 @@
+use crate::errors::ConductorError;
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::path::Path;
+
+/// Schema version written by this build of Conductor.
+/// Bump this and append a step to `MIGRATIONS` whenever the on-disk shape
+/// of `config.json` changes in a way `#[serde(default)]` cannot express.
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+/// A single upgrade step from `from` to `from + 1`.
+struct Migration {
+    from: u32,
+    description: &'static str,
+    apply: fn(&mut Value) -> Result<()>,
+}
+
+const MIGRATIONS: &[Migration] = &[Migration {
+    from: 0,
+    description: "add schemaVersion and seed previouslySyncedNames",
+    apply: migrate_v0_to_v1,
+}];
+
+/// Returns the schema version recorded in a raw config document.
+/// Files written before versioning was introduced have no field and are v0.
+pub fn schema_version(value: &Value) -> u32 {
+    value
+        .get("schemaVersion")
+        .and_then(|v| v.as_u64())
+        .map(|v| v as u32)
+        .unwrap_or(0)
+}
+
+/// Upgrades a raw config document to `CURRENT_SCHEMA_VERSION`, one step at a time.
+///
+/// Before each step a backup is written next to `path` as
+/// `config.schema-v{N}_{timestamp}.json.bak`: the file exactly as read
+/// (`original`) before the first step, and the document as it stands before
+/// each later one, so any intermediate state can be recovered by hand.
+/// Returns `true` if any migration ran.
+///
+/// Refuses documents from a newer Conductor rather than silently dropping
+/// fields this build doesn't know about on the next write.
+pub fn migrate(path: &Path, original: &str, value: &mut Value) -> Result<bool> {
+    let mut version = schema_version(value);
+
+    if version > CURRENT_SCHEMA_VERSION {
+        return Err(ConductorError::ConfigTooNew {
+            found: version,
+            supported: CURRENT_SCHEMA_VERSION,
+        }
+        .into());
+    }
+
+    let mut migrated = false;
+    while version < CURRENT_SCHEMA_VERSION {
+        let step = MIGRATIONS
+            .iter()
+            .find(|m| m.from == version)
+            .ok_or_else(|| anyhow::anyhow!("No migration registered from schema v{}", version))?;
+
+        let snapshot = if migrated {
+            serde_json::to_string_pretty(value).context("Failed to snapshot config for backup")?
+        } else {
+            original.to_string()
+        };
+        let backup_path =
+            super::backup::write_backup(path, &snapshot, &format!("schema-v{}", version))
+                .with_context(|| {
+                    format!("Failed to back up config before migrating from v{}", version)
+                })?;
+
+        (step.apply)(value).with_context(|| {
+            format!(
+                "Config migration v{} -> v{} ({}) failed; original kept at {}",
+                version,
+                version + 1,
+                step.description,
+                backup_path.display()
+            )
+        })?;
+
+        version += 1;
+        value["schemaVersion"] = Value::from(version);
+        migrated = true;
+    }
+
+    Ok(migrated)
+}
+
+/// v0 -> v1
+///
+/// v0 covers every unversioned file: the original `{ servers, sync }` layout
+/// and later ones that grew `activity`, `settings`, `stacks`,
+/// `syncedServerNames` and `previouslySyncedNames` through serde defaults.
+///
+/// Orphan removal relies on `previouslySyncedNames`. Files that predate it get
+/// it seeded from what Conductor last wrote to each client: `syncedServerNames`
+/// when present, otherwise the names of the servers in `serverIds`.
+fn migrate_v0_to_v1(value: &mut Value) -> Result<()> {
+    let root = value
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("Config root is not a JSON object"))?;
+
+    if !root.get("servers").map(|v| v.is_array()).unwrap_or(false) {
+        root.insert("servers".to_string(), Value::Array(Vec::new()));
+    }
+
+    let id_to_name: std::collections::HashMap<String, String> = root["servers"]
+        .as_array()
+        .map(|servers| {
+            servers
+                .iter()
+                .filter_map(|s| {
+                    Some((
+                        s.get("id")?.as_str()?.to_string(),
+                        s.get("name")?.as_str()?.to_string(),
+                    ))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let Some(sync) = root.get_mut("sync").and_then(|v| v.as_array_mut()) else {
+        return Ok(());
+    };
+
+    for entry in sync.iter_mut().filter_map(|e| e.as_object_mut()) {
+        let has_previous = entry
+            .get("previouslySyncedNames")
+            .and_then(|v| v.as_array())
+            .map(|a| !a.is_empty())
+            .unwrap_or(false);
+        if has_previous {
+            continue;
+        }
+
+        let mut seed: Vec<String> = entry
+            .get("syncedServerNames")
+            .and_then(|v| v.as_array())
+            .map(|a| {
+                a.iter()
+                    .filter_map(|n| n.as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if seed.is_empty() {
+            seed = entry
+                .get("serverIds")
+                .and_then(|v| v.as_array())
+                .map(|ids| {
+                    ids.iter()
+                        .filter_map(|id| id.as_str())
+                        .filter_map(|id| id_to_name.get(id).cloned())
+                        .collect()
+                })
+                .unwrap_or_default();
+        }
+
+        seed.sort();
+        seed.dedup();
+        entry.insert(
+            "previouslySyncedNames".to_string(),
+            Value::Array(seed.into_iter().map(Value::String).collect()),
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::McpConfig;
+    use std::path::PathBuf;
+
+    /// A config.json path in a fresh directory, for the backups to land in.
+    fn scratch_path() -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("conductor-migrations-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("config.json")
+    }
+
+    /// Migrates a fixture the way `read_config` does.
+    fn load(original: &str) -> (McpConfig, bool, PathBuf) {
+        let path = scratch_path();
+        let mut value: Value = serde_json::from_str(original).unwrap();
+        let migrated = migrate(&path, original, &mut value).unwrap();
+        (serde_json::from_value(value).unwrap(), migrated, path)
+    }
+
+    fn backups(path: &Path) -> Vec<PathBuf> {
+        std::fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|p| p.to_string_lossy().ends_with(".bak"))
+            .collect()
+    }
+
+    #[test]
+    fn seeds_previously_synced_names_from_server_ids() {
+        let (cfg, migrated, _) = load(include_str!(
+            "../../tests/fixtures/config/v0-servers-and-sync.json"
+        ));
+        assert!(migrated);
+        assert_eq!(cfg.schema_version, CURRENT_SCHEMA_VERSION);
+        assert_eq!(cfg.servers.len(), 2);
+        assert_eq!(
+            cfg.sync[0].previously_synced_names,
+            ["filesystem", "github"]
+        );
+    }
+
+    #[test]
+    fn seeds_previously_synced_names_from_synced_server_names() {
+        let (cfg, migrated, _) = load(include_str!(
+            "../../tests/fixtures/config/v0-synced-server-names.json"
+        ));
+        assert!(migrated);
+        assert_eq!(
+            cfg.sync[0].previously_synced_names,
+            ["filesystem", "linear", "old-server"]
+        );
+        assert_eq!(cfg.activity.len(), 1);
+        assert_eq!(cfg.settings.sync_delay, 10);
+        assert!(!cfg.settings.launch_at_login);
+        assert_eq!(cfg.stacks.len(), 1);
+    }
+
+    #[test]
+    fn keeps_existing_previously_synced_names() {
+        let (cfg, migrated, _) = load(include_str!(
+            "../../tests/fixtures/config/v0-previously-synced-names.json"
+        ));
+        assert!(migrated);
+        assert_eq!(
+            cfg.sync[0].previously_synced_names,
+            ["filesystem", "removed-server"]
+        );
+    }
+
+    #[test]
+    fn leaves_current_files_alone() {
+        let (cfg, migrated, path) = load(include_str!("../../tests/fixtures/config/v1.json"));
+        assert!(!migrated);
+        assert!(backups(&path).is_empty());
+        assert_eq!(cfg.sync[0].targets.len(), 1);
+        assert_eq!(cfg.secret_variables, ["GITHUB_TOKEN"]);
+    }
+
+    #[test]
+    fn backs_up_the_file_as_read() {
+        let original = include_str!("../../tests/fixtures/config/v0-servers-and-sync.json");
+        let (_, _, path) = load(original);
+        let backups = backups(&path);
+        assert_eq!(backups.len(), 1);
+        assert!(backups[0].to_string_lossy().contains("config.schema-v0_"));
+        assert_eq!(std::fs::read_to_string(&backups[0]).unwrap(), original);
+    }
+
+    #[test]
+    fn refuses_files_from_a_newer_conductor() {
+        let original = r#"{ "schemaVersion": 99, "servers": [] }"#;
+        let mut value: Value = serde_json::from_str(original).unwrap();
+        let err = migrate(&scratch_path(), original, &mut value).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ConductorError>(),
+            Some(ConductorError::ConfigTooNew { found: 99, .. })
+        ));
+    }
+}
@@ -1,26 +1,28 @@
 pub mod backup;
+pub mod jsonc;
+pub mod migrations;
+pub mod names;
 pub mod normalizer;
+pub mod profiles;
 pub mod serializer;
+pub mod variables;
+pub mod yaml;
 
+use crate::errors::{CommandError, ConductorError};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 const ACTIVITY_RETENTION_DAYS: i64 = 30;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 #[serde(rename_all = "camelCase")]
 pub enum TransportType {
+    #[default]
     Stdio,
     Sse,
     StreamableHttp,
 }
 
-impl Default for TransportType {
-    fn default() -> Self {
-        TransportType::Stdio
-    }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct McpServerConfig {
@@ -42,6 +44,10 @@ pub struct McpServerConfig {
     pub env: HashMap<String, String>,
     #[serde(default)]
     pub url: Option<String>,
+    /// HTTP headers sent to a remote server. Secret values are stored as
+    /// secret variables and referenced here as `${NAME}`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
     #[serde(default)]
     pub secret_env_keys: Vec<String>,
     #[serde(default)]
@@ -56,6 +62,89 @@ pub struct McpServerConfig {
     pub created_at: Option<String>,
     #[serde(default)]
     pub updated_at: Option<String>,
+    /// Codex-only `[mcp_servers.<name>]` fields, kept so they survive an
+    /// import and re-sync.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub codex: Option<CodexServerOptions>,
+    /// Entry fields Conductor doesn't model (Cursor's `envFile`, Zed's
+    /// `settings`, ...), keyed by the client they were imported from and
+    /// written back when syncing to that client.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub extra: HashMap<String, serde_json::Map<String, serde_json::Value>>,
+    /// Tool allow/deny lists keyed by client ID; `*` applies to every client
+    /// without its own entry.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tool_filters: HashMap<String, ToolFilter>,
+    /// Record this server's JSON-RPC traffic (see `inspector`).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub inspect: bool,
+    /// Stdio only: Conductor runs one supervised copy and clients reach it
+    /// through the bridge (see `supervisor`).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub managed: bool,
+}
+
+impl McpServerConfig {
+    /// The tool filter `client_id` gets, if it hides anything.
+    pub fn tool_filter(&self, client_id: &str) -> Option<&ToolFilter> {
+        self.tool_filters
+            .get(client_id)
+            .or_else(|| self.tool_filters.get("*"))
+            .filter(|f| !f.is_empty())
+    }
+
+    /// The project directory a server imported from a project scope belongs
+    /// to, from its `project:<path>` tag. Sync only writes such servers to
+    /// that project's own targets.
+    pub fn project(&self) -> Option<&str> {
+        self.tags.iter().find_map(|tag| tag.strip_prefix(PROJECT_TAG_PREFIX))
+    }
+}
+
+/// Tag prefix marking a server as scoped to one project.
+pub const PROJECT_TAG_PREFIX: &str = "project:";
+
+/// Glob patterns over tool names (`get_*`, `*_issue`). A tool is visible when
+/// `allow` is empty or matches it, and `deny` doesn't.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolFilter {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+}
+
+impl ToolFilter {
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    pub fn permits(&self, tool: &str) -> bool {
+        let matches = |pattern: &String| {
+            glob::Pattern::new(pattern).map_or(pattern == tool, |p| p.matches(tool))
+        };
+        (self.allow.is_empty() || self.allow.iter().any(matches))
+            && !self.deny.iter().any(matches)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexServerOptions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub startup_timeout_sec: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_timeout_sec: Option<f64>,
+    /// Env var Codex reads the bearer token of a streamable HTTP server from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bearer_token_env_var: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled_tools: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disabled_tools: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -93,6 +182,91 @@ pub struct AppSettings {
     pub sync_notifications: bool,
     #[serde(default = "default_true")]
     pub error_notifications: bool,
+    /// Write secret env vars to VS Code as `${input:...}` prompts instead of
+    /// plaintext values.
+    #[serde(default)]
+    pub vscode_secret_inputs: bool,
+    /// How remote servers are written for clients that can't read them natively.
+    #[serde(default)]
+    pub remote_fallback: RemoteFallback,
+    /// Command line for `RemoteFallback::CustomProxy`. `{url}` is replaced by
+    /// the server URL and `{token}` by its bearer token; arguments containing
+    /// `{token}` are dropped when the server has none.
+    #[serde(default)]
+    pub remote_proxy_command: Vec<String>,
+    /// Serve every enabled server through one local MCP endpoint and write
+    /// only that endpoint to clients.
+    #[serde(default)]
+    pub gateway_enabled: bool,
+    #[serde(default = "default_gateway_port")]
+    pub gateway_port: u16,
+    /// Glob patterns of process environment variables `${NAME}` references
+    /// may read (see `variables`).
+    #[serde(default = "default_env_allowlist")]
+    pub env_allowlist: Vec<String>,
+    /// What sync does with a server named like an entry the user wrote in
+    /// the client file (see `names`).
+    #[serde(default)]
+    pub name_conflict_policy: NameConflictPolicy,
+    /// Where secrets are kept (see `secrets`). Changed through
+    /// `migrate_secrets`, which moves the stored values along.
+    #[serde(default)]
+    pub secret_backend: SecretBackend,
+    /// 1Password vault `SecretBackend::OnePassword` writes items to.
+    #[serde(default = "default_onepassword_vault")]
+    pub onepassword_vault: String,
+    /// Vault server for `SecretBackend::Vault`; `VAULT_ADDR` when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vault_address: Option<String>,
+    /// KV version 2 mount secrets are written under.
+    #[serde(default = "default_vault_mount")]
+    pub vault_mount: String,
+}
+
+/// Stdio wrapper used for remote servers a client can't read natively.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum RemoteFallback {
+    /// `npx -y mcp-remote <url>`; needs Node.js.
+    McpRemote,
+    /// The user's own proxy, from `remote_proxy_command`.
+    CustomProxy,
+    /// Conductor's built-in bridge (`conductor bridge`); needs nothing else
+    /// installed.
+    #[default]
+    Conductor,
+}
+
+/// Handling of a server whose name collides with a user's entry.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum NameConflictPolicy {
+    /// Write it as `conductor-<name>` next to the user's entry.
+    #[default]
+    Prefix,
+    /// Leave the user's entry and don't write the server.
+    Skip,
+    /// Replace the user's entry; Conductor manages it from then on.
+    TakeOwnership,
+}
+
+/// Backing store for secrets.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum SecretBackend {
+    /// The OS keychain (Keychain, Credential Manager, Secret Service).
+    #[default]
+    Keyring,
+    /// `~/.conductor/secrets.enc`, encrypted with a passphrase.
+    EncryptedFile,
+    /// The `pass` password store.
+    Pass,
+    /// 1Password, through the `op` CLI.
+    OnePassword,
+    /// Bitwarden, through the `bw` CLI.
+    Bitwarden,
+    /// HashiCorp Vault's KV version 2 engine.
+    Vault,
 }
 
 fn default_true() -> bool {
@@ -104,6 +278,18 @@ fn default_sync_delay() -> u32 {
 fn default_backup_retention() -> u32 {
     30
 }
+fn default_gateway_port() -> u16 {
+    7373
+}
+fn default_onepassword_vault() -> String {
+    "Conductor".to_string()
+}
+fn default_vault_mount() -> String {
+    "secret".to_string()
+}
+fn default_env_allowlist() -> Vec<String> {
+    vec!["HOME".to_string(), "USER".to_string(), "USERPROFILE".to_string()]
+}
 
 impl Default for AppSettings {
     fn default() -> Self {
@@ -116,13 +302,27 @@ impl Default for AppSettings {
             backup_retention: 30,
             sync_notifications: true,
             error_notifications: true,
+            vscode_secret_inputs: false,
+            remote_fallback: RemoteFallback::Conductor,
+            remote_proxy_command: Vec::new(),
+            gateway_enabled: false,
+            gateway_port: default_gateway_port(),
+            env_allowlist: default_env_allowlist(),
+            name_conflict_policy: NameConflictPolicy::default(),
+            secret_backend: SecretBackend::default(),
+            onepassword_vault: default_onepassword_vault(),
+            vault_address: None,
+            vault_mount: default_vault_mount(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct McpConfig {
+    /// On-disk format version, see `migrations::CURRENT_SCHEMA_VERSION`.
+    #[serde(default = "current_schema_version")]
+    pub schema_version: u32,
     pub servers: Vec<McpServerConfig>,
     #[serde(default)]
     pub sync: Vec<ClientSyncConfig>,
@@ -132,6 +332,38 @@ pub struct McpConfig {
     pub settings: AppSettings,
     #[serde(default)]
     pub stacks: Vec<SavedStack>,
+    /// Values for `${NAME}` references in server definitions.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub variables: HashMap<String, String>,
+    /// Names of variables whose values live in the keychain.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub secret_variables: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<profiles::Profile>,
+    /// ID of the profile whose server set is enabled, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_profile: Option<String>,
+}
+
+fn current_schema_version() -> u32 {
+    migrations::CURRENT_SCHEMA_VERSION
+}
+
+impl Default for McpConfig {
+    fn default() -> Self {
+        Self {
+            schema_version: migrations::CURRENT_SCHEMA_VERSION,
+            servers: Vec::new(),
+            sync: Vec::new(),
+            activity: Vec::new(),
+            settings: AppSettings::default(),
+            stacks: Vec::new(),
+            variables: HashMap::new(),
+            secret_variables: Vec::new(),
+            profiles: Vec::new(),
+            active_profile: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -158,6 +390,44 @@ pub struct ClientSyncConfig {
     pub previously_synced_names: Vec<String>,
     #[serde(default)]
     pub last_synced: Option<String>,
+    /// Per-file sync state for clients with several config files.
+    /// Orphans are tracked per target, so removing a server from one IDE
+    /// never touches a same-named server another IDE's user added.
+    #[serde(default)]
+    pub targets: Vec<TargetSyncState>,
+    /// Target IDs the user chose to sync to. Empty means the adapter's defaults.
+    #[serde(default)]
+    pub selected_targets: Vec<String>,
+    /// Servers the last sync wrote under another name (`Some`) or not at all
+    /// (`None`) because of a name conflict or a name the client can't take.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub resolved_names: HashMap<String, Option<String>>,
+}
+
+impl ClientSyncConfig {
+    /// Names Conductor has previously written to `target_id`. Entries from
+    /// before per-target tracking fall back to the client-wide set; once any
+    /// target has its own record, new targets start empty so a fresh scope
+    /// never loses servers to names synced elsewhere.
+    pub fn previously_synced_for(&self, target_id: &str) -> Vec<String> {
+        match self.targets.iter().find(|t| t.target_id == target_id) {
+            Some(t) => t.previously_synced_names.clone(),
+            None if self.targets.is_empty() => self.previously_synced_names.clone(),
+            None => Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetSyncState {
+    pub target_id: String,
+    #[serde(default)]
+    pub synced_server_names: Vec<String>,
+    #[serde(default)]
+    pub previously_synced_names: Vec<String>,
+    #[serde(default)]
+    pub last_synced: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -168,9 +438,21 @@ pub struct SyncResult {
     #[serde(default)]
     pub servers_written: usize,
     #[serde(default)]
-    pub error: Option<String>,
+    pub error: Option<CommandError>,
     #[serde(default)]
     pub warnings: Vec<String>,
+    #[serde(default)]
+    pub targets: Vec<TargetSyncResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetSyncResult {
+    pub target_id: String,
+    pub label: String,
+    pub success: bool,
+    #[serde(default)]
+    pub error: Option<CommandError>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -193,21 +475,40 @@ pub fn master_config_path() -> anyhow::Result<std::path::PathBuf> {
 }
 
 /// Reads the master config from disk, returning a default if it doesn't exist.
+///
+/// Older files are upgraded through `migrations::migrate` and written back
+/// immediately, so every later read sees the current schema. Files from a
+/// newer Conductor are rejected instead of being loaded with fields missing.
 pub fn read_config() -> anyhow::Result<McpConfig> {
     let path = master_config_path()?;
     if !path.exists() {
         return Ok(McpConfig::default());
     }
-    let content = std::fs::read_to_string(&path)?;
-    let config: McpConfig = serde_json::from_str(&content)?;
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| ConductorError::ConfigRead(format!("{}: {}", path.display(), e)))?;
+    let mut raw: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| ConductorError::ConfigParse(format!("{}: {}", path.display(), e)))?;
+    let migrated = migrations::migrate(&path, &content, &mut raw)?;
+    let config: McpConfig = serde_json::from_value(raw)
+        .map_err(|e| ConductorError::ConfigParse(format!("{}: {}", path.display(), e)))?;
+    if migrated {
+        write_config(&config)?;
+    }
     Ok(config)
 }
 
 /// Writes the master config to disk with atomic write and backup.
+/// Always stamps the current schema version.
 pub fn write_config(config: &McpConfig) -> anyhow::Result<()> {
     let path = master_config_path()?;
-    let content = serde_json::to_string_pretty(config)?;
-    backup::atomic_write(&path, &content)?;
+    let mut value = serde_json::to_value(config)?;
+    value["schemaVersion"] = serde_json::Value::from(migrations::CURRENT_SCHEMA_VERSION);
+    let content = serde_json::to_string_pretty(&value)?;
+    backup::atomic_write(&path, &content)
+        .map_err(|e| ConductorError::ConfigWrite(format!("{:#}", e)))?;
+    crate::gateway::config_changed();
+    crate::supervisor::config_changed();
+    crate::tray::refresh();
     Ok(())
 }
 
@@ -250,3 +551,47 @@ pub fn prune_activity_entries(entries: &mut Vec<ActivityEntry>) -> usize {
     });
     before.saturating_sub(entries.len())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(allow: &[&str], deny: &[&str]) -> ToolFilter {
+        ToolFilter {
+            allow: allow.iter().map(|s| s.to_string()).collect(),
+            deny: deny.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn empty_tool_filter_permits_everything() {
+        let empty = ToolFilter::default();
+        assert!(empty.is_empty());
+        assert!(empty.permits("get_issue"));
+        assert!(filter(&[], &["delete_*"]).permits("get_issue"));
+    }
+
+    #[test]
+    fn tool_filter_allow_list_hides_everything_else() {
+        let allow = filter(&["get_*", "search"], &[]);
+        assert!(allow.permits("get_issue"));
+        assert!(allow.permits("search"));
+        assert!(!allow.permits("search_code"));
+        assert!(!allow.permits("create_issue"));
+    }
+
+    #[test]
+    fn tool_filter_deny_wins_over_allow() {
+        let both = filter(&["*_issue"], &["delete_*"]);
+        assert!(both.permits("get_issue"));
+        assert!(!both.permits("delete_issue"));
+        assert!(!filter(&["get_issue"], &["get_issue"]).permits("get_issue"));
+    }
+
+    #[test]
+    fn invalid_tool_filter_pattern_matches_literally() {
+        let broken = filter(&[], &["[oops"]);
+        assert!(!broken.permits("[oops"));
+        assert!(broken.permits("oops"));
+    }
+}
@@ -0,0 +1,340 @@
+//! Server names as clients see them.
+//!
+//! Sync writes servers by name, so a Conductor server can land on an entry
+//! the user wrote by hand. Names are compared folded (NFKC and lowercase):
+//! `GitHub`, `github` and `ｇｉｔｈｕｂ` are the same entry. An entry in the
+//! client file that Conductor never wrote is the user's, and a server
+//! colliding with one is handled by `settings.name_conflict_policy`. Names a
+//! client can't take (Codex's TOML keys, Zed's server IDs) are skipped.
+
+use super::{McpServerConfig, NameConflictPolicy};
+use icu_normalizer::ComposingNormalizerBorrowed;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Prepended to a server renamed to get out of the way of the user's entry.
+pub const PREFIX: &str = "conductor-";
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", tag = "kind", content = "name")]
+pub enum Resolution {
+    /// Written under this name instead.
+    Renamed(String),
+    Skipped,
+    /// The user's entry is replaced and Conductor manages it from now on.
+    Replaced,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NameConflict {
+    pub server: String,
+    /// The user's entry it collides with, as written in the client file.
+    pub existing: String,
+    pub resolution: Resolution,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvalidName {
+    pub server: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Resolved {
+    /// The servers to write, renamed where needed.
+    pub servers: Vec<McpServerConfig>,
+    pub conflicts: Vec<NameConflict>,
+    pub invalid: Vec<InvalidName>,
+}
+
+impl Resolved {
+    /// Names of the user's entries that Conductor takes over; passed to the
+    /// serializer as previously synced so they are replaced.
+    pub fn replaced(&self) -> impl Iterator<Item = &str> {
+        self.conflicts
+            .iter()
+            .filter(|c| c.resolution == Resolution::Replaced)
+            .map(|c| c.existing.as_str())
+    }
+
+    pub fn warnings(&self, target: &str) -> Vec<String> {
+        let conflicts = self.conflicts.iter().map(|c| {
+            let outcome = match &c.resolution {
+                Resolution::Renamed(name) => format!("written as '{}'", name),
+                Resolution::Skipped => "skipped".to_string(),
+                Resolution::Replaced => "replaced it".to_string(),
+            };
+            format!(
+                "{}: '{}' collides with your entry '{}'; {}",
+                target, c.server, c.existing, outcome
+            )
+        });
+        let invalid = self
+            .invalid
+            .iter()
+            .map(|i| format!("{}: skipped '{}': {}", target, i.server, i.reason));
+        conflicts.chain(invalid).collect()
+    }
+}
+
+/// The form names are compared in.
+pub fn fold(name: &str) -> String {
+    let nfkc = ComposingNormalizerBorrowed::new_nfkc();
+    nfkc.normalize(&nfkc.normalize(name).to_lowercase())
+        .into_owned()
+}
+
+/// Why `client_id` can't take `name` as a server name, if it can't.
+pub fn constraint_violation(client_id: &str, name: &str) -> Option<String> {
+    if name.trim().is_empty() {
+        return Some("the name is empty".to_string());
+    }
+    if name.trim() != name {
+        return Some("the name starts or ends with whitespace".to_string());
+    }
+    if name.chars().any(char::is_control) {
+        return Some("the name contains control characters".to_string());
+    }
+    match client_id {
+        // Codex refuses `[mcp_servers.<name>]` keys outside this set.
+        "codex"
+            if !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') =>
+        {
+            Some("Codex only accepts letters, digits, '-' and '_'".to_string())
+        }
+        // Zed uses the key as the context server ID in commands and logs.
+        "zed" if name.chars().any(char::is_whitespace) => {
+            Some("Zed server IDs can't contain whitespace".to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Decides what is written for `servers` to a client file holding
+/// `existing` entries, of which `previously_synced` are Conductor's.
+pub fn resolve(
+    client_id: &str,
+    servers: &[McpServerConfig],
+    existing: &[String],
+    previously_synced: &[String],
+    policy: NameConflictPolicy,
+) -> Resolved {
+    let ours: HashSet<String> = previously_synced.iter().map(|n| fold(n)).collect();
+    let user_owned: Vec<(String, &String)> = existing
+        .iter()
+        .map(|n| (fold(n), n))
+        .filter(|(folded, _)| !ours.contains(folded))
+        .collect();
+    let user_entry = |folded: &str| {
+        user_owned
+            .iter()
+            .find(|(f, _)| f == folded)
+            .map(|(_, n)| (*n).clone())
+    };
+
+    let mut resolved = Resolved::default();
+    let mut taken: HashSet<String> = HashSet::new();
+    for server in servers {
+        if let Some(reason) = constraint_violation(client_id, &server.name) {
+            resolved.invalid.push(InvalidName {
+                server: server.name.clone(),
+                reason,
+            });
+            continue;
+        }
+        let folded = fold(&server.name);
+        if taken.contains(&folded) {
+            resolved.invalid.push(InvalidName {
+                server: server.name.clone(),
+                reason: "another server has the same name".to_string(),
+            });
+            continue;
+        }
+        let Some(existing) = user_entry(&folded) else {
+            taken.insert(folded);
+            resolved.servers.push(server.clone());
+            continue;
+        };
+
+        let resolution = match policy {
+            NameConflictPolicy::Prefix => {
+                let renamed = format!("{}{}", PREFIX, server.name);
+                let folded = fold(&renamed);
+                if user_entry(&folded).is_some() || taken.contains(&folded) {
+                    Resolution::Skipped
+                } else {
+                    taken.insert(folded);
+                    resolved.servers.push(McpServerConfig {
+                        name: renamed.clone(),
+                        ..server.clone()
+                    });
+                    Resolution::Renamed(renamed)
+                }
+            }
+            NameConflictPolicy::Skip => Resolution::Skipped,
+            NameConflictPolicy::TakeOwnership => {
+                taken.insert(folded);
+                resolved.servers.push(server.clone());
+                Resolution::Replaced
+            }
+        };
+        resolved.conflicts.push(NameConflict {
+            server: server.name.clone(),
+            existing,
+            resolution,
+        });
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(name: &str) -> McpServerConfig {
+        serde_json::from_value(serde_json::json!({
+            "id": format!("id-{}", name),
+            "name": name,
+            "enabled": true,
+            "transport": "stdio",
+            "command": "npx",
+        }))
+        .unwrap()
+    }
+
+    fn names(resolved: &Resolved) -> Vec<&str> {
+        resolved.servers.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    fn strings(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn folds_case_and_compatibility_forms() {
+        assert_eq!(fold("GitHub"), fold("ｇｉｔｈｕｂ"));
+        assert_eq!(fold("GitHub"), "github");
+        assert_ne!(fold("github"), fold("gitlab"));
+    }
+
+    #[test]
+    fn prefixes_a_server_colliding_with_a_user_entry() {
+        let resolved = resolve(
+            "cursor",
+            &[server("GitHub"), server("filesystem")],
+            &strings(&["ｇｉｔｈｕｂ"]),
+            &[],
+            NameConflictPolicy::Prefix,
+        );
+        assert_eq!(names(&resolved), ["conductor-GitHub", "filesystem"]);
+        assert_eq!(resolved.conflicts.len(), 1);
+        assert_eq!(resolved.conflicts[0].existing, "ｇｉｔｈｕｂ");
+        assert_eq!(
+            resolved.conflicts[0].resolution,
+            Resolution::Renamed("conductor-GitHub".to_string())
+        );
+        assert_eq!(resolved.replaced().count(), 0);
+    }
+
+    #[test]
+    fn prefix_falls_back_to_skipping_when_the_prefixed_name_is_taken() {
+        let resolved = resolve(
+            "cursor",
+            &[server("github")],
+            &strings(&["github", "Conductor-GitHub"]),
+            &[],
+            NameConflictPolicy::Prefix,
+        );
+        assert!(resolved.servers.is_empty());
+        assert_eq!(resolved.conflicts[0].resolution, Resolution::Skipped);
+    }
+
+    #[test]
+    fn skip_policy_leaves_the_user_entry_alone() {
+        let resolved = resolve(
+            "cursor",
+            &[server("github")],
+            &strings(&["GitHub"]),
+            &[],
+            NameConflictPolicy::Skip,
+        );
+        assert!(resolved.servers.is_empty());
+        assert_eq!(resolved.conflicts[0].resolution, Resolution::Skipped);
+        assert_eq!(
+            resolved.warnings("Cursor"),
+            ["Cursor: 'github' collides with your entry 'GitHub'; skipped"]
+        );
+    }
+
+    #[test]
+    fn take_ownership_replaces_the_user_entry() {
+        let resolved = resolve(
+            "cursor",
+            &[server("github")],
+            &strings(&["GitHub"]),
+            &[],
+            NameConflictPolicy::TakeOwnership,
+        );
+        assert_eq!(names(&resolved), ["github"]);
+        assert_eq!(resolved.conflicts[0].resolution, Resolution::Replaced);
+        assert_eq!(resolved.replaced().collect::<Vec<_>>(), ["GitHub"]);
+    }
+
+    #[test]
+    fn entries_conductor_wrote_are_not_conflicts() {
+        let resolved = resolve(
+            "cursor",
+            &[server("github")],
+            &strings(&["GitHub"]),
+            &strings(&["github"]),
+            NameConflictPolicy::Skip,
+        );
+        assert_eq!(names(&resolved), ["github"]);
+        assert!(resolved.conflicts.is_empty());
+    }
+
+    #[test]
+    fn skips_a_duplicate_server_name() {
+        let resolved = resolve(
+            "cursor",
+            &[server("github"), server("GITHUB")],
+            &[],
+            &[],
+            NameConflictPolicy::Prefix,
+        );
+        assert_eq!(names(&resolved), ["github"]);
+        assert_eq!(resolved.invalid.len(), 1);
+        assert_eq!(resolved.invalid[0].server, "GITHUB");
+        assert_eq!(resolved.invalid[0].reason, "another server has the same name");
+    }
+
+    #[test]
+    fn applies_client_name_constraints() {
+        assert!(constraint_violation("codex", "my-server_2").is_none());
+        assert!(constraint_violation("codex", "my.server").is_some());
+        assert!(constraint_violation("codex", "my server").is_some());
+        assert!(constraint_violation("zed", "my.server").is_none());
+        assert!(constraint_violation("zed", "my server").is_some());
+        assert!(constraint_violation("cursor", "my server").is_none());
+        assert!(constraint_violation("cursor", " padded").is_some());
+        assert!(constraint_violation("cursor", "").is_some());
+        assert!(constraint_violation("cursor", "tab\there").is_some());
+
+        let resolved = resolve(
+            "codex",
+            &[server("my.server"), server("ok")],
+            &[],
+            &[],
+            NameConflictPolicy::Prefix,
+        );
+        assert_eq!(names(&resolved), ["ok"]);
+        assert_eq!(
+            resolved.warnings("Codex"),
+            ["Codex: skipped 'my.server': Codex only accepts letters, digits, '-' and '_'"]
+        );
+    }
+}
@@ -1,30 +1,39 @@
-use crate::config::{McpServerConfig, TransportType};
-use anyhow::{Context, Result};
+use crate::clients::spec::{AdapterSpec, SpecFormat};
+use crate::config::{jsonc, CodexServerOptions, McpServerConfig, TransportType};
+use crate::errors::ConductorError;
+use anyhow::Result;
 use std::collections::HashMap;
 
 /// Parse a client's raw config file content and extract MCP server configurations.
 pub fn parse_client_config(client_id: &str, raw: &str) -> Result<Vec<McpServerConfig>> {
     match client_id {
-        "claude-desktop" | "cursor" | "windsurf" | "claude-code" => {
-            parse_mcp_servers_json(raw, client_id)
-        }
+        "claude-code" => parse_json_servers_at(raw, &["mcpServers"], client_id),
         "vscode" => parse_vscode_config(raw),
         "vscode-mcp" => parse_vscode_mcp_json(raw),
         "zed" => parse_zed_config(raw),
         "jetbrains" => parse_jetbrains_config(raw),
         "codex" => parse_codex_config(raw),
-        _ => Err(anyhow::anyhow!("Unknown client: {}", client_id)),
+        "continue" => parse_continue_config(raw),
+        "gemini" => parse_gemini_config(raw),
+        "goose" => parse_goose_config(raw),
+        "opencode" => parse_opencode_config(raw),
+        _ => Err(ConductorError::ClientNotFound(client_id.to_string()).into()),
     }
 }
 
-/// Parse standard JSON format with top-level "mcpServers" key.
-/// Used by Claude Desktop, Cursor, Windsurf, Claude Code.
-fn parse_mcp_servers_json(raw: &str, source: &str) -> Result<Vec<McpServerConfig>> {
-    let value: serde_json::Value =
-        serde_json::from_str(raw).context("Failed to parse JSON config")?;
+/// Parse standard JSON format: servers keyed by name in the object at
+/// `key_path` (`["mcpServers"]` for most clients). Used by Claude Code.
+pub fn parse_json_servers_at(
+    raw: &str,
+    key_path: &[&str],
+    source: &str,
+) -> Result<Vec<McpServerConfig>> {
+    let value: serde_json::Value = jsonc::parse(raw)
+        .map_err(|e| ConductorError::invalid_client_config(source, "JSON", e))?;
 
-    let servers_obj = value
-        .get("mcpServers")
+    let servers_obj = key_path
+        .iter()
+        .try_fold(&value, |node, key| node.get(key))
         .and_then(|v| v.as_object())
         .cloned()
         .unwrap_or_default();
@@ -40,8 +49,8 @@ fn parse_mcp_servers_json(raw: &str, source: &str) -> Result<Vec<McpServerConfig
 
 /// Parse VS Code settings.json with nested "mcp" -> "servers" key.
 fn parse_vscode_config(raw: &str) -> Result<Vec<McpServerConfig>> {
-    let value: serde_json::Value =
-        serde_json::from_str(raw).context("Failed to parse VS Code JSON config")?;
+    let value: serde_json::Value = jsonc::parse(raw)
+        .map_err(|e| ConductorError::invalid_client_config("vscode", "JSON", e))?;
 
     // VS Code stores MCP servers under "mcp" -> "servers"
     let servers_obj = value
@@ -62,8 +71,8 @@ fn parse_vscode_config(raw: &str) -> Result<Vec<McpServerConfig>> {
 
 /// Parse VS Code mcp.json with top-level "servers" key.
 fn parse_vscode_mcp_json(raw: &str) -> Result<Vec<McpServerConfig>> {
-    let value: serde_json::Value =
-        serde_json::from_str(raw).context("Failed to parse VS Code mcp.json config")?;
+    let value: serde_json::Value = jsonc::parse(raw)
+        .map_err(|e| ConductorError::invalid_client_config("vscode-mcp", "JSON", e))?;
 
     let servers_obj = value
         .get("servers")
@@ -83,8 +92,8 @@ fn parse_vscode_mcp_json(raw: &str) -> Result<Vec<McpServerConfig>> {
 /// Parse Zed editor settings.json with "context_servers" key.
 /// Zed wraps the command in a nested object structure.
 fn parse_zed_config(raw: &str) -> Result<Vec<McpServerConfig>> {
-    let value: serde_json::Value =
-        serde_json::from_str(raw).context("Failed to parse Zed JSON config")?;
+    let value: serde_json::Value = jsonc::parse(raw)
+        .map_err(|e| ConductorError::invalid_client_config("zed", "JSON", e))?;
 
     let servers_obj = value
         .get("context_servers")
@@ -166,6 +175,7 @@ fn parse_zed_config(raw: &str) -> Result<Vec<McpServerConfig>> {
                 .get("url")
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string()),
+            headers: extract_env_map(server_val.get("headers")),
             secret_env_keys: Vec::new(),
             icon_url: None,
             tags: Vec::new(),
@@ -175,6 +185,15 @@ fn parse_zed_config(raw: &str) -> Result<Vec<McpServerConfig>> {
             description: None,
             created_at: None,
             updated_at: None,
+            codex: None,
+            extra: unknown_fields(
+                "zed",
+                &server_val,
+                &["command", "args", "env", "url", "headers"],
+            ),
+            tool_filters: HashMap::new(),
+            inspect: false,
+            managed: false,
         });
     }
 
@@ -183,7 +202,8 @@ fn parse_zed_config(raw: &str) -> Result<Vec<McpServerConfig>> {
 
 /// Parse JetBrains MCP XML configuration.
 fn parse_jetbrains_config(raw: &str) -> Result<Vec<McpServerConfig>> {
-    let doc = roxmltree::Document::parse(raw).context("Failed to parse JetBrains XML config")?;
+    let doc = roxmltree::Document::parse(raw)
+        .map_err(|e| ConductorError::invalid_client_config("jetbrains", "XML", e))?;
 
     let mut servers = Vec::new();
 
@@ -234,6 +254,7 @@ fn parse_jetbrains_config(raw: &str) -> Result<Vec<McpServerConfig>> {
                 args,
                 env,
                 url: node.attribute("url").map(|s| s.to_string()),
+                headers: HashMap::new(),
                 secret_env_keys: Vec::new(),
                 icon_url: None,
                 tags: Vec::new(),
@@ -243,6 +264,15 @@ fn parse_jetbrains_config(raw: &str) -> Result<Vec<McpServerConfig>> {
                 description: None,
                 created_at: None,
                 updated_at: None,
+                codex: None,
+                extra: unknown_attributes(
+                    "jetbrains",
+                    node,
+                    &["name", "command", "args", "url", "enabled"],
+                ),
+                tool_filters: HashMap::new(),
+                inspect: false,
+                managed: false,
             });
         }
     }
@@ -255,7 +285,9 @@ fn parse_jetbrains_config(raw: &str) -> Result<Vec<McpServerConfig>> {
 /// - Named subtables: [mcp_servers.name] (official Codex format)
 /// - Array of tables: [[mcp_servers]] with name field (legacy)
 fn parse_codex_config(raw: &str) -> Result<Vec<McpServerConfig>> {
-    let value: toml_edit::DocumentMut = raw.parse().context("Failed to parse Codex TOML config")?;
+    let value: toml_edit::DocumentMut = raw
+        .parse()
+        .map_err(|e| ConductorError::invalid_client_config("codex", "TOML", e))?;
 
     let mut servers = Vec::new();
 
@@ -332,8 +364,9 @@ fn parse_codex_server_table(name: &str, table: &toml_edit::Table) -> McpServerCo
         }
     }
 
+    // Codex only speaks streamable HTTP to URL servers.
     let transport = if table.get("url").is_some() {
-        TransportType::Sse
+        TransportType::StreamableHttp
     } else {
         TransportType::Stdio
     };
@@ -353,6 +386,7 @@ fn parse_codex_server_table(name: &str, table: &toml_edit::Table) -> McpServerCo
             .get("url")
             .and_then(|v| v.as_str())
             .map(|s| s.to_string()),
+        headers: codex_http_headers(table),
         secret_env_keys: Vec::new(),
         icon_url: None,
         tags: Vec::new(),
@@ -362,6 +396,11 @@ fn parse_codex_server_table(name: &str, table: &toml_edit::Table) -> McpServerCo
         description: None,
         created_at: None,
         updated_at: None,
+        codex: parse_codex_options(table),
+        extra: HashMap::new(),
+        tool_filters: HashMap::new(),
+        inspect: false,
+        managed: false,
     }
 }
 
@@ -399,7 +438,7 @@ fn parse_codex_server_inline(name: &str, table: &toml_edit::InlineTable) -> McpS
             .and_then(|v| v.as_bool())
             .unwrap_or(true),
         transport: if table.get("url").is_some() {
-            TransportType::Sse
+            TransportType::StreamableHttp
         } else {
             TransportType::Stdio
         },
@@ -410,6 +449,7 @@ fn parse_codex_server_inline(name: &str, table: &toml_edit::InlineTable) -> McpS
             .get("url")
             .and_then(|v| v.as_str())
             .map(|s| s.to_string()),
+        headers: codex_http_headers(table),
         secret_env_keys: Vec::new(),
         icon_url: None,
         tags: Vec::new(),
@@ -419,9 +459,54 @@ fn parse_codex_server_inline(name: &str, table: &toml_edit::InlineTable) -> McpS
         description: None,
         created_at: None,
         updated_at: None,
+        codex: parse_codex_options(table),
+        extra: HashMap::new(),
+        tool_filters: HashMap::new(),
+        inspect: false,
+        managed: false,
     }
 }
 
+/// Codex-only server fields. `None` when the entry sets none of them.
+fn parse_codex_options(table: &dyn toml_edit::TableLike) -> Option<CodexServerOptions> {
+    let seconds = |key: &str| {
+        let value = table.get(key)?;
+        value
+            .as_float()
+            .or_else(|| value.as_integer().map(|i| i as f64))
+    };
+    let string = |key: &str| table.get(key)?.as_str().map(|s| s.to_string());
+    let list = |key: &str| {
+        table.get(key)?.as_array().map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    let options = CodexServerOptions {
+        startup_timeout_sec: seconds("startup_timeout_sec"),
+        tool_timeout_sec: seconds("tool_timeout_sec"),
+        bearer_token_env_var: string("bearer_token_env_var"),
+        cwd: string("cwd"),
+        enabled_tools: list("enabled_tools"),
+        disabled_tools: list("disabled_tools"),
+    };
+    (options != CodexServerOptions::default()).then_some(options)
+}
+
+/// Keys of a standard JSON server entry that map onto `McpServerConfig`.
+const JSON_SERVER_FIELDS: &[&str] = &[
+    "command",
+    "args",
+    "env",
+    "url",
+    "headers",
+    "type",
+    "transport",
+    "disabled",
+];
+
 /// Convert a JSON value into an McpServerConfig.
 fn json_value_to_server(
     name: &str,
@@ -450,13 +535,13 @@ fn json_value_to_server(
         .and_then(|v| v.as_str())
         .map(|s| s.to_string());
 
+    // `transport` is the older spelling; Claude Code and VS Code use `type`.
+    let declared = value
+        .get("type")
+        .or_else(|| value.get("transport"))
+        .and_then(|v| v.as_str());
     let transport = if url.is_some() {
-        if value
-            .get("transport")
-            .and_then(|v| v.as_str())
-            .map(|s| s == "streamable-http")
-            .unwrap_or(false)
-        {
+        if matches!(declared, Some("streamable-http" | "http")) {
             TransportType::StreamableHttp
         } else {
             TransportType::Sse
@@ -479,6 +564,7 @@ fn json_value_to_server(
         args,
         env,
         url,
+        headers: extract_env_map(value.get("headers")),
         secret_env_keys: Vec::new(),
         icon_url: None,
         tags: Vec::new(),
@@ -488,10 +574,289 @@ fn json_value_to_server(
         description: None,
         created_at: None,
         updated_at: None,
+        codex: None,
+        extra: unknown_fields(source, value, JSON_SERVER_FIELDS),
+        tool_filters: HashMap::new(),
+        inspect: false,
+        managed: false,
     })
 }
 
-/// Extract environment variables from a JSON Value.
+/// Parse Continue's config.yaml: `mcpServers` is a list of blocks, each with
+/// its own `name`.
+fn parse_continue_config(raw: &str) -> Result<Vec<McpServerConfig>> {
+    let value: serde_json::Value = if raw.trim().is_empty() {
+        serde_json::Value::Null
+    } else {
+        serde_yaml::from_str(raw)
+            .map_err(|e| ConductorError::invalid_client_config("continue", "YAML", e))?
+    };
+
+    let mut servers = Vec::new();
+    let entries = value.get("mcpServers").and_then(|v| v.as_array());
+    for entry in entries.into_iter().flatten() {
+        let Some(name) = entry.get("name").and_then(|n| n.as_str()) else {
+            continue;
+        };
+        let mut server = json_value_to_server(name, entry, "continue")?;
+        // Continue keeps headers under `requestOptions`, next to timeouts
+        // and TLS settings that stay client-only.
+        if let Some(extra) = server.extra.get_mut("continue") {
+            let options = extra.get_mut("requestOptions").and_then(|o| o.as_object_mut());
+            if let Some(headers) = options.and_then(|o| o.remove("headers")) {
+                server.headers = extract_env_map(Some(&headers));
+            }
+            if extra
+                .get("requestOptions")
+                .and_then(|o| o.as_object())
+                .is_some_and(|o| o.is_empty())
+            {
+                extra.remove("requestOptions");
+            }
+        }
+        if server.url.is_some()
+            && entry.get("type").and_then(|t| t.as_str()) == Some("streamable-http")
+        {
+            server.transport = TransportType::StreamableHttp;
+        }
+        servers.push(server);
+    }
+
+    Ok(servers)
+}
+
+/// Parse Gemini CLI settings.json. Remote servers use `url` for SSE and
+/// `httpUrl` for streamable HTTP.
+fn parse_gemini_config(raw: &str) -> Result<Vec<McpServerConfig>> {
+    let value: serde_json::Value = jsonc::parse(raw)
+        .map_err(|e| ConductorError::invalid_client_config("gemini", "JSON", e))?;
+
+    let servers_obj = value
+        .get("mcpServers")
+        .and_then(|v| v.as_object())
+        .cloned()
+        .unwrap_or_default();
+
+    let mut servers = Vec::new();
+    for (name, server_val) in servers_obj {
+        let mut server = json_value_to_server(&name, &server_val, "gemini")?;
+        if let Some(http_url) = server_val.get("httpUrl").and_then(|v| v.as_str()) {
+            server.url = Some(http_url.to_string());
+            server.transport = TransportType::StreamableHttp;
+        }
+        if let Some(extra) = server.extra.get_mut("gemini") {
+            extra.remove("httpUrl");
+        }
+        servers.push(server);
+    }
+
+    Ok(servers)
+}
+
+/// Keys of a Goose extension that map onto `McpServerConfig`. The rest
+/// (`timeout`, `bundled`, `available_tools`, ...) is kept in `extra`.
+const GOOSE_FIELDS: &[&str] = &[
+    "name",
+    "enabled",
+    "type",
+    "cmd",
+    "args",
+    "envs",
+    "uri",
+    "headers",
+    "description",
+];
+
+/// Parse Goose config.yaml. MCP servers are `extensions` of type `stdio`,
+/// `sse` or `streamable_http`; built-in extensions are skipped.
+fn parse_goose_config(raw: &str) -> Result<Vec<McpServerConfig>> {
+    let value: serde_json::Value = if raw.trim().is_empty() {
+        serde_json::Value::Null
+    } else {
+        serde_yaml::from_str(raw)
+            .map_err(|e| ConductorError::invalid_client_config("goose", "YAML", e))?
+    };
+
+    let extensions = value
+        .get("extensions")
+        .and_then(|v| v.as_object())
+        .cloned()
+        .unwrap_or_default();
+
+    let mut servers = Vec::new();
+    for (name, ext) in extensions {
+        let transport = match ext.get("type").and_then(|t| t.as_str()) {
+            Some("stdio") => TransportType::Stdio,
+            Some("sse") => TransportType::Sse,
+            Some("streamable_http") => TransportType::StreamableHttp,
+            _ => continue,
+        };
+        let get_str = |key: &str| ext.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+        let args = ext
+            .get("args")
+            .and_then(|a| a.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        servers.push(McpServerConfig {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            enabled: ext.get("enabled").and_then(|v| v.as_bool()).unwrap_or(true),
+            transport,
+            command: get_str("cmd"),
+            args,
+            env: extract_env_map(ext.get("envs")),
+            url: get_str("uri"),
+            headers: extract_env_map(ext.get("headers")),
+            secret_env_keys: Vec::new(),
+            icon_url: None,
+            tags: Vec::new(),
+            source: Some("goose".to_string()),
+            registry_id: None,
+            display_name: None,
+            description: get_str("description").filter(|d| !d.is_empty()),
+            created_at: None,
+            updated_at: None,
+            codex: None,
+            extra: unknown_fields("goose", &ext, GOOSE_FIELDS),
+            tool_filters: HashMap::new(),
+            inspect: false,
+            managed: false,
+        });
+    }
+
+    Ok(servers)
+}
+
+/// Parse opencode.json. Local servers keep the program and its arguments in
+/// one `command` array; remote servers have a `url`.
+fn parse_opencode_config(raw: &str) -> Result<Vec<McpServerConfig>> {
+    let value: serde_json::Value = jsonc::parse(raw)
+        .map_err(|e| ConductorError::invalid_client_config("opencode", "JSON", e))?;
+
+    let servers_obj = value
+        .get("mcp")
+        .and_then(|v| v.as_object())
+        .cloned()
+        .unwrap_or_default();
+
+    let mut servers = Vec::new();
+    for (name, server_val) in servers_obj {
+        let mut command_line = server_val
+            .get("command")
+            .and_then(|c| c.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+            .into_iter();
+        let url = server_val
+            .get("url")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let transport = match server_val.get("type").and_then(|t| t.as_str()) {
+            Some("remote") => TransportType::StreamableHttp,
+            _ if url.is_some() => TransportType::StreamableHttp,
+            _ => TransportType::Stdio,
+        };
+
+        servers.push(McpServerConfig {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            enabled: server_val
+                .get("enabled")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true),
+            transport,
+            command: command_line.next(),
+            args: command_line.collect(),
+            env: extract_env_map(server_val.get("environment")),
+            url,
+            headers: extract_env_map(server_val.get("headers")),
+            secret_env_keys: Vec::new(),
+            icon_url: None,
+            tags: Vec::new(),
+            source: Some("opencode".to_string()),
+            registry_id: None,
+            display_name: None,
+            description: None,
+            created_at: None,
+            updated_at: None,
+            codex: None,
+            extra: unknown_fields(
+                "opencode",
+                &server_val,
+                &["type", "command", "environment", "url", "headers", "enabled"],
+            ),
+            tool_filters: HashMap::new(),
+            inspect: false,
+            managed: false,
+        });
+    }
+
+    Ok(servers)
+}
+
+/// Fields of `entry` outside `known`, stored under `client_id` in
+/// `McpServerConfig::extra` so a re-sync can write them back.
+fn unknown_fields(
+    client_id: &str,
+    entry: &serde_json::Value,
+    known: &[&str],
+) -> HashMap<String, serde_json::Map<String, serde_json::Value>> {
+    let fields: serde_json::Map<String, serde_json::Value> = entry
+        .as_object()
+        .map(|obj| {
+            obj.iter()
+                .filter(|(key, _)| !known.contains(&key.as_str()))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect()
+        })
+        .unwrap_or_default();
+    if fields.is_empty() {
+        HashMap::new()
+    } else {
+        HashMap::from([(client_id.to_string(), fields)])
+    }
+}
+
+/// Attributes of `node` outside `known`, stored under `client_id` in
+/// `McpServerConfig::extra` like [`unknown_fields`].
+fn unknown_attributes(
+    client_id: &str,
+    node: roxmltree::Node,
+    known: &[&str],
+) -> HashMap<String, serde_json::Map<String, serde_json::Value>> {
+    let fields: serde_json::Map<String, serde_json::Value> = node
+        .attributes()
+        .filter(|a| !known.contains(&a.name()))
+        .map(|a| (a.name().to_string(), serde_json::json!(a.value())))
+        .collect();
+    if fields.is_empty() {
+        HashMap::new()
+    } else {
+        HashMap::from([(client_id.to_string(), fields)])
+    }
+}
+
+/// A Codex entry's `http_headers` table, inline or not.
+fn codex_http_headers(table: &dyn toml_edit::TableLike) -> HashMap<String, String> {
+    let Some(table) = table.get("http_headers").and_then(|i| i.as_table_like()) else {
+        return HashMap::new();
+    };
+    table
+        .iter()
+        .filter_map(|(key, value)| Some((key.to_string(), value.as_str()?.to_string())))
+        .collect()
+}
+
+/// Extract a string map (environment variables, headers) from a JSON Value.
 fn extract_env_map(env_val: Option<&serde_json::Value>) -> HashMap<String, String> {
     let mut env = HashMap::new();
     if let Some(obj) = env_val.and_then(|v| v.as_object()) {
@@ -505,3 +870,382 @@ fn extract_env_map(env_val: Option<&serde_json::Value>) -> HashMap<String, Strin
     }
     env
 }
+
+/// Parse a client config described by an `AdapterSpec`.
+pub fn parse_with_spec(spec: &AdapterSpec, raw: &str) -> Result<Vec<McpServerConfig>> {
+    if spec.format == SpecFormat::Xml {
+        return parse_xml_with_spec(spec, raw);
+    }
+
+    let root = parse_document(spec, raw)?;
+    let mut current = &root;
+    for segment in spec.pointer_segments() {
+        match current.get(&segment) {
+            Some(next) => current = next,
+            None => return Ok(Vec::new()),
+        }
+    }
+
+    let mut servers = Vec::new();
+    if let Some(entries) = current.as_object() {
+        for (name, value) in entries {
+            servers.push(spec_entry_to_server(spec, name, value));
+        }
+    }
+    Ok(servers)
+}
+
+/// Parse a JSON, JSONC, YAML or TOML document into a JSON value so specs can
+/// address it with a JSON pointer. Empty documents read as `{}`.
+pub fn parse_document(spec: &AdapterSpec, raw: &str) -> Result<serde_json::Value> {
+    let invalid = |e: &dyn std::fmt::Display| {
+        ConductorError::invalid_client_config(&spec.id, spec.format.label(), e)
+    };
+    if raw.trim().is_empty() {
+        return Ok(serde_json::json!({}));
+    }
+    let value: serde_json::Value = match spec.format {
+        SpecFormat::Json | SpecFormat::Jsonc => jsonc::parse(raw).map_err(|e| invalid(&e))?,
+        SpecFormat::Yaml => serde_yaml::from_str(raw).map_err(|e| invalid(&e))?,
+        SpecFormat::Toml => toml_edit::de::from_str(raw).map_err(|e| invalid(&e))?,
+        SpecFormat::Xml => anyhow::bail!("XML configs are not addressable by JSON pointer"),
+    };
+    Ok(if value.is_null() {
+        serde_json::json!({})
+    } else {
+        value
+    })
+}
+
+fn spec_entry_to_server(spec: &AdapterSpec, name: &str, value: &serde_json::Value) -> McpServerConfig {
+    let fields = &spec.fields;
+    let get_str = |key: &str| value.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+
+    let args = value
+        .get(&fields.args)
+        .and_then(|a| a.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let url = get_str(&fields.url);
+    let transport = if url.is_some() {
+        let declared = fields.transport.as_deref().and_then(get_str);
+        if declared.is_some() && declared == spec.transport.streamable_http_value {
+            TransportType::StreamableHttp
+        } else {
+            TransportType::Sse
+        }
+    } else {
+        TransportType::Stdio
+    };
+
+    let flag = |key: &Option<String>| {
+        key.as_deref()
+            .and_then(|k| value.get(k))
+            .and_then(|v| v.as_bool())
+    };
+    let enabled = flag(&fields.enabled).unwrap_or(true) && !flag(&fields.disabled).unwrap_or(false);
+
+    let mut known = vec![
+        fields.command.as_str(),
+        fields.args.as_str(),
+        fields.env.as_str(),
+        fields.url.as_str(),
+        fields.headers.as_str(),
+    ];
+    known.extend(
+        [&fields.transport, &fields.enabled, &fields.disabled]
+            .into_iter()
+            .flatten()
+            .map(|k| k.as_str()),
+    );
+
+    McpServerConfig {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        enabled,
+        transport,
+        command: get_str(&fields.command),
+        args,
+        env: extract_env_map(value.get(&fields.env)),
+        url,
+        headers: extract_env_map(value.get(&fields.headers)),
+        secret_env_keys: Vec::new(),
+        icon_url: None,
+        tags: Vec::new(),
+        source: Some(spec.id.clone()),
+        registry_id: None,
+        display_name: None,
+        description: None,
+        created_at: None,
+        updated_at: None,
+        codex: None,
+        extra: unknown_fields(&spec.id, value, &known),
+        tool_filters: HashMap::new(),
+        inspect: false,
+        managed: false,
+    }
+}
+
+fn parse_xml_with_spec(spec: &AdapterSpec, raw: &str) -> Result<Vec<McpServerConfig>> {
+    let doc = roxmltree::Document::parse(raw)
+        .map_err(|e| ConductorError::invalid_client_config(&spec.id, "XML", e))?;
+    let layout = &spec.xml;
+    let fields = &spec.fields;
+
+    let mut servers = Vec::new();
+    for node in doc
+        .descendants()
+        .filter(|n| n.tag_name().name() == layout.server_element)
+    {
+        let args: Vec<String> = node
+            .attribute(fields.args.as_str())
+            .map(|a| a.split_whitespace().map(|s| s.to_string()).collect())
+            .unwrap_or_default();
+
+        let mut env = HashMap::new();
+        for container in node
+            .children()
+            .filter(|c| c.tag_name().name() == layout.env_container)
+        {
+            for entry in container
+                .children()
+                .filter(|c| c.tag_name().name() == layout.env_element)
+            {
+                if let (Some(key), Some(val)) = (entry.attribute("name"), entry.attribute("value")) {
+                    env.insert(key.to_string(), val.to_string());
+                }
+            }
+        }
+
+        let url = node.attribute(fields.url.as_str()).map(|s| s.to_string());
+        let mut known = vec![
+            layout.name_attribute.as_str(),
+            fields.command.as_str(),
+            fields.args.as_str(),
+            fields.url.as_str(),
+        ];
+        known.extend(layout.enabled_attribute.as_deref());
+        servers.push(McpServerConfig {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: node
+                .attribute(layout.name_attribute.as_str())
+                .unwrap_or("unnamed")
+                .to_string(),
+            enabled: layout
+                .enabled_attribute
+                .as_deref()
+                .and_then(|a| node.attribute(a))
+                .map(|v| v == "true")
+                .unwrap_or(true),
+            transport: if url.is_some() {
+                TransportType::Sse
+            } else {
+                TransportType::Stdio
+            },
+            command: node.attribute(fields.command.as_str()).map(|s| s.to_string()),
+            args,
+            env,
+            url,
+            headers: HashMap::new(),
+            secret_env_keys: Vec::new(),
+            icon_url: None,
+            tags: Vec::new(),
+            source: Some(spec.id.clone()),
+            registry_id: None,
+            display_name: None,
+            description: None,
+            created_at: None,
+            updated_at: None,
+            codex: None,
+            extra: unknown_attributes(&spec.id, node, &known),
+            tool_filters: HashMap::new(),
+            inspect: false,
+            managed: false,
+        });
+    }
+
+    Ok(servers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::serializer;
+
+    /// Parses `raw` as `client_id`'s config, writes the servers back as a new
+    /// file and parses that, returning both parses and the file.
+    fn round_trip(
+        client_id: &str,
+        raw: &str,
+    ) -> (Vec<McpServerConfig>, String, Vec<McpServerConfig>) {
+        let first = parse_client_config(client_id, raw).unwrap();
+        let written = serializer::serialize_to_client_format(
+            client_id,
+            &first,
+            None,
+            &[],
+            &serializer::FallbackSettings::default(),
+        )
+        .unwrap();
+        let second = parse_client_config(client_id, &written).unwrap();
+        (first, written, second)
+    }
+
+    /// Everything Conductor models or keeps in `extra` survives the trip.
+    fn assert_kept(first: &[McpServerConfig], second: &[McpServerConfig]) {
+        assert_eq!(first.len(), second.len());
+        for before in first {
+            let after = second.iter().find(|s| s.name == before.name).unwrap();
+            assert_eq!(after.transport, before.transport, "{}", before.name);
+            assert_eq!(after.command, before.command, "{}", before.name);
+            assert_eq!(after.args, before.args, "{}", before.name);
+            assert_eq!(after.env, before.env, "{}", before.name);
+            assert_eq!(after.url, before.url, "{}", before.name);
+            assert_eq!(after.headers, before.headers, "{}", before.name);
+            assert_eq!(after.extra, before.extra, "{}", before.name);
+        }
+    }
+
+    fn headers(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn json_headers_are_a_field_not_extra() {
+        let raw = r#"{
+  "mcpServers": {
+    "api": {
+      "type": "http",
+      "url": "https://api.example.com/mcp",
+      "headers": { "Authorization": "Bearer abc", "X-Team": "core" },
+      "timeout": 30
+    },
+    "local": { "command": "npx", "args": ["-y", "srv"], "envFile": ".env" }
+  }
+}"#;
+        let (first, written, second) = round_trip("claude-code", raw);
+        let api = first.iter().find(|s| s.name == "api").unwrap();
+        assert_eq!(
+            api.headers,
+            headers(&[("Authorization", "Bearer abc"), ("X-Team", "core")])
+        );
+        assert!(!api.extra["claude-code"].contains_key("headers"));
+        assert_eq!(api.extra["claude-code"]["timeout"], 30);
+        assert!(written.contains("envFile"));
+        assert_kept(&first, &second);
+    }
+
+    #[test]
+    fn zed_opencode_and_gemini_keep_headers() {
+        let zed = r#"{ "context_servers": { "api": {
+            "url": "https://api.example.com/mcp",
+            "headers": { "X-Api-Key": "k" },
+            "settings": { "depth": 2 }
+        } } }"#;
+        let opencode = r#"{ "mcp": { "api": {
+            "type": "remote",
+            "url": "https://api.example.com/mcp",
+            "headers": { "X-Api-Key": "k" },
+            "oauth": false
+        } } }"#;
+        let gemini = r#"{ "mcpServers": { "api": {
+            "httpUrl": "https://api.example.com/mcp",
+            "headers": { "X-Api-Key": "k" },
+            "trust": true
+        } } }"#;
+        let zed = parse_client_config("zed", zed).unwrap();
+        assert_eq!(zed[0].headers, headers(&[("X-Api-Key", "k")]));
+        assert_eq!(zed[0].extra["zed"]["settings"]["depth"], 2);
+        for (client_id, raw) in [("opencode", opencode), ("gemini", gemini)] {
+            let (first, _, second) = round_trip(client_id, raw);
+            assert_eq!(first[0].headers, headers(&[("X-Api-Key", "k")]), "{}", client_id);
+            assert_kept(&first, &second);
+        }
+    }
+
+    #[test]
+    fn continue_headers_come_out_of_request_options() {
+        let raw = "\
+mcpServers:
+  - name: api
+    type: streamable-http
+    url: https://api.example.com/mcp
+    requestOptions:
+      headers:
+        Authorization: Bearer abc
+";
+        let (first, written, second) = round_trip("continue", raw);
+        assert_eq!(first[0].headers, headers(&[("Authorization", "Bearer abc")]));
+        assert!(!first[0].extra["continue"].contains_key("requestOptions"));
+        assert!(written.contains("Authorization: Bearer abc"));
+        assert_kept(&first, &second);
+    }
+
+    #[test]
+    fn codex_keeps_http_headers() {
+        let raw = r#"
+[mcp_servers.api]
+url = "https://api.example.com/mcp"
+http_headers = { "X-Team" = "core" }
+"#;
+        let (first, written, second) = round_trip("codex", raw);
+        assert_eq!(first[0].headers, headers(&[("X-Team", "core")]));
+        assert!(written.contains("http_headers"));
+        assert_kept(&first, &second);
+    }
+
+    #[test]
+    fn goose_keeps_headers_and_unknown_fields() {
+        let raw = "\
+extensions:
+  api:
+    name: api
+    enabled: true
+    type: streamable_http
+    uri: https://api.example.com/mcp
+    headers:
+      X-Team: core
+    timeout: 60
+    available_tools: [search]
+  local:
+    name: local
+    enabled: true
+    type: stdio
+    cmd: npx
+    args: [-y, srv]
+    envs: {}
+    timeout: 300
+    bundled: false
+";
+        let (first, written, second) = round_trip("goose", raw);
+        let api = first.iter().find(|s| s.name == "api").unwrap();
+        assert_eq!(api.headers, headers(&[("X-Team", "core")]));
+        assert_eq!(api.extra["goose"]["timeout"], 60);
+        assert!(written.contains("available_tools"));
+        assert!(written.contains("bundled: false"));
+        assert_kept(&first, &second);
+    }
+
+    #[test]
+    fn jetbrains_keeps_unknown_attributes() {
+        let raw = r#"<?xml version="1.0" encoding="UTF-8"?>
+<mcpSettings version="1">
+  <servers>
+    <serverConfiguration name="local" command="npx" args="-y srv" enabled="true"
+                         workingDirectory="/work" />
+  </servers>
+</mcpSettings>"#;
+        let (first, written, second) = round_trip("jetbrains", raw);
+        assert_eq!(first[0].extra["jetbrains"]["workingDirectory"], "/work");
+        assert!(written.contains(r#"workingDirectory="/work""#));
+        assert_kept(&first, &second);
+    }
+}
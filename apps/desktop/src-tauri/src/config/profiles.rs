@@ -0,0 +1,115 @@
+//! Named server sets ("work", "personal", "client-x").
+//!
+//! The active profile's set is what the servers' `enabled` flags hold while
+//! it is active; it is copied back into the profile when another one is
+//! activated. Each profile also has its own variables and keychain namespace:
+//! server secrets saved while it is active are stored under
+//! `profile:<id>/<server-id>:<key>` and read from there before the shared
+//! `<server-id>:<key>` entry. A profile bound to project directories is used
+//! for client configs inside them (such as Claude Code's project
+//! `.mcp.json`) whichever profile is active.
+
+use super::McpConfig;
+use crate::errors::ConductorError;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    /// IDs of the servers enabled while this profile is active.
+    #[serde(default)]
+    pub servers: Vec<String>,
+    /// Variables that take precedence over the global ones.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub variables: HashMap<String, String>,
+    /// Names of this profile's variables whose values live in its keychain
+    /// namespace.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub secret_variables: Vec<String>,
+    /// Project directories whose client configs get this profile's servers.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub projects: Vec<String>,
+}
+
+impl Profile {
+    /// Keychain username of `username` in this profile's namespace.
+    pub fn keychain_username(&self, username: &str) -> String {
+        format!("profile:{}/{}", self.id, username)
+    }
+}
+
+pub fn active(cfg: &McpConfig) -> Option<&Profile> {
+    let id = cfg.active_profile.as_deref()?;
+    cfg.profiles.iter().find(|p| p.id == id)
+}
+
+/// The profile bound to the project containing `path`; the innermost
+/// binding wins when projects are nested.
+pub fn bound_to<'a>(cfg: &'a McpConfig, path: &Path) -> Option<&'a Profile> {
+    cfg.profiles
+        .iter()
+        .flat_map(|p| p.projects.iter().map(move |dir| (p, Path::new(dir))))
+        .filter(|(_, dir)| !dir.as_os_str().is_empty() && path.starts_with(dir))
+        .max_by_key(|(_, dir)| dir.components().count())
+        .map(|(p, _)| p)
+}
+
+/// Makes `profile_id` the active profile (or none), saving the current
+/// enabled set into the profile being left and applying the new one's.
+pub fn activate(cfg: &mut McpConfig, profile_id: Option<&str>) -> Result<()> {
+    if let Some(id) = profile_id {
+        if !cfg.profiles.iter().any(|p| p.id == id) {
+            return Err(ConductorError::InvalidInput(format!("Profile not found: {}", id)).into());
+        }
+    }
+    let enabled: Vec<String> = cfg
+        .servers
+        .iter()
+        .filter(|s| s.enabled)
+        .map(|s| s.id.clone())
+        .collect();
+    if let Some(current) = cfg.active_profile.as_deref() {
+        if let Some(profile) = cfg.profiles.iter_mut().find(|p| p.id == current) {
+            profile.servers = enabled;
+        }
+    }
+    if let Some(profile) = profile_id.and_then(|id| cfg.profiles.iter().find(|p| p.id == id)) {
+        for server in &mut cfg.servers {
+            server.enabled = profile.servers.contains(&server.id);
+        }
+    }
+    cfg.active_profile = profile_id.map(str::to_string);
+    Ok(())
+}
+
+/// Reads a server secret (`<server-id>:<key>`) from `profile`'s namespace,
+/// falling back to the shared entry. Fails if the secret store can't be read.
+pub fn read_secret(
+    profile: Option<&Profile>,
+    username: &str,
+) -> Result<Option<String>, ConductorError> {
+    let store = crate::secrets::store();
+    for username in profile
+        .map(|p| p.keychain_username(username))
+        .into_iter()
+        .chain(std::iter::once(username.to_string()))
+    {
+        if let Some(value) = store.get(&username)? {
+            return Ok(Some(value));
+        }
+    }
+    Ok(None)
+}
+
+/// Where a server secret is written: `profile`'s namespace if there is one.
+pub fn secret_username(profile: Option<&Profile>, username: &str) -> String {
+    match profile {
+        Some(p) => p.keychain_username(username),
+        None => username.to_string(),
+    }
+}
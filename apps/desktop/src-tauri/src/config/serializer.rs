@@ -1,67 +1,202 @@
-use crate::config::{McpServerConfig, TransportType};
+use crate::clients::capabilities::{self, RemoteCapabilities};
+use crate::clients::spec::{AdapterSpec, SpecFormat};
+use crate::config::{
+    jsonc, normalizer, AppSettings, McpServerConfig, RemoteFallback, TransportType,
+};
+use crate::errors::ConductorError;
 use anyhow::{Context, Result};
 
-/// Resolve the full path to `npx` once per process. Falls back to `"npx"`.
-fn find_npx_path() -> &'static str {
-    static NPX_PATH: std::sync::OnceLock<String> = std::sync::OnceLock::new();
-    NPX_PATH.get_or_init(|| {
-        std::process::Command::new("which")
-            .arg("npx")
-            .output()
-            .ok()
-            .filter(|o| o.status.success())
-            .and_then(|o| String::from_utf8(o.stdout).ok())
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .unwrap_or_else(|| "npx".to_string())
-    })
+/// Resolve the full path to `npx` once per process.
+fn find_npx_path() -> Option<&'static str> {
+    static NPX_PATH: std::sync::OnceLock<Option<String>> = std::sync::OnceLock::new();
+    NPX_PATH
+        .get_or_init(|| {
+            std::process::Command::new("which")
+                .arg("npx")
+                .output()
+                .ok()
+                .filter(|o| o.status.success())
+                .and_then(|o| String::from_utf8(o.stdout).ok())
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        })
+        .as_deref()
+}
+
+/// How entries reach remote servers a client can't read natively: the
+/// `remote_fallback` setting and the `remote_proxy_command` it may use.
+/// Passed in by the caller, which has the config already loaded.
+#[derive(Debug, Clone, Default)]
+pub struct FallbackSettings {
+    pub mode: RemoteFallback,
+    pub proxy_command: Vec<String>,
+}
+
+impl FallbackSettings {
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        Self {
+            mode: settings.remote_fallback,
+            proxy_command: settings.remote_proxy_command.clone(),
+        }
+    }
+}
+
+/// Command line of a stdio entry that reaches a remote server for a client
+/// that can't read it natively, per the `remote_fallback` setting.
+fn remote_fallback_command(
+    client_id: &str,
+    server: &McpServerConfig,
+    fallback: &FallbackSettings,
+) -> Result<(String, Vec<String>)> {
+    let url = server.url.as_deref().unwrap_or_default();
+    let token = bearer_token(server);
+    let headers = remote_headers(server);
+    let unavailable = |reason: &str| ConductorError::RemoteFallbackUnavailable {
+        client_id: client_id.to_string(),
+        reason: reason.to_string(),
+    };
+
+    match fallback.mode {
+        RemoteFallback::McpRemote => {
+            let npx = find_npx_path().ok_or_else(|| unavailable("npx was not found on PATH"))?;
+            let mut args = vec!["-y".to_string(), "mcp-remote".to_string(), url.to_string()];
+            for (name, value) in &headers {
+                args.push("--header".to_string());
+                args.push(format!("{}:{}", name, value.as_str().unwrap_or_default()));
+            }
+            Ok((npx.to_string(), args))
+        }
+        RemoteFallback::CustomProxy => {
+            let (program, template) = fallback
+                .proxy_command
+                .split_first()
+                .ok_or_else(|| unavailable("no custom proxy command is set"))?;
+            let args = template
+                .iter()
+                .filter(|arg| token.is_some() || !arg.contains("{token}"))
+                .map(|arg| {
+                    arg.replace("{url}", url)
+                        .replace("{token}", token.unwrap_or_default())
+                })
+                .collect();
+            Ok((program.clone(), args))
+        }
+        RemoteFallback::Conductor => {
+            bridge_command(server).map_err(|e| unavailable(&e.to_string()).into())
+        }
+    }
+}
+
+/// `conductor bridge` command line for a remote server. The bridge looks the
+/// server up by ID (a synced name can be changed by the name conflict
+/// policy) and reads its token from the secret store, so the token never
+/// lands in a client's config. The name is only passed for its messages.
+pub(crate) fn bridge_command(server: &McpServerConfig) -> std::io::Result<(String, Vec<String>)> {
+    let exe = std::env::current_exe()?;
+    let mut args = vec!["bridge".to_string()];
+    // The gateway and managed servers are local endpoints with no
+    // credentials of their own to look up.
+    if server.id != crate::gateway::GATEWAY_SERVER_ID && !server.managed {
+        args.push("--server-id".to_string());
+        args.push(server.id.clone());
+        args.push("--server".to_string());
+        args.push(server.name.clone());
+    }
+    if server.transport == TransportType::Sse {
+        args.push("--transport".to_string());
+        args.push("sse".to_string());
+    }
+    args.push(server.url.clone().unwrap_or_default());
+    Ok((exe.to_string_lossy().to_string(), args))
+}
+
+/// Rewrites `server` as a stdio entry running `conductor <args> --
+/// <original command>`, for Conductor's stdio wrappers (`filter`, `tap`).
+/// Remote servers run through the bridge, which reads its own token, so none
+/// is written into the entry.
+pub(crate) fn wrap_with_conductor(server: &mut McpServerConfig, args: &[String]) -> Result<()> {
+    let (program, program_args) = match server.transport {
+        TransportType::Stdio => (
+            server
+                .command
+                .clone()
+                .context("Server has no command to run")?,
+            server.args.clone(),
+        ),
+        _ => {
+            server.env.remove("OAUTH_TOKEN");
+            bridge_command(server).context("Failed to locate the Conductor executable")?
+        }
+    };
+    let exe = std::env::current_exe().context("Failed to locate the Conductor executable")?;
+
+    let mut wrapped = args.to_vec();
+    wrapped.push("--".to_string());
+    wrapped.push(program);
+    wrapped.extend(program_args);
+
+    server.transport = TransportType::Stdio;
+    server.command = Some(exe.to_string_lossy().to_string());
+    server.args = wrapped;
+    server.url = None;
+    Ok(())
 }
 
 /// Serialize servers into the format expected by a specific client.
 /// If `existing_content` is provided, the serializer preserves non-MCP settings.
 /// `previously_synced_names` is the cumulative set of all server names Conductor
 /// has ever synced to this client — used to remove orphaned servers during merge.
+/// `fallback` says how to reach remote servers the client can't read natively.
 pub fn serialize_to_client_format(
     client_id: &str,
     servers: &[McpServerConfig],
     existing_content: Option<&str>,
     previously_synced_names: &[String],
+    fallback: &FallbackSettings,
 ) -> Result<String> {
     match client_id {
-        "claude-desktop" | "cursor" | "windsurf" | "claude-code" => {
-            serialize_standard_json(client_id, servers, existing_content, previously_synced_names)
+        "claude-code" => serialize_json_servers_at(
+            client_id,
+            &["mcpServers"],
+            servers,
+            existing_content,
+            previously_synced_names,
+            fallback,
+        ),
+        "vscode" => serialize_vscode(servers, existing_content, previously_synced_names, fallback),
+        "vscode-mcp" => {
+            serialize_vscode_mcp(servers, existing_content, previously_synced_names, fallback)
         }
-        "vscode" => serialize_vscode(servers, existing_content, previously_synced_names),
-        "vscode-mcp" => serialize_vscode_mcp(servers, existing_content, previously_synced_names),
-        "zed" => serialize_zed(servers, existing_content, previously_synced_names),
-        "jetbrains" => serialize_jetbrains(servers),
-        "codex" => serialize_codex(servers, existing_content, previously_synced_names),
-        _ => Err(anyhow::anyhow!("Unknown client format: {}", client_id)),
+        "zed" => serialize_zed(servers, existing_content, previously_synced_names, fallback),
+        "jetbrains" => serialize_jetbrains(servers, fallback),
+        "codex" => serialize_codex(servers, existing_content, previously_synced_names, fallback),
+        "continue" => serialize_continue(servers, existing_content, previously_synced_names),
+        "gemini" => serialize_gemini(servers, existing_content, previously_synced_names),
+        "goose" => serialize_goose(servers, existing_content, previously_synced_names),
+        "opencode" => serialize_opencode(servers, existing_content, previously_synced_names),
+        _ => Err(ConductorError::ClientNotFound(client_id.to_string()).into()),
     }
 }
 
-/// Standard JSON format with top-level "mcpServers" key.
+/// Standard JSON format: servers keyed by name in the object at `key_path`
+/// (`["mcpServers"]` for most clients).
 /// Merges Conductor servers into existing content, preserving client-specific servers.
 /// Removes orphans: servers previously synced by Conductor but no longer expected.
-fn serialize_standard_json(
+pub fn serialize_json_servers_at(
     client_id: &str,
+    key_path: &[&str],
     servers: &[McpServerConfig],
     existing_content: Option<&str>,
     previously_synced_names: &[String],
+    fallback: &FallbackSettings,
 ) -> Result<String> {
-    let mut root: serde_json::Value = match existing_content {
-        Some(content) => serde_json::from_str(content).map_err(|e| {
-            anyhow::anyhow!(
-                "Client config has invalid JSON ({}). Fix it manually or delete and re-sync.",
-                e
-            )
-        })?,
+    let root: serde_json::Value = match existing_content {
+        Some(content) => jsonc::parse(content)
+            .map_err(|e| ConductorError::invalid_client_config(client_id, "JSON", e))?,
         None => serde_json::json!({}),
     };
 
-    // Claude Code and VS Code support native `headers`; others need mcp-remote proxy.
-    let use_proxy = !matches!(client_id, "claude-code");
-    let conductor_servers = servers_to_json_object(servers, use_proxy);
+    let conductor_servers = servers_to_json_object(servers, client_id, fallback)?;
     let conductor_names_lower: std::collections::HashSet<String> =
         servers.iter().map(|s| s.name.to_lowercase()).collect();
     let prev_synced_lower: std::collections::HashSet<String> =
@@ -70,7 +205,11 @@ fn serialize_standard_json(
     // Start with existing servers that are NOT managed by Conductor.
     // Skip orphans: servers previously synced by Conductor but no longer expected.
     let mut merged = serde_json::Map::new();
-    if let Some(existing) = root.get("mcpServers").and_then(|v| v.as_object()) {
+    let existing = key_path
+        .iter()
+        .try_fold(&root, |node, key| node.get(key))
+        .and_then(|v| v.as_object());
+    if let Some(existing) = existing {
         for (name, value) in existing {
             let name_lower = name.to_lowercase();
             let is_conductor = conductor_names_lower.contains(&name_lower);
@@ -85,34 +224,29 @@ fn serialize_standard_json(
         merged.insert(name, value);
     }
 
-    root["mcpServers"] = serde_json::Value::Object(merged);
-
-    serde_json::to_string_pretty(&root).context("Failed to serialize JSON")
+    Ok(jsonc::replace_object_at(existing_content, key_path, &merged)
+        .map_err(|e| ConductorError::invalid_client_config(client_id, "JSON", e))?)
 }
 
 /// VS Code format: preserves all non-mcp settings, merges into "mcp" -> "servers".
-fn serialize_vscode(servers: &[McpServerConfig], existing_content: Option<&str>, previously_synced_names: &[String]) -> Result<String> {
-    let mut root: serde_json::Value = match existing_content {
-        Some(content) => serde_json::from_str(content).map_err(|e| {
-            anyhow::anyhow!(
-                "VS Code settings has invalid JSON ({}). Fix it manually or delete and re-sync.",
-                e
-            )
-        })?,
+fn serialize_vscode(
+    servers: &[McpServerConfig],
+    existing_content: Option<&str>,
+    previously_synced_names: &[String],
+    fallback: &FallbackSettings,
+) -> Result<String> {
+    let root: serde_json::Value = match existing_content {
+        Some(content) => jsonc::parse(content)
+            .map_err(|e| ConductorError::invalid_client_config("vscode", "JSON", e))?,
         None => serde_json::json!({}),
     };
 
-    let conductor_servers = servers_to_json_object(servers, false); // VS Code supports native headers
+    let conductor_servers = servers_to_json_object(servers, "vscode", fallback)?;
     let conductor_names_lower: std::collections::HashSet<String> =
         servers.iter().map(|s| s.name.to_lowercase()).collect();
     let prev_synced_lower: std::collections::HashSet<String> =
         previously_synced_names.iter().map(|s| s.to_lowercase()).collect();
 
-    // Ensure "mcp" object exists and preserve other mcp settings
-    if root.get("mcp").is_none() {
-        root["mcp"] = serde_json::json!({});
-    }
-
     // Merge: keep truly user-added servers, skip Conductor-managed orphans
     let mut merged = serde_json::Map::new();
     if let Some(existing) = root
@@ -133,9 +267,8 @@ fn serialize_vscode(servers: &[McpServerConfig], existing_content: Option<&str>,
         merged.insert(name, value);
     }
 
-    root["mcp"]["servers"] = serde_json::Value::Object(merged);
-
-    serde_json::to_string_pretty(&root).context("Failed to serialize VS Code JSON")
+    Ok(jsonc::replace_object_at(existing_content, &["mcp", "servers"], &merged)
+        .map_err(|e| ConductorError::invalid_client_config("vscode", "JSON", e))?)
 }
 
 /// VS Code mcp.json format: top-level "servers" key.
@@ -143,18 +276,15 @@ fn serialize_vscode_mcp(
     servers: &[McpServerConfig],
     existing_content: Option<&str>,
     previously_synced_names: &[String],
+    fallback: &FallbackSettings,
 ) -> Result<String> {
-    let mut root: serde_json::Value = match existing_content {
-        Some(content) => serde_json::from_str(content).map_err(|e| {
-            anyhow::anyhow!(
-                "VS Code mcp.json has invalid JSON ({}). Fix it manually or delete and re-sync.",
-                e
-            )
-        })?,
+    let root: serde_json::Value = match existing_content {
+        Some(content) => jsonc::parse(content)
+            .map_err(|e| ConductorError::invalid_client_config("vscode-mcp", "JSON", e))?,
         None => serde_json::json!({}),
     };
 
-    let conductor_servers = servers_to_json_object(servers, false); // VS Code supports native headers
+    let conductor_servers = servers_to_json_object(servers, "vscode", fallback)?;
     let conductor_names_lower: std::collections::HashSet<String> =
         servers.iter().map(|s| s.name.to_lowercase()).collect();
     let prev_synced_lower: std::collections::HashSet<String> =
@@ -175,20 +305,97 @@ fn serialize_vscode_mcp(
         merged.insert(name, value);
     }
 
-    root["servers"] = serde_json::Value::Object(merged);
+    Ok(jsonc::replace_object_at(existing_content, &["servers"], &merged)
+        .map_err(|e| ConductorError::invalid_client_config("vscode-mcp", "JSON", e))?)
+}
+
+/// Prefix of `inputs` IDs Conductor owns in VS Code configs. Inputs with any
+/// other ID were added by the user and are never touched.
+const VSCODE_INPUT_PREFIX: &str = "conductor-";
 
-    serde_json::to_string_pretty(&root).context("Failed to serialize VS Code mcp.json")
+fn vscode_input_id(server_name: &str, key: &str) -> String {
+    let slug = |s: &str| -> String {
+        s.chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+            .collect()
+    };
+    format!("{}{}-{}", VSCODE_INPUT_PREFIX, slug(server_name), slug(key))
+}
+
+/// Swap each server's secret env values for `${input:...}` references and
+/// return the `promptString` inputs VS Code needs to ask for them.
+pub fn vscode_secret_inputs(
+    servers: &[McpServerConfig],
+) -> (Vec<McpServerConfig>, Vec<serde_json::Value>) {
+    let mut inputs = Vec::new();
+    let servers = servers
+        .iter()
+        .map(|server| {
+            let mut server = server.clone();
+            for key in &server.secret_env_keys {
+                let id = vscode_input_id(&server.name, key);
+                server.env.insert(key.clone(), format!("${{input:{}}}", id));
+                inputs.push(serde_json::json!({
+                    "type": "promptString",
+                    "id": id,
+                    "description": format!("{} for {}", key, server.name),
+                    "password": true,
+                }));
+            }
+            server
+        })
+        .collect();
+    (servers, inputs)
+}
+
+/// Replace Conductor's entries in the VS Code `inputs` array at `inputs_path`,
+/// keeping the user's own inputs. Content is returned unchanged when there is
+/// nothing to add or remove.
+pub fn merge_vscode_inputs(
+    content: &str,
+    inputs_path: &[&str],
+    inputs: &[serde_json::Value],
+) -> Result<String> {
+    let root = jsonc::parse(content)
+        .map_err(|e| ConductorError::invalid_client_config("vscode", "JSON", e))?;
+    let existing: Vec<serde_json::Value> = inputs_path
+        .iter()
+        .try_fold(&root, |node, key| node.get(key))
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    let mut merged: Vec<serde_json::Value> = existing
+        .iter()
+        .filter(|input| {
+            !input
+                .get("id")
+                .and_then(|id| id.as_str())
+                .is_some_and(|id| id.starts_with(VSCODE_INPUT_PREFIX))
+        })
+        .cloned()
+        .collect();
+    merged.extend(inputs.iter().cloned());
+
+    if merged == existing {
+        return Ok(content.to_string());
+    }
+    Ok(
+        jsonc::replace_value_at(Some(content), inputs_path, &serde_json::Value::Array(merged))
+            .map_err(|e| ConductorError::invalid_client_config("vscode", "JSON", e))?,
+    )
 }
 
 /// Zed format: flat command structure. Merges with existing context_servers.
-fn serialize_zed(servers: &[McpServerConfig], existing_content: Option<&str>, previously_synced_names: &[String]) -> Result<String> {
-    let mut root: serde_json::Value = match existing_content {
-        Some(content) => serde_json::from_str(content).map_err(|e| {
-            anyhow::anyhow!(
-                "Zed settings has invalid JSON ({}). Fix it manually or delete and re-sync.",
-                e
-            )
-        })?,
+fn serialize_zed(
+    servers: &[McpServerConfig],
+    existing_content: Option<&str>,
+    previously_synced_names: &[String],
+    fallback: &FallbackSettings,
+) -> Result<String> {
+    let root: serde_json::Value = match existing_content {
+        Some(content) => jsonc::parse(content)
+            .map_err(|e| ConductorError::invalid_client_config("zed", "JSON", e))?,
         None => serde_json::json!({}),
     };
 
@@ -229,39 +436,33 @@ fn serialize_zed(servers: &[McpServerConfig], existing_content: Option<&str>, pr
                 server_obj.insert("env".to_string(), serde_json::json!(server.env));
             }
         } else if let Some(ref url) = server.url {
-            // URL server — Zed doesn't support the MCP-level OAuth protocol,
-            // so always wrap URL servers via mcp-remote which handles auth
-            // negotiation transparently.
-            let mut args = vec![
-                "-y".to_string(),
-                "mcp-remote".to_string(),
-                url.clone(),
-            ];
-
-            if let Some(token) = server.env.get("OAUTH_TOKEN") {
-                if !token.trim().is_empty() {
-                    args.push("--header".to_string());
-                    args.push(format!("Authorization:Bearer {}", token));
+            let headers = remote_headers(server);
+            if capabilities::for_client("zed").native(&server.transport, !headers.is_empty()) {
+                server_obj.insert("url".to_string(), serde_json::json!(url));
+                if !headers.is_empty() {
+                    server_obj.insert("headers".to_string(), serde_json::Value::Object(headers));
                 }
+            } else {
+                // Older Zed only runs stdio servers, so the remote fallback
+                // bridges to the URL.
+                let (command, args) = remote_fallback_command("zed", server, fallback)?;
+                server_obj.insert("command".to_string(), serde_json::json!(command));
+                server_obj.insert("args".to_string(), serde_json::json!(args));
             }
-
-            server_obj.insert(
-                "command".to_string(),
-                serde_json::json!(find_npx_path()),
-            );
-            server_obj.insert("args".to_string(), serde_json::json!(args));
         }
 
+        apply_extra(&mut server_obj, server, "zed");
         context_servers.insert(server.name.clone(), serde_json::Value::Object(server_obj));
     }
 
-    root["context_servers"] = serde_json::Value::Object(context_servers);
-
-    serde_json::to_string_pretty(&root).context("Failed to serialize Zed JSON")
+    Ok(
+        jsonc::replace_object_at(existing_content, &["context_servers"], &context_servers)
+            .map_err(|e| ConductorError::invalid_client_config("zed", "JSON", e))?,
+    )
 }
 
 /// JetBrains XML format.
-fn serialize_jetbrains(servers: &[McpServerConfig]) -> Result<String> {
+fn serialize_jetbrains(servers: &[McpServerConfig], fallback: &FallbackSettings) -> Result<String> {
     use quick_xml::events::{BytesEnd, BytesStart, Event};
     use quick_xml::Writer;
     use std::io::Cursor;
@@ -293,24 +494,16 @@ fn serialize_jetbrains(servers: &[McpServerConfig]) -> Result<String> {
         let mut elem = BytesStart::new("serverConfiguration");
         elem.push_attribute(("name", server.name.as_str()));
 
-        // JetBrains doesn't support MCP-level OAuth for URL servers, so
-        // always wrap URL servers via mcp-remote which handles auth transparently.
+        // JetBrains doesn't read URL servers from mcp.xml, so they go
+        // through the remote fallback.
         let is_url_server = server.url.is_some()
-            && matches!(
-                server.transport,
-                TransportType::Sse | TransportType::StreamableHttp
-            );
+            && !capabilities::for_client("jetbrains")
+                .native(&server.transport, !remote_headers(server).is_empty());
 
         if is_url_server {
-            let url = server.url.as_deref().unwrap_or_default();
-            elem.push_attribute(("command", find_npx_path()));
-            let mut args_str = format!("-y mcp-remote {}", url);
-            if let Some(token) = server.env.get("OAUTH_TOKEN") {
-                if !token.trim().is_empty() {
-                    args_str.push_str(&format!(" --header Authorization:Bearer {}", token));
-                }
-            }
-            elem.push_attribute(("args", args_str.as_str()));
+            let (command, args) = remote_fallback_command("jetbrains", server, fallback)?;
+            elem.push_attribute(("command", command.as_str()));
+            elem.push_attribute(("args", args.join(" ").as_str()));
         } else if server.transport == TransportType::Stdio {
             if let Some(ref cmd) = server.command {
                 elem.push_attribute(("command", cmd.as_str()));
@@ -324,8 +517,9 @@ fn serialize_jetbrains(servers: &[McpServerConfig]) -> Result<String> {
 
         let enabled_str = server.enabled.to_string();
         elem.push_attribute(("enabled", enabled_str.as_str()));
+        push_extra_attributes(&mut elem, server, "jetbrains");
 
-        // Only emit <envs> for stdio servers (URL servers are wrapped via mcp-remote).
+        // Only emit <envs> for stdio servers (URL servers run the remote fallback).
         let has_env = !is_url_server && !server.env.is_empty();
 
         if !has_env {
@@ -372,14 +566,16 @@ fn serialize_jetbrains(servers: &[McpServerConfig]) -> Result<String> {
 
 /// Codex TOML format with [mcp_servers.name] named subtables.
 /// Merges Conductor servers with existing client-specific servers.
-fn serialize_codex(servers: &[McpServerConfig], existing_content: Option<&str>, previously_synced_names: &[String]) -> Result<String> {
+fn serialize_codex(
+    servers: &[McpServerConfig],
+    existing_content: Option<&str>,
+    previously_synced_names: &[String],
+    fallback: &FallbackSettings,
+) -> Result<String> {
     let mut doc: toml_edit::DocumentMut = match existing_content {
-        Some(content) => content.parse().map_err(|e| {
-            anyhow::anyhow!(
-                "Codex config has invalid TOML ({}). Fix it manually or delete and re-sync.",
-                e
-            )
-        })?,
+        Some(content) => content
+            .parse()
+            .map_err(|e| ConductorError::invalid_client_config("codex", "TOML", e))?,
         None => toml_edit::DocumentMut::new(),
     };
 
@@ -388,48 +584,53 @@ fn serialize_codex(servers: &[McpServerConfig], existing_content: Option<&str>,
     let prev_synced_lower: std::collections::HashSet<String> =
         previously_synced_names.iter().map(|s| s.to_lowercase()).collect();
 
-    // Preserve truly user-added servers, skip Conductor-managed orphans
-    let mut mcp_table = toml_edit::Table::new();
-    if let Some(existing) = doc.get("mcp_servers").and_then(|v| v.as_table()) {
-        for (name, value) in existing.iter() {
-            let name_lower = name.to_lowercase();
-            let is_conductor = conductor_names_lower.contains(&name_lower);
-            let was_conductor = prev_synced_lower.contains(&name_lower);
-            if !is_conductor && !was_conductor {
-                mcp_table.insert(name, value.clone());
-            }
-        }
+    // Edit `[mcp_servers]` in place so profiles and every other table keep
+    // their position and formatting.
+    if !doc.get("mcp_servers").is_some_and(|v| v.is_table()) {
+        let mut fresh = toml_edit::Table::new();
+        fresh.set_implicit(true);
+        doc.insert("mcp_servers", toml_edit::Item::Table(fresh));
     }
+    let mcp_table = doc["mcp_servers"]
+        .as_table_mut()
+        .context("mcp_servers is not a table")?;
 
-    // Remove old mcp_servers and rebuild with merged content
-    doc.remove("mcp_servers");
+    // Preserve truly user-added servers, skip Conductor-managed orphans
+    mcp_table.retain(|name, _| {
+        let name_lower = name.to_lowercase();
+        !conductor_names_lower.contains(&name_lower) && !prev_synced_lower.contains(&name_lower)
+    });
 
     // Add Conductor servers
     for server in servers {
         let mut table = toml_edit::Table::new();
 
-        if server.url.is_some() {
-            // URL-based server: Codex doesn't support the MCP-level OAuth
-            // protocol, so we always wrap URL servers via `mcp-remote` which
-            // handles the OAuth dance transparently.  If we have an explicit
-            // OAUTH_TOKEN, pass it as a --header arg so mcp-remote includes it
-            // without needing to negotiate.
-            let url = server.url.as_deref().unwrap_or_default();
-            let npx = find_npx_path();
-            table.insert("command", toml_edit::value(npx));
-
-            let mut args_array = toml_edit::Array::new();
-            args_array.push("-y");
-            args_array.push("mcp-remote");
-            args_array.push(url);
-
-            if let Some(token) = server.env.get("OAUTH_TOKEN") {
-                if !token.trim().is_empty() {
-                    args_array.push("--header");
-                    args_array.push(format!("Authorization:Bearer {}", token).as_str());
+        let options = server.codex.clone().unwrap_or_default();
+        // Recent Codex talks streamable HTTP natively and reads the bearer
+        // token from an env var. SSE servers, older Codex and tokens Codex
+        // has no env var for go through the remote fallback.
+        let native_http = server.transport == TransportType::StreamableHttp
+            && capabilities::for_client("codex").streamable_http
+            && (options.bearer_token_env_var.is_some() || bearer_token(server).is_none());
+
+        if let Some(url) = server.url.as_deref().filter(|_| native_http) {
+            table.insert("url", toml_edit::value(url));
+            if let Some(ref var) = options.bearer_token_env_var {
+                table.insert("bearer_token_env_var", toml_edit::value(var));
+            }
+            if !server.headers.is_empty() {
+                let mut headers: Vec<_> = server.headers.iter().collect();
+                headers.sort();
+                let mut headers_table = toml_edit::InlineTable::new();
+                for (name, value) in headers {
+                    headers_table.insert(name, value.as_str().into());
                 }
+                table.insert("http_headers", toml_edit::value(headers_table));
             }
-            table.insert("args", toml_edit::value(args_array));
+        } else if server.url.is_some() {
+            let (command, args) = remote_fallback_command("codex", server, fallback)?;
+            table.insert("command", toml_edit::value(command));
+            table.insert("args", toml_edit::value(args.iter().collect::<toml_edit::Array>()));
         } else {
             // Stdio server
             if let Some(ref cmd) = server.command {
@@ -451,6 +652,24 @@ fn serialize_codex(servers: &[McpServerConfig], existing_content: Option<&str>,
             }
         }
 
+        if server.url.is_none() {
+            if let Some(ref cwd) = options.cwd {
+                table.insert("cwd", toml_edit::value(cwd));
+            }
+        }
+        if let Some(secs) = options.startup_timeout_sec {
+            table.insert("startup_timeout_sec", toml_edit::value(toml_seconds(secs)));
+        }
+        if let Some(secs) = options.tool_timeout_sec {
+            table.insert("tool_timeout_sec", toml_edit::value(toml_seconds(secs)));
+        }
+        if let Some(ref tools) = options.enabled_tools {
+            table.insert("enabled_tools", toml_edit::value(tools.iter().collect::<toml_edit::Array>()));
+        }
+        if let Some(ref tools) = options.disabled_tools {
+            table.insert("disabled_tools", toml_edit::value(tools.iter().collect::<toml_edit::Array>()));
+        }
+
         if !server.enabled {
             table.insert("enabled", toml_edit::value(false));
         }
@@ -458,29 +677,444 @@ fn serialize_codex(servers: &[McpServerConfig], existing_content: Option<&str>,
         mcp_table.insert(&server.name, toml_edit::Item::Table(table));
     }
 
-    doc.insert("mcp_servers", toml_edit::Item::Table(mcp_table));
-
     Ok(doc.to_string())
 }
 
+/// Whole seconds are written as integers, as Codex's own docs show them.
+fn toml_seconds(secs: f64) -> toml_edit::Value {
+    if secs.fract() == 0.0 && secs.abs() < i64::MAX as f64 {
+        toml_edit::Value::from(secs as i64)
+    } else {
+        toml_edit::Value::from(secs)
+    }
+}
+
+/// Keys of a Continue `mcpServers` block that Conductor writes. Anything else
+/// on an existing block (e.g. `connectionTimeout`) is carried over.
+const CONTINUE_MANAGED_KEYS: &[&str] = &["name", "command", "args", "env", "type", "url", "requestOptions"];
+
+/// Continue config.yaml: `mcpServers` is a list of named blocks. The list is
+/// edited in place, so comments and every other top-level section survive.
+fn serialize_continue(
+    servers: &[McpServerConfig],
+    existing_content: Option<&str>,
+    previously_synced_names: &[String],
+) -> Result<String> {
+    let existing: Vec<serde_json::Value> = match existing_content {
+        Some(content) if !content.trim().is_empty() => {
+            let root: serde_json::Value = serde_yaml::from_str(content)
+                .map_err(|e| ConductorError::invalid_client_config("continue", "YAML", e))?;
+            root.get("mcpServers")
+                .and_then(|v| v.as_array())
+                .cloned()
+                .unwrap_or_default()
+        }
+        _ => Vec::new(),
+    };
+
+    let conductor_names_lower: std::collections::HashSet<String> =
+        servers.iter().map(|s| s.name.to_lowercase()).collect();
+    let prev_synced_lower: std::collections::HashSet<String> =
+        previously_synced_names.iter().map(|s| s.to_lowercase()).collect();
+    let is_user_entry = |entry: &serde_yaml::Value| {
+        let name_lower = entry
+            .get("name")
+            .and_then(|n| n.as_str())
+            .unwrap_or_default()
+            .to_lowercase();
+        !conductor_names_lower.contains(&name_lower) && !prev_synced_lower.contains(&name_lower)
+    };
+
+    let mut blocks = Vec::with_capacity(servers.len());
+    for server in servers {
+        let mut block = serde_json::Map::new();
+        block.insert("name".to_string(), serde_json::json!(server.name));
+
+        match server.transport {
+            TransportType::Stdio => {
+                block.insert("type".to_string(), serde_json::json!("stdio"));
+                if let Some(ref cmd) = server.command {
+                    block.insert("command".to_string(), serde_json::json!(cmd));
+                }
+                if !server.args.is_empty() {
+                    block.insert("args".to_string(), serde_json::json!(server.args));
+                }
+                if !server.env.is_empty() {
+                    block.insert("env".to_string(), serde_json::json!(server.env));
+                }
+            }
+            TransportType::Sse | TransportType::StreamableHttp => {
+                let kind = if server.transport == TransportType::Sse {
+                    "sse"
+                } else {
+                    "streamable-http"
+                };
+                block.insert("type".to_string(), serde_json::json!(kind));
+                if let Some(ref url) = server.url {
+                    block.insert("url".to_string(), serde_json::json!(url));
+                }
+                // Continue sends request headers natively.
+                let headers = remote_headers(server);
+                if !headers.is_empty() {
+                    block.insert(
+                        "requestOptions".to_string(),
+                        serde_json::json!({ "headers": headers }),
+                    );
+                }
+            }
+        }
+
+        let previous = existing.iter().find(|e| {
+            e.get("name").and_then(|n| n.as_str()) == Some(server.name.as_str())
+        });
+        if let Some(previous) = previous.and_then(|p| p.as_object()) {
+            for (key, value) in previous {
+                if !CONTINUE_MANAGED_KEYS.contains(&key.as_str()) {
+                    block.entry(key.clone()).or_insert_with(|| value.clone());
+                }
+            }
+        }
+
+        blocks.push(
+            serde_yaml::to_value(serde_json::Value::Object(block))
+                .context("Failed to serialize YAML")?,
+        );
+    }
+
+    // Continue requires these top-level keys in a new config.yaml.
+    let content = existing_content
+        .filter(|c| !c.trim().is_empty())
+        .unwrap_or("name: Local Config\nversion: 1.0.0\nschema: v1\n");
+    crate::config::yaml::edit_top_level_sequence(Some(content), "mcpServers", is_user_entry, &blocks)
+}
+
+/// Existing entries Conductor never wrote, keyed by name. Entries for servers
+/// Conductor manages now, or managed before (orphans), are left out.
+fn user_entries(
+    existing: Option<&serde_json::Map<String, serde_json::Value>>,
+    servers: &[McpServerConfig],
+    previously_synced_names: &[String],
+) -> serde_json::Map<String, serde_json::Value> {
+    let conductor_names_lower: std::collections::HashSet<String> =
+        servers.iter().map(|s| s.name.to_lowercase()).collect();
+    let prev_synced_lower: std::collections::HashSet<String> =
+        previously_synced_names.iter().map(|s| s.to_lowercase()).collect();
+
+    let mut kept = serde_json::Map::new();
+    for (name, value) in existing.into_iter().flatten() {
+        let name_lower = name.to_lowercase();
+        if !conductor_names_lower.contains(&name_lower) && !prev_synced_lower.contains(&name_lower) {
+            kept.insert(name.clone(), value.clone());
+        }
+    }
+    kept
+}
+
+/// Copy client-only keys from the entry a client already has for this server,
+/// so settings made in the client survive a sync.
+fn carry_over_keys(
+    entry: &mut serde_json::Map<String, serde_json::Value>,
+    previous: Option<&serde_json::Value>,
+    keys: &[&str],
+) {
+    let Some(previous) = previous.and_then(|p| p.as_object()) else {
+        return;
+    };
+    for key in keys {
+        if let Some(value) = previous.get(*key) {
+            entry.entry(key.to_string()).or_insert_with(|| value.clone());
+        }
+    }
+}
+
+fn bearer_token(server: &McpServerConfig) -> Option<&str> {
+    server
+        .env
+        .get("OAUTH_TOKEN")
+        .map(|t| t.as_str())
+        .filter(|t| !t.trim().is_empty())
+}
+
+/// Headers a remote entry sends: the server's own, plus an `Authorization`
+/// header for its OAuth token unless it sets one itself. Sorted by name so
+/// syncs are stable.
+fn remote_headers(server: &McpServerConfig) -> serde_json::Map<String, serde_json::Value> {
+    let mut headers: std::collections::BTreeMap<&str, String> = server
+        .headers
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.clone()))
+        .collect();
+    if let Some(token) = bearer_token(server) {
+        if !headers.keys().any(|name| name.eq_ignore_ascii_case("authorization")) {
+            headers.insert("Authorization", format!("Bearer {}", token));
+        }
+    }
+    headers
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), serde_json::json!(value)))
+        .collect()
+}
+
+/// Write back the fields `client_id`'s entry had that Conductor doesn't
+/// model. Fields Conductor just wrote take precedence.
+fn apply_extra(
+    entry: &mut serde_json::Map<String, serde_json::Value>,
+    server: &McpServerConfig,
+    client_id: &str,
+) {
+    if let Some(extra) = server.extra.get(client_id) {
+        for (key, value) in extra {
+            entry.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+    }
+}
+
+/// XML counterpart of [`apply_extra`]: writes back the attributes
+/// `client_id`'s element had that Conductor doesn't model.
+fn push_extra_attributes(
+    elem: &mut quick_xml::events::BytesStart,
+    server: &McpServerConfig,
+    client_id: &str,
+) {
+    let Some(extra) = server.extra.get(client_id) else {
+        return;
+    };
+    let written: Vec<Vec<u8>> = elem
+        .attributes()
+        .filter_map(|a| a.ok())
+        .map(|a| a.key.as_ref().to_vec())
+        .collect();
+    for (key, value) in extra {
+        if let Some(value) = value.as_str() {
+            if !written.iter().any(|w| w == key.as_bytes()) {
+                elem.push_attribute((key.as_str(), value));
+            }
+        }
+    }
+}
+
+/// Gemini CLI settings.json: `mcpServers` with `url` for SSE and `httpUrl`
+/// for streamable HTTP. Headers are supported natively.
+fn serialize_gemini(
+    servers: &[McpServerConfig],
+    existing_content: Option<&str>,
+    previously_synced_names: &[String],
+) -> Result<String> {
+    let root: serde_json::Value = match existing_content {
+        Some(content) => jsonc::parse(content)
+            .map_err(|e| ConductorError::invalid_client_config("gemini", "JSON", e))?,
+        None => serde_json::json!({}),
+    };
+    let existing = root.get("mcpServers").and_then(|v| v.as_object());
+
+    let mut merged = user_entries(existing, servers, previously_synced_names);
+    for server in servers {
+        let mut entry = serde_json::Map::new();
+        match server.transport {
+            TransportType::Stdio => {
+                if let Some(ref cmd) = server.command {
+                    entry.insert("command".to_string(), serde_json::json!(cmd));
+                }
+                if !server.args.is_empty() {
+                    entry.insert("args".to_string(), serde_json::json!(server.args));
+                }
+                if !server.env.is_empty() {
+                    entry.insert("env".to_string(), serde_json::json!(server.env));
+                }
+            }
+            TransportType::Sse | TransportType::StreamableHttp => {
+                let key = if server.transport == TransportType::Sse {
+                    "url"
+                } else {
+                    "httpUrl"
+                };
+                if let Some(ref url) = server.url {
+                    entry.insert(key.to_string(), serde_json::json!(url));
+                }
+                let headers = remote_headers(server);
+                if !headers.is_empty() {
+                    entry.insert("headers".to_string(), serde_json::Value::Object(headers));
+                }
+            }
+        }
+        carry_over_keys(
+            &mut entry,
+            existing.and_then(|e| e.get(&server.name)),
+            &["cwd", "timeout", "trust", "includeTools", "excludeTools", "description"],
+        );
+        apply_extra(&mut entry, server, "gemini");
+        merged.insert(server.name.clone(), serde_json::Value::Object(entry));
+    }
+
+    Ok(jsonc::replace_object_at(existing_content, &["mcpServers"], &merged)
+        .map_err(|e| ConductorError::invalid_client_config("gemini", "JSON", e))?)
+}
+
+/// Goose config.yaml: MCP servers are entries in the `extensions` map, each
+/// repeating its name and carrying an explicit `enabled` flag. Built-in
+/// extensions are never Conductor's, so they are always kept. The map is
+/// edited in place, so comments and every other top-level key survive.
+fn serialize_goose(
+    servers: &[McpServerConfig],
+    existing_content: Option<&str>,
+    previously_synced_names: &[String],
+) -> Result<String> {
+    let root: serde_json::Value = match existing_content {
+        Some(content) if !content.trim().is_empty() => serde_yaml::from_str(content)
+            .map_err(|e| ConductorError::invalid_client_config("goose", "YAML", e))?,
+        _ => serde_json::json!({}),
+    };
+    let existing = root.get("extensions").and_then(|v| v.as_object());
+
+    let kept = user_entries(existing, servers, previously_synced_names);
+    let mut entries = Vec::with_capacity(servers.len());
+    for server in servers {
+        let mut entry = serde_json::Map::new();
+        entry.insert("name".to_string(), serde_json::json!(server.name));
+        entry.insert("enabled".to_string(), serde_json::json!(server.enabled));
+        match server.transport {
+            TransportType::Stdio => {
+                entry.insert("type".to_string(), serde_json::json!("stdio"));
+                entry.insert(
+                    "cmd".to_string(),
+                    serde_json::json!(server.command.as_deref().unwrap_or_default()),
+                );
+                entry.insert("args".to_string(), serde_json::json!(server.args));
+                entry.insert("envs".to_string(), serde_json::json!(server.env));
+            }
+            TransportType::Sse | TransportType::StreamableHttp => {
+                let kind = if server.transport == TransportType::Sse {
+                    "sse"
+                } else {
+                    "streamable_http"
+                };
+                entry.insert("type".to_string(), serde_json::json!(kind));
+                entry.insert(
+                    "uri".to_string(),
+                    serde_json::json!(server.url.as_deref().unwrap_or_default()),
+                );
+                entry.insert("envs".to_string(), serde_json::json!({}));
+                let headers = remote_headers(server);
+                if !headers.is_empty() {
+                    entry.insert("headers".to_string(), serde_json::Value::Object(headers));
+                }
+            }
+        }
+        carry_over_keys(
+            &mut entry,
+            existing.and_then(|e| e.get(&server.name)),
+            &["timeout", "description", "bundled", "env_keys", "available_tools"],
+        );
+        apply_extra(&mut entry, server, "goose");
+        entry.entry("timeout".to_string()).or_insert(serde_json::json!(300));
+        entries.push((
+            server.name.clone(),
+            serde_yaml::to_value(serde_json::Value::Object(entry))
+                .context("Failed to serialize YAML")?,
+        ));
+    }
+
+    crate::config::yaml::edit_top_level_mapping(
+        existing_content,
+        "extensions",
+        |name, _| kept.contains_key(name),
+        &entries,
+    )
+}
+
+/// opencode.json: servers live under `mcp` as `type: "local"` with a single
+/// `command` array, or `type: "remote"` with a `url`.
+fn serialize_opencode(
+    servers: &[McpServerConfig],
+    existing_content: Option<&str>,
+    previously_synced_names: &[String],
+) -> Result<String> {
+    let root: serde_json::Value = match existing_content {
+        Some(content) => jsonc::parse(content)
+            .map_err(|e| ConductorError::invalid_client_config("opencode", "JSON", e))?,
+        None => serde_json::json!({ "$schema": "https://opencode.ai/config.json" }),
+    };
+    let existing = root.get("mcp").and_then(|v| v.as_object());
+
+    let mut merged = user_entries(existing, servers, previously_synced_names);
+    for server in servers {
+        let mut entry = serde_json::Map::new();
+        match server.transport {
+            TransportType::Stdio => {
+                entry.insert("type".to_string(), serde_json::json!("local"));
+                let command_line: Vec<&str> = server
+                    .command
+                    .iter()
+                    .map(|c| c.as_str())
+                    .chain(server.args.iter().map(|a| a.as_str()))
+                    .collect();
+                entry.insert("command".to_string(), serde_json::json!(command_line));
+                if !server.env.is_empty() {
+                    entry.insert("environment".to_string(), serde_json::json!(server.env));
+                }
+            }
+            TransportType::Sse | TransportType::StreamableHttp => {
+                entry.insert("type".to_string(), serde_json::json!("remote"));
+                if let Some(ref url) = server.url {
+                    entry.insert("url".to_string(), serde_json::json!(url));
+                }
+                let headers = remote_headers(server);
+                if !headers.is_empty() {
+                    entry.insert("headers".to_string(), serde_json::Value::Object(headers));
+                }
+            }
+        }
+        entry.insert("enabled".to_string(), serde_json::json!(server.enabled));
+        carry_over_keys(
+            &mut entry,
+            existing.and_then(|e| e.get(&server.name)),
+            &["timeout", "oauth"],
+        );
+        apply_extra(&mut entry, server, "opencode");
+        merged.insert(server.name.clone(), serde_json::Value::Object(entry));
+    }
+
+    // A new file gets its `$schema` line from the seed document above.
+    let seed;
+    let base = match existing_content {
+        Some(content) => Some(content),
+        None => {
+            seed = serde_json::to_string_pretty(&root)? + "\n";
+            Some(seed.as_str())
+        }
+    };
+    Ok(jsonc::replace_object_at(base, &["mcp"], &merged)
+        .map_err(|e| ConductorError::invalid_client_config("opencode", "JSON", e))?)
+}
+
 /// Convert a slice of server configs into a JSON object for standard formats.
 ///
-/// When `use_proxy_for_auth` is **false** (Claude Code, VS Code), URL servers
-/// with an `OAUTH_TOKEN` emit `headers.Authorization` natively.
+/// URL servers are written as `url` entries with an `Authorization` header
+/// when the client reads them natively, and as stdio entries running the
+/// remote fallback otherwise.
 ///
-/// When `use_proxy_for_auth` is **true** (Cursor, Claude Desktop, Windsurf),
-/// URL servers with an `OAUTH_TOKEN` are wrapped as stdio via `mcp-remote`
-/// so that the auth header is passed through the proxy subprocess.  URL servers
-/// *without* an OAuth token are still emitted as plain URLs.
+/// Claude Code and VS Code entries carry an explicit `type`.
 fn servers_to_json_object(
     servers: &[McpServerConfig],
-    use_proxy_for_auth: bool,
-) -> serde_json::Map<String, serde_json::Value> {
+    client_id: &str,
+    fallback: &FallbackSettings,
+) -> Result<serde_json::Map<String, serde_json::Value>> {
     let mut map = serde_json::Map::new();
+    let writes_type = matches!(client_id, "claude-code" | "vscode");
+    let caps = capabilities::for_client(client_id);
 
     for server in servers {
         let mut obj = serde_json::Map::new();
 
+        if writes_type {
+            let kind = match server.transport {
+                TransportType::Stdio => "stdio",
+                TransportType::Sse => "sse",
+                TransportType::StreamableHttp => "http",
+            };
+            obj.insert("type".to_string(), serde_json::json!(kind));
+        }
+
         match server.transport {
             TransportType::Stdio => {
                 if let Some(ref cmd) = server.command {
@@ -495,52 +1129,27 @@ fn servers_to_json_object(
                 }
             }
             TransportType::Sse | TransportType::StreamableHttp => {
-                let has_oauth = server
-                    .env
-                    .get("OAUTH_TOKEN")
-                    .map(|t| !t.trim().is_empty())
-                    .unwrap_or(false);
-
-                if use_proxy_for_auth && has_oauth {
-                    // Client doesn't support native headers — wrap via mcp-remote.
-                    let url = server.url.as_deref().unwrap_or_default();
-                    let token = server.env.get("OAUTH_TOKEN").unwrap();
-                    obj.insert(
-                        "command".to_string(),
-                        serde_json::json!(find_npx_path()),
-                    );
-                    obj.insert(
-                        "args".to_string(),
-                        serde_json::json!([
-                            "-y",
-                            "mcp-remote",
-                            url,
-                            "--header",
-                            format!("Authorization:Bearer {}", token),
-                        ]),
-                    );
-                } else {
-                    // Client supports native headers (or no auth needed).
+                let headers = remote_headers(server);
+                if caps.native(&server.transport, !headers.is_empty()) {
                     if let Some(ref url) = server.url {
                         obj.insert("url".to_string(), serde_json::json!(url));
                     }
-                    if server.transport == TransportType::StreamableHttp {
+                    if server.transport == TransportType::StreamableHttp && !writes_type {
                         obj.insert(
                             "transport".to_string(),
                             serde_json::json!("streamable-http"),
                         );
                     }
-                    // Promote OAUTH_TOKEN to Authorization header for clients
-                    // that support it (Claude Code, VS Code).
-                    if has_oauth {
-                        let token = server.env.get("OAUTH_TOKEN").unwrap();
-                        obj.insert(
-                            "headers".to_string(),
-                            serde_json::json!({
-                                "Authorization": format!("Bearer {}", token)
-                            }),
-                        );
+                    if !headers.is_empty() {
+                        obj.insert("headers".to_string(), serde_json::Value::Object(headers));
+                    }
+                } else {
+                    let (command, args) = remote_fallback_command(client_id, server, fallback)?;
+                    if writes_type {
+                        obj.insert("type".to_string(), serde_json::json!("stdio"));
                     }
+                    obj.insert("command".to_string(), serde_json::json!(command));
+                    obj.insert("args".to_string(), serde_json::json!(args));
                 }
             }
         }
@@ -548,9 +1157,353 @@ fn servers_to_json_object(
         if !server.enabled {
             obj.insert("disabled".to_string(), serde_json::json!(true));
         }
+        apply_extra(&mut obj, server, client_id);
 
         map.insert(server.name.clone(), serde_json::Value::Object(obj));
     }
 
-    map
+    Ok(map)
+}
+
+/// Serialize servers for a client described by an `AdapterSpec`.
+/// Same merge and orphan rules as the built-in formats: entries Conductor never
+/// wrote are kept, entries it wrote before but no longer manages are dropped.
+pub fn serialize_with_spec(
+    spec: &AdapterSpec,
+    servers: &[McpServerConfig],
+    existing_content: Option<&str>,
+    previously_synced_names: &[String],
+    fallback: &FallbackSettings,
+) -> Result<String> {
+    let conductor_names_lower: std::collections::HashSet<String> =
+        servers.iter().map(|s| s.name.to_lowercase()).collect();
+    let prev_synced_lower: std::collections::HashSet<String> =
+        previously_synced_names.iter().map(|s| s.to_lowercase()).collect();
+    let is_user_entry = |name: &str| {
+        let name_lower = name.to_lowercase();
+        !conductor_names_lower.contains(&name_lower) && !prev_synced_lower.contains(&name_lower)
+    };
+
+    let segments = spec.pointer_segments();
+    let existing_root = match existing_content {
+        Some(content) if spec.format != SpecFormat::Xml => {
+            normalizer::parse_document(spec, content)?
+        }
+        _ => serde_json::json!({}),
+    };
+    let mut current = &existing_root;
+    for segment in &segments {
+        current = current.get(segment).unwrap_or(&serde_json::Value::Null);
+    }
+    let existing_entries = current;
+    let caps = capabilities::for_spec(spec);
+    let entry_for = |server: &McpServerConfig| -> Result<serde_json::Map<String, serde_json::Value>> {
+        let mut entry = spec_server_entry(spec, server, &caps, fallback)?;
+        apply_extra(&mut entry, server, &spec.id);
+        if let Some(existing) = existing_entries.get(&server.name).and_then(|v| v.as_object()) {
+            for key in &spec.preserve_fields {
+                if let Some(value) = existing.get(key) {
+                    entry.entry(key.clone()).or_insert_with(|| value.clone());
+                }
+            }
+        }
+        // Auto-approving a tool the filter hides would be meaningless at best.
+        if let Some(filter) = server.tool_filter(&spec.id) {
+            for key in &spec.tool_approval_fields {
+                if let Some(tools) = entry.get_mut(key).and_then(|v| v.as_array_mut()) {
+                    tools.retain(|t| t.as_str().is_none_or(|name| filter.permits(name)));
+                }
+            }
+        }
+        Ok(entry)
+    };
+
+    match spec.format {
+        SpecFormat::Json | SpecFormat::Jsonc => {
+
+            let mut merged = serde_json::Map::new();
+            if let Some(existing) = existing_entries.as_object() {
+                for (name, value) in existing {
+                    if is_user_entry(name) {
+                        merged.insert(name.clone(), value.clone());
+                    }
+                }
+            }
+            for server in servers {
+                merged.insert(server.name.clone(), serde_json::Value::Object(entry_for(server)?));
+            }
+
+            let path: Vec<&str> = segments.iter().map(|s| s.as_str()).collect();
+            Ok(jsonc::replace_object_at(existing_content, &path, &merged).map_err(|e| {
+                ConductorError::invalid_client_config(&spec.id, spec.format.label(), e)
+            })?)
+        }
+        SpecFormat::Yaml => {
+            // Edited in place like Goose, so comments and other keys survive.
+            let [key] = segments.as_slice() else {
+                anyhow::bail!(
+                    "adapter '{}': YAML specs need a servers_pointer with exactly one key",
+                    spec.id
+                );
+            };
+            let mut entries = Vec::with_capacity(servers.len());
+            for server in servers {
+                entries.push((
+                    server.name.clone(),
+                    serde_yaml::to_value(serde_json::Value::Object(entry_for(server)?))
+                        .context("Failed to serialize YAML")?,
+                ));
+            }
+            crate::config::yaml::edit_top_level_mapping(
+                existing_content,
+                key,
+                |name, _| is_user_entry(name),
+                &entries,
+            )
+        }
+        SpecFormat::Toml => {
+            let mut doc: toml_edit::DocumentMut = match existing_content {
+                Some(content) => content.parse().map_err(|e| {
+                    ConductorError::invalid_client_config(&spec.id, spec.format.label(), e)
+                })?,
+                None => toml_edit::DocumentMut::new(),
+            };
+
+            let mut table = doc.as_table_mut();
+            for segment in &segments {
+                if !table.get(segment).map(|i| i.is_table()).unwrap_or(false) {
+                    let mut child = match table.remove(segment) {
+                        Some(toml_edit::Item::Value(toml_edit::Value::InlineTable(t))) => {
+                            t.into_table()
+                        }
+                        _ => toml_edit::Table::new(),
+                    };
+                    child.set_implicit(true);
+                    table.insert(segment, toml_edit::Item::Table(child));
+                }
+                table = table[segment.as_str()].as_table_mut().expect("just inserted a table");
+            }
+
+            let stale: Vec<String> = table
+                .iter()
+                .map(|(name, _)| name.to_string())
+                .filter(|name| !is_user_entry(name))
+                .collect();
+            for name in stale {
+                table.remove(&name);
+            }
+            for server in servers {
+                let mut entry = toml_edit::Table::new();
+                for (key, value) in entry_for(server)? {
+                    entry.insert(&key, toml_edit::Item::Value(json_to_toml_value(&value)));
+                }
+                table.insert(&server.name, toml_edit::Item::Table(entry));
+            }
+
+            Ok(doc.to_string())
+        }
+        SpecFormat::Xml => {
+            let mut all: Vec<McpServerConfig> = match existing_content {
+                Some(content) => normalizer::parse_with_spec(spec, content)?
+                    .into_iter()
+                    .filter(|s| is_user_entry(&s.name))
+                    .collect(),
+                None => Vec::new(),
+            };
+            all.extend(servers.iter().cloned());
+            serialize_xml_with_spec(spec, &all, &caps, fallback)
+        }
+    }
+}
+
+/// Build one server entry using the spec's field names and the client's
+/// remote transport support.
+fn spec_server_entry(
+    spec: &AdapterSpec,
+    server: &McpServerConfig,
+    caps: &RemoteCapabilities,
+    fallback: &FallbackSettings,
+) -> Result<serde_json::Map<String, serde_json::Value>> {
+    let fields = &spec.fields;
+    let support = &spec.transport;
+    let mut obj = serde_json::Map::new();
+
+    let headers = remote_headers(server);
+
+    let transport_value = match server.transport {
+        TransportType::Stdio => &support.stdio_value,
+        TransportType::Sse => &support.sse_value,
+        TransportType::StreamableHttp => &support.streamable_http_value,
+    };
+
+    match server.transport {
+        TransportType::Stdio => {
+            if let Some(ref cmd) = server.command {
+                obj.insert(fields.command.clone(), serde_json::json!(cmd));
+            }
+            if !server.args.is_empty() {
+                obj.insert(fields.args.clone(), serde_json::json!(server.args));
+            }
+            if !server.env.is_empty() {
+                obj.insert(fields.env.clone(), serde_json::json!(server.env));
+            }
+            if let (Some(key), Some(value)) = (&fields.transport, transport_value) {
+                obj.insert(key.clone(), serde_json::json!(value));
+            }
+        }
+        TransportType::Sse | TransportType::StreamableHttp => {
+            let url = server.url.as_deref().unwrap_or_default();
+
+            if !caps.native(&server.transport, !headers.is_empty()) {
+                let (command, args) = remote_fallback_command(&spec.id, server, fallback)?;
+                obj.insert(fields.command.clone(), serde_json::json!(command));
+                obj.insert(fields.args.clone(), serde_json::json!(args));
+            } else {
+                if server.url.is_some() {
+                    obj.insert(fields.url.clone(), serde_json::json!(url));
+                }
+                if let (Some(key), Some(value)) = (&fields.transport, transport_value) {
+                    obj.insert(key.clone(), serde_json::json!(value));
+                }
+                if !headers.is_empty() {
+                    obj.insert(fields.headers.clone(), serde_json::Value::Object(headers));
+                }
+            }
+        }
+    }
+
+    if !server.enabled {
+        if let Some(ref key) = fields.enabled {
+            obj.insert(key.clone(), serde_json::json!(false));
+        } else if let Some(ref key) = fields.disabled {
+            obj.insert(key.clone(), serde_json::json!(true));
+        }
+    }
+
+    Ok(obj)
+}
+
+fn json_to_toml_value(value: &serde_json::Value) -> toml_edit::Value {
+    match value {
+        serde_json::Value::Bool(b) => (*b).into(),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => i.into(),
+            None => n.as_f64().unwrap_or_default().into(),
+        },
+        serde_json::Value::String(s) => s.as_str().into(),
+        serde_json::Value::Array(items) => {
+            let mut array = toml_edit::Array::new();
+            for item in items {
+                array.push(json_to_toml_value(item));
+            }
+            toml_edit::Value::Array(array)
+        }
+        serde_json::Value::Object(map) => {
+            let mut table = toml_edit::InlineTable::new();
+            for (key, item) in map {
+                table.insert(key, json_to_toml_value(item));
+            }
+            toml_edit::Value::InlineTable(table)
+        }
+        // TOML has no null; an empty string is the closest harmless value.
+        serde_json::Value::Null => "".into(),
+    }
+}
+
+/// XML specs own the whole file: user entries are re-read and written back
+/// alongside Conductor's, as with the built-in JetBrains format.
+fn serialize_xml_with_spec(
+    spec: &AdapterSpec,
+    servers: &[McpServerConfig],
+    caps: &RemoteCapabilities,
+    fallback: &FallbackSettings,
+) -> Result<String> {
+    use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, Event};
+    use quick_xml::Writer;
+    use std::io::Cursor;
+
+    let layout = &spec.xml;
+    let fields = &spec.fields;
+    let segments = spec.pointer_segments();
+    let mut writer = Writer::new_with_indent(Cursor::new(Vec::new()), b' ', 2);
+
+    writer
+        .write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))
+        .context("Failed to write XML declaration")?;
+
+    for (i, segment) in segments.iter().enumerate() {
+        let mut elem = BytesStart::new(segment.as_str());
+        if i == 0 {
+            for (key, value) in &layout.root_attributes {
+                elem.push_attribute((key.as_str(), value.as_str()));
+            }
+        }
+        writer
+            .write_event(Event::Start(elem))
+            .context("Failed to write XML element")?;
+    }
+
+    for server in servers {
+        let entry = spec_server_entry(spec, server, caps, fallback)?;
+        let mut elem = BytesStart::new(layout.server_element.as_str());
+        elem.push_attribute((layout.name_attribute.as_str(), server.name.as_str()));
+
+        for key in [&fields.command, &fields.url] {
+            if let Some(value) = entry.get(key).and_then(|v| v.as_str()) {
+                elem.push_attribute((key.as_str(), value));
+            }
+        }
+        if let Some(args) = entry.get(&fields.args).and_then(|v| v.as_array()) {
+            let joined = args
+                .iter()
+                .filter_map(|a| a.as_str())
+                .collect::<Vec<_>>()
+                .join(" ");
+            elem.push_attribute((fields.args.as_str(), joined.as_str()));
+        }
+        if let Some(ref key) = layout.enabled_attribute {
+            elem.push_attribute((key.as_str(), server.enabled.to_string().as_str()));
+        }
+        push_extra_attributes(&mut elem, server, &spec.id);
+
+        let env = entry.get(&fields.env).and_then(|v| v.as_object());
+        match env {
+            Some(env) if !env.is_empty() => {
+                writer
+                    .write_event(Event::Start(elem))
+                    .context("Failed to write server start")?;
+                writer
+                    .write_event(Event::Start(BytesStart::new(layout.env_container.as_str())))
+                    .context("Failed to write env container")?;
+                for (key, value) in env {
+                    let mut env_elem = BytesStart::new(layout.env_element.as_str());
+                    env_elem.push_attribute(("name", key.as_str()));
+                    env_elem.push_attribute(("value", value.as_str().unwrap_or_default()));
+                    writer
+                        .write_event(Event::Empty(env_elem))
+                        .context("Failed to write env element")?;
+                }
+                writer
+                    .write_event(Event::End(BytesEnd::new(layout.env_container.as_str())))
+                    .context("Failed to write env container end")?;
+                writer
+                    .write_event(Event::End(BytesEnd::new(layout.server_element.as_str())))
+                    .context("Failed to write server end")?;
+            }
+            _ => {
+                writer
+                    .write_event(Event::Empty(elem))
+                    .context("Failed to write server element")?;
+            }
+        }
+    }
+
+    for segment in segments.iter().rev() {
+        writer
+            .write_event(Event::End(BytesEnd::new(segment.as_str())))
+            .context("Failed to write XML element end")?;
+    }
+
+    let result = writer.into_inner().into_inner();
+    String::from_utf8(result).context("Invalid UTF-8 in XML output")
 }
@@ -0,0 +1,387 @@
+//! `${NAME}` references in server definitions, resolved when a server is
+//! written to a client or launched.
+//!
+//! Values come from layers, highest precedence first: the profile's variables
+//! (see `profiles`), global variables kept in the master config, secret
+//! variables kept in the secret store (see `secrets`), and the process
+//! environment for names matching `settings.env_allowlist`. A profile's
+//! secret variables live in its own namespace of the store.
+//! `$${NAME}` writes a literal `${NAME}`; references a client expands itself
+//! (`${workspaceFolder}`, `${input:...}`, `${env:...}`) are left alone.
+
+use super::profiles::{self, Profile};
+use super::{McpConfig, McpServerConfig};
+use crate::errors::ConductorError;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Shown in place of secret values in previews.
+pub const MASK: &str = "••••••";
+
+/// Variables VS Code and Cursor substitute in their own configs.
+const CLIENT_VARIABLES: &[&str] = &[
+    "workspaceFolder",
+    "workspaceFolderBasename",
+    "userHome",
+    "pathSeparator",
+    "cwd",
+];
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum VariableSource {
+    Profile,
+    Global,
+    Secret,
+    Environment,
+}
+
+#[derive(Debug, Clone)]
+struct Variable {
+    value: String,
+    source: VariableSource,
+    secret: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VariableStore {
+    values: HashMap<String, Variable>,
+}
+
+impl VariableStore {
+    /// Every variable visible to `cfg` under its active profile. With
+    /// `mask_secrets`, secret values are replaced by [`MASK`] and the
+    /// secret store isn't read. Fails when the secret store can't be read.
+    pub fn load(cfg: &McpConfig, mask_secrets: bool) -> Result<Self, ConductorError> {
+        Self::load_for(cfg, profiles::active(cfg), mask_secrets)
+    }
+
+    /// Like [`load`](Self::load), with `profile` in place of the active one.
+    pub fn load_for(
+        cfg: &McpConfig,
+        profile: Option<&Profile>,
+        mask_secrets: bool,
+    ) -> Result<Self, ConductorError> {
+        let read = |username: String| {
+            if mask_secrets {
+                Ok(Some(MASK.to_string()))
+            } else {
+                crate::secrets::get(&username)
+            }
+        };
+        let mut store = Self::default();
+        for (name, value) in std::env::vars() {
+            if env_allowed(&cfg.settings.env_allowlist, &name) {
+                store.insert(name, value, VariableSource::Environment, false);
+            }
+        }
+        for name in &cfg.secret_variables {
+            if let Some(value) = read(secret_username(name))? {
+                store.insert(name.clone(), value, VariableSource::Secret, true);
+            }
+        }
+        for (name, value) in &cfg.variables {
+            store.insert(name.clone(), value.clone(), VariableSource::Global, false);
+        }
+        if let Some(profile) = profile {
+            for name in &profile.secret_variables {
+                if let Some(value) = read(profile.keychain_username(&secret_username(name)))? {
+                    store.insert(name.clone(), value, VariableSource::Profile, true);
+                }
+            }
+            for (name, value) in &profile.variables {
+                store.insert(name.clone(), value.clone(), VariableSource::Profile, false);
+            }
+        }
+        Ok(store)
+    }
+
+    fn insert(&mut self, name: String, value: String, source: VariableSource, secret: bool) {
+        self.values.insert(
+            name,
+            Variable {
+                value,
+                source,
+                secret,
+            },
+        );
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(|v| v.value.as_str())
+    }
+
+    pub fn is_secret(&self, name: &str) -> bool {
+        self.values.get(name).is_some_and(|v| v.secret)
+    }
+
+    /// This store without its secret variables, whose references are then
+    /// left as written.
+    pub fn without_secrets(&self) -> Self {
+        let mut public = self.clone();
+        public.values.retain(|_, v| !v.secret);
+        public
+    }
+
+    /// Name, value and source of every variable, sorted by name.
+    pub fn entries(&self) -> Vec<(&str, &str, VariableSource)> {
+        let mut entries: Vec<_> = self
+            .values
+            .iter()
+            .map(|(name, v)| (name.as_str(), v.value.as_str(), v.source))
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        entries
+    }
+}
+
+pub fn secret_username(name: &str) -> String {
+    format!("variable:{}", name)
+}
+
+fn env_allowed(allowlist: &[String], name: &str) -> bool {
+    allowlist
+        .iter()
+        .any(|pattern| glob::Pattern::new(pattern).map_or(pattern == name, |p| p.matches(name)))
+}
+
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Replaces the references in `text`. Names with no value are pushed onto
+/// `undefined` and their reference is kept as written.
+pub fn interpolate(text: &str, store: &VariableStore, undefined: &mut Vec<String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('$') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if let Some(escaped) = rest.strip_prefix("$${") {
+            out.push_str("${");
+            rest = escaped;
+            continue;
+        }
+        let Some(body) = rest.strip_prefix("${") else {
+            out.push('$');
+            rest = &rest[1..];
+            continue;
+        };
+        let Some(end) = body.find('}') else {
+            break;
+        };
+        let name = &body[..end];
+        match store.get(name) {
+            _ if !is_valid_name(name) || CLIENT_VARIABLES.contains(&name) => {
+                out.push_str(&rest[..end + 3]);
+            }
+            Some(value) => out.push_str(value),
+            None => {
+                if !undefined.iter().any(|n| n == name) {
+                    undefined.push(name.to_string());
+                }
+                out.push_str(&rest[..end + 3]);
+            }
+        }
+        rest = &body[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Resolves the command, args, env values, URL and per-client headers of
+/// `server` in place and returns the names that had no value.
+pub fn interpolate_server(server: &mut McpServerConfig, store: &VariableStore) -> Vec<String> {
+    let mut undefined = Vec::new();
+    if let Some(command) = &mut server.command {
+        *command = interpolate(command, store, &mut undefined);
+    }
+    for arg in &mut server.args {
+        *arg = interpolate(arg, store, &mut undefined);
+    }
+    for value in server.env.values_mut() {
+        *value = interpolate(value, store, &mut undefined);
+    }
+    if let Some(url) = &mut server.url {
+        *url = interpolate(url, store, &mut undefined);
+    }
+    for value in server.headers.values_mut() {
+        *value = interpolate(value, store, &mut undefined);
+    }
+    // Configs imported before `headers` was a field keep them in `extra`.
+    for fields in server.extra.values_mut() {
+        let Some(headers) = fields.get_mut("headers").and_then(|h| h.as_object_mut()) else {
+            continue;
+        };
+        for value in headers.values_mut() {
+            if let Some(text) = value.as_str() {
+                *value = interpolate(text, store, &mut undefined).into();
+            }
+        }
+    }
+    undefined
+}
+
+/// The names `server` references that `store` can't resolve.
+pub fn undefined_in(server: &McpServerConfig, store: &VariableStore) -> Vec<String> {
+    interpolate_server(&mut server.clone(), store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::EnvGuard;
+
+    fn store(variables: &[(&str, &str)]) -> VariableStore {
+        let cfg = McpConfig {
+            variables: variables
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            ..Default::default()
+        };
+        VariableStore::load_for(&cfg, None, true).unwrap()
+    }
+
+    fn profile(variables: &[(&str, &str)], secret_variables: &[&str]) -> Profile {
+        Profile {
+            id: "work".to_string(),
+            name: "Work".to_string(),
+            servers: Vec::new(),
+            variables: variables
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            secret_variables: secret_variables.iter().map(|s| s.to_string()).collect(),
+            projects: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn replaces_references_and_unescapes_dollar_dollar() {
+        let store = store(&[("ORG", "acme")]);
+        let mut undefined = Vec::new();
+        assert_eq!(
+            interpolate("https://${ORG}.example.com/$${ORG}/$ORG/$", &store, &mut undefined),
+            "https://acme.example.com/${ORG}/$ORG/$"
+        );
+        assert!(undefined.is_empty());
+    }
+
+    #[test]
+    fn reports_unknown_variables_once_and_keeps_them() {
+        let store = store(&[]);
+        let mut undefined = Vec::new();
+        assert_eq!(
+            interpolate("${MISSING}-${MISSING}-${OTHER}", &store, &mut undefined),
+            "${MISSING}-${MISSING}-${OTHER}"
+        );
+        assert_eq!(undefined, ["MISSING", "OTHER"]);
+    }
+
+    #[test]
+    fn leaves_client_variables_alone() {
+        let store = store(&[]);
+        let mut undefined = Vec::new();
+        let text = "${workspaceFolder}/${input:token}/${env:HOME}/${unterminated";
+        assert_eq!(interpolate(text, &store, &mut undefined), text);
+        assert!(undefined.is_empty());
+    }
+
+    #[test]
+    fn profile_beats_global_beats_secret_beats_environment() {
+        let mut env = EnvGuard::lock();
+        env.set("CONDUCTOR_TEST_LAYER", Some("from-env"));
+        let mut cfg = McpConfig::default();
+        cfg.settings.env_allowlist = vec!["CONDUCTOR_TEST_*".to_string()];
+        let value = |cfg: &McpConfig, profile: Option<&Profile>| {
+            VariableStore::load_for(cfg, profile, true)
+                .unwrap()
+                .entries()
+                .into_iter()
+                .find(|(name, _, _)| *name == "CONDUCTOR_TEST_LAYER")
+                .map(|(_, value, source)| (value.to_string(), source))
+        };
+
+        assert_eq!(
+            value(&cfg, None),
+            Some(("from-env".to_string(), VariableSource::Environment))
+        );
+        cfg.secret_variables = vec!["CONDUCTOR_TEST_LAYER".to_string()];
+        assert_eq!(value(&cfg, None), Some((MASK.to_string(), VariableSource::Secret)));
+        cfg.variables.insert("CONDUCTOR_TEST_LAYER".to_string(), "from-global".to_string());
+        assert_eq!(
+            value(&cfg, None),
+            Some(("from-global".to_string(), VariableSource::Global))
+        );
+        let work = profile(&[("CONDUCTOR_TEST_LAYER", "from-profile")], &[]);
+        assert_eq!(
+            value(&cfg, Some(&work)),
+            Some(("from-profile".to_string(), VariableSource::Profile))
+        );
+        let other = profile(&[], &[]);
+        assert_eq!(
+            value(&cfg, Some(&other)),
+            Some(("from-global".to_string(), VariableSource::Global))
+        );
+    }
+
+    #[test]
+    fn environment_outside_the_allowlist_is_ignored() {
+        let mut env = EnvGuard::lock();
+        env.set("CONDUCTOR_TEST_HIDDEN", Some("nope"));
+        let store = VariableStore::load_for(&McpConfig::default(), None, true).unwrap();
+        assert_eq!(store.get("CONDUCTOR_TEST_HIDDEN"), None);
+    }
+
+    #[test]
+    fn masks_secret_values_and_drops_them_from_the_public_store() {
+        let cfg = McpConfig {
+            secret_variables: vec!["API_TOKEN".to_string()],
+            ..Default::default()
+        };
+        let work = profile(&[], &["PROFILE_TOKEN"]);
+        let store = VariableStore::load_for(&cfg, Some(&work), true).unwrap();
+        assert_eq!(store.get("API_TOKEN"), Some(MASK));
+        assert_eq!(store.get("PROFILE_TOKEN"), Some(MASK));
+        assert!(store.is_secret("API_TOKEN") && store.is_secret("PROFILE_TOKEN"));
+
+        let public = store.without_secrets();
+        let mut undefined = Vec::new();
+        assert_eq!(
+            interpolate("Bearer ${API_TOKEN}", &public, &mut undefined),
+            "Bearer ${API_TOKEN}"
+        );
+        assert_eq!(undefined, ["API_TOKEN"]);
+    }
+
+    #[test]
+    fn interpolates_every_server_field() {
+        let store = store(&[("ORG", "acme"), ("REGION", "eu")]);
+        let mut server: McpServerConfig = serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "name": "api",
+            "enabled": true,
+            "transport": "stdio",
+            "command": "${ORG}-server",
+            "args": ["--region", "${REGION}"],
+            "env": {"ORG": "${ORG}"},
+            "url": "https://${REGION}.example.com",
+            "headers": {"X-Org": "${ORG}", "X-Key": "${KEY}"},
+        }))
+        .unwrap();
+
+        assert_eq!(undefined_in(&server, &store), ["KEY"]);
+        assert_eq!(interpolate_server(&mut server, &store), ["KEY"]);
+        assert_eq!(server.command.as_deref(), Some("acme-server"));
+        assert_eq!(server.args, ["--region", "eu"]);
+        assert_eq!(server.env["ORG"], "acme");
+        assert_eq!(server.url.as_deref(), Some("https://eu.example.com"));
+        assert_eq!(server.headers["X-Org"], "acme");
+        assert_eq!(server.headers["X-Key"], "${KEY}");
+    }
+}
@@ -0,0 +1,398 @@
+use anyhow::{Context, Result};
+
+/// Rewrite the items of the top-level block sequence `key`, editing the text
+/// in place.
+///
+/// Items for which `keep` returns true are kept verbatim, comments included;
+/// the others are dropped and `append` is added at the end using the existing
+/// item indentation. Everything outside the `key` block is left byte for byte.
+/// A missing key is appended to the document; a flow-style value
+/// (`key: [...]`) is re-rendered as a block sequence.
+pub fn edit_top_level_sequence(
+    existing_content: Option<&str>,
+    key: &str,
+    keep: impl Fn(&serde_yaml::Value) -> bool,
+    append: &[serde_yaml::Value],
+) -> Result<String> {
+    let source = existing_content.unwrap_or("");
+    let lines: Vec<&str> = source.split_inclusive('\n').collect();
+
+    let Some((start, end)) = find_block(&lines, key) else {
+        return Ok(append_block(source, &render_block(key, &[], append, 2)?));
+    };
+
+    let value_on_key_line = strip_comment(&lines[start][key.len() + 1..])
+        .trim()
+        .to_string();
+    let (kept, trailer, indent) = if value_on_key_line.is_empty() {
+        split_items(&lines[start + 1..end], &keep)?
+    } else {
+        // Flow style: re-render the kept items.
+        let items: Vec<serde_yaml::Value> = serde_yaml::from_str(&value_on_key_line)
+            .with_context(|| format!("Invalid YAML sequence at '{}'", key))?;
+        let kept = items
+            .iter()
+            .filter(|item| keep(item))
+            .map(|item| render_item(item, 2))
+            .collect::<Result<Vec<_>>>()?;
+        (kept, String::new(), 2)
+    };
+
+    let mut output: String = lines[..start].concat();
+    output.push_str(&render_block(key, &kept, append, indent)?);
+    output.push_str(&trailer);
+    output.push_str(&lines[end..].concat());
+    Ok(output)
+}
+
+/// Rewrite the entries of the top-level block mapping `key`, editing the text
+/// in place the way [`edit_top_level_sequence`] does for sequences.
+///
+/// Entries for which `keep` returns true are kept verbatim, comments included;
+/// the others are dropped and `append` is added at the end using the existing
+/// entry indentation. Entries with a key that isn't a string are always kept.
+/// A missing key is appended to the document; a flow-style value
+/// (`key: {...}`) is re-rendered as a block mapping.
+pub fn edit_top_level_mapping(
+    existing_content: Option<&str>,
+    key: &str,
+    keep: impl Fn(&str, &serde_yaml::Value) -> bool,
+    append: &[(String, serde_yaml::Value)],
+) -> Result<String> {
+    let source = existing_content.unwrap_or("");
+    let lines: Vec<&str> = source.split_inclusive('\n').collect();
+    let keep = |name: &serde_yaml::Value, value: &serde_yaml::Value| {
+        name.as_str().is_none_or(|name| keep(name, value))
+    };
+
+    let Some((start, end)) = find_block(&lines, key) else {
+        return Ok(append_block(
+            source,
+            &render_map_block(key, &[], append, 2)?,
+        ));
+    };
+
+    let value_on_key_line = strip_comment(&lines[start][key.len() + 1..])
+        .trim()
+        .to_string();
+    let (kept, trailer, indent) = if value_on_key_line.is_empty() {
+        split_entries(&lines[start + 1..end], &keep)?
+    } else {
+        // Flow style: re-render the kept entries.
+        let entries: serde_yaml::Mapping = serde_yaml::from_str(&value_on_key_line)
+            .with_context(|| format!("Invalid YAML mapping at '{}'", key))?;
+        let kept = entries
+            .iter()
+            .filter(|(name, value)| keep(name, value))
+            .map(|(name, value)| render_entry(name, value, 2))
+            .collect::<Result<Vec<_>>>()?;
+        (kept, String::new(), 2)
+    };
+
+    let mut output: String = lines[..start].concat();
+    output.push_str(&render_map_block(key, &kept, append, indent)?);
+    output.push_str(&trailer);
+    output.push_str(&lines[end..].concat());
+    Ok(output)
+}
+
+/// The line range of the top-level `key` block: its key line up to the next
+/// line that starts a new top-level entry.
+fn find_block(lines: &[&str], key: &str) -> Option<(usize, usize)> {
+    let start = lines.iter().position(|line| {
+        line.strip_prefix(key)
+            .and_then(|rest| rest.strip_prefix(':'))
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\t', '\n', '\r']))
+    })?;
+    let mut end = start + 1;
+    while end < lines.len() && is_block_continuation(lines[end]) {
+        end += 1;
+    }
+    // Trailing blank lines and column-0 comments belong to what follows.
+    while end > start + 1 && is_detached_trailer(lines[end - 1]) {
+        end -= 1;
+    }
+    Some((start, end))
+}
+
+fn append_block(source: &str, block: &str) -> String {
+    let mut output = source.to_string();
+    if !output.is_empty() && !output.ends_with('\n') {
+        output.push('\n');
+    }
+    output.push_str(block);
+    output
+}
+
+fn is_block_continuation(line: &str) -> bool {
+    line.trim().is_empty()
+        || line.starts_with([' ', '\t', '#'])
+        || line.starts_with("- ")
+        || line.trim_end() == "-"
+}
+
+fn is_detached_trailer(line: &str) -> bool {
+    line.trim().is_empty() || line.starts_with('#')
+}
+
+fn strip_comment(value: &str) -> &str {
+    match value.find(" #") {
+        Some(i) => &value[..i],
+        None if value.trim_start().starts_with('#') => "",
+        None => value,
+    }
+}
+
+/// Splits block lines into sequence items. Returns the text of the kept items,
+/// any comments after the last item, and the item indentation. Comment lines
+/// directly above an item travel with it.
+fn split_items(
+    block: &[&str],
+    keep: &impl Fn(&serde_yaml::Value) -> bool,
+) -> Result<(Vec<String>, String, usize)> {
+    let indent = block
+        .iter()
+        .find_map(|line| {
+            let trimmed = line.trim_start_matches(' ');
+            (trimmed.starts_with("- ") || trimmed.trim_end() == "-")
+                .then(|| line.len() - trimmed.len())
+        })
+        .unwrap_or(2);
+    let is_item_start = |line: &str| {
+        line.len() > indent
+            && line[..indent].chars().all(|c| c == ' ')
+            && (line[indent..].starts_with("- ") || line[indent..].trim_end() == "-")
+    };
+
+    let mut items: Vec<String> = Vec::new();
+    let mut prefix = String::new();
+    // Blank and comment lines seen since the last content line. They attach
+    // to the next item, or stay at the end of the block if none follows.
+    let mut pending = String::new();
+    for line in block {
+        if is_item_start(line) {
+            items.push(std::mem::take(&mut pending) + line);
+        } else if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            pending.push_str(line);
+        } else {
+            let target = items.last_mut().unwrap_or(&mut prefix);
+            target.push_str(&std::mem::take(&mut pending));
+            target.push_str(line);
+        }
+    }
+
+    let mut kept = Vec::new();
+    if !prefix.is_empty() {
+        kept.push(prefix);
+    }
+    for item in items {
+        let dedented: String = item
+            .split_inclusive('\n')
+            .map(|line| match line.get(..indent) {
+                Some(pad) if pad.chars().all(|c| c == ' ') => &line[indent..],
+                _ => line.trim_start(),
+            })
+            .collect::<Vec<_>>()
+            .join("");
+        let parsed: Vec<serde_yaml::Value> =
+            serde_yaml::from_str(&dedented).context("Invalid YAML sequence item")?;
+        if parsed.first().is_some_and(keep) {
+            let mut text = item;
+            if !text.ends_with('\n') {
+                text.push('\n');
+            }
+            kept.push(text);
+        }
+    }
+    Ok((kept, pending, indent))
+}
+
+/// Splits block lines into mapping entries, like [`split_items`] does for
+/// sequence items.
+fn split_entries(
+    block: &[&str],
+    keep: &impl Fn(&serde_yaml::Value, &serde_yaml::Value) -> bool,
+) -> Result<(Vec<String>, String, usize)> {
+    let indent = block
+        .iter()
+        .find(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|line| line.len() - line.trim_start_matches(' ').len())
+        .unwrap_or(2);
+    let is_entry_start = |line: &str| {
+        line.len() > indent
+            && line[..indent].chars().all(|c| c == ' ')
+            && !line[indent..].starts_with([' ', '\t', '#', '-', '\n', '\r'])
+    };
+
+    let mut entries: Vec<String> = Vec::new();
+    let mut prefix = String::new();
+    let mut pending = String::new();
+    for line in block {
+        if is_entry_start(line) {
+            entries.push(std::mem::take(&mut pending) + line);
+        } else if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            pending.push_str(line);
+        } else {
+            let target = entries.last_mut().unwrap_or(&mut prefix);
+            target.push_str(&std::mem::take(&mut pending));
+            target.push_str(line);
+        }
+    }
+
+    let mut kept = Vec::new();
+    if !prefix.is_empty() {
+        kept.push(prefix);
+    }
+    for entry in entries {
+        let dedented: String = entry
+            .split_inclusive('\n')
+            .map(|line| match line.get(..indent) {
+                Some(pad) if pad.chars().all(|c| c == ' ') => &line[indent..],
+                _ => line.trim_start(),
+            })
+            .collect::<Vec<_>>()
+            .join("");
+        let parsed: serde_yaml::Mapping =
+            serde_yaml::from_str(&dedented).context("Invalid YAML mapping entry")?;
+        if parsed
+            .iter()
+            .next()
+            .is_some_and(|(name, value)| keep(name, value))
+        {
+            let mut text = entry;
+            if !text.ends_with('\n') {
+                text.push('\n');
+            }
+            kept.push(text);
+        }
+    }
+    Ok((kept, pending, indent))
+}
+
+fn render_entry(
+    name: &serde_yaml::Value,
+    value: &serde_yaml::Value,
+    indent: usize,
+) -> Result<String> {
+    let mut entry = serde_yaml::Mapping::new();
+    entry.insert(name.clone(), value.clone());
+    let rendered = serde_yaml::to_string(&entry).context("Failed to serialize YAML")?;
+    let pad = " ".repeat(indent);
+    Ok(rendered
+        .lines()
+        .map(|line| format!("{}{}\n", pad, line))
+        .collect())
+}
+
+fn render_map_block(
+    key: &str,
+    kept: &[String],
+    append: &[(String, serde_yaml::Value)],
+    indent: usize,
+) -> Result<String> {
+    if kept.is_empty() && append.is_empty() {
+        return Ok(format!("{}: {{}}\n", key));
+    }
+    let mut block = format!("{}:\n", key);
+    for entry in kept {
+        block.push_str(entry);
+    }
+    for (name, value) in append {
+        block.push_str(&render_entry(&name.as_str().into(), value, indent)?);
+    }
+    Ok(block)
+}
+
+fn render_item(value: &serde_yaml::Value, indent: usize) -> Result<String> {
+    let rendered = serde_yaml::to_string(&vec![value]).context("Failed to serialize YAML")?;
+    let pad = " ".repeat(indent);
+    Ok(rendered
+        .lines()
+        .map(|line| format!("{}{}\n", pad, line))
+        .collect())
+}
+
+fn render_block(
+    key: &str,
+    kept: &[String],
+    append: &[serde_yaml::Value],
+    indent: usize,
+) -> Result<String> {
+    let mut block = String::new();
+    if kept.is_empty() && append.is_empty() {
+        block.push_str(&format!("{}: []\n", key));
+        return Ok(block);
+    }
+    block.push_str(&format!("{}:\n", key));
+    for item in kept {
+        block.push_str(item);
+    }
+    for value in append {
+        block.push_str(&render_item(value, indent)?);
+    }
+    Ok(block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GOOSE: &str = "\
+# Goose settings
+GOOSE_PROVIDER: anthropic
+extensions:
+  # built in
+  developer:
+    enabled: true
+    type: builtin
+  github:
+    cmd: old
+GOOSE_MODEL: claude # pinned
+";
+
+    #[test]
+    fn mapping_edit_keeps_comments_and_other_keys() {
+        let github: serde_yaml::Value = serde_yaml::from_str("cmd: npx\nenabled: true").unwrap();
+        let output = edit_top_level_mapping(
+            Some(GOOSE),
+            "extensions",
+            |name, _| name != "github",
+            &[("github".to_string(), github)],
+        )
+        .unwrap();
+        assert_eq!(
+            output,
+            "\
+# Goose settings
+GOOSE_PROVIDER: anthropic
+extensions:
+  # built in
+  developer:
+    enabled: true
+    type: builtin
+  github:
+    cmd: npx
+    enabled: true
+GOOSE_MODEL: claude # pinned
+"
+        );
+    }
+
+    #[test]
+    fn mapping_edit_adds_a_missing_key_and_expands_flow_style() {
+        let entry: serde_yaml::Value = serde_yaml::from_str("cmd: npx").unwrap();
+        let append = [("github".to_string(), entry)];
+        let added = edit_top_level_mapping(Some("a: 1"), "extensions", |_, _| true, &append);
+        assert_eq!(
+            added.unwrap(),
+            "a: 1\nextensions:\n  github:\n    cmd: npx\n"
+        );
+
+        let flow = "extensions: {developer: {enabled: true}}\n";
+        let expanded = edit_top_level_mapping(Some(flow), "extensions", |_, _| true, &append);
+        assert_eq!(
+            expanded.unwrap(),
+            "extensions:\n  developer:\n    enabled: true\n  github:\n    cmd: npx\n"
+        );
+    }
+}
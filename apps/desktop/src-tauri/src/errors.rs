@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -11,35 +13,312 @@ pub enum ConductorError {
     #[error("Failed to parse config: {0}")]
     ConfigParse(String),
 
+    #[error("Config was written by a newer Conductor (schema v{found}, supported up to v{supported})")]
+    ConfigTooNew { found: u32, supported: u32 },
+
+    #[error("{client_id} config has invalid {format}: {reason}")]
+    ClientConfigInvalid {
+        client_id: String,
+        format: &'static str,
+        reason: String,
+        path: Option<PathBuf>,
+    },
+
+    #[error("Failed to write {client_id} config: {reason}")]
+    ClientConfigWrite {
+        client_id: String,
+        reason: String,
+        path: Option<PathBuf>,
+    },
+
+    #[error("Cannot determine config path for {0}")]
+    ClientPathUnknown(String),
+
     #[error("Client not found: {0}")]
     ClientNotFound(String),
 
-    #[error("No MCP clients detected")]
+    #[error("Client '{0}' is not installed or not detected")]
     ClientNotDetected(String),
 
+    #[error("Sync verification failed for {client_id}: {reason}")]
+    SyncVerification { client_id: String, reason: String },
+
+    #[error("No remote fallback for {client_id}: {reason}")]
+    RemoteFallbackUnavailable { client_id: String, reason: String },
+
     #[error("Server not found: {0}")]
     ServerNotFound(String),
 
     #[error("Server name collision: {0}")]
     ServerNameCollision(String),
 
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+
     #[error("Secret access failed: {0}")]
     SecretAccess(String),
 
+    #[error("Keychain is locked or unavailable: {0}")]
+    KeychainUnavailable(String),
+
+    #[error("{backend} is unavailable: {reason}")]
+    SecretBackendUnavailable { backend: String, reason: String },
+
     #[error("OAuth flow failed: {0}")]
     OAuthFailed(String),
 
+    #[error("OAuth is not configured: {0}")]
+    OAuthNotConfigured(String),
+
+    #[error("Network request failed: {0}")]
+    Network(String),
+
     #[error("Registry fetch failed: {0}")]
     RegistryFetch(String),
 
+    #[error("Tool discovery failed: {0}")]
+    ToolDiscovery(String),
+
+    #[error("'{server}' references undefined variables: {}", .names.join(", "))]
+    UndefinedVariable { server: String, names: Vec<String> },
+
     #[error("File watcher failed: {0}")]
     WatcherFailed(String),
 
+    #[error("Could not update launch at login: {0}")]
+    Autostart(String),
+
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 
     #[error("Serialization error: {0}")]
     SerializationError(String),
+
+    #[error("{0}")]
+    Internal(String),
+}
+
+/// Coarse error category the frontend can branch on.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorKind {
+    ConfigRead,
+    ConfigWrite,
+    ConfigParse,
+    ConfigTooNew,
+    ClientConfigInvalid,
+    ClientConfigWrite,
+    ClientNotFound,
+    ClientNotDetected,
+    SyncVerification,
+    RemoteFallbackUnavailable,
+    ServerNotFound,
+    ServerNameCollision,
+    InvalidInput,
+    SecretAccess,
+    KeychainUnavailable,
+    SecretBackendUnavailable,
+    OAuthFailed,
+    OAuthNotConfigured,
+    Network,
+    Registry,
+    ToolDiscovery,
+    UndefinedVariable,
+    Watcher,
+    Autostart,
+    Io,
+    Serialization,
+    Internal,
+}
+
+impl ConductorError {
+    /// A client config that could not be parsed as `format` (e.g. "JSON", "TOML").
+    /// The path is attached later by whoever knows it, via `with_path`.
+    pub fn invalid_client_config(
+        client_id: &str,
+        format: &'static str,
+        reason: impl std::fmt::Display,
+    ) -> Self {
+        ConductorError::ClientConfigInvalid {
+            client_id: client_id.to_string(),
+            format,
+            reason: reason.to_string(),
+            path: None,
+        }
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            ConductorError::ConfigRead(_) => ErrorKind::ConfigRead,
+            ConductorError::ConfigWrite(_) => ErrorKind::ConfigWrite,
+            ConductorError::ConfigParse(_) => ErrorKind::ConfigParse,
+            ConductorError::ConfigTooNew { .. } => ErrorKind::ConfigTooNew,
+            ConductorError::ClientConfigInvalid { .. } => ErrorKind::ClientConfigInvalid,
+            ConductorError::ClientConfigWrite { .. } => ErrorKind::ClientConfigWrite,
+            ConductorError::ClientPathUnknown(_) => ErrorKind::ClientNotDetected,
+            ConductorError::ClientNotFound(_) => ErrorKind::ClientNotFound,
+            ConductorError::ClientNotDetected(_) => ErrorKind::ClientNotDetected,
+            ConductorError::SyncVerification { .. } => ErrorKind::SyncVerification,
+            ConductorError::RemoteFallbackUnavailable { .. } => {
+                ErrorKind::RemoteFallbackUnavailable
+            }
+            ConductorError::ServerNotFound(_) => ErrorKind::ServerNotFound,
+            ConductorError::ServerNameCollision(_) => ErrorKind::ServerNameCollision,
+            ConductorError::InvalidInput(_) => ErrorKind::InvalidInput,
+            ConductorError::SecretAccess(_) => ErrorKind::SecretAccess,
+            ConductorError::KeychainUnavailable(_) => ErrorKind::KeychainUnavailable,
+            ConductorError::SecretBackendUnavailable { .. } => ErrorKind::SecretBackendUnavailable,
+            ConductorError::OAuthFailed(_) => ErrorKind::OAuthFailed,
+            ConductorError::OAuthNotConfigured(_) => ErrorKind::OAuthNotConfigured,
+            ConductorError::Network(_) => ErrorKind::Network,
+            ConductorError::RegistryFetch(_) => ErrorKind::Registry,
+            ConductorError::ToolDiscovery(_) => ErrorKind::ToolDiscovery,
+            ConductorError::UndefinedVariable { .. } => ErrorKind::UndefinedVariable,
+            ConductorError::WatcherFailed(_) => ErrorKind::Watcher,
+            ConductorError::Autostart(_) => ErrorKind::Autostart,
+            ConductorError::IoError(_) => ErrorKind::Io,
+            ConductorError::SerializationError(_) => ErrorKind::Serialization,
+            ConductorError::Internal(_) => ErrorKind::Internal,
+        }
+    }
+
+    /// Whether retrying the same operation later can reasonably succeed.
+    pub fn retryable(&self) -> bool {
+        match self {
+            ConductorError::Network(_)
+            | ConductorError::RegistryFetch(_)
+            | ConductorError::KeychainUnavailable(_)
+            | ConductorError::SecretBackendUnavailable { .. }
+            | ConductorError::ToolDiscovery(_)
+            | ConductorError::SyncVerification { .. }
+            | ConductorError::ClientConfigWrite { .. } => true,
+            ConductorError::IoError(e) => matches!(
+                e.kind(),
+                std::io::ErrorKind::Interrupted
+                    | std::io::ErrorKind::WouldBlock
+                    | std::io::ErrorKind::TimedOut
+            ),
+            _ => false,
+        }
+    }
+
+    /// A short, user-facing hint about how to resolve the error.
+    pub fn suggested_action(&self) -> Option<String> {
+        let hint = match self {
+            ConductorError::ConfigTooNew { .. } => "Update Conductor to the latest version.",
+            ConductorError::ConfigParse(_) => {
+                "Fix ~/.conductor/config.json by hand or restore one of its .bak files."
+            }
+            ConductorError::ClientConfigInvalid { .. } => {
+                "Fix the client's config file manually, or delete it and re-sync."
+            }
+            ConductorError::ClientConfigWrite { .. } => {
+                "Check that the file is writable and not locked by the client, then retry."
+            }
+            ConductorError::ClientNotDetected(_) | ConductorError::ClientPathUnknown(_) => {
+                "Install or launch the client once so it creates its config, then retry."
+            }
+            ConductorError::SyncVerification { .. } => "Retry the sync.",
+            ConductorError::RemoteFallbackUnavailable { .. } => {
+                "Install Node.js so npx is on PATH, or pick another remote fallback in Settings."
+            }
+            ConductorError::ServerNameCollision(_) => "Choose a different server name.",
+            ConductorError::KeychainUnavailable(_) => {
+                "Unlock your system keychain and allow Conductor access, then retry."
+            }
+            ConductorError::SecretBackendUnavailable { .. } => {
+                "Sign in to or unlock the secret backend chosen in Settings, then retry."
+            }
+            ConductorError::OAuthNotConfigured(_) => {
+                "Add the OAuth client ID and secret for this server in its settings."
+            }
+            ConductorError::Network(_) | ConductorError::RegistryFetch(_) => {
+                "Check your internet connection and retry."
+            }
+            ConductorError::ToolDiscovery(_) => {
+                "Check that the server starts and its secrets are set, then retry."
+            }
+            ConductorError::UndefinedVariable { .. } => {
+                "Define the variables in Settings, allow them from the environment, or write $${NAME} for a literal ${NAME}."
+            }
+            _ => return None,
+        };
+        Some(hint.to_string())
+    }
+
+    pub fn client_id(&self) -> Option<&str> {
+        match self {
+            ConductorError::ClientConfigInvalid { client_id, .. }
+            | ConductorError::ClientConfigWrite { client_id, .. }
+            | ConductorError::SyncVerification { client_id, .. }
+            | ConductorError::RemoteFallbackUnavailable { client_id, .. } => Some(client_id),
+            ConductorError::ClientPathUnknown(id)
+            | ConductorError::ClientNotFound(id)
+            | ConductorError::ClientNotDetected(id) => Some(id),
+            _ => None,
+        }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            ConductorError::ClientConfigInvalid { path, .. }
+            | ConductorError::ClientConfigWrite { path, .. } => path.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Fills in the file path on client-config errors that were raised before
+    /// the path was known (e.g. by a serializer working on a string).
+    pub fn with_path(mut self, new_path: &Path) -> Self {
+        match &mut self {
+            ConductorError::ClientConfigInvalid { path, .. }
+            | ConductorError::ClientConfigWrite { path, .. }
+                if path.is_none() =>
+            {
+                *path = Some(new_path.to_path_buf());
+            }
+            _ => {}
+        }
+        self
+    }
+
+    /// Classifies an `anyhow::Error` by the first recognizable error in its
+    /// chain. Unrecognized errors become `Internal`.
+    pub fn from_anyhow(e: anyhow::Error) -> Self {
+        let e = match e.downcast::<ConductorError>() {
+            Ok(ce) => return ce,
+            Err(e) => e,
+        };
+        let message = format!("{:#}", e);
+        for cause in e.chain() {
+            if let Some(ke) = cause.downcast_ref::<keyring::Error>() {
+                return ConductorError::from_keyring_ref(ke, message);
+            }
+            if let Some(re) = cause.downcast_ref::<reqwest::Error>() {
+                return ConductorError::Network(if re.is_timeout() {
+                    format!("timed out: {}", message)
+                } else {
+                    message
+                });
+            }
+            if cause.downcast_ref::<serde_json::Error>().is_some() {
+                return ConductorError::SerializationError(message);
+            }
+            if let Some(io) = cause.downcast_ref::<std::io::Error>() {
+                return ConductorError::IoError(std::io::Error::new(io.kind(), message));
+            }
+        }
+        ConductorError::Internal(message)
+    }
+
+    fn from_keyring_ref(e: &keyring::Error, message: String) -> Self {
+        match e {
+            keyring::Error::NoStorageAccess(_) | keyring::Error::PlatformFailure(_) => {
+                ConductorError::KeychainUnavailable(message)
+            }
+            _ => ConductorError::SecretAccess(message),
+        }
+    }
 }
 
 impl From<serde_json::Error> for ConductorError {
@@ -48,9 +327,22 @@ impl From<serde_json::Error> for ConductorError {
     }
 }
 
+impl From<keyring::Error> for ConductorError {
+    fn from(e: keyring::Error) -> Self {
+        let message = e.to_string();
+        ConductorError::from_keyring_ref(&e, message)
+    }
+}
+
+impl From<reqwest::Error> for ConductorError {
+    fn from(e: reqwest::Error) -> Self {
+        ConductorError::Network(e.to_string())
+    }
+}
+
 impl From<anyhow::Error> for ConductorError {
     fn from(e: anyhow::Error) -> Self {
-        ConductorError::ConfigRead(e.to_string())
+        ConductorError::from_anyhow(e)
     }
 }
 
@@ -59,3 +351,119 @@ impl From<ConductorError> for String {
         e.to_string()
     }
 }
+
+/// Error shape returned by every Tauri command.
+///
+/// Serialized as a plain object so the UI can branch on `kind`, show
+/// `suggestedAction`, and offer a retry button when `retryable` is set.
+/// Sync results carry one per failed client and file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandError {
+    pub kind: ErrorKind,
+    pub message: String,
+    pub client_id: Option<String>,
+    pub server_id: Option<String>,
+    pub path: Option<String>,
+    pub retryable: bool,
+    pub suggested_action: Option<String>,
+}
+
+pub type CommandResult<T> = Result<T, CommandError>;
+
+impl CommandError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            client_id: None,
+            server_id: None,
+            path: None,
+            retryable: false,
+            suggested_action: None,
+        }
+    }
+
+    /// Attaches a client ID unless the error already carries one.
+    pub fn client(mut self, client_id: &str) -> Self {
+        if self.client_id.is_none() {
+            self.client_id = Some(client_id.to_string());
+        }
+        self
+    }
+
+    /// Attaches a server ID unless the error already carries one.
+    pub fn server(mut self, server_id: &str) -> Self {
+        if self.server_id.is_none() {
+            self.server_id = Some(server_id.to_string());
+        }
+        self
+    }
+
+    /// Attaches a file path unless the error already carries one.
+    pub fn path(mut self, path: &Path) -> Self {
+        if self.path.is_none() {
+            self.path = Some(path.to_string_lossy().to_string());
+        }
+        self
+    }
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<&ConductorError> for CommandError {
+    fn from(e: &ConductorError) -> Self {
+        Self {
+            kind: e.kind(),
+            message: e.to_string(),
+            client_id: e.client_id().map(|s| s.to_string()),
+            server_id: None,
+            path: e.path().map(|p| p.to_string_lossy().to_string()),
+            retryable: e.retryable(),
+            suggested_action: e.suggested_action(),
+        }
+    }
+}
+
+impl From<ConductorError> for CommandError {
+    fn from(e: ConductorError) -> Self {
+        CommandError::from(&e)
+    }
+}
+
+impl From<anyhow::Error> for CommandError {
+    /// Prefers a `ConductorError` anywhere in the chain for kind and context,
+    /// but keeps the full chain as the message so no `.context()` is lost.
+    fn from(e: anyhow::Error) -> Self {
+        if let Some(ce) = e.chain().find_map(|c| c.downcast_ref::<ConductorError>()) {
+            let mut out = CommandError::from(ce);
+            if e.downcast_ref::<ConductorError>().is_none() {
+                out.message = format!("{:#}", e);
+            }
+            return out;
+        }
+        ConductorError::from_anyhow(e).into()
+    }
+}
+
+impl From<serde_json::Error> for CommandError {
+    fn from(e: serde_json::Error) -> Self {
+        ConductorError::from(e).into()
+    }
+}
+
+impl From<keyring::Error> for CommandError {
+    fn from(e: keyring::Error) -> Self {
+        ConductorError::from(e).into()
+    }
+}
+
+impl From<reqwest::Error> for CommandError {
+    fn from(e: reqwest::Error) -> Self {
+        ConductorError::from(e).into()
+    }
+}
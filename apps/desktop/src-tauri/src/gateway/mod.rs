@@ -0,0 +1,813 @@
+//! Local MCP gateway: one streamable HTTP endpoint that serves every enabled
+//! server. Each server runs as an upstream process; their tools and prompts
+//! are merged under `<server>__<name>` and calls are routed back by prefix.
+//! Config changes are applied live and announced with `list_changed`.
+//! The same listener serves the supervisor's managed servers, so it also
+//! runs while the gateway itself is off.
+
+pub mod upstream;
+
+use crate::config::{self, McpServerConfig, ToolFilter, TransportType};
+use anyhow::{anyhow, Context, Result};
+use axum::extract::Path;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+use tokio::sync::{broadcast, Notify};
+use tokio::task::JoinHandle;
+use tokio::time::Duration;
+use upstream::Upstream;
+
+/// ID and name of the single entry written to clients in gateway mode.
+pub const GATEWAY_SERVER_ID: &str = "conductor-gateway";
+pub const GATEWAY_SERVER_NAME: &str = "conductor";
+
+/// Newest first; the first is also what the gateway asks upstreams for.
+pub(crate) const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+const SEPARATOR: &str = "__";
+pub(crate) const SESSION_HEADER: &str = "mcp-session-id";
+const LIST_TIMEOUT: Duration = Duration::from_secs(30);
+pub(crate) const CALL_TIMEOUT: Duration = Duration::from_secs(300);
+const MAX_PAGES: usize = 20;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayStatus {
+    pub enabled: bool,
+    /// Set while the gateway is listening.
+    pub url: Option<String>,
+    pub error: Option<String>,
+    pub servers: Vec<GatewayServerStatus>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewayServerStatus {
+    pub server_id: String,
+    pub name: String,
+    pub running: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+struct Listener {
+    port: Option<u16>,
+    error: Option<String>,
+    /// Whether `/mcp` is served; off when only managed servers need the port.
+    gateway: bool,
+}
+
+struct Gateway {
+    upstreams: RwLock<HashMap<String, Arc<Upstream>>>,
+    /// Last start failure per server ID, with the fingerprint that failed so
+    /// an unchanged broken server isn't relaunched on every config write.
+    failures: Mutex<HashMap<String, (String, String)>>,
+    sessions: Mutex<HashSet<String>>,
+    /// Which server listed each resource URI, for routing reads.
+    resource_owners: Mutex<HashMap<String, String>>,
+    events: broadcast::Sender<Value>,
+    listener: Mutex<Listener>,
+}
+
+static GATEWAY: LazyLock<Gateway> = LazyLock::new(|| Gateway {
+    upstreams: RwLock::new(HashMap::new()),
+    failures: Mutex::new(HashMap::new()),
+    sessions: Mutex::new(HashSet::new()),
+    resource_owners: Mutex::new(HashMap::new()),
+    events: broadcast::channel(64).0,
+    listener: Mutex::new(Listener::default()),
+});
+
+static CONFIG_CHANGED: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn gateway_url(port: u16) -> String {
+    format!("http://127.0.0.1:{}/mcp", port)
+}
+
+/// The entry `client_id` gets in gateway mode. Each client has its own path
+/// so the gateway can apply that client's tool filters.
+pub fn client_entry(port: u16, client_id: &str) -> McpServerConfig {
+    McpServerConfig {
+        id: GATEWAY_SERVER_ID.to_string(),
+        name: GATEWAY_SERVER_NAME.to_string(),
+        display_name: Some("Conductor gateway".to_string()),
+        description: Some("Every server enabled in Conductor".to_string()),
+        enabled: true,
+        transport: TransportType::StreamableHttp,
+        command: None,
+        args: vec![],
+        env: HashMap::new(),
+        url: Some(format!("{}/{}", gateway_url(port), client_id)),
+        headers: HashMap::new(),
+        secret_env_keys: vec![],
+        icon_url: None,
+        tags: vec![],
+        source: Some("conductor".to_string()),
+        registry_id: None,
+        created_at: None,
+        updated_at: None,
+        codex: None,
+        extra: HashMap::new(),
+        tool_filters: HashMap::new(),
+        inspect: false,
+        managed: false,
+    }
+}
+
+/// Called after every master config write; the gateway re-reads the config.
+pub fn config_changed() {
+    CONFIG_CHANGED.notify_one();
+}
+
+/// Starts the gateway supervisor. It follows the `gateway_enabled` setting,
+/// managed servers and the enabled servers for the lifetime of the app.
+pub fn spawn() {
+    tauri::async_runtime::spawn(supervise());
+}
+
+async fn supervise() {
+    let mut server: Option<(u16, JoinHandle<()>)> = None;
+    loop {
+        let settings = config::read_config().ok().filter(|c| {
+            c.settings.gateway_enabled || c.servers.iter().any(crate::supervisor::is_managed)
+        });
+        match settings {
+            Some(cfg) => {
+                let port = cfg.settings.gateway_port;
+                if server.as_ref().map(|(p, _)| *p) != Some(port) {
+                    if let Some((_, task)) = server.take() {
+                        task.abort();
+                    }
+                    let result = listen(port).await;
+                    let mut listener = lock(&GATEWAY.listener);
+                    match result {
+                        Ok(task) => {
+                            server = Some((port, task));
+                            *listener = Listener {
+                                port: Some(port),
+                                ..Listener::default()
+                            };
+                        }
+                        Err(e) => {
+                            eprintln!("Gateway error: {:#}", e);
+                            *listener = Listener {
+                                error: Some(format!("{:#}", e)),
+                                ..Listener::default()
+                            };
+                        }
+                    }
+                }
+                let gateway = cfg.settings.gateway_enabled;
+                lock(&GATEWAY.listener).gateway = gateway;
+                if gateway {
+                    reconcile(&cfg.servers).await;
+                } else {
+                    lock(&GATEWAY.sessions).clear();
+                    reconcile(&[]).await;
+                }
+            }
+            None => {
+                if let Some((_, task)) = server.take() {
+                    task.abort();
+                }
+                *lock(&GATEWAY.listener) = Listener::default();
+                lock(&GATEWAY.sessions).clear();
+                reconcile(&[]).await;
+            }
+        }
+        CONFIG_CHANGED.notified().await;
+    }
+}
+
+async fn listen(port: u16) -> Result<JoinHandle<()>> {
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port))
+        .await
+        .with_context(|| format!("Failed to listen on port {}", port))?;
+    let app = Router::new()
+        .route(
+            "/mcp",
+            post(handle_post).get(handle_get).delete(handle_delete),
+        )
+        .route(
+            "/mcp/{client}",
+            post(handle_client_post)
+                .get(handle_get)
+                .delete(handle_delete),
+        )
+        .merge(crate::supervisor::endpoint::routes());
+    Ok(tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            eprintln!("Gateway error: {}", e);
+        }
+    }))
+}
+
+/// Starts, restarts and stops upstreams so they match the enabled servers.
+/// Managed servers are the supervisor's; the gateway shares its process.
+async fn reconcile(servers: &[McpServerConfig]) {
+    let desired: Vec<&McpServerConfig> = servers
+        .iter()
+        .filter(|s| s.enabled && !crate::supervisor::is_managed(s))
+        .collect();
+    let mut changed = false;
+
+    {
+        let mut upstreams = GATEWAY.upstreams.write().unwrap_or_else(|e| e.into_inner());
+        let before = upstreams.len();
+        upstreams.retain(|id, _| desired.iter().any(|s| &s.id == id));
+        changed |= upstreams.len() != before;
+    }
+    lock(&GATEWAY.failures).retain(|id, _| desired.iter().any(|s| &s.id == id));
+    // Inspection is toggled live; it doesn't restart the server.
+    for (id, upstream) in GATEWAY
+        .upstreams
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+    {
+        if let Some(server) = desired.iter().find(|s| &s.id == id) {
+            upstream.recorder.set_enabled(server.inspect);
+        }
+    }
+
+    let to_start: Vec<(McpServerConfig, String)> = desired
+        .into_iter()
+        .filter_map(|server| {
+            let fingerprint = fingerprint(server);
+            let running = GATEWAY
+                .upstreams
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .get(&server.id)
+                .is_some_and(|u| u.fingerprint == fingerprint);
+            let failed = lock(&GATEWAY.failures)
+                .get(&server.id)
+                .is_some_and(|(fp, _)| *fp == fingerprint);
+            (!running && !failed).then(|| (server.clone(), fingerprint))
+        })
+        .collect();
+
+    let started = futures_util::future::join_all(
+        to_start
+            .into_iter()
+            .map(|(server, fingerprint)| start_upstream(server, fingerprint)),
+    )
+    .await;
+    changed |= started.into_iter().any(|ok| ok);
+
+    if changed {
+        announce_all_changed();
+    }
+}
+
+async fn start_upstream(mut server: McpServerConfig, fingerprint: String) -> bool {
+    if let Err(e) = crate::commands::sync::inject_secrets(&mut server).await {
+        eprintln!("Gateway: secrets for '{}': {}", server.name, e);
+    }
+    match Upstream::start(&server, fingerprint.clone()).await {
+        Ok(upstream) => {
+            lock(&GATEWAY.failures).remove(&server.id);
+            GATEWAY
+                .upstreams
+                .write()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(server.id.clone(), upstream);
+            true
+        }
+        Err(e) => {
+            eprintln!("Gateway: failed to start '{}': {:#}", server.name, e);
+            lock(&GATEWAY.failures).insert(server.id, (fingerprint, format!("{:#}", e)));
+            false
+        }
+    }
+}
+
+/// Everything that decides how a server is launched.
+pub(crate) fn fingerprint(server: &McpServerConfig) -> String {
+    serde_json::to_string(&(
+        &server.name,
+        &server.transport,
+        &server.command,
+        &server.args,
+        &server.env,
+        &server.url,
+        &server.secret_env_keys,
+    ))
+    .unwrap_or_default()
+}
+
+/// The gateway's or supervisor's upstream for a server, if it runs exactly
+/// this config.
+fn running_upstream(server_id: &str, fingerprint: &str) -> Option<Arc<Upstream>> {
+    if let Some(upstream) =
+        crate::supervisor::upstream(server_id).filter(|u| u.fingerprint == fingerprint)
+    {
+        return Some(upstream);
+    }
+    GATEWAY
+        .upstreams
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(server_id)
+        .filter(|u| u.fingerprint == fingerprint)
+        .cloned()
+}
+
+/// The gateway's upstream for `server` if it is running this config, else a
+/// new one started just for the caller and stopped when dropped.
+pub(crate) async fn upstream_for(server: &McpServerConfig) -> Result<Arc<Upstream>> {
+    let fingerprint = fingerprint(server);
+    if let Some(upstream) = running_upstream(&server.id, &fingerprint) {
+        return Ok(upstream);
+    }
+    let mut launch = server.clone();
+    if let Err(e) = crate::commands::sync::inject_secrets(&mut launch).await {
+        eprintln!("Secrets for '{}': {}", server.name, e);
+    }
+    Upstream::start(&launch, fingerprint).await
+}
+
+/// An upstream process ended on its own.
+pub(crate) fn upstream_exited(upstream: &Arc<Upstream>) {
+    let removed = {
+        let mut upstreams = GATEWAY.upstreams.write().unwrap_or_else(|e| e.into_inner());
+        match upstreams.get(&upstream.server_id) {
+            Some(current) if Arc::ptr_eq(current, upstream) => {
+                upstreams.remove(&upstream.server_id);
+                true
+            }
+            _ => false,
+        }
+    };
+    if removed {
+        eprintln!("Gateway: '{}' exited", upstream.name);
+        lock(&GATEWAY.failures).insert(
+            upstream.server_id.clone(),
+            (
+                upstream.fingerprint.clone(),
+                "The server exited".to_string(),
+            ),
+        );
+        announce_all_changed();
+    }
+}
+
+/// Relays an upstream's `notifications/*/list_changed` to connected clients.
+pub(crate) fn list_changed(method: &str) {
+    let _ = GATEWAY
+        .events
+        .send(json!({ "jsonrpc": "2.0", "method": method }));
+}
+
+pub(crate) fn announce_all_changed() {
+    for kind in ["tools", "resources", "prompts"] {
+        list_changed(&format!("notifications/{}/list_changed", kind));
+    }
+}
+
+pub fn status() -> GatewayStatus {
+    let cfg = config::read_config().unwrap_or_default();
+    let listener = lock(&GATEWAY.listener);
+    let upstreams = GATEWAY.upstreams.read().unwrap_or_else(|e| e.into_inner());
+    let failures = lock(&GATEWAY.failures);
+    GatewayStatus {
+        enabled: cfg.settings.gateway_enabled,
+        url: listener.port.filter(|_| listener.gateway).map(gateway_url),
+        error: listener.error.clone(),
+        servers: cfg
+            .servers
+            .iter()
+            .filter(|s| s.enabled && cfg.settings.gateway_enabled)
+            .map(|s| GatewayServerStatus {
+                server_id: s.id.clone(),
+                name: s.name.clone(),
+                running: upstreams.contains_key(&s.id)
+                    || crate::supervisor::upstream(&s.id).is_some(),
+                error: failures.get(&s.id).map(|(_, e)| e.clone()),
+            })
+            .collect(),
+    }
+}
+
+// --- HTTP endpoint ---
+
+/// Only local pages may call the gateway; this blocks DNS rebinding from
+/// websites open in the user's browser.
+pub(crate) fn allowed_origin(headers: &HeaderMap) -> bool {
+    let Some(origin) = headers.get(header::ORIGIN) else {
+        return true;
+    };
+    origin
+        .to_str()
+        .ok()
+        .and_then(|o| url::Url::parse(o).ok())
+        .and_then(|u| u.host_str().map(|h| h.to_string()))
+        .is_some_and(|host| matches!(host.as_str(), "localhost" | "127.0.0.1" | "[::1]"))
+}
+
+fn session_of(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(SESSION_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string())
+}
+
+fn unknown_session(headers: &HeaderMap) -> bool {
+    session_of(headers).is_some_and(|id| !lock(&GATEWAY.sessions).contains(&id))
+}
+
+fn serving() -> bool {
+    lock(&GATEWAY.listener).gateway
+}
+
+async fn handle_post(headers: HeaderMap, body: String) -> Response {
+    handle_post_for(None, headers, body).await
+}
+
+async fn handle_client_post(
+    Path(client): Path<String>,
+    headers: HeaderMap,
+    body: String,
+) -> Response {
+    handle_post_for(Some(client), headers, body).await
+}
+
+/// `client` is the client ID from the path, used for its tool filters.
+async fn handle_post_for(client: Option<String>, headers: HeaderMap, body: String) -> Response {
+    if !allowed_origin(&headers) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    if !serving() {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let message: Value = match serde_json::from_str(&body) {
+        Ok(message) => message,
+        Err(e) => {
+            let error = error_response(
+                &Value::Null,
+                rpc_error(-32700, &format!("Parse error: {}", e)),
+            );
+            return (StatusCode::BAD_REQUEST, axum::Json(error)).into_response();
+        }
+    };
+
+    let is_initialize = message.get("method").and_then(|m| m.as_str()) == Some("initialize");
+    if !is_initialize && unknown_session(&headers) {
+        // Tells the client to initialize a new session.
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    let filters = client.as_deref().map(tool_filters).unwrap_or_default();
+    let (replies, is_batch) = match message {
+        Value::Array(batch) => (
+            futures_util::future::join_all(batch.into_iter().map(|m| handle_message(m, &filters)))
+                .await
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>(),
+            true,
+        ),
+        message => (
+            handle_message(message, &filters)
+                .await
+                .into_iter()
+                .collect(),
+            false,
+        ),
+    };
+
+    if replies.is_empty() {
+        return StatusCode::ACCEPTED.into_response();
+    }
+    let body = if is_batch {
+        Value::Array(replies)
+    } else {
+        replies.into_iter().next().unwrap_or(Value::Null)
+    };
+    let mut response = axum::Json(body).into_response();
+    if is_initialize {
+        let session = uuid::Uuid::new_v4().to_string();
+        if let Ok(value) = HeaderValue::from_str(&session) {
+            response.headers_mut().insert(SESSION_HEADER, value);
+        }
+        lock(&GATEWAY.sessions).insert(session);
+    }
+    response
+}
+
+/// Server-to-client stream carrying `list_changed` notifications.
+async fn handle_get(headers: HeaderMap) -> Response {
+    if !allowed_origin(&headers) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    if !serving() || unknown_session(&headers) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let stream = futures_util::stream::unfold(GATEWAY.events.subscribe(), |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(message) => {
+                    let event = Event::default().event("message").data(message.to_string());
+                    return Some((Ok::<_, Infallible>(event), rx));
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+async fn handle_delete(headers: HeaderMap) -> StatusCode {
+    match session_of(&headers) {
+        Some(id) if lock(&GATEWAY.sessions).remove(&id) => StatusCode::OK,
+        _ => StatusCode::NOT_FOUND,
+    }
+}
+
+/// Tool filters of `client_id`, keyed by server ID.
+fn tool_filters(client_id: &str) -> HashMap<String, ToolFilter> {
+    config::read_config()
+        .map(|cfg| {
+            cfg.servers
+                .iter()
+                .filter_map(|s| Some((s.id.clone(), s.tool_filter(client_id)?.clone())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Answers one client message. Notifications and responses get no reply.
+async fn handle_message(message: Value, filters: &HashMap<String, ToolFilter>) -> Option<Value> {
+    let method = message.get("method")?.as_str()?.to_string();
+    let id = message.get("id")?.clone();
+    let params = message.get("params").cloned().unwrap_or(json!({}));
+    Some(match dispatch(&method, params, filters).await {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => error_response(&id, error),
+    })
+}
+
+async fn dispatch(
+    method: &str,
+    params: Value,
+    filters: &HashMap<String, ToolFilter>,
+) -> Result<Value, Value> {
+    match method {
+        "initialize" => Ok(initialize_result(&params)),
+        "ping" | "logging/setLevel" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": list_named("tools", method, "tools", filters).await })),
+        "prompts/list" => Ok(json!({
+            "prompts": list_named("prompts", method, "prompts", &HashMap::new()).await
+        })),
+        "resources/list" => Ok(json!({ "resources": list_resources(method, "resources").await })),
+        "resources/templates/list" => Ok(json!({
+            "resourceTemplates": list_resources(method, "resourceTemplates").await
+        })),
+        "tools/call" => call_named(method, params, filters).await,
+        "prompts/get" => call_named(method, params, &HashMap::new()).await,
+        "resources/read" | "resources/subscribe" | "resources/unsubscribe" => {
+            route_resource(method, params).await
+        }
+        _ => Err(rpc_error(-32601, &format!("Method not found: {}", method))),
+    }
+}
+
+fn initialize_result(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(|v| v.as_str());
+    let version = requested
+        .filter(|v| PROTOCOL_VERSIONS.contains(v))
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": {
+            "tools": { "listChanged": true },
+            "resources": { "listChanged": true },
+            "prompts": { "listChanged": true },
+            "logging": {}
+        },
+        "serverInfo": { "name": GATEWAY_SERVER_NAME, "version": env!("CARGO_PKG_VERSION") },
+        "instructions": format!(
+            "Tools and prompts from every server enabled in Conductor, named <server>{}<name>.",
+            SEPARATOR
+        )
+    })
+}
+
+pub(crate) fn rpc_error(code: i64, message: &str) -> Value {
+    json!({ "code": code, "message": message })
+}
+
+pub(crate) fn error_response(id: &Value, error: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": error })
+}
+
+fn into_result(response: Value) -> Result<Value, Value> {
+    match response.get("error") {
+        Some(error) => Err(error.clone()),
+        None => Ok(response.get("result").cloned().unwrap_or(json!({}))),
+    }
+}
+
+/// Running upstreams, ordered by name so merged lists are stable.
+fn upstreams() -> Vec<Arc<Upstream>> {
+    let mut upstreams: Vec<Arc<Upstream>> = GATEWAY
+        .upstreams
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .values()
+        .cloned()
+        .chain(crate::supervisor::upstreams())
+        .collect();
+    upstreams.sort_by(|a, b| a.name.cmp(&b.name));
+    upstreams
+}
+
+/// Every page of a list method from one upstream.
+pub(crate) async fn collect_pages(
+    upstream: &Upstream,
+    method: &str,
+    key: &str,
+) -> Result<Vec<Value>> {
+    let mut items = Vec::new();
+    let mut cursor: Option<String> = None;
+    for _ in 0..MAX_PAGES {
+        let params = match &cursor {
+            Some(cursor) => json!({ "cursor": cursor }),
+            None => json!({}),
+        };
+        let result = into_result(upstream.request(method, params, LIST_TIMEOUT).await?)
+            .map_err(|e| anyhow!("{}", e))?;
+        if let Some(page) = result.get(key).and_then(|v| v.as_array()) {
+            items.extend(page.iter().cloned());
+        }
+        cursor = result
+            .get("nextCursor")
+            .and_then(|c| c.as_str())
+            .map(|c| c.to_string());
+        if cursor.is_none() {
+            break;
+        }
+    }
+    Ok(items)
+}
+
+/// `method` from every upstream with `capability`. Upstreams that fail are
+/// left out rather than failing the whole list.
+async fn list_from_all(
+    capability: &str,
+    method: &str,
+    key: &str,
+) -> Vec<(Arc<Upstream>, Vec<Value>)> {
+    let lists = futures_util::future::join_all(
+        upstreams()
+            .into_iter()
+            .filter(|u| u.supports(capability))
+            .map(|u| async move {
+                let items = collect_pages(&u, method, key).await;
+                (u, items)
+            }),
+    )
+    .await;
+    lists
+        .into_iter()
+        .filter_map(|(u, items)| match items {
+            Ok(items) => Some((u, items)),
+            Err(e) => {
+                eprintln!("Gateway: {} failed on '{}': {:#}", method, u.name, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Tools or prompts, each renamed to `<namespace>__<name>`. `filters`
+/// (by server ID) hide items by their original name. Tool lists also
+/// refresh the discovery cache.
+async fn list_named(
+    capability: &str,
+    method: &str,
+    key: &str,
+    filters: &HashMap<String, ToolFilter>,
+) -> Vec<Value> {
+    let mut merged = Vec::new();
+    for (upstream, items) in list_from_all(capability, method, key).await {
+        if capability == "tools" {
+            if let Err(e) = crate::tools::record(&upstream.server_id, &upstream.fingerprint, &items)
+            {
+                eprintln!("Gateway: caching tools of '{}': {:#}", upstream.name, e);
+            }
+        }
+        let filter = filters.get(&upstream.server_id);
+        for mut item in items {
+            if let Some(name) = item.get("name").and_then(|n| n.as_str()) {
+                if filter.is_some_and(|f| !f.permits(name)) {
+                    continue;
+                }
+                item["name"] = json!(format!("{}{}{}", upstream.namespace, SEPARATOR, name));
+            }
+            merged.push(item);
+        }
+    }
+    merged
+}
+
+/// Resources keep their URIs; the owner of each is remembered for reads.
+async fn list_resources(method: &str, key: &str) -> Vec<Value> {
+    let mut merged = Vec::new();
+    let mut owners = HashMap::new();
+    for (upstream, items) in list_from_all("resources", method, key).await {
+        for item in items {
+            if let Some(uri) = item.get("uri").and_then(|u| u.as_str()) {
+                owners.insert(uri.to_string(), upstream.server_id.clone());
+            }
+            merged.push(item);
+        }
+    }
+    lock(&GATEWAY.resource_owners).extend(owners);
+    merged
+}
+
+async fn forward(
+    upstream: &Upstream,
+    method: &str,
+    params: Value,
+    wait: Duration,
+) -> Result<Value, Value> {
+    match upstream.request(method, params, wait).await {
+        Ok(response) => into_result(response),
+        Err(e) => Err(rpc_error(-32603, &format!("{:#}", e))),
+    }
+}
+
+async fn call_named(
+    method: &str,
+    mut params: Value,
+    filters: &HashMap<String, ToolFilter>,
+) -> Result<Value, Value> {
+    let name = params
+        .get("name")
+        .and_then(|n| n.as_str())
+        .ok_or_else(|| rpc_error(-32602, "Missing name"))?
+        .to_string();
+    // The longest matching namespace wins, so `git` doesn't shadow `git_hub`.
+    let (upstream, original) = upstreams()
+        .into_iter()
+        .filter_map(|u| {
+            let original = name
+                .strip_prefix(format!("{}{}", u.namespace, SEPARATOR).as_str())?
+                .to_string();
+            Some((u, original))
+        })
+        .max_by_key(|(u, _)| u.namespace.len())
+        .ok_or_else(|| rpc_error(-32602, &format!("Unknown tool or prompt '{}'", name)))?;
+    if filters
+        .get(&upstream.server_id)
+        .is_some_and(|f| !f.permits(&original))
+    {
+        return Err(rpc_error(
+            -32602,
+            &format!("Tool '{}' is not available to this client", name),
+        ));
+    }
+    params["name"] = json!(original);
+    forward(&upstream, method, params, CALL_TIMEOUT).await
+}
+
+async fn route_resource(method: &str, params: Value) -> Result<Value, Value> {
+    let uri = params
+        .get("uri")
+        .and_then(|u| u.as_str())
+        .ok_or_else(|| rpc_error(-32602, "Missing uri"))?
+        .to_string();
+    let owner = lock(&GATEWAY.resource_owners).get(&uri).cloned();
+    let candidates: Vec<Arc<Upstream>> = upstreams()
+        .into_iter()
+        .filter(|u| u.supports("resources"))
+        .filter(|u| owner.as_ref().is_none_or(|owner| *owner == u.server_id))
+        .collect();
+
+    // URIs from templates have no known owner; ask each server in turn.
+    let mut last_error = rpc_error(-32602, &format!("Unknown resource '{}'", uri));
+    for upstream in candidates {
+        match forward(&upstream, method, params.clone(), CALL_TIMEOUT).await {
+            Ok(result) => return Ok(result),
+            Err(error) => last_error = error,
+        }
+    }
+    Err(last_error)
+}
@@ -0,0 +1,328 @@
+use crate::config::{serializer, McpServerConfig, TransportType};
+use crate::inspector::{Direction, Recorder, Redactor};
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, Command};
+use tokio::sync::oneshot;
+use tokio::time::{timeout, Duration};
+
+const INIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A running MCP server the gateway or supervisor talks to over stdio.
+/// Remote servers run through `conductor bridge`, so every upstream looks
+/// the same.
+pub struct Upstream {
+    pub server_id: String,
+    pub name: String,
+    /// Prefix of this server's tools and prompts: `<namespace>__<tool>`.
+    pub namespace: String,
+    /// The config the process was started from; a change restarts it.
+    pub fingerprint: String,
+    /// `result` of the server's `initialize` response.
+    initialize: OnceLock<Value>,
+    stdin: tokio::sync::Mutex<ChildStdin>,
+    pending: Mutex<HashMap<u64, oneshot::Sender<Value>>>,
+    next_id: AtomicU64,
+    /// Logs traffic while the server has `inspect` set.
+    pub recorder: Recorder,
+    pub pid: Option<u32>,
+    /// Run by the supervisor, which owns its logs and restarts.
+    pub managed: bool,
+    // Killed when the upstream is dropped.
+    child: tokio::sync::Mutex<Child>,
+}
+
+impl Upstream {
+    /// Launches `server` (secrets already injected) and completes the MCP
+    /// handshake.
+    pub async fn start(server: &McpServerConfig, fingerprint: String) -> Result<Arc<Self>> {
+        Self::launch(server, fingerprint, false).await
+    }
+
+    /// Like `start`, for the supervisor: output goes to the server's log
+    /// and notifications to its managed endpoint.
+    pub async fn start_managed(
+        server: &McpServerConfig,
+        fingerprint: String,
+    ) -> Result<Arc<Self>> {
+        Self::launch(server, fingerprint, true).await
+    }
+
+    async fn launch(
+        server: &McpServerConfig,
+        fingerprint: String,
+        managed: bool,
+    ) -> Result<Arc<Self>> {
+        let (program, args, env): (String, Vec<String>, HashMap<String, String>) =
+            match server.transport {
+                TransportType::Stdio => (
+                    server
+                        .command
+                        .clone()
+                        .context("Server has no command to run")?,
+                    server.args.clone(),
+                    server.env.clone(),
+                ),
+                _ => {
+                    let (program, args) = serializer::bridge_command(server)
+                        .context("Failed to locate the Conductor executable")?;
+                    (program, args, HashMap::new())
+                }
+            };
+
+        let mut child = Command::new(&program)
+            .args(&args)
+            .envs(&env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("Failed to start '{}'", program))?;
+
+        let mut secret_keys = server.secret_env_keys.clone();
+        secret_keys.push("OAUTH_TOKEN".to_string());
+        let header_secrets = if server.inspect {
+            crate::inspector::secret_header_values(&server.id)
+        } else {
+            Vec::new()
+        };
+        let recorder = Recorder::new(
+            &server.id,
+            None,
+            Redactor::new(&server.env, &secret_keys, &header_secrets),
+            server.inspect,
+        );
+        let command: Vec<String> = std::iter::once(program.clone())
+            .chain(args.clone())
+            .collect();
+        recorder.session_started(&command, &env, &secret_keys);
+
+        let stdin = child.stdin.take().context("No stdin")?;
+        let stdout = child.stdout.take().context("No stdout")?;
+        let stderr = child.stderr.take().context("No stderr")?;
+
+        let name = server.name.clone();
+        let server_id = server.id.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if managed {
+                    crate::supervisor::log_line(&server_id, "stderr", &line);
+                } else {
+                    eprintln!("[gateway:{}] {}", name, line);
+                }
+            }
+        });
+
+        let upstream = Arc::new(Self {
+            server_id: server.id.clone(),
+            name: server.name.clone(),
+            namespace: namespace(&server.name),
+            fingerprint,
+            initialize: OnceLock::new(),
+            stdin: tokio::sync::Mutex::new(stdin),
+            pending: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+            recorder,
+            pid: child.id(),
+            managed,
+            child: tokio::sync::Mutex::new(child),
+        });
+
+        let init = {
+            let (id, rx) = upstream.register();
+            tokio::spawn(read_stdout(Arc::downgrade(&upstream), stdout));
+            upstream
+                .write(&json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "method": "initialize",
+                    "params": {
+                        "protocolVersion": super::PROTOCOL_VERSIONS[0],
+                        "capabilities": {},
+                        "clientInfo": {
+                            "name": "conductor-gateway",
+                            "version": env!("CARGO_PKG_VERSION")
+                        }
+                    }
+                }))
+                .await?;
+            timeout(INIT_TIMEOUT, rx)
+                .await
+                .context("Timed out waiting for the server to initialize")?
+                .context("The server exited during startup")?
+        };
+        if let Some(error) = init.get("error") {
+            bail!("The server refused to initialize: {}", error);
+        }
+
+        let result = init.get("result").cloned().unwrap_or(json!({}));
+        let _ = upstream.initialize.set(result);
+        upstream
+            .notify("notifications/initialized", json!({}))
+            .await?;
+        Ok(upstream)
+    }
+
+    /// Whether the server advertised `capability` (`tools`, `resources`, ...).
+    pub fn supports(&self, capability: &str) -> bool {
+        self.initialize
+            .get()
+            .and_then(|init| init.get("capabilities"))
+            .is_some_and(|caps| caps.get(capability).is_some())
+    }
+
+    /// The server's `initialize` result: capabilities, `serverInfo` and
+    /// instructions.
+    pub fn initialize_result(&self) -> Option<&Value> {
+        self.initialize.get()
+    }
+
+    /// Waits for the process to exit.
+    pub async fn wait(&self) -> Option<std::process::ExitStatus> {
+        self.child.lock().await.wait().await.ok()
+    }
+
+    pub async fn kill(&self) {
+        let _ = self.child.lock().await.kill().await;
+    }
+
+    /// Sends a request and waits for the full JSON-RPC response, `result` or
+    /// `error`.
+    pub async fn request(&self, method: &str, params: Value, wait: Duration) -> Result<Value> {
+        let (id, rx) = self.register();
+        let message = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        if let Err(e) = self.write(&message).await {
+            self.pending
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&id);
+            return Err(e);
+        }
+        timeout(wait, rx)
+            .await
+            .with_context(|| format!("'{}' timed out on {}", method, self.name))?
+            .with_context(|| format!("{} exited", self.name))
+    }
+
+    pub async fn notify(&self, method: &str, params: Value) -> Result<()> {
+        self.write(&json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+            .await
+    }
+
+    fn register(&self) -> (u64, oneshot::Receiver<Value>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(id, tx);
+        (id, rx)
+    }
+
+    async fn write(&self, message: &Value) -> Result<()> {
+        self.recorder.record(Direction::ClientToServer, message);
+        let mut line = message.to_string();
+        line.push('\n');
+        let mut stdin = self.stdin.lock().await;
+        stdin
+            .write_all(line.as_bytes())
+            .await
+            .with_context(|| format!("Failed to write to {}", self.name))?;
+        stdin.flush().await?;
+        Ok(())
+    }
+}
+
+/// Tool-name-safe prefix for a server name.
+pub fn namespace(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+async fn read_stdout(upstream: Weak<Upstream>, stdout: tokio::process::ChildStdout) {
+    let mut lines = BufReader::new(stdout).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let Some(upstream) = upstream.upgrade() else {
+            return;
+        };
+        let Ok(message) = serde_json::from_str::<Value>(&line) else {
+            if upstream.managed {
+                crate::supervisor::log_line(&upstream.server_id, "stdout", &line);
+            }
+            continue;
+        };
+        upstream
+            .recorder
+            .record(Direction::ServerToClient, &message);
+
+        match (
+            message.get("method").and_then(|m| m.as_str()),
+            message.get("id"),
+        ) {
+            // Requests from the server. The gateway has no roots and can't
+            // sample on a client's behalf.
+            (Some(method), Some(id)) => {
+                let reply = match method {
+                    "ping" => json!({ "jsonrpc": "2.0", "id": id, "result": {} }),
+                    "roots/list" => {
+                        json!({ "jsonrpc": "2.0", "id": id, "result": { "roots": [] } })
+                    }
+                    _ => json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": { "code": -32601, "message": format!("{} is not supported by the Conductor gateway", method) }
+                    }),
+                };
+                let _ = upstream.write(&reply).await;
+            }
+            (Some(method), None) => {
+                if method.ends_with("/list_changed") {
+                    super::list_changed(method);
+                }
+                if upstream.managed {
+                    crate::supervisor::forward_notification(&upstream.server_id, &message);
+                }
+            }
+            (None, Some(id)) => {
+                if let Some(tx) = id.as_u64().and_then(|id| {
+                    upstream
+                        .pending
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .remove(&id)
+                }) {
+                    let _ = tx.send(message);
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    // The process exited on its own: fail whatever was waiting on it. An
+    // upstream the gateway dropped is already gone, and the supervisor
+    // notices its own processes exiting.
+    if let Some(upstream) = upstream.upgrade() {
+        upstream
+            .pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+        if !upstream.managed {
+            super::upstream_exited(&upstream);
+        }
+    }
+}
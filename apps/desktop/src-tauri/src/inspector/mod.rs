@@ -0,0 +1,785 @@
+//! Traffic inspector: an opt-in, per-server log of every JSON-RPC message
+//! between a client and the server, with timing. Synced entries of inspected
+//! servers run through `conductor tap` (see `tap`); in gateway mode the
+//! gateway records its upstreams itself. Logs are JSON lines in
+//! `~/.conductor/traffic/<server-id>.jsonl`, rotated by size, and secrets
+//! are redacted before anything is written.
+
+pub mod tap;
+
+use crate::clients::ClientAdapter;
+use crate::config::variables::{self, VariableStore};
+use crate::config::{self, serializer, McpServerConfig};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+/// Rotated files kept besides the live one: `<id>.1.jsonl` is the newest.
+const ROTATED_LOGS: usize = 3;
+const DEFAULT_QUERY_LIMIT: usize = 500;
+const REPLAY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+
+pub const REDACTED: &str = "[REDACTED]";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum Direction {
+    ClientToServer,
+    ServerToClient,
+}
+
+impl Direction {
+    fn reverse(self) -> Self {
+        match self {
+            Direction::ClientToServer => Direction::ServerToClient,
+            Direction::ServerToClient => Direction::ClientToServer,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum MessageKind {
+    /// How the server was launched; written once per session.
+    Session,
+    Request,
+    Response,
+    Notification,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrafficEntry {
+    pub id: String,
+    pub session_id: String,
+    pub server_id: String,
+    /// Client the session belongs to; `None` when Conductor itself (the
+    /// gateway or a tool probe) talked to the server.
+    #[serde(default)]
+    pub client_id: Option<String>,
+    pub timestamp: String,
+    #[serde(default)]
+    pub direction: Option<Direction>,
+    pub kind: MessageKind,
+    #[serde(default)]
+    pub method: Option<String>,
+    #[serde(default)]
+    pub rpc_id: Option<Value>,
+    /// For responses: time since the matching request was sent.
+    #[serde(default)]
+    pub duration_ms: Option<f64>,
+    #[serde(default)]
+    pub is_error: bool,
+    pub message: Value,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TrafficQuery {
+    pub client_id: Option<String>,
+    pub session_id: Option<String>,
+    /// Substring of the method name.
+    pub method: Option<String>,
+    pub errors_only: bool,
+    /// RFC 3339 timestamp; older entries are skipped.
+    pub since: Option<String>,
+    pub limit: Option<usize>,
+}
+
+impl TrafficQuery {
+    fn matches(&self, entry: &TrafficEntry) -> bool {
+        self.client_id
+            .as_ref()
+            .is_none_or(|c| entry.client_id.as_ref() == Some(c))
+            && self
+                .session_id
+                .as_ref()
+                .is_none_or(|s| entry.session_id == *s)
+            && self.method.as_ref().is_none_or(|m| {
+                entry
+                    .method
+                    .as_deref()
+                    .is_some_and(|method| method.contains(m.as_str()))
+            })
+            && (!self.errors_only || entry.is_error)
+            && self
+                .since
+                .as_ref()
+                .is_none_or(|since| entry.timestamp.as_str() >= since.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayResult {
+    pub response: Value,
+    pub duration_ms: f64,
+}
+
+// ── Redaction ───────────────────────────────────────────────────────
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    key != "progresstoken"
+        && [
+            "authorization",
+            "token",
+            "secret",
+            "password",
+            "passwd",
+            "api_key",
+            "apikey",
+            "api-key",
+            "cookie",
+            "credential",
+            "private_key",
+        ]
+        .iter()
+        .any(|s| key.contains(s))
+}
+
+/// Scrubs secrets from everything the inspector writes: values under
+/// secret-looking keys, bearer tokens, and the literal values of the
+/// server's secret env vars and secret headers wherever they appear.
+#[derive(Debug, Default)]
+pub struct Redactor {
+    secrets: Vec<String>,
+}
+
+impl Redactor {
+    /// `secret_keys` are the server's keychain-backed env keys; any env var
+    /// with a secret-looking name counts too. `header_secrets` are resolved
+    /// header values, see `secret_header_values`.
+    pub fn new(
+        env: &HashMap<String, String>,
+        secret_keys: &[String],
+        header_secrets: &[String],
+    ) -> Self {
+        let mut secrets: Vec<String> = env
+            .iter()
+            .filter(|(key, _)| secret_keys.contains(key) || is_secret_key(key))
+            .map(|(_, value)| value)
+            .chain(header_secrets)
+            .filter(|value| value.len() >= 4)
+            .cloned()
+            .collect();
+        // Longest first, so a secret containing another is replaced whole.
+        secrets.sort_by_key(|s| std::cmp::Reverse(s.len()));
+        secrets.dedup();
+        Self { secrets }
+    }
+
+    pub fn text(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for secret in &self.secrets {
+            if text.contains(secret.as_str()) {
+                text = text.replace(secret.as_str(), REDACTED);
+            }
+        }
+        redact_bearer(&text)
+    }
+
+    pub fn value(&self, value: &Value) -> Value {
+        match value {
+            Value::String(s) => Value::String(self.text(s)),
+            Value::Array(items) => Value::Array(items.iter().map(|v| self.value(v)).collect()),
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(key, v)| {
+                        let v = if v.is_string() && is_secret_key(key) {
+                            json!(REDACTED)
+                        } else {
+                            self.value(v)
+                        };
+                        (key.clone(), v)
+                    })
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    pub fn env(&self, env: &HashMap<String, String>, secret_keys: &[String]) -> Map<String, Value> {
+        env.iter()
+            .map(|(key, value)| {
+                let value = if secret_keys.contains(key) || is_secret_key(key) {
+                    REDACTED.to_string()
+                } else {
+                    self.text(value)
+                };
+                (key.clone(), json!(value))
+            })
+            .collect()
+    }
+
+    /// Command lines, where headers look like `Authorization:Bearer x`.
+    pub fn args(&self, args: &[String]) -> Vec<String> {
+        args.iter()
+            .map(|arg| match arg.split_once(':') {
+                Some((name, _)) if is_secret_key(name) => format!("{}:{}", name, REDACTED),
+                _ => self.text(arg),
+            })
+            .collect()
+    }
+}
+
+/// Resolved values of the headers of the configured server `server_id` that
+/// are secret: under a secret-looking name or filled in from a secret
+/// variable. Servers are launched with their headers already resolved, so
+/// this goes back to the master config to tell which ones were secret.
+pub fn secret_header_values(server_id: &str) -> Vec<String> {
+    let Ok(cfg) = config::read_config() else {
+        return Vec::new();
+    };
+    let Some(server) = cfg.servers.iter().find(|s| s.id == server_id) else {
+        return Vec::new();
+    };
+    // If the store can't be read, neither can the server's secrets: it
+    // fails to launch before anything is logged.
+    let Ok(store) = VariableStore::load_for(&cfg, config::profiles::active(&cfg), false) else {
+        return Vec::new();
+    };
+    secret_headers(&server.headers, &store)
+}
+
+fn secret_headers(headers: &HashMap<String, String>, store: &VariableStore) -> Vec<String> {
+    let public = store.without_secrets();
+    headers
+        .iter()
+        .filter(|(name, raw)| {
+            let mut secret_refs = Vec::new();
+            variables::interpolate(raw, &public, &mut secret_refs);
+            is_secret_key(name) || secret_refs.iter().any(|n| store.is_secret(n))
+        })
+        .map(|(_, raw)| variables::interpolate(raw, store, &mut Vec::new()))
+        .collect()
+}
+
+/// Replaces the token after every `Bearer ` (any case).
+fn redact_bearer(text: &str) -> String {
+    let lower = text.to_ascii_lowercase();
+    if !lower.contains("bearer ") {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = 0;
+    for (start, _) in lower.match_indices("bearer ") {
+        if start < rest {
+            continue;
+        }
+        let token_start = start + "bearer ".len();
+        let token_end = text[token_start..]
+            .find(|c: char| c.is_whitespace() || c == '"' || c == '\'' || c == ',')
+            .map_or(text.len(), |i| token_start + i);
+        out.push_str(&text[rest..token_start]);
+        if token_end > token_start {
+            out.push_str(REDACTED);
+        }
+        rest = token_end;
+    }
+    out.push_str(&text[rest..]);
+    out
+}
+
+// ── Recording ───────────────────────────────────────────────────────
+
+// Serializes appends and rotation within this process.
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Records one session (a tap process or a gateway upstream) into the
+/// server's log.
+pub struct Recorder {
+    server_id: String,
+    client_id: Option<String>,
+    session_id: String,
+    redactor: Redactor,
+    enabled: AtomicBool,
+    /// Requests awaiting a response, by sender and JSON-RPC id.
+    pending: Mutex<HashMap<(Direction, String), (String, Instant)>>,
+}
+
+impl Recorder {
+    pub fn new(
+        server_id: &str,
+        client_id: Option<&str>,
+        redactor: Redactor,
+        enabled: bool,
+    ) -> Self {
+        Self {
+            server_id: server_id.to_string(),
+            client_id: client_id.map(|c| c.to_string()),
+            session_id: uuid::Uuid::new_v4().to_string(),
+            redactor,
+            enabled: AtomicBool::new(enabled),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Writes the session header: how the server was launched.
+    pub fn session_started(
+        &self,
+        command: &[String],
+        env: &HashMap<String, String>,
+        secret_keys: &[String],
+    ) {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return;
+        }
+        let message = json!({
+            "command": self.redactor.args(command),
+            "env": self.redactor.env(env, secret_keys),
+        });
+        self.write(self.entry(None, MessageKind::Session, message));
+    }
+
+    /// Records a message, or each message of a batch.
+    pub fn record(&self, direction: Direction, message: &Value) {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Value::Array(batch) = message {
+            for message in batch {
+                self.record(direction, message);
+            }
+            return;
+        }
+
+        let method = message.get("method").and_then(|m| m.as_str());
+        let rpc_id = message.get("id").filter(|id| !id.is_null());
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let (kind, method, duration_ms) = match (method, rpc_id) {
+            (Some(method), Some(id)) => {
+                pending.insert(
+                    (direction, id.to_string()),
+                    (method.to_string(), Instant::now()),
+                );
+                (MessageKind::Request, Some(method.to_string()), None)
+            }
+            (Some(method), None) => (MessageKind::Notification, Some(method.to_string()), None),
+            (None, Some(id)) => match pending.remove(&(direction.reverse(), id.to_string())) {
+                Some((method, sent)) => (
+                    MessageKind::Response,
+                    Some(method),
+                    Some(sent.elapsed().as_secs_f64() * 1000.0),
+                ),
+                None => (MessageKind::Response, None, None),
+            },
+            (None, None) => (MessageKind::Notification, None, None),
+        };
+        drop(pending);
+
+        let mut entry = self.entry(Some(direction), kind, self.redactor.value(message));
+        entry.method = method;
+        entry.rpc_id = rpc_id.cloned();
+        entry.duration_ms = duration_ms;
+        entry.is_error = message.get("error").is_some();
+        self.write(entry);
+    }
+
+    fn entry(
+        &self,
+        direction: Option<Direction>,
+        kind: MessageKind,
+        message: Value,
+    ) -> TrafficEntry {
+        TrafficEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            session_id: self.session_id.clone(),
+            server_id: self.server_id.clone(),
+            client_id: self.client_id.clone(),
+            timestamp: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            direction,
+            kind,
+            method: None,
+            rpc_id: None,
+            duration_ms: None,
+            is_error: false,
+            message,
+        }
+    }
+
+    fn write(&self, entry: TrafficEntry) {
+        if let Err(e) = append(&self.server_id, &entry) {
+            eprintln!(
+                "Inspector: failed to log traffic of {}: {:#}",
+                self.server_id, e
+            );
+        }
+    }
+}
+
+fn traffic_dir() -> Result<PathBuf> {
+    Ok(config::master_config_path()?.with_file_name("traffic"))
+}
+
+fn log_path(server_id: &str, generation: usize) -> Result<PathBuf> {
+    let stem: String = server_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let name = match generation {
+        0 => format!("{}.jsonl", stem),
+        n => format!("{}.{}.jsonl", stem, n),
+    };
+    Ok(traffic_dir()?.join(name))
+}
+
+fn append(server_id: &str, entry: &TrafficEntry) -> Result<()> {
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = log_path(server_id, 0)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if std::fs::metadata(&path).is_ok_and(|m| m.len() + line.len() as u64 > MAX_LOG_BYTES) {
+        for generation in (1..=ROTATED_LOGS).rev() {
+            let from = log_path(server_id, generation - 1)?;
+            if from.exists() {
+                std::fs::rename(&from, log_path(server_id, generation)?)?;
+            }
+        }
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    // One write per line keeps concurrent taps of the same server from
+    // interleaving within a line.
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// Every logged entry of a server, oldest first.
+fn read_all(server_id: &str) -> Result<Vec<TrafficEntry>> {
+    let mut entries = Vec::new();
+    for generation in (0..=ROTATED_LOGS).rev() {
+        let Ok(file) = std::fs::File::open(log_path(server_id, generation)?) else {
+            continue;
+        };
+        entries.extend(
+            BufReader::new(file)
+                .lines()
+                .map_while(|line| line.ok())
+                .filter_map(|line| serde_json::from_str::<TrafficEntry>(&line).ok()),
+        );
+    }
+    Ok(entries)
+}
+
+/// Matching entries, newest first.
+pub fn query(server_id: &str, query: &TrafficQuery) -> Result<Vec<TrafficEntry>> {
+    let limit = query.limit.unwrap_or(DEFAULT_QUERY_LIMIT);
+    Ok(read_all(server_id)?
+        .into_iter()
+        .rev()
+        .filter(|entry| query.matches(entry))
+        .take(limit)
+        .collect())
+}
+
+pub fn clear(server_id: &str) -> Result<()> {
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    for generation in 0..=ROTATED_LOGS {
+        let path = log_path(server_id, generation)?;
+        if path.exists() {
+            std::fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Matching requests and notifications as a HAR 1.2 log, each request
+/// paired with its response. `mcp://<server>/<method>` stands in for URLs.
+pub fn to_har(server: &McpServerConfig, query: &TrafficQuery) -> Result<Value> {
+    let mut matching = read_all(&server.id)?;
+    matching.retain(|entry| query.matches(entry));
+
+    // Responses are matched by session, sender and id, in log order.
+    let mut responses: HashMap<(String, Direction, String), TrafficEntry> = HashMap::new();
+    let mut har_entries = Vec::new();
+    let mut requests: Vec<TrafficEntry> = Vec::new();
+    for entry in matching {
+        match (entry.kind, entry.direction, &entry.rpc_id) {
+            (MessageKind::Response, Some(direction), Some(id)) => {
+                responses
+                    .entry((
+                        entry.session_id.clone(),
+                        direction.reverse(),
+                        id.to_string(),
+                    ))
+                    .or_insert(entry);
+            }
+            (MessageKind::Request | MessageKind::Notification, Some(_), _) => requests.push(entry),
+            _ => {}
+        }
+    }
+
+    for request in requests {
+        let response = match (&request.direction, &request.rpc_id) {
+            (Some(direction), Some(id)) => {
+                responses.remove(&(request.session_id.clone(), *direction, id.to_string()))
+            }
+            _ => None,
+        };
+        let request_text = request.message.to_string();
+        let (status, status_text, response_text) = match &response {
+            Some(r) if r.is_error => (500, "JSON-RPC Error", r.message.to_string()),
+            Some(r) => (200, "OK", r.message.to_string()),
+            None if request.kind == MessageKind::Notification => (204, "No Content", String::new()),
+            None => (0, "No Response", String::new()),
+        };
+        let time = response.as_ref().and_then(|r| r.duration_ms).unwrap_or(0.0);
+        har_entries.push(json!({
+            "startedDateTime": request.timestamp,
+            "time": time,
+            "request": {
+                "method": "POST",
+                "url": format!(
+                    "mcp://{}/{}",
+                    urlencoding::encode(&server.name),
+                    request.method.as_deref().unwrap_or_default()
+                ),
+                "httpVersion": "JSON-RPC/2.0",
+                "cookies": [],
+                "headers": [],
+                "queryString": [],
+                "postData": { "mimeType": "application/json", "text": request_text },
+                "headersSize": -1,
+                "bodySize": request_text.len(),
+            },
+            "response": {
+                "status": status,
+                "statusText": status_text,
+                "httpVersion": "JSON-RPC/2.0",
+                "cookies": [],
+                "headers": [],
+                "content": {
+                    "size": response_text.len(),
+                    "mimeType": "application/json",
+                    "text": response_text,
+                },
+                "redirectURL": "",
+                "headersSize": -1,
+                "bodySize": response_text.len(),
+            },
+            "cache": {},
+            "timings": { "send": 0, "wait": time, "receive": 0 },
+            "_sessionId": request.session_id,
+            "_clientId": request.client_id,
+            "_direction": request.direction,
+        }));
+    }
+
+    Ok(json!({
+        "log": {
+            "version": "1.2",
+            "creator": { "name": "Conductor", "version": env!("CARGO_PKG_VERSION") },
+            "entries": har_entries,
+        }
+    }))
+}
+
+/// Re-sends a captured client request to the server and returns its reply.
+pub async fn replay(server: &McpServerConfig, entry_id: &str) -> Result<ReplayResult> {
+    let entry = read_all(&server.id)?
+        .into_iter()
+        .find(|e| e.id == entry_id)
+        .with_context(|| format!("No captured message '{}'", entry_id))?;
+    if entry.kind != MessageKind::Request || entry.direction != Some(Direction::ClientToServer) {
+        bail!("Only requests sent to the server can be replayed");
+    }
+    let method = entry.method.as_deref().unwrap_or_default();
+    if method == "initialize" {
+        bail!("initialize can't be replayed; the server is initialized when it starts");
+    }
+    let params = entry.message.get("params").cloned().unwrap_or(json!({}));
+    if params.to_string().contains(REDACTED) {
+        bail!("The captured request had secrets redacted, so it can't be replayed as-is");
+    }
+
+    let upstream = crate::gateway::upstream_for(server).await?;
+    let started = Instant::now();
+    let response = upstream.request(method, params, REPLAY_TIMEOUT).await?;
+    Ok(ReplayResult {
+        response,
+        duration_ms: started.elapsed().as_secs_f64() * 1000.0,
+    })
+}
+
+/// Wraps the entries of inspected servers in `conductor tap`. Runs after
+/// tool filters, so the log shows what the client actually exchanged.
+pub fn apply_tap(
+    adapter: &dyn ClientAdapter,
+    servers: &mut [McpServerConfig],
+    warnings: &mut Vec<String>,
+) {
+    for server in servers.iter_mut().filter(|s| s.inspect) {
+        let args = [
+            "tap".to_string(),
+            "--server".to_string(),
+            server.name.clone(),
+            "--client".to_string(),
+            adapter.id().to_string(),
+        ];
+        if let Err(e) = serializer::wrap_with_conductor(server, &args) {
+            warnings.push(format!(
+                "Server '{}': traffic inspector not applied: {:#}",
+                server.name, e
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn redacts_secret_env_values_in_message_bodies() {
+        let redactor = Redactor::new(
+            &env(&[
+                ("GITHUB_TOKEN", "ghp_0123456789"),
+                ("CUSTOM", "k3y-from-keychain"),
+                ("LOG_LEVEL", "debug"),
+            ]),
+            &["CUSTOM".to_string()],
+            &[],
+        );
+        let message = json!({
+            "result": {
+                "content": [{
+                    "type": "text",
+                    "text": "token ghp_0123456789 and k3y-from-keychain at debug level"
+                }]
+            }
+        });
+        assert_eq!(
+            redactor.value(&message)["result"]["content"][0]["text"],
+            "token [REDACTED] and [REDACTED] at debug level"
+        );
+
+        let logged = redactor.env(
+            &env(&[("GITHUB_TOKEN", "ghp_0123456789"), ("LOG_LEVEL", "debug")]),
+            &[],
+        );
+        assert_eq!(logged["GITHUB_TOKEN"], REDACTED);
+        assert_eq!(logged["LOG_LEVEL"], "debug");
+    }
+
+    #[test]
+    fn redacts_secret_header_values_echoed_anywhere() {
+        let redactor = Redactor::new(&HashMap::new(), &[], &["opaque-value-42".to_string()]);
+        assert_eq!(
+            redactor.text(r#"{"echo":"X-Custom: opaque-value-42"}"#),
+            r#"{"echo":"X-Custom: [REDACTED]"}"#
+        );
+    }
+
+    #[test]
+    fn picks_headers_by_name_or_secret_variable() {
+        let cfg = config::McpConfig {
+            secret_variables: vec!["API_TOKEN".to_string()],
+            variables: env(&[("REGION", "eu-west-1")]),
+            ..Default::default()
+        };
+        let store = VariableStore::load_for(&cfg, None, true).unwrap();
+        let headers = env(&[
+            ("X-Custom", "${API_TOKEN}"),
+            ("X-Api-Key", "literal-key"),
+            ("X-Region", "${REGION}"),
+            ("Accept", "application/json"),
+        ]);
+
+        let mut secrets = secret_headers(&headers, &store);
+        secrets.sort();
+        assert_eq!(secrets, ["literal-key", variables::MASK]);
+    }
+
+    #[test]
+    fn redacts_authorization_headers_in_args() {
+        let redactor = Redactor::default();
+        let args: Vec<String> = ["mcp-remote", "https://example.com/mcp", "--header"]
+            .into_iter()
+            .map(String::from)
+            .chain(["Authorization:Bearer abc.def".to_string()])
+            .collect();
+        assert_eq!(
+            redactor.args(&args),
+            [
+                "mcp-remote",
+                "https://example.com/mcp",
+                "--header",
+                "Authorization:[REDACTED]"
+            ]
+        );
+        assert_eq!(
+            redactor.args(&["X-Trace:Bearer abc.def".to_string()]),
+            ["X-Trace:Bearer [REDACTED]"]
+        );
+    }
+
+    #[test]
+    fn redacts_bearer_tokens_wherever_they_end() {
+        assert_eq!(redact_bearer("sent BEARER abc123"), "sent BEARER [REDACTED]");
+        assert_eq!(
+            redact_bearer("\"Bearer abc\", bearer def ok"),
+            "\"Bearer [REDACTED]\", bearer [REDACTED] ok"
+        );
+        assert_eq!(redact_bearer("no token here"), "no token here");
+    }
+
+    #[test]
+    fn redacts_secret_keys_in_nested_objects() {
+        let redactor = Redactor::default();
+        let message = json!({
+            "params": {
+                "arguments": {
+                    "auth": {"apiKey": "k", "user": "me"},
+                    "items": [{"password": "p", "count": 2}],
+                    "tokenCount": 5
+                }
+            }
+        });
+        let redacted = redactor.value(&message);
+        let arguments = &redacted["params"]["arguments"];
+        assert_eq!(arguments["auth"]["apiKey"], REDACTED);
+        assert_eq!(arguments["auth"]["user"], "me");
+        assert_eq!(arguments["items"][0]["password"], REDACTED);
+        assert_eq!(arguments["items"][0]["count"], 2);
+        assert_eq!(arguments["tokenCount"], 5);
+    }
+
+    #[test]
+    fn keeps_progress_tokens() {
+        let message = json!({"params": {"_meta": {"progressToken": "progress-1"}}});
+        assert_eq!(Redactor::default().value(&message), message);
+    }
+}
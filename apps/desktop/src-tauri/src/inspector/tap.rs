@@ -0,0 +1,183 @@
+//! `conductor tap`: runs a stdio MCP server unchanged and records every
+//! message in both directions to the server's traffic log.
+
+use super::{Direction, Recorder, Redactor};
+use crate::config;
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+
+const USAGE: &str =
+    "Usage: conductor tap --server <name> --client <client-id> -- <command> [args...]";
+
+struct TapArgs {
+    server: String,
+    client: String,
+    command: Vec<String>,
+}
+
+/// Runs the tap if `args` (without the program name) start with `tap`.
+/// Returns the process exit code, or `None` when this isn't a tap invocation.
+pub fn run_from_args(args: &[String]) -> Option<i32> {
+    if args.first().map(String::as_str) != Some("tap") {
+        return None;
+    }
+
+    let args = match parse_args(&args[1..]) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("conductor tap: {}", e);
+            eprintln!("{}", USAGE);
+            return Some(2);
+        }
+    };
+
+    let runtime = match tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("conductor tap: failed to start runtime: {}", e);
+            return Some(1);
+        }
+    };
+
+    let code = match runtime.block_on(run(args)) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("conductor tap: {:#}", e);
+            1
+        }
+    };
+    // The stdin reader may still be blocked; don't wait for it.
+    runtime.shutdown_background();
+    Some(code)
+}
+
+fn parse_args(args: &[String]) -> Result<TapArgs> {
+    let mut server = None;
+    let mut client = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--server" => server = Some(iter.next().context("--server needs a value")?.clone()),
+            "--client" => client = Some(iter.next().context("--client needs a value")?.clone()),
+            "--" => break,
+            other => bail!("Unexpected argument '{}'", other),
+        }
+    }
+
+    let command: Vec<String> = iter.cloned().collect();
+    if command.is_empty() {
+        bail!("Missing the server command after '--'");
+    }
+    Ok(TapArgs {
+        server: server.context("Missing --server")?,
+        client: client.context("Missing --client")?,
+        command,
+    })
+}
+
+async fn run(args: TapArgs) -> Result<i32> {
+    let server = config::read_config()
+        .ok()
+        .and_then(|cfg| cfg.servers.into_iter().find(|s| s.name == args.server));
+    // Inspection switched off since the last sync: relay without logging.
+    let enabled = server.as_ref().is_none_or(|s| s.inspect);
+    let server_id = server
+        .as_ref()
+        .map_or_else(|| args.server.clone(), |s| s.id.clone());
+    let (configured_keys, mut secret_keys): (Vec<String>, Vec<String>) = server
+        .map(|s| (s.env.into_keys().collect(), s.secret_env_keys))
+        .unwrap_or_default();
+    secret_keys.push("OAUTH_TOKEN".to_string());
+
+    // The client launched us with the server's env, secrets included. Only
+    // the server's own variables are logged, not the whole environment.
+    let env: HashMap<String, String> = std::env::vars().collect();
+    let server_env: HashMap<String, String> = env
+        .iter()
+        .filter(|(key, _)| configured_keys.contains(key) || secret_keys.contains(key))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    let header_secrets = if enabled {
+        super::secret_header_values(&server_id)
+    } else {
+        Vec::new()
+    };
+    let recorder = Arc::new(Recorder::new(
+        &server_id,
+        Some(&args.client),
+        Redactor::new(&env, &secret_keys, &header_secrets),
+        enabled,
+    ));
+    recorder.session_started(&args.command, &server_env, &secret_keys);
+
+    let (program, program_args) = args.command.split_first().context("Missing command")?;
+    let mut child = Command::new(program)
+        .args(program_args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("Failed to start '{}'", program))?;
+    let mut child_stdin = child.stdin.take().context("No stdin")?;
+    let child_stdout = child.stdout.take().context("No stdout")?;
+
+    let (out_tx, out_rx) = mpsc::unbounded_channel::<String>();
+    let writer = tokio::spawn(write_stdout(out_rx));
+
+    {
+        let recorder = recorder.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(tokio::io::stdin()).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if let Ok(message) = serde_json::from_str::<Value>(&line) {
+                    recorder.record(Direction::ClientToServer, &message);
+                }
+                let line = line + "\n";
+                if child_stdin.write_all(line.as_bytes()).await.is_err()
+                    || child_stdin.flush().await.is_err()
+                {
+                    break;
+                }
+            }
+            // Dropping stdin tells the server the client is gone.
+        });
+    }
+
+    let mut lines = BufReader::new(child_stdout).lines();
+    while let Some(line) = lines
+        .next_line()
+        .await
+        .context("Failed to read the server")?
+    {
+        if let Ok(message) = serde_json::from_str::<Value>(&line) {
+            recorder.record(Direction::ServerToClient, &message);
+        }
+        let _ = out_tx.send(line);
+    }
+    drop(out_tx);
+    let _ = writer.await;
+
+    let status = child.wait().await?;
+    Ok(status.code().unwrap_or(1))
+}
+
+async fn write_stdout(mut rx: mpsc::UnboundedReceiver<String>) {
+    let mut stdout = tokio::io::stdout();
+    while let Some(mut line) = rx.recv().await {
+        line.push('\n');
+        if stdout.write_all(line.as_bytes()).await.is_err() || stdout.flush().await.is_err() {
+            break;
+        }
+    }
+}
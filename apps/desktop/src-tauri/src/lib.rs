@@ -1,9 +1,20 @@
+pub mod autostart;
+pub mod bridge;
 pub mod clients;
 pub mod commands;
 pub mod config;
 pub mod errors;
 pub mod file_guard;
+pub mod gateway;
+pub mod inspector;
+pub mod notifications;
 pub mod oauth;
+pub mod secrets;
+pub mod supervisor;
+#[cfg(test)]
+mod test_support;
+pub mod tools;
+pub mod tray;
 pub mod watcher;
 
 use tauri::Manager;
@@ -34,11 +45,16 @@ pub fn run() {
             // sync
             commands::sync::sync_to_client,
             commands::sync::sync_to_all_clients,
+            commands::sync::set_sync_targets,
+            commands::sync::preview_name_conflicts,
             // secrets
             commands::secrets::save_secret,
             commands::secrets::get_secret,
             commands::secrets::delete_secret,
             commands::secrets::list_secret_keys,
+            commands::secrets::check_secret_backend,
+            commands::secrets::unlock_secret_file,
+            commands::secrets::migrate_secrets,
             // registry
             commands::registry::get_popular_servers,
             commands::registry::search_registry,
@@ -67,43 +83,49 @@ pub fn run() {
             // system
             commands::system::open_config_folder,
             commands::system::export_config,
+            // gateway
+            commands::gateway::get_gateway_status,
+            // tools
+            commands::tools::get_server_tools,
+            commands::tools::set_tool_filter,
+            // inspector
+            commands::inspector::query_traffic,
+            commands::inspector::export_traffic_har,
+            commands::inspector::replay_traffic,
+            commands::inspector::clear_traffic,
+            // supervisor
+            commands::supervisor::list_managed_servers,
+            commands::supervisor::get_managed_server_logs,
+            commands::supervisor::restart_managed_server,
+            // variables
+            commands::variables::get_variables,
+            commands::variables::set_variable,
+            commands::variables::delete_variable,
+            commands::variables::preview_server,
+            // profiles
+            commands::profiles::list_profiles,
+            commands::profiles::create_profile,
+            commands::profiles::update_profile,
+            commands::profiles::delete_profile,
+            commands::profiles::switch_profile,
         ])
         .setup(|app| {
             // Setup system tray
-            let tray = app.tray_by_id("main");
-            if let Some(tray) = tray {
-                let show_item = tauri::menu::MenuItem::with_id(
-                    app,
-                    "show",
-                    "Show Conductor",
-                    true,
-                    None::<&str>,
-                )?;
-                let quit_item = tauri::menu::MenuItem::with_id(
-                    app,
-                    "quit",
-                    "Quit Conductor",
-                    true,
-                    None::<&str>,
-                )?;
-                let menu = tauri::menu::Menu::with_items(app, &[&show_item, &quit_item])?;
-                tray.set_menu(Some(menu))?;
+            tray::setup(app.handle())?;
+            notifications::init(app.handle());
 
-                let app_handle = app.handle().clone();
-                tray.on_menu_event(move |_tray, event| match event.id().as_ref() {
-                    "show" => {
-                        if let Some(window) = app_handle.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
-                    }
-                    "quit" => {
-                        std::process::exit(0);
-                    }
-                    _ => {}
-                });
+            // The window starts hidden so it doesn't flash when minimized.
+            let settings = config::read_config().unwrap_or_default().settings;
+            autostart::sync(settings.launch_at_login);
+            if !settings.start_minimized {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.show();
+                }
             }
 
+            gateway::spawn();
+            supervisor::spawn(app.handle().clone());
+
             // Start file watcher
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
@@ -2,5 +2,12 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = conductor_lib::bridge::run_from_args(&args)
+        .or_else(|| conductor_lib::tools::proxy::run_from_args(&args))
+        .or_else(|| conductor_lib::inspector::tap::run_from_args(&args))
+    {
+        std::process::exit(code);
+    }
     conductor_lib::run();
 }
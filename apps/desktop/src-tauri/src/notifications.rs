@@ -0,0 +1,325 @@
+//! Desktop notifications for edits made outside Conductor, sync outcomes and
+//! expired OAuth tokens, each behind its setting (`notify_external`,
+//! `sync_notifications`, `error_notifications`).
+//!
+//! Notices are collected for a couple of seconds so a burst (a sync of every
+//! client, an editor saving several files) becomes one notification. The
+//! system notification is only shown while the window isn't in front; the
+//! same notification, with its actions, is emitted as a "notification" event
+//! for the app to show as a toast. Sync outcomes the app reports itself are
+//! only emitted while it's in the background.
+
+use crate::clients::get_all_adapters;
+use crate::config;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex, OnceLock};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tokio::time::Duration;
+
+/// How long notices are collected before they are sent.
+const GROUP_WINDOW: Duration = Duration::from_secs(2);
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+static PENDING: LazyLock<Mutex<Vec<Notice>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+static FLUSH_SCHEDULED: AtomicBool = AtomicBool::new(false);
+/// Servers whose expired token was already reported, until a new one is
+/// stored.
+static EXPIRED: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[derive(Debug, Clone)]
+enum Notice {
+    ExternalEdit {
+        client_id: String,
+        client: String,
+    },
+    Synced {
+        client: String,
+    },
+    SyncFailed {
+        client_id: String,
+        client: String,
+        error: String,
+    },
+    TokenExpired {
+        server_id: String,
+        server: String,
+    },
+}
+
+/// Payload of "notification" events.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    pub title: String,
+    pub body: String,
+    pub actions: Vec<NotificationAction>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationAction {
+    pub kind: ActionKind,
+    pub label: String,
+    /// The client to act on; every client when absent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ActionKind {
+    /// Open the client's sync status and drift.
+    ShowChanges,
+    Resync,
+    /// Import the client's edited servers into Conductor.
+    Adopt,
+    Reauthorize,
+}
+
+impl NotificationAction {
+    fn new(kind: ActionKind, label: &str, client_id: Option<&str>) -> Self {
+        Self {
+            kind,
+            label: label.to_string(),
+            client_id: client_id.map(str::to_string),
+            server_id: None,
+        }
+    }
+}
+
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+/// Client config files changed by something other than Conductor.
+pub fn external_edit(paths: &[String]) {
+    if !config::read_config().is_ok_and(|cfg| cfg.settings.notify_external) {
+        return;
+    }
+    for adapter in get_all_adapters() {
+        let touched = adapter
+            .targets()
+            .iter()
+            .any(|t| paths.iter().any(|p| Path::new(p) == t.config_path));
+        if touched {
+            push(Notice::ExternalEdit {
+                client_id: adapter.id().to_string(),
+                client: adapter.display_name().to_string(),
+            });
+        }
+    }
+}
+
+/// A sync to `client_id` finished, with its error if it failed.
+pub fn sync_finished(client_id: &str, error: Option<&str>) {
+    let Ok(cfg) = config::read_config() else {
+        return;
+    };
+    let client = crate::clients::get_adapter(client_id)
+        .map_or(client_id.to_string(), |a| a.display_name().to_string());
+    match error {
+        Some(error) if cfg.settings.error_notifications => push(Notice::SyncFailed {
+            client_id: client_id.to_string(),
+            client,
+            error: error.to_string(),
+        }),
+        None if cfg.settings.sync_notifications => push(Notice::Synced { client }),
+        _ => {}
+    }
+}
+
+/// A server's OAuth token expired and couldn't be refreshed. Reported once
+/// until [`token_renewed`] is called for the server.
+pub fn token_expired(server_id: &str) {
+    let Ok(cfg) = config::read_config() else {
+        return;
+    };
+    if !cfg.settings.error_notifications || !lock(&EXPIRED).insert(server_id.to_string()) {
+        return;
+    }
+    let server = cfg
+        .servers
+        .iter()
+        .find(|s| s.id == server_id)
+        .map_or(server_id.to_string(), |s| {
+            s.display_name.clone().unwrap_or_else(|| s.name.clone())
+        });
+    push(Notice::TokenExpired {
+        server_id: server_id.to_string(),
+        server,
+    });
+}
+
+pub fn token_renewed(server_id: &str) {
+    lock(&EXPIRED).remove(server_id);
+}
+
+fn push(notice: Notice) {
+    let Some(app) = APP.get() else {
+        return;
+    };
+    lock(&PENDING).push(notice);
+    if FLUSH_SCHEDULED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(GROUP_WINDOW).await;
+        FLUSH_SCHEDULED.store(false, Ordering::SeqCst);
+        let notices = std::mem::take(&mut *lock(&PENDING));
+        flush(&app, notices);
+    });
+}
+
+fn flush(app: &AppHandle, notices: Vec<Notice>) {
+    let in_front = app
+        .get_webview_window("main")
+        .is_some_and(|w| w.is_visible().unwrap_or(false) && w.is_focused().unwrap_or(false));
+
+    let mut edited: Vec<(String, String)> = Vec::new();
+    let mut synced: Vec<String> = Vec::new();
+    let mut failed: Vec<(String, String, String)> = Vec::new();
+    let mut expired: Vec<(String, String)> = Vec::new();
+    for notice in notices {
+        match notice {
+            Notice::ExternalEdit { client_id, client } => {
+                if !edited.iter().any(|(id, _)| *id == client_id) {
+                    edited.push((client_id, client));
+                }
+            }
+            Notice::Synced { client } => {
+                if !synced.contains(&client) {
+                    synced.push(client);
+                }
+            }
+            Notice::SyncFailed {
+                client_id,
+                client,
+                error,
+            } => {
+                // Only the latest error of a client.
+                failed.retain(|(id, _, _)| *id != client_id);
+                failed.push((client_id, client, error));
+            }
+            Notice::TokenExpired { server_id, server } => expired.push((server_id, server)),
+        }
+    }
+    // A client that failed in the same burst didn't end up synced.
+    synced.retain(|name| !failed.iter().any(|(_, client, _)| client == name));
+
+    let mut notifications = Vec::new();
+    if let Some(n) = external_notification(&edited) {
+        notifications.push((n, true));
+    }
+    if let Some(n) = failed_notification(&failed) {
+        notifications.push((n, false));
+    }
+    if !synced.is_empty() {
+        let notification = Notification {
+            title: match synced.as_slice() {
+                [client] => format!("Synced to {}", client),
+                _ => format!("Synced to {} clients", synced.len()),
+            },
+            body: if synced.len() == 1 {
+                "Servers are up to date".to_string()
+            } else {
+                synced.join(", ")
+            },
+            actions: Vec::new(),
+        };
+        notifications.push((notification, false));
+    }
+    for (server_id, server) in expired {
+        let notification = Notification {
+            title: format!("Sign-in expired for {}", server),
+            body: "The OAuth token couldn't be refreshed. Clients keep the old one until you sign in again.".to_string(),
+            actions: vec![NotificationAction {
+                kind: ActionKind::Reauthorize,
+                label: "Sign in".to_string(),
+                client_id: None,
+                server_id: Some(server_id),
+            }],
+        };
+        notifications.push((notification, true));
+    }
+
+    for (notification, shown_in_front) in notifications {
+        if !in_front {
+            let _ = app
+                .notification()
+                .builder()
+                .title(&notification.title)
+                .body(&notification.body)
+                .show();
+        }
+        if !in_front || shown_in_front {
+            let _ = app.emit("notification", &notification);
+        }
+    }
+}
+
+fn external_notification(edited: &[(String, String)]) -> Option<Notification> {
+    match edited {
+        [] => None,
+        [(client_id, client)] => Some(Notification {
+            title: format!("{} config changed", client),
+            body: "It was edited outside Conductor.".to_string(),
+            actions: vec![
+                NotificationAction::new(ActionKind::ShowChanges, "Show changes", Some(client_id)),
+                NotificationAction::new(ActionKind::Adopt, "Adopt changes", Some(client_id)),
+                NotificationAction::new(ActionKind::Resync, "Re-sync", Some(client_id)),
+            ],
+        }),
+        _ => Some(Notification {
+            title: format!("{} client configs changed", edited.len()),
+            body: format!(
+                "Edited outside Conductor: {}",
+                edited
+                    .iter()
+                    .map(|(_, c)| c.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            actions: vec![
+                NotificationAction::new(ActionKind::ShowChanges, "Show changes", None),
+                NotificationAction::new(ActionKind::Resync, "Re-sync all", None),
+            ],
+        }),
+    }
+}
+
+fn failed_notification(failed: &[(String, String, String)]) -> Option<Notification> {
+    match failed {
+        [] => None,
+        [(client_id, client, error)] => Some(Notification {
+            title: format!("Sync failed for {}", client),
+            body: error.clone(),
+            actions: vec![
+                NotificationAction::new(ActionKind::Resync, "Retry", Some(client_id)),
+                NotificationAction::new(ActionKind::ShowChanges, "Show details", Some(client_id)),
+            ],
+        }),
+        _ => Some(Notification {
+            title: format!("Sync failed for {} clients", failed.len()),
+            body: failed
+                .iter()
+                .map(|(_, c, _)| c.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            actions: vec![
+                NotificationAction::new(ActionKind::Resync, "Retry all", None),
+                NotificationAction::new(ActionKind::ShowChanges, "Show details", None),
+            ],
+        }),
+    }
+}
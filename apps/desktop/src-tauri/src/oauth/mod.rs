@@ -1,3 +1,4 @@
+use crate::errors::ConductorError;
 use anyhow::{Context, Result};
 use axum::extract::Query;
 use axum::response::Html;
@@ -121,12 +122,12 @@ pub async fn start_oauth_server(
 }
 
 pub async fn get_valid_oauth_token(server_id: &str) -> Result<Option<String>> {
-    let current_token = get_keyring_value(server_id, "oauth_token");
+    let current_token = get_stored_value(server_id, "oauth_token")?;
     let Some(token) = current_token else {
         return Ok(None);
     };
 
-    let expires_at = get_keyring_value(server_id, "oauth_expires")
+    let expires_at = get_stored_value(server_id, "oauth_expires")?
         .as_deref()
         .and_then(parse_rfc3339_utc);
 
@@ -149,11 +150,20 @@ pub async fn get_valid_oauth_token(server_id: &str) -> Result<Option<String>> {
                 "Warning: OAuth refresh failed for '{}': {}. Using existing token.",
                 server_id, e
             );
+            if expires_at.is_some_and(|ts| ts <= Utc::now()) {
+                crate::notifications::token_expired(server_id);
+            }
             Ok(Some(token))
         }
     }
 }
 
+/// Refresh the OAuth access token whatever its recorded expiry, e.g. after
+/// the server rejected it.
+pub async fn refresh_oauth_token(server_id: &str) -> Result<String> {
+    Ok(refresh_access_token(server_id).await?.access_token)
+}
+
 fn provider_spec(provider: &str) -> Result<ProviderSpec> {
     let normalized = provider.trim().to_lowercase();
     let spec = match normalized.as_str() {
@@ -258,19 +268,21 @@ async fn handle_callback(
             .get("error_description")
             .cloned()
             .unwrap_or_else(|| error.clone());
-        anyhow::bail!("OAuth error: {}", description);
+        return Err(ConductorError::OAuthFailed(description).into());
     }
 
     let returned_state = query
         .get("state")
-        .ok_or_else(|| anyhow::anyhow!("Missing OAuth state in callback"))?;
+        .ok_or_else(|| ConductorError::OAuthFailed("Missing OAuth state in callback".to_string()))?;
     if returned_state != &ctx.expected_state {
-        anyhow::bail!("OAuth state mismatch");
+        return Err(ConductorError::OAuthFailed("OAuth state mismatch".to_string()).into());
     }
 
     let code = query
         .get("code")
-        .ok_or_else(|| anyhow::anyhow!("No authorization code in callback"))?;
+        .ok_or_else(|| {
+            ConductorError::OAuthFailed("No authorization code in callback".to_string())
+        })?;
 
     let bundle = exchange_code_for_tokens(
         &ctx.provider_spec,
@@ -296,10 +308,14 @@ async fn handle_callback(
 }
 
 async fn refresh_access_token(server_id: &str) -> Result<OAuthTokenBundle> {
-    let provider = get_keyring_value(server_id, "oauth_provider")
-        .ok_or_else(|| anyhow::anyhow!("Missing OAuth provider"))?;
-    let refresh_token = get_keyring_value(server_id, "oauth_refresh")
-        .ok_or_else(|| anyhow::anyhow!("OAuth token expired and no refresh token is available"))?;
+    let provider = get_stored_value(server_id, "oauth_provider")?
+        .ok_or_else(|| ConductorError::OAuthNotConfigured("Missing OAuth provider".to_string()))?;
+    let refresh_token = get_stored_value(server_id, "oauth_refresh")?
+        .ok_or_else(|| {
+            ConductorError::OAuthFailed(
+                "OAuth token expired and no refresh token is available".to_string(),
+            )
+        })?;
 
     let spec = provider_spec(&provider)?;
     let credentials = resolve_client_credentials(server_id, &provider)?;
@@ -430,7 +446,9 @@ async fn parse_oauth_token_response(response: reqwest::Response) -> Result<OAuth
             .and_then(|v| v.as_str())
             .or_else(|| body.get("error").and_then(|v| v.as_str()))
             .unwrap_or("unknown OAuth error");
-        anyhow::bail!("OAuth token request failed: {}", error);
+        return Err(
+            ConductorError::OAuthFailed(format!("OAuth token request failed: {}", error)).into(),
+        );
     }
 
     if body.get("ok").and_then(|v| v.as_bool()) == Some(false) {
@@ -438,7 +456,9 @@ async fn parse_oauth_token_response(response: reqwest::Response) -> Result<OAuth
             .get("error")
             .and_then(|v| v.as_str())
             .unwrap_or("unknown OAuth error");
-        anyhow::bail!("OAuth token request failed: {}", error);
+        return Err(
+            ConductorError::OAuthFailed(format!("OAuth token request failed: {}", error)).into(),
+        );
     }
 
     let access_token = body
@@ -449,7 +469,9 @@ async fn parse_oauth_token_response(response: reqwest::Response) -> Result<OAuth
                 .and_then(|u| u.get("access_token"))
                 .and_then(|v| v.as_str())
         })
-        .ok_or_else(|| anyhow::anyhow!("OAuth token response missing access_token"))?
+        .ok_or_else(|| {
+            ConductorError::OAuthFailed("OAuth token response missing access_token".to_string())
+        })?
         .to_string();
 
     let refresh_token = body
@@ -463,14 +485,11 @@ async fn parse_oauth_token_response(response: reqwest::Response) -> Result<OAuth
         DateTime::from_timestamp(epoch_secs, 0)
     } else if let Some(expires_in) = body.get("expires_in").and_then(|v| v.as_i64()) {
         Some(Utc::now() + ChronoDuration::seconds(expires_in.max(0)))
-    } else if let Some(expires_in) = body
-        .get("authed_user")
-        .and_then(|u| u.get("expires_in"))
-        .and_then(|v| v.as_i64())
-    {
-        Some(Utc::now() + ChronoDuration::seconds(expires_in.max(0)))
     } else {
-        None
+        body.get("authed_user")
+            .and_then(|u| u.get("expires_in"))
+            .and_then(|v| v.as_i64())
+            .map(|expires_in| Utc::now() + ChronoDuration::seconds(expires_in.max(0)))
     };
 
     Ok(OAuthTokenBundle {
@@ -494,11 +513,10 @@ fn resolve_client_credentials(server_id: &str, provider: &str) -> Result<OAuthCl
         ],
     )
     .ok_or_else(|| {
-        anyhow::anyhow!(
+        ConductorError::OAuthNotConfigured(format!(
             "Missing OAuth client ID for provider '{}'. Set OAUTH_{}_CLIENT_ID or OAUTH_CLIENT_ID.",
-            provider,
-            provider_key
-        )
+            provider, provider_key
+        ))
     })?;
 
     let client_secret = resolve_credential_value(
@@ -511,11 +529,10 @@ fn resolve_client_credentials(server_id: &str, provider: &str) -> Result<OAuthCl
         ],
     )
     .ok_or_else(|| {
-        anyhow::anyhow!(
+        ConductorError::OAuthNotConfigured(format!(
             "Missing OAuth client secret for provider '{}'. Set OAUTH_{}_CLIENT_SECRET or OAUTH_CLIENT_SECRET.",
-            provider,
-            provider_key
-        )
+            provider, provider_key
+        ))
     })?;
 
     Ok(OAuthClientCredentials {
@@ -535,13 +552,12 @@ fn resolve_credential_value(
     server: Option<&crate::config::McpServerConfig>,
     candidate_keys: &[String],
 ) -> Option<String> {
+    let store = crate::secrets::store();
     for key in candidate_keys {
-        let keychain_username = format!("{}:{}", server_id, key);
-        if let Ok(entry) = keyring::Entry::new("conductor", &keychain_username) {
-            if let Ok(value) = entry.get_password() {
-                if !value.trim().is_empty() {
-                    return Some(value);
-                }
+        let username = format!("{}:{}", server_id, key);
+        if let Ok(Some(value)) = store.get(&username) {
+            if !value.trim().is_empty() {
+                return Some(value);
             }
         }
     }
@@ -586,43 +602,67 @@ fn parse_rfc3339_utc(value: &str) -> Option<DateTime<Utc>> {
         .map(|dt| dt.with_timezone(&Utc))
 }
 
-fn get_keyring_value(server_id: &str, suffix: &str) -> Option<String> {
-    let username = format!("{}:{}", server_id, suffix);
-    keyring::Entry::new("conductor", &username)
-        .ok()
-        .and_then(|entry| entry.get_password().ok())
+/// Every secret the OAuth flow may have stored for a server: the token
+/// bundle and client credentials, including dynamically registered ones.
+pub fn stored_keys(server_id: &str) -> Vec<String> {
+    let mut suffixes: Vec<String> = [
+        "oauth_token",
+        "oauth_provider",
+        "oauth_expires",
+        "oauth_refresh",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    for provider in ["GITHUB", "GOOGLE", "NOTION", "SLACK", "LINEAR"] {
+        suffixes.push(format!("OAUTH_{}_CLIENT_ID", provider));
+        suffixes.push(format!("OAUTH_{}_CLIENT_SECRET", provider));
+    }
+    for generic in [
+        "OAUTH_CLIENT_ID",
+        "OAUTH_CLIENT_SECRET",
+        "CLIENT_ID",
+        "CLIENT_SECRET",
+    ] {
+        suffixes.push(generic.to_string());
+    }
+    suffixes
+        .into_iter()
+        .map(|suffix| format!("{}:{}", server_id, suffix))
+        .collect()
+}
+
+fn get_stored_value(server_id: &str, suffix: &str) -> Result<Option<String>, ConductorError> {
+    crate::secrets::get(&format!("{}:{}", server_id, suffix))
 }
 
-fn set_keyring_value(server_id: &str, suffix: &str, value: &str) -> Result<()> {
+fn set_stored_value(server_id: &str, suffix: &str, value: &str) -> Result<()> {
     let username = format!("{}:{}", server_id, suffix);
-    let entry =
-        keyring::Entry::new("conductor", &username).context("Failed to create keyring entry")?;
-    entry
-        .set_password(value)
-        .context("Failed to store value in keychain")
+    crate::secrets::store()
+        .set(&username, value)
+        .context("Failed to store OAuth value")
 }
 
-fn delete_keyring_value(server_id: &str, suffix: &str) {
+fn delete_stored_value(server_id: &str, suffix: &str) {
     let username = format!("{}:{}", server_id, suffix);
-    if let Ok(entry) = keyring::Entry::new("conductor", &username) {
-        let _ = entry.delete_credential();
-    }
+    let _ = crate::secrets::store().delete(&username);
 }
 
 fn store_oauth_bundle(server_id: &str, provider: &str, bundle: OAuthTokenBundle) -> Result<()> {
-    set_keyring_value(server_id, "oauth_token", &bundle.access_token)?;
-    set_keyring_value(server_id, "oauth_provider", provider)?;
+    set_stored_value(server_id, "oauth_token", &bundle.access_token)?;
+    set_stored_value(server_id, "oauth_provider", provider)?;
+    crate::notifications::token_renewed(server_id);
 
     if let Some(refresh) = bundle.refresh_token {
-        set_keyring_value(server_id, "oauth_refresh", &refresh)?;
+        set_stored_value(server_id, "oauth_refresh", &refresh)?;
     } else {
-        delete_keyring_value(server_id, "oauth_refresh");
+        delete_stored_value(server_id, "oauth_refresh");
     }
 
     if let Some(expires_at) = bundle.expires_at {
-        set_keyring_value(server_id, "oauth_expires", &expires_at.to_rfc3339())?;
+        set_stored_value(server_id, "oauth_expires", &expires_at.to_rfc3339())?;
     } else {
-        delete_keyring_value(server_id, "oauth_expires");
+        delete_stored_value(server_id, "oauth_expires");
     }
 
     Ok(())
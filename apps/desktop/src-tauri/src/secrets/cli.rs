@@ -0,0 +1,397 @@
+//! Password managers driven through their command-line tools. The tool has
+//! to be on PATH and signed in (`op signin`, `bw unlock` with `BW_SESSION`
+//! exported, a `pass` store with its GPG key); secret values are passed on
+//! stdin, never as arguments.
+
+use super::{unavailable, SecretStore};
+use crate::errors::ConductorError;
+use base64::Engine;
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+/// Items Conductor creates in 1Password and Bitwarden are titled
+/// `conductor:<key>`.
+const ITEM_PREFIX: &str = "conductor:";
+/// Folder of the `pass` store Conductor's entries go in.
+const PASS_FOLDER: &str = "conductor";
+
+fn run(
+    backend: &str,
+    program: &str,
+    args: &[&str],
+    stdin: Option<&str>,
+) -> Result<Output, ConductorError> {
+    let mut command = Command::new(program);
+    command
+        .args(args)
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    let mut child = command.spawn().map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => unavailable(
+            backend,
+            format!("`{}` isn't installed or isn't on PATH", program),
+        ),
+        _ => ConductorError::SecretAccess(format!("Failed to run {}: {}", program, e)),
+    })?;
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        pipe.write_all(input.as_bytes())?;
+    }
+    Ok(child.wait_with_output()?)
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).trim().to_string()
+}
+
+fn failed(program: &str, output: &Output) -> ConductorError {
+    ConductorError::SecretAccess(format!("{} failed: {}", program, stderr(output)))
+}
+
+/// The `pass` password store. Entries go in its `conductor/` folder.
+pub struct Pass;
+
+impl Pass {
+    fn show(path: &str) -> Result<Option<String>, ConductorError> {
+        let output = run("pass", "pass", &["show", path], None)?;
+        if output.status.success() {
+            Ok(Some(stdout(&output)))
+        } else if stderr(&output).contains("is not in the password store") {
+            Ok(None)
+        } else {
+            Err(failed("pass", &output))
+        }
+    }
+
+    /// `pass://<path>`: the entry's first line, which `pass` treats as the
+    /// password.
+    pub fn read(path: &str) -> Result<String, ConductorError> {
+        Self::show(path)?
+            .map(|entry| entry.lines().next().unwrap_or_default().to_string())
+            .ok_or_else(|| ConductorError::SecretAccess(format!("pass has no entry '{}'", path)))
+    }
+
+    fn store_dir() -> Option<PathBuf> {
+        std::env::var_os("PASSWORD_STORE_DIR")
+            .map(PathBuf::from)
+            .or_else(|| dirs::home_dir().map(|home| home.join(".password-store")))
+    }
+}
+
+impl SecretStore for Pass {
+    fn name(&self) -> &'static str {
+        "pass"
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>, ConductorError> {
+        let entry = Self::show(&format!("{}/{}", PASS_FOLDER, key))?;
+        Ok(entry.map(|value| match value.strip_suffix('\n') {
+            Some(stripped) => stripped.to_string(),
+            None => value,
+        }))
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), ConductorError> {
+        let path = format!("{}/{}", PASS_FOLDER, key);
+        let output = run("pass", "pass", &["insert", "-m", "-f", &path], Some(value))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(failed("pass", &output))
+        }
+    }
+
+    fn delete(&self, key: &str) -> Result<(), ConductorError> {
+        let path = format!("{}/{}", PASS_FOLDER, key);
+        let output = run("pass", "pass", &["rm", "-f", &path], None)?;
+        if output.status.success() || stderr(&output).contains("is not in the password store") {
+            Ok(())
+        } else {
+            Err(failed("pass", &output))
+        }
+    }
+
+    fn list(&self) -> Result<Option<Vec<String>>, ConductorError> {
+        let mut keys = Vec::new();
+        if let Some(dir) = Self::store_dir() {
+            collect_entries(&dir.join(PASS_FOLDER), "", &mut keys);
+        }
+        Ok(Some(keys))
+    }
+
+    fn check(&self) -> Result<(), ConductorError> {
+        let output = run("pass", "pass", &["version"], None)?;
+        if !output.status.success() {
+            return Err(failed("pass", &output));
+        }
+        match Self::store_dir() {
+            Some(dir) if dir.join(".gpg-id").exists() => Ok(()),
+            _ => Err(unavailable(
+                "pass",
+                "the password store isn't initialized; run `pass init <gpg-id>`",
+            )),
+        }
+    }
+}
+
+/// Names of the `.gpg` files under `dir`, relative to it.
+fn collect_entries(dir: &Path, prefix: &str, keys: &mut Vec<String>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let path = entry.path();
+        if path.is_dir() {
+            collect_entries(&path, &format!("{}{}/", prefix, name), keys);
+        } else if let Some(stem) = name.strip_suffix(".gpg") {
+            keys.push(format!("{}{}", prefix, stem));
+        }
+    }
+}
+
+/// 1Password, through the `op` CLI. Each secret is a Password item in
+/// `settings.onepassword_vault`.
+pub struct OnePassword {
+    pub vault: String,
+}
+
+impl OnePassword {
+    fn op(&self, args: &[&str], stdin: Option<&str>) -> Result<Output, ConductorError> {
+        let output = run("1Password", "op", args, stdin)?;
+        if output.status.success() {
+            return Ok(output);
+        }
+        let message = stderr(&output);
+        if message.contains("not currently signed in") || message.contains("no accounts") {
+            Err(unavailable(
+                "1Password",
+                format!("{}; run `op signin`", message),
+            ))
+        } else {
+            Err(failed("op", &output))
+        }
+    }
+
+    /// The item's ID and password, if it exists.
+    fn find(&self, key: &str) -> Result<Option<(String, String)>, ConductorError> {
+        let title = format!("{}{}", ITEM_PREFIX, key);
+        let args = [
+            "item",
+            "get",
+            &title,
+            "--vault",
+            &self.vault,
+            "--format",
+            "json",
+        ];
+        let item: Value = match self.op(&args, None) {
+            Ok(output) => serde_json::from_slice(&output.stdout)?,
+            Err(ConductorError::SecretAccess(message))
+                if message.contains("isn't an item") || message.contains("not found") =>
+            {
+                return Ok(None);
+            }
+            Err(e) => return Err(e),
+        };
+        let id = item["id"].as_str().unwrap_or_default().to_string();
+        let password = item["fields"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|field| field["id"] == "password")
+            .and_then(|field| field["value"].as_str())
+            .unwrap_or_default()
+            .to_string();
+        Ok(Some((id, password)))
+    }
+
+    /// `op://<vault>/<item>/<field>`, through `op read`.
+    pub fn read(uri: &str) -> Result<String, ConductorError> {
+        let output = run("1Password", "op", &["read", "--no-newline", uri], None)?;
+        if output.status.success() {
+            Ok(stdout(&output))
+        } else {
+            Err(failed("op", &output))
+        }
+    }
+}
+
+impl SecretStore for OnePassword {
+    fn name(&self) -> &'static str {
+        "1Password"
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>, ConductorError> {
+        Ok(self.find(key)?.map(|(_, password)| password))
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), ConductorError> {
+        // Replaced rather than edited: `op item edit` only takes new values
+        // as arguments, where other processes can see them.
+        self.delete(key)?;
+        let template = json!({
+            "title": format!("{}{}", ITEM_PREFIX, key),
+            "category": "PASSWORD",
+            "fields": [{
+                "id": "password",
+                "label": "password",
+                "type": "CONCEALED",
+                "purpose": "PASSWORD",
+                "value": value,
+            }],
+        });
+        self.op(
+            &["item", "create", "--vault", &self.vault, "-"],
+            Some(&template.to_string()),
+        )?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> Result<(), ConductorError> {
+        if let Some((id, _)) = self.find(key)? {
+            self.op(&["item", "delete", &id, "--vault", &self.vault], None)?;
+        }
+        Ok(())
+    }
+
+    fn list(&self) -> Result<Option<Vec<String>>, ConductorError> {
+        let output = self.op(
+            &["item", "list", "--vault", &self.vault, "--format", "json"],
+            None,
+        )?;
+        let items: Vec<Value> = serde_json::from_slice(&output.stdout)?;
+        Ok(Some(
+            items
+                .iter()
+                .filter_map(|item| item["title"].as_str()?.strip_prefix(ITEM_PREFIX))
+                .map(str::to_string)
+                .collect(),
+        ))
+    }
+}
+
+/// Bitwarden, through the `bw` CLI. Each secret is a login item whose
+/// password is the value.
+pub struct Bitwarden;
+
+impl Bitwarden {
+    fn bw(args: &[&str], stdin: Option<&str>) -> Result<Output, ConductorError> {
+        let output = run("Bitwarden", "bw", args, stdin)?;
+        if output.status.success() {
+            return Ok(output);
+        }
+        let message = stderr(&output);
+        if message.contains("locked") || message.contains("not logged in") {
+            Err(unavailable(
+                "Bitwarden",
+                format!("{}; run `bw unlock` and export BW_SESSION", message),
+            ))
+        } else {
+            Err(failed("bw", &output))
+        }
+    }
+
+    fn find(key: &str) -> Result<Option<Value>, ConductorError> {
+        let name = format!("{}{}", ITEM_PREFIX, key);
+        let output = Self::bw(&["list", "items", "--search", &name], None)?;
+        let items: Vec<Value> = serde_json::from_slice(&output.stdout)?;
+        Ok(items.into_iter().find(|item| item["name"] == name.as_str()))
+    }
+
+    /// `bw://<item>[/<field>]`, through `bw get`. The field is one `bw get`
+    /// knows (`password`, `username`, `notes`, `totp`) and defaults to the
+    /// password.
+    pub fn read(reference: &str) -> Result<String, ConductorError> {
+        let (item, field) = reference.split_once('/').unwrap_or((reference, "password"));
+        let output = Self::bw(&["get", field, item], None)?;
+        Ok(stdout(&output).trim_end_matches('\n').to_string())
+    }
+
+    /// Hands `item` to `bw create` / `bw edit`, which read it base64-encoded
+    /// from stdin.
+    fn encode(item: &Value) -> String {
+        base64::engine::general_purpose::STANDARD.encode(item.to_string())
+    }
+}
+
+impl SecretStore for Bitwarden {
+    fn name(&self) -> &'static str {
+        "Bitwarden"
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>, ConductorError> {
+        Ok(Self::find(key)?.map(|item| {
+            item["login"]["password"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string()
+        }))
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), ConductorError> {
+        match Self::find(key)? {
+            Some(mut item) => {
+                let id = item["id"].as_str().unwrap_or_default().to_string();
+                item["login"]["password"] = json!(value);
+                Self::bw(&["edit", "item", &id], Some(&Self::encode(&item)))?;
+            }
+            None => {
+                let item = json!({
+                    "type": 1,
+                    "name": format!("{}{}", ITEM_PREFIX, key),
+                    "notes": null,
+                    "favorite": false,
+                    "fields": [],
+                    "login": {
+                        "uris": [],
+                        "username": null,
+                        "password": value,
+                        "totp": null,
+                    },
+                    "reprompt": 0,
+                    "organizationId": null,
+                    "folderId": null,
+                });
+                Self::bw(&["create", "item"], Some(&Self::encode(&item)))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> Result<(), ConductorError> {
+        if let Some(item) = Self::find(key)? {
+            let id = item["id"].as_str().unwrap_or_default();
+            Self::bw(&["delete", "item", id], None)?;
+        }
+        Ok(())
+    }
+
+    fn list(&self) -> Result<Option<Vec<String>>, ConductorError> {
+        let output = Self::bw(&["list", "items", "--search", ITEM_PREFIX], None)?;
+        let items: Vec<Value> = serde_json::from_slice(&output.stdout)?;
+        Ok(Some(
+            items
+                .iter()
+                .filter_map(|item| item["name"].as_str()?.strip_prefix(ITEM_PREFIX))
+                .map(str::to_string)
+                .collect(),
+        ))
+    }
+}
@@ -0,0 +1,231 @@
+//! Secrets in `~/.conductor/secrets.enc`, for machines without a keychain.
+//!
+//! The file holds every secret as one JSON map, sealed with
+//! ChaCha20-Poly1305 under a key derived from a passphrase by
+//! PBKDF2-HMAC-SHA256. The passphrase comes from
+//! `CONDUCTOR_SECRETS_PASSPHRASE`, or is entered in the app ([`unlock`])
+//! and kept in memory only.
+
+use super::{unavailable, SecretStore};
+use crate::config;
+use crate::errors::ConductorError;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::num::NonZeroU32;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+
+const PASSPHRASE_VAR: &str = "CONDUCTOR_SECRETS_PASSPHRASE";
+const FORMAT_VERSION: u32 = 1;
+const ITERATIONS: u32 = 600_000;
+const SALT_LEN: usize = 16;
+const BACKEND: &str = "Encrypted file";
+
+/// Entered through [`unlock`].
+static PASSPHRASE: LazyLock<Mutex<Option<String>>> = LazyLock::new(|| Mutex::new(None));
+/// The last key derived, since derivation is slow on purpose.
+static DERIVED: LazyLock<Mutex<Option<Derived>>> = LazyLock::new(|| Mutex::new(None));
+/// Held across read-modify-write so concurrent writes don't drop each other.
+static WRITE: Mutex<()> = Mutex::new(());
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+struct Derived {
+    passphrase: String,
+    salt: Vec<u8>,
+    iterations: u32,
+    key: [u8; 32],
+}
+
+/// On-disk form. The salt is kept across writes; the nonce is new each time.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Sealed {
+    version: u32,
+    iterations: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+struct Contents {
+    secrets: BTreeMap<String, String>,
+    salt: Vec<u8>,
+    iterations: u32,
+}
+
+pub struct EncryptedFile;
+
+/// Remembers `passphrase` for this session after checking it opens the file,
+/// if there is one yet.
+pub fn unlock(passphrase: &str) -> Result<(), ConductorError> {
+    if passphrase.is_empty() {
+        return Err(ConductorError::InvalidInput(
+            "The passphrase can't be empty".to_string(),
+        ));
+    }
+    let path = path()?;
+    if path.exists() {
+        open(&read_sealed(&path)?, passphrase)?;
+    }
+    *lock(&PASSPHRASE) = Some(passphrase.to_string());
+    Ok(())
+}
+
+fn path() -> Result<PathBuf, ConductorError> {
+    Ok(config::master_config_path()?.with_file_name("secrets.enc"))
+}
+
+fn passphrase() -> Result<String, ConductorError> {
+    match std::env::var(PASSPHRASE_VAR) {
+        Ok(passphrase) if !passphrase.is_empty() => Ok(passphrase),
+        _ => lock(&PASSPHRASE).clone().ok_or_else(|| {
+            unavailable(
+                BACKEND,
+                format!(
+                    "it's locked; enter its passphrase in Settings or set {}",
+                    PASSPHRASE_VAR
+                ),
+            )
+        }),
+    }
+}
+
+fn key(passphrase: &str, salt: &[u8], iterations: u32) -> Result<LessSafeKey, ConductorError> {
+    let mut cached = lock(&DERIVED);
+    let bytes = match &*cached {
+        Some(d) if d.passphrase == passphrase && d.salt == salt && d.iterations == iterations => {
+            d.key
+        }
+        _ => {
+            let rounds = NonZeroU32::new(iterations).ok_or_else(damaged)?;
+            let mut key = [0u8; 32];
+            pbkdf2::derive(
+                pbkdf2::PBKDF2_HMAC_SHA256,
+                rounds,
+                salt,
+                passphrase.as_bytes(),
+                &mut key,
+            );
+            *cached = Some(Derived {
+                passphrase: passphrase.to_string(),
+                salt: salt.to_vec(),
+                iterations,
+                key,
+            });
+            key
+        }
+    };
+    UnboundKey::new(&CHACHA20_POLY1305, &bytes)
+        .map(LessSafeKey::new)
+        .map_err(|_| ConductorError::Internal("Failed to set up encryption".to_string()))
+}
+
+fn damaged() -> ConductorError {
+    ConductorError::SecretAccess("secrets.enc is damaged".to_string())
+}
+
+fn read_sealed(path: &std::path::Path) -> Result<Sealed, ConductorError> {
+    let sealed: Sealed = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    if sealed.version > FORMAT_VERSION {
+        return Err(ConductorError::SecretAccess(
+            "secrets.enc was written by a newer Conductor".to_string(),
+        ));
+    }
+    Ok(sealed)
+}
+
+fn open(sealed: &Sealed, passphrase: &str) -> Result<Contents, ConductorError> {
+    let salt = BASE64.decode(&sealed.salt).map_err(|_| damaged())?;
+    let nonce = BASE64.decode(&sealed.nonce).map_err(|_| damaged())?;
+    let mut data = BASE64.decode(&sealed.ciphertext).map_err(|_| damaged())?;
+    let nonce = Nonce::try_assume_unique_for_key(&nonce).map_err(|_| damaged())?;
+    let plain = key(passphrase, &salt, sealed.iterations)?
+        .open_in_place(nonce, Aad::empty(), &mut data)
+        .map_err(|_| {
+            ConductorError::SecretAccess("wrong passphrase for secrets.enc".to_string())
+        })?;
+    Ok(Contents {
+        secrets: serde_json::from_slice(plain)?,
+        salt,
+        iterations: sealed.iterations,
+    })
+}
+
+fn load() -> Result<Contents, ConductorError> {
+    let passphrase = passphrase()?;
+    let path = path()?;
+    if path.exists() {
+        return open(&read_sealed(&path)?, &passphrase);
+    }
+    let mut salt = vec![0u8; SALT_LEN];
+    SystemRandom::new()
+        .fill(&mut salt)
+        .map_err(|_| rng_failed())?;
+    Ok(Contents {
+        secrets: BTreeMap::new(),
+        salt,
+        iterations: ITERATIONS,
+    })
+}
+
+fn save(contents: &Contents) -> Result<(), ConductorError> {
+    let key = key(&passphrase()?, &contents.salt, contents.iterations)?;
+    let mut nonce = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| rng_failed())?;
+    let mut data = serde_json::to_vec(&contents.secrets)?;
+    key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut data)
+        .map_err(|_| ConductorError::Internal("Failed to encrypt secrets".to_string()))?;
+    let sealed = Sealed {
+        version: FORMAT_VERSION,
+        iterations: contents.iterations,
+        salt: BASE64.encode(&contents.salt),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(&data),
+    };
+    config::backup::atomic_write(&path()?, &serde_json::to_string_pretty(&sealed)?)?;
+    Ok(())
+}
+
+fn rng_failed() -> ConductorError {
+    ConductorError::Internal("No secure random numbers available".to_string())
+}
+
+impl SecretStore for EncryptedFile {
+    fn name(&self) -> &'static str {
+        BACKEND
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>, ConductorError> {
+        Ok(load()?.secrets.remove(key))
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), ConductorError> {
+        let _guard = lock(&WRITE);
+        let mut contents = load()?;
+        contents.secrets.insert(key.to_string(), value.to_string());
+        save(&contents)
+    }
+
+    fn delete(&self, key: &str) -> Result<(), ConductorError> {
+        let _guard = lock(&WRITE);
+        let mut contents = load()?;
+        if contents.secrets.remove(key).is_some() {
+            save(&contents)?;
+        }
+        Ok(())
+    }
+
+    fn list(&self) -> Result<Option<Vec<String>>, ConductorError> {
+        Ok(Some(load()?.secrets.into_keys().collect()))
+    }
+}
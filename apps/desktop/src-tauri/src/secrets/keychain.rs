@@ -0,0 +1,39 @@
+use super::SecretStore;
+use crate::errors::ConductorError;
+
+/// Service name every entry is filed under.
+const SERVICE: &str = "conductor";
+
+/// The OS keychain: Keychain on macOS, Credential Manager on Windows and
+/// the Secret Service on Linux.
+pub struct Keychain;
+
+impl SecretStore for Keychain {
+    fn name(&self) -> &'static str {
+        "System keychain"
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>, ConductorError> {
+        match keyring::Entry::new(SERVICE, key)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), ConductorError> {
+        keyring::Entry::new(SERVICE, key)?.set_password(value)?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> Result<(), ConductorError> {
+        match keyring::Entry::new(SERVICE, key)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn check(&self) -> Result<(), ConductorError> {
+        self.get("conductor:check").map(|_| ())
+    }
+}
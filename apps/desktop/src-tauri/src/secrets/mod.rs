@@ -0,0 +1,525 @@
+//! Where secrets are kept.
+//!
+//! Server secrets, OAuth tokens and secret variables are stored under the
+//! names the keychain has always used: `<server-id>:<key>` and
+//! `variable:<NAME>`, prefixed `profile:<id>/` for a profile's own copy.
+//! `settings.secret_backend` picks the [`SecretStore`] holding them, and
+//! [`migrate`] moves them from one store to another.
+//!
+//! Whatever the backend, a server env value can also reference a secret
+//! kept elsewhere by URI (`op://vault/item/field`, `pass://path`,
+//! `bw://item/field`, `vault://mount/path#field`). References are read when
+//! the server is synced or launched, so the value never touches the config.
+
+mod cli;
+mod file;
+mod keychain;
+mod vault;
+
+pub use file::unlock;
+
+use crate::config::{self, variables, AppSettings, McpConfig, SecretBackend};
+use crate::errors::ConductorError;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+
+pub trait SecretStore {
+    /// Shown in errors and the UI.
+    fn name(&self) -> &'static str;
+    fn get(&self, key: &str) -> Result<Option<String>, ConductorError>;
+    fn set(&self, key: &str, value: &str) -> Result<(), ConductorError>;
+    /// Succeeds when there is nothing to delete.
+    fn delete(&self, key: &str) -> Result<(), ConductorError>;
+    /// Every key held, for stores that can enumerate them.
+    fn list(&self) -> Result<Option<Vec<String>>, ConductorError> {
+        Ok(None)
+    }
+    /// Fails with the reason the store can't be used right now (not
+    /// installed, signed out, locked).
+    fn check(&self) -> Result<(), ConductorError> {
+        self.list().map(|_| ())
+    }
+}
+
+pub fn open(backend: SecretBackend, settings: &AppSettings) -> Box<dyn SecretStore> {
+    match backend {
+        SecretBackend::Keyring => Box::new(keychain::Keychain),
+        SecretBackend::EncryptedFile => Box::new(file::EncryptedFile),
+        SecretBackend::Pass => Box::new(cli::Pass),
+        SecretBackend::OnePassword => Box::new(cli::OnePassword {
+            vault: settings.onepassword_vault.clone(),
+        }),
+        SecretBackend::Bitwarden => Box::new(cli::Bitwarden),
+        SecretBackend::Vault => Box::new(vault::Vault::new(settings)),
+    }
+}
+
+/// The store chosen in settings.
+pub fn store() -> Box<dyn SecretStore> {
+    let settings = config::read_config()
+        .map(|cfg| cfg.settings)
+        .unwrap_or_default();
+    open(settings.secret_backend, &settings)
+}
+
+/// Reads `key` from the chosen store. A store that can't be reached (a
+/// locked keychain, a signed-out CLI, a Vault 403) is an error, not an unset
+/// secret, so callers never go on with empty credentials.
+pub fn get(key: &str) -> Result<Option<String>, ConductorError> {
+    store().get(key)
+}
+
+/// Every key the config says may be stored. The keychain can't list its
+/// entries, so migration goes by these.
+pub fn known_keys(cfg: &McpConfig) -> BTreeSet<String> {
+    let server_keys: Vec<String> = cfg
+        .servers
+        .iter()
+        .flat_map(|s| {
+            s.secret_env_keys
+                .iter()
+                .map(move |key| format!("{}:{}", s.id, key))
+        })
+        .collect();
+    let mut keys: BTreeSet<String> = cfg
+        .servers
+        .iter()
+        .flat_map(|s| crate::oauth::stored_keys(&s.id))
+        .chain(server_keys.iter().cloned())
+        .chain(
+            cfg.secret_variables
+                .iter()
+                .map(|n| variables::secret_username(n)),
+        )
+        .collect();
+    for profile in &cfg.profiles {
+        let own = profile
+            .secret_variables
+            .iter()
+            .map(|n| variables::secret_username(n));
+        keys.extend(
+            server_keys
+                .iter()
+                .cloned()
+                .chain(own)
+                .map(|key| profile.keychain_username(&key)),
+        );
+    }
+    keys
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Migration {
+    pub moved: usize,
+    /// Secrets that couldn't be copied. The backend is only switched when
+    /// there are none.
+    pub failed: Vec<MigrationFailure>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationFailure {
+    pub key: String,
+    pub error: String,
+}
+
+/// Copies every stored secret to `to`, switches `cfg` over to it and saves
+/// the config, then removes the copies left in the old store. Nothing is
+/// switched or removed when a secret fails to copy.
+pub fn migrate(cfg: &mut McpConfig, to: SecretBackend) -> Result<Migration, ConductorError> {
+    let from = open(cfg.settings.secret_backend, &cfg.settings);
+    let target = open(to, &cfg.settings);
+    target.check()?;
+
+    let mut keys = known_keys(cfg);
+    if let Some(listed) = from.list()? {
+        keys.extend(listed);
+    }
+    let mut moved = Vec::new();
+    let mut failed = Vec::new();
+    for key in keys {
+        let copied = from.get(&key).and_then(|value| match value {
+            Some(value) => target.set(&key, &value).map(|()| true),
+            None => Ok(false),
+        });
+        match copied {
+            Ok(true) => moved.push(key),
+            Ok(false) => {}
+            Err(e) => failed.push(MigrationFailure {
+                key,
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    if failed.is_empty() {
+        cfg.settings.secret_backend = to;
+        config::write_config(cfg)?;
+        for key in &moved {
+            if let Err(e) = from.delete(key) {
+                eprintln!(
+                    "Warning: Failed to remove '{}' from {}: {}",
+                    key,
+                    from.name(),
+                    e
+                );
+            }
+        }
+    }
+    Ok(Migration {
+        moved: moved.len(),
+        failed,
+    })
+}
+
+/// Whether `value` references a secret kept elsewhere.
+pub fn is_reference(value: &str) -> bool {
+    !value.contains(char::is_whitespace)
+        && value
+            .split_once("://")
+            .is_some_and(|(scheme, _)| ["op", "pass", "bw", "vault"].contains(&scheme))
+}
+
+/// The secret `value` references, or `None` when it isn't a reference.
+pub fn resolve_uri(value: &str, settings: &AppSettings) -> Option<Result<String, ConductorError>> {
+    if !is_reference(value) {
+        return None;
+    }
+    let (scheme, rest) = value.split_once("://")?;
+    Some(match scheme {
+        "op" => cli::OnePassword::read(value),
+        "pass" => cli::Pass::read(rest),
+        "bw" => cli::Bitwarden::read(rest),
+        "vault" => vault::Vault::new(settings).read(rest),
+        _ => return None,
+    })
+}
+
+/// Replaces env values that reference a secret with the secret. Returns the
+/// references that couldn't be read; those variables are removed rather
+/// than handing the reference to the server as its value.
+pub fn resolve_references(env: &mut HashMap<String, String>) -> Vec<String> {
+    if !env.values().any(|v| v.contains("://")) {
+        return Vec::new();
+    }
+    let settings = config::read_config()
+        .map(|cfg| cfg.settings)
+        .unwrap_or_default();
+    let mut unresolved = Vec::new();
+    env.retain(|key, value| match resolve_uri(value, &settings) {
+        None => true,
+        Some(Ok(secret)) => {
+            *value = secret;
+            true
+        }
+        Some(Err(e)) => {
+            unresolved.push(format!("{} ({}): {}", key, value, e));
+            false
+        }
+    });
+    unresolved.sort();
+    unresolved
+}
+
+fn unavailable(backend: &str, reason: impl Into<String>) -> ConductorError {
+    ConductorError::SecretBackendUnavailable {
+        backend: backend.to_string(),
+        reason: reason.into(),
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::test_support::EnvGuard;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::PathBuf;
+
+    /// Stand-ins for `pass`, `op` and `bw` keeping each entry as a file in
+    /// `$STUB_DIR/<tool>/`. `STUB_FAIL=1` makes writes fail.
+    const PASS: &str = r#"#!/bin/sh
+store="$PASSWORD_STORE_DIR"
+case "$1" in
+  version) echo "pass v1.7.4" ;;
+  show)
+    if [ -f "$store/$2.gpg" ]; then cat "$store/$2.gpg"
+    else echo "Error: $2 is not in the password store." >&2; exit 1; fi ;;
+  insert)
+    [ -n "$STUB_FAIL" ] && { echo "gpg: encryption failed" >&2; exit 1; }
+    mkdir -p "$(dirname "$store/$4.gpg")"; cat > "$store/$4.gpg" ;;
+  rm) rm -f "$store/$3.gpg" ;;
+esac
+"#;
+
+    const OP: &str = r#"#!/bin/sh
+dir="$STUB_DIR/op"; mkdir -p "$dir"
+file() { printf '%s' "$1" | tr '/:' '__'; }
+case "$1 $2" in
+  "read --no-newline")
+    [ "$3" = "op://Private/github/token" ] || { echo "[ERROR] isn't an item" >&2; exit 1; }
+    printf 'op-secret' ;;
+  "item get")
+    f=$(file "$3")
+    [ -f "$dir/$f" ] || { echo "[ERROR] \"$3\" isn't an item" >&2; exit 1; }
+    printf '{"id":"%s","fields":[{"id":"password","value":"%s"}]}' "$f" "$(cat "$dir/$f")" ;;
+  "item create")
+    [ -n "$STUB_FAIL" ] && { echo "[ERROR] vault is read-only" >&2; exit 1; }
+    json=$(cat)
+    title=$(printf '%s' "$json" | sed 's/.*"title":"\([^"]*\)".*/\1/')
+    printf '%s' "$json" | sed 's/.*"value":"\([^"]*\)".*/\1/' > "$dir/$(file "$title")"
+    printf '%s' "$title" > "$dir/$(file "$title").title" ;;
+  "item delete") rm -f "$dir/$3" "$dir/$3.title" ;;
+  "item list")
+    printf '['; sep=''
+    for t in "$dir"/*.title; do
+      [ -f "$t" ] && { printf '%s{"title":"%s"}' "$sep" "$(cat "$t")"; sep=','; }
+    done
+    printf ']' ;;
+esac
+"#;
+
+    const BW: &str = r#"#!/bin/sh
+dir="$STUB_DIR/bw"; mkdir -p "$dir"
+file() { printf '%s' "$1" | tr '/:' '__'; }
+item() {
+  printf '{"id":"%s","name":"%s","login":{"password":"%s"}}' \
+    "$1" "$(cat "$dir/$1.title")" "$(cat "$dir/$1")"
+}
+case "$1 $2" in
+  "list items")
+    printf '['; sep=''
+    for t in "$dir"/*.title; do
+      [ -f "$t" ] || continue
+      case "$(cat "$t")" in
+        *"$4"*) printf '%s' "$sep"; item "$(basename "$t" .title)"; sep=',' ;;
+      esac
+    done
+    printf ']' ;;
+  "get password")
+    [ -f "$dir/$3" ] || { echo "Not found." >&2; exit 1; }
+    cat "$dir/$3" ;;
+  "create item"|"edit item")
+    [ -n "$STUB_FAIL" ] && { echo "Vault is locked." >&2; exit 1; }
+    json=$(base64 -d)
+    name=$(printf '%s' "$json" | sed 's/.*"name":"\([^"]*\)".*/\1/')
+    f=${3:-$(file "$name")}
+    printf '%s' "$json" | sed 's/.*"password":"\([^"]*\)".*/\1/' > "$dir/$f"
+    printf '%s' "$name" > "$dir/$f.title" ;;
+  "delete item") rm -f "$dir/$3" "$dir/$3.title" ;;
+esac
+"#;
+
+    /// A scratch HOME with the stubs first on PATH, restored on drop.
+    struct Sandbox {
+        root: PathBuf,
+        env: EnvGuard,
+    }
+
+    impl Sandbox {
+        fn new() -> Self {
+            let mut env = EnvGuard::lock();
+            let root =
+                std::env::temp_dir().join(format!("conductor-secrets-{}", uuid::Uuid::new_v4()));
+            let bin = root.join("bin");
+            std::fs::create_dir_all(&bin).unwrap();
+            for (name, script) in [("pass", PASS), ("op", OP), ("bw", BW)] {
+                let path = bin.join(name);
+                std::fs::write(&path, script).unwrap();
+                std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+            }
+            let store = root.join("password-store");
+            std::fs::create_dir_all(&store).unwrap();
+            std::fs::write(store.join(".gpg-id"), "test@example.com\n").unwrap();
+
+            let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap_or_default());
+            let vars: [(&str, Option<std::ffi::OsString>); 6] = [
+                ("HOME", Some(root.join("home").into())),
+                ("PATH", Some(path.into())),
+                ("PASSWORD_STORE_DIR", Some(store.into())),
+                ("STUB_DIR", Some(root.clone().into())),
+                ("STUB_FAIL", None),
+                ("CONDUCTOR_SECRETS_PASSPHRASE", None),
+            ];
+            for (name, value) in vars {
+                env.set(name, value);
+            }
+            Self { root, env }
+        }
+
+        fn pass_entry(&self, path: &str) -> PathBuf {
+            self.root.join("password-store").join(format!("{}.gpg", path))
+        }
+    }
+
+    impl Drop for Sandbox {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.root);
+        }
+    }
+
+    fn round_trip(store: &dyn SecretStore) {
+        assert_eq!(store.get("server-1:API_KEY").unwrap(), None);
+        store.set("server-1:API_KEY", "first").unwrap();
+        store.set("server-1:API_KEY", "second").unwrap();
+        store.set("profile:work/variable:TOKEN", "work-token").unwrap();
+        assert_eq!(store.get("server-1:API_KEY").unwrap().as_deref(), Some("second"));
+        let mut keys = store.list().unwrap().unwrap();
+        keys.sort();
+        assert_eq!(keys, ["profile:work/variable:TOKEN", "server-1:API_KEY"]);
+        store.delete("server-1:API_KEY").unwrap();
+        store.delete("server-1:API_KEY").unwrap();
+        assert_eq!(store.get("server-1:API_KEY").unwrap(), None);
+    }
+
+    #[test]
+    fn pass_round_trip() {
+        let sandbox = Sandbox::new();
+        let store = open(SecretBackend::Pass, &AppSettings::default());
+        store.check().unwrap();
+        round_trip(&*store);
+        // The value reaches `pass` on stdin, exactly as given.
+        store.set("variable:MULTI", "line one\nline two").unwrap();
+        let entry = std::fs::read_to_string(sandbox.pass_entry("conductor/variable:MULTI"));
+        assert_eq!(entry.unwrap(), "line one\nline two");
+    }
+
+    #[test]
+    fn onepassword_round_trip() {
+        let _sandbox = Sandbox::new();
+        round_trip(&*open(SecretBackend::OnePassword, &AppSettings::default()));
+    }
+
+    #[test]
+    fn bitwarden_round_trip() {
+        let _sandbox = Sandbox::new();
+        round_trip(&*open(SecretBackend::Bitwarden, &AppSettings::default()));
+    }
+
+    #[test]
+    fn missing_tool_makes_the_backend_unavailable() {
+        let mut sandbox = Sandbox::new();
+        sandbox.env.set("PATH", Some("/nonexistent"));
+        let error = open(SecretBackend::Bitwarden, &AppSettings::default())
+            .check()
+            .unwrap_err();
+        assert!(matches!(error, ConductorError::SecretBackendUnavailable { .. }));
+    }
+
+    #[test]
+    fn get_reports_an_unreachable_store_instead_of_an_unset_secret() {
+        let mut sandbox = Sandbox::new();
+        let conductor = sandbox.root.join("home").join(".conductor");
+        std::fs::create_dir_all(&conductor).unwrap();
+        std::fs::write(
+            conductor.join("config.json"),
+            r#"{"servers": [], "settings": {"secretBackend": "pass"}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(get("server-1:API_KEY").unwrap(), None);
+        sandbox.env.set("PATH", Some("/nonexistent"));
+        assert!(matches!(
+            get("server-1:API_KEY"),
+            Err(ConductorError::SecretBackendUnavailable { .. })
+        ));
+    }
+
+    #[test]
+    fn resolve_uri_reads_each_scheme() {
+        let sandbox = Sandbox::new();
+        let settings = AppSettings::default();
+        let entry = sandbox.pass_entry("team/github");
+        std::fs::create_dir_all(entry.parent().unwrap()).unwrap();
+        std::fs::write(&entry, "pass-secret\nuser: me\n").unwrap();
+        std::fs::create_dir_all(sandbox.root.join("bw")).unwrap();
+        std::fs::write(sandbox.root.join("bw").join("github"), "bw-secret\n").unwrap();
+
+        let resolve = |value: &str| resolve_uri(value, &settings).map(|r| r.unwrap());
+        assert_eq!(resolve("pass://team/github").as_deref(), Some("pass-secret"));
+        assert_eq!(resolve("op://Private/github/token").as_deref(), Some("op-secret"));
+        assert_eq!(resolve("bw://github").as_deref(), Some("bw-secret"));
+
+        assert!(resolve_uri("plain value", &settings).is_none());
+        assert!(resolve_uri("https://example.com", &settings).is_none());
+        assert!(resolve_uri("pass://has a space", &settings).is_none());
+        assert!(resolve_uri("pass://team/missing", &settings).unwrap().is_err());
+        assert!(matches!(
+            resolve_uri("vault://no-path", &settings).unwrap(),
+            Err(ConductorError::InvalidInput(_))
+        ));
+    }
+
+    fn config_with_secrets(from: SecretBackend) -> McpConfig {
+        let mut cfg = McpConfig::default();
+        cfg.settings.secret_backend = from;
+        cfg.secret_variables = vec!["TOKEN".to_string(), "OTHER".to_string()];
+        let store = open(from, &cfg.settings);
+        store.set("variable:TOKEN", "t0ken").unwrap();
+        store.set("variable:OTHER", "0ther").unwrap();
+        cfg
+    }
+
+    fn saved_backend() -> Option<SecretBackend> {
+        let path = config::master_config_path().unwrap();
+        path.exists().then(|| config::read_config().unwrap().settings.secret_backend)
+    }
+
+    #[test]
+    fn migrate_moves_every_secret_and_switches() {
+        let _sandbox = Sandbox::new();
+        let mut cfg = config_with_secrets(SecretBackend::Pass);
+
+        let migration = migrate(&mut cfg, SecretBackend::OnePassword).unwrap();
+
+        assert_eq!(migration.moved, 2);
+        assert!(migration.failed.is_empty());
+        assert_eq!(cfg.settings.secret_backend, SecretBackend::OnePassword);
+        assert_eq!(saved_backend(), Some(SecretBackend::OnePassword));
+        let op = open(SecretBackend::OnePassword, &cfg.settings);
+        assert_eq!(op.get("variable:TOKEN").unwrap().as_deref(), Some("t0ken"));
+        let pass = open(SecretBackend::Pass, &cfg.settings);
+        assert_eq!(pass.list().unwrap(), Some(Vec::new()));
+    }
+
+    #[test]
+    fn migrate_switches_nothing_when_a_copy_fails() {
+        let mut sandbox = Sandbox::new();
+        let mut cfg = config_with_secrets(SecretBackend::Pass);
+        sandbox.env.set("STUB_FAIL", Some("1"));
+
+        let migration = migrate(&mut cfg, SecretBackend::Bitwarden).unwrap();
+
+        assert_eq!(migration.failed.len(), 2);
+        assert_eq!(cfg.settings.secret_backend, SecretBackend::Pass);
+        assert_eq!(saved_backend(), None);
+        let pass = open(SecretBackend::Pass, &cfg.settings);
+        assert_eq!(pass.get("variable:TOKEN").unwrap().as_deref(), Some("t0ken"));
+        assert_eq!(pass.get("variable:OTHER").unwrap().as_deref(), Some("0ther"));
+    }
+
+    #[test]
+    fn encrypted_file_round_trip_and_wrong_passphrase() {
+        let mut sandbox = Sandbox::new();
+        sandbox.env.set("CONDUCTOR_SECRETS_PASSPHRASE", Some("correct horse"));
+        let store = open(SecretBackend::EncryptedFile, &AppSettings::default());
+        store.set("variable:TOKEN", "t0ken").unwrap();
+        store.set("server-1:API_KEY", "k3y").unwrap();
+        store.delete("server-1:API_KEY").unwrap();
+        assert_eq!(store.get("variable:TOKEN").unwrap().as_deref(), Some("t0ken"));
+        assert_eq!(store.list().unwrap(), Some(vec!["variable:TOKEN".to_string()]));
+
+        let sealed = config::master_config_path().unwrap().with_file_name("secrets.enc");
+        let on_disk = std::fs::read_to_string(sealed).unwrap();
+        assert!(!on_disk.contains("t0ken") && !on_disk.contains("variable:TOKEN"));
+
+        sandbox.env.set("CONDUCTOR_SECRETS_PASSPHRASE", Some("wrong"));
+        match store.get("variable:TOKEN") {
+            Err(ConductorError::SecretAccess(message)) => {
+                assert!(message.contains("wrong passphrase"), "{}", message)
+            }
+            other => panic!("expected a wrong passphrase error, got {:?}", other),
+        }
+        assert!(unlock("wrong").is_err());
+    }
+}
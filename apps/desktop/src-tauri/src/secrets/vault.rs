@@ -0,0 +1,356 @@
+//! HashiCorp Vault's KV version 2 engine, over its HTTP API. Secrets are
+//! written to `<mount>/conductor/<key>` with the value in a `value` field.
+//! The token comes from `VAULT_TOKEN`, or `~/.vault-token` as left by
+//! `vault login`.
+
+use super::{unavailable, SecretStore};
+use crate::config::AppSettings;
+use crate::errors::ConductorError;
+use reqwest::{Method, StatusCode};
+use serde_json::{json, Value};
+use std::future::Future;
+use std::time::Duration;
+
+const DEFAULT_ADDRESS: &str = "http://127.0.0.1:8200";
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// Folder of the mount Conductor's secrets go in.
+const FOLDER: &str = "conductor";
+const BACKEND: &str = "Vault";
+
+pub struct Vault {
+    address: String,
+    mount: String,
+}
+
+impl Vault {
+    pub fn new(settings: &AppSettings) -> Self {
+        let address = settings
+            .vault_address
+            .clone()
+            .filter(|a| !a.trim().is_empty())
+            .or_else(|| std::env::var("VAULT_ADDR").ok())
+            .unwrap_or_else(|| DEFAULT_ADDRESS.to_string());
+        Self {
+            address: address.trim().trim_end_matches('/').to_string(),
+            mount: settings.vault_mount.trim_matches('/').to_string(),
+        }
+    }
+
+    fn token() -> Result<String, ConductorError> {
+        std::env::var("VAULT_TOKEN")
+            .ok()
+            .or_else(|| {
+                let home = dirs::home_dir()?;
+                std::fs::read_to_string(home.join(".vault-token")).ok()
+            })
+            .map(|token| token.trim().to_string())
+            .filter(|token| !token.is_empty())
+            .ok_or_else(|| unavailable(BACKEND, "no token; set VAULT_TOKEN or run `vault login`"))
+    }
+
+    /// Sends a request to `/v1/<path>`. `None` when Vault has nothing there.
+    fn request(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> Result<Option<Value>, ConductorError> {
+        let url = format!("{}/v1/{}", self.address, path);
+        let token = Self::token()?;
+        block_on(async move {
+            let client = reqwest::Client::builder().timeout(TIMEOUT).build()?;
+            let mut request = client.request(method, &url).header("X-Vault-Token", token);
+            if let Some(body) = body {
+                request = request.json(&body);
+            }
+            let response = request
+                .send()
+                .await
+                .map_err(|e| unavailable(BACKEND, format!("can't reach {}: {}", url, e)))?;
+            let status = response.status();
+            let text = response.text().await?;
+            match status {
+                StatusCode::NOT_FOUND => Ok(None),
+                StatusCode::FORBIDDEN => Err(unavailable(
+                    BACKEND,
+                    "permission denied; check the token and its policies",
+                )),
+                s if s.is_success() && text.trim().is_empty() => Ok(Some(Value::Null)),
+                s if s.is_success() => Ok(Some(serde_json::from_str(&text)?)),
+                s => Err(ConductorError::SecretAccess(format!(
+                    "Vault answered {}: {}",
+                    s,
+                    text.trim()
+                ))),
+            }
+        })
+    }
+
+    fn secret_path(&self, kind: &str, key: &str) -> String {
+        let key = key
+            .split('/')
+            .map(|segment| urlencoding::encode(segment).into_owned())
+            .collect::<Vec<_>>()
+            .join("/");
+        format!("{}/{}/{}/{}", self.mount, kind, FOLDER, key)
+    }
+
+    /// `vault://<mount>/<path>[#<field>]`; the field defaults to `value`.
+    pub fn read(&self, reference: &str) -> Result<String, ConductorError> {
+        let (path, field) = reference.split_once('#').unwrap_or((reference, "value"));
+        let (mount, path) = path.split_once('/').ok_or_else(|| {
+            ConductorError::InvalidInput(format!(
+                "'vault://{}' needs a mount and a path, like vault://secret/app#token",
+                reference
+            ))
+        })?;
+        let secret = self
+            .request(Method::GET, &format!("{}/data/{}", mount, path), None)?
+            .ok_or_else(|| {
+                ConductorError::SecretAccess(format!("Vault has no secret at {}/{}", mount, path))
+            })?;
+        match &secret["data"]["data"][field] {
+            Value::String(value) => Ok(value.clone()),
+            Value::Null => Err(ConductorError::SecretAccess(format!(
+                "{}/{} has no field '{}'",
+                mount, path, field
+            ))),
+            value => Ok(value.to_string()),
+        }
+    }
+
+    fn list_folder(&self, prefix: &str, keys: &mut Vec<String>) -> Result<(), ConductorError> {
+        let path = format!("{}/metadata/{}/{}", self.mount, FOLDER, prefix);
+        let list = Method::from_bytes(b"LIST").expect("LIST is a valid method");
+        let Some(listing) = self.request(list, &path, None)? else {
+            return Ok(());
+        };
+        let names: Vec<String> = listing["data"]["keys"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|name| name.as_str().map(str::to_string))
+            .collect();
+        for name in names {
+            if name.ends_with('/') {
+                self.list_folder(&format!("{}{}", prefix, name), keys)?;
+            } else {
+                keys.push(format!("{}{}", prefix, name));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs `future` to completion from synchronous code, which may itself be
+/// on the async runtime.
+fn block_on<T: Send>(
+    future: impl Future<Output = Result<T, ConductorError>> + Send,
+) -> Result<T, ConductorError> {
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()?
+                    .block_on(future)
+            })
+            .join()
+            .unwrap_or_else(|_| {
+                Err(ConductorError::Internal(
+                    "Vault request panicked".to_string(),
+                ))
+            })
+    })
+}
+
+impl SecretStore for Vault {
+    fn name(&self) -> &'static str {
+        BACKEND
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>, ConductorError> {
+        let secret = self.request(Method::GET, &self.secret_path("data", key), None)?;
+        Ok(secret.and_then(|s| s["data"]["data"]["value"].as_str().map(str::to_string)))
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), ConductorError> {
+        let body = json!({ "data": { "value": value } });
+        self.request(Method::POST, &self.secret_path("data", key), Some(body))?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> Result<(), ConductorError> {
+        // Through the metadata, so every version goes.
+        self.request(Method::DELETE, &self.secret_path("metadata", key), None)?;
+        Ok(())
+    }
+
+    fn list(&self) -> Result<Option<Vec<String>>, ConductorError> {
+        let mut keys = Vec::new();
+        self.list_folder("", &mut keys)?;
+        Ok(Some(keys))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::EnvGuard;
+    use axum::extract::State;
+    use axum::http::{HeaderMap, Uri};
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+
+    type Secrets = Arc<Mutex<BTreeMap<String, Value>>>;
+
+    /// A KV v2 engine mounted at `secret/`, answering like a dev server to
+    /// the token `root` and with 403 to anything else.
+    async fn stub(
+        State(secrets): State<Secrets>,
+        method: axum::http::Method,
+        uri: Uri,
+        headers: HeaderMap,
+        body: String,
+    ) -> (axum::http::StatusCode, String) {
+        use axum::http::StatusCode;
+        if headers.get("X-Vault-Token").and_then(|t| t.to_str().ok()) != Some("root") {
+            return (StatusCode::FORBIDDEN, r#"{"errors":["permission denied"]}"#.into());
+        }
+        let path = urlencoding::decode(uri.path()).unwrap().into_owned();
+        let mut secrets = secrets.lock().unwrap();
+        if let Some(key) = path.strip_prefix("/v1/secret/data/") {
+            return match method.as_str() {
+                "GET" => match secrets.get(key) {
+                    Some(data) => (StatusCode::OK, json!({"data": {"data": data}}).to_string()),
+                    None => (StatusCode::NOT_FOUND, r#"{"errors":[]}"#.into()),
+                },
+                "POST" => {
+                    let body: Value = serde_json::from_str(&body).unwrap();
+                    secrets.insert(key.to_string(), body["data"].clone());
+                    (StatusCode::OK, json!({"data": {"version": 1}}).to_string())
+                }
+                _ => (StatusCode::METHOD_NOT_ALLOWED, String::new()),
+            };
+        }
+        if let Some(key) = path.strip_prefix("/v1/secret/metadata/") {
+            return match method.as_str() {
+                "DELETE" => {
+                    secrets.remove(key);
+                    (StatusCode::NO_CONTENT, String::new())
+                }
+                "LIST" => {
+                    let mut names: Vec<String> = secrets
+                        .keys()
+                        .filter_map(|k| k.strip_prefix(key))
+                        .map(|rest| match rest.split_once('/') {
+                            Some((folder, _)) => format!("{}/", folder),
+                            None => rest.to_string(),
+                        })
+                        .collect();
+                    names.dedup();
+                    if names.is_empty() {
+                        (StatusCode::NOT_FOUND, r#"{"errors":[]}"#.into())
+                    } else {
+                        (StatusCode::OK, json!({"data": {"keys": names}}).to_string())
+                    }
+                }
+                _ => (StatusCode::METHOD_NOT_ALLOWED, String::new()),
+            };
+        }
+        (StatusCode::NOT_FOUND, r#"{"errors":[]}"#.into())
+    }
+
+    /// Starts the stub on a free port and returns a `Vault` pointed at it.
+    fn start(secrets: &Secrets) -> Vault {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        let app = axum::Router::new()
+            .fallback(stub)
+            .with_state(secrets.clone());
+        std::thread::spawn(move || {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(async move {
+                    let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+                    axum::serve(listener, app).await.unwrap();
+                });
+        });
+        Vault::new(&AppSettings {
+            vault_address: Some(address),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn round_trips_secrets_through_kv_v2() {
+        let mut env = EnvGuard::lock();
+        env.set("VAULT_TOKEN", Some("root"));
+        let vault = start(&Secrets::default());
+
+        vault.set("server-1:API_KEY", "first").unwrap();
+        vault.set("server-1:API_KEY", "second").unwrap();
+        vault.set("profile:work/variable:TOKEN", "work-token").unwrap();
+        assert_eq!(vault.get("server-1:API_KEY").unwrap().as_deref(), Some("second"));
+        assert_eq!(
+            vault.get("profile:work/variable:TOKEN").unwrap().as_deref(),
+            Some("work-token")
+        );
+        assert_eq!(vault.get("missing").unwrap(), None);
+
+        let mut keys = vault.list().unwrap().unwrap();
+        keys.sort();
+        assert_eq!(keys, ["profile:work/variable:TOKEN", "server-1:API_KEY"]);
+        assert!(vault.check().is_ok());
+
+        vault.delete("server-1:API_KEY").unwrap();
+        assert_eq!(vault.get("server-1:API_KEY").unwrap(), None);
+    }
+
+    #[test]
+    fn reads_vault_references() {
+        let mut env = EnvGuard::lock();
+        env.set("VAULT_TOKEN", Some("root"));
+        let secrets = Secrets::default();
+        secrets.lock().unwrap().insert(
+            "team/github".to_string(),
+            json!({"value": "default-field", "token": "ghp_x", "port": 8080}),
+        );
+        let vault = start(&secrets);
+
+        assert_eq!(vault.read("secret/team/github").unwrap(), "default-field");
+        assert_eq!(vault.read("secret/team/github#token").unwrap(), "ghp_x");
+        assert_eq!(vault.read("secret/team/github#port").unwrap(), "8080");
+        assert!(matches!(
+            vault.read("secret/team/github#missing"),
+            Err(ConductorError::SecretAccess(_))
+        ));
+        assert!(matches!(
+            vault.read("secret/team/gitlab"),
+            Err(ConductorError::SecretAccess(_))
+        ));
+    }
+
+    #[test]
+    fn a_rejected_token_is_an_error_not_a_missing_secret() {
+        let mut env = EnvGuard::lock();
+        env.set("VAULT_TOKEN", Some("expired"));
+        let vault = start(&Secrets::default());
+
+        assert!(matches!(
+            vault.get("server-1:API_KEY"),
+            Err(ConductorError::SecretBackendUnavailable { .. })
+        ));
+        assert!(vault.check().is_err());
+
+        env.set("VAULT_TOKEN", None::<&str>);
+        env.set("HOME", Some(std::env::temp_dir().join("conductor-no-vault-token")));
+        assert!(matches!(
+            vault.get("server-1:API_KEY"),
+            Err(ConductorError::SecretBackendUnavailable { .. })
+        ));
+    }
+}
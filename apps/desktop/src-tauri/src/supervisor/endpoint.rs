@@ -0,0 +1,169 @@
+//! Streamable HTTP endpoint of each managed server,
+//! `/managed/<server-id>` on the gateway's listener. Every client shares the
+//! one process: requests are forwarded with the upstream's own IDs, the
+//! handshake is answered from the process's `initialize` result, and server
+//! notifications go to every open stream.
+
+use crate::gateway::{self, upstream::Upstream, SESSION_HEADER};
+use axum::extract::Path;
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::Router;
+use serde_json::{json, Value};
+use std::convert::Infallible;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio::time::{sleep, Duration, Instant};
+
+/// How long a request waits for a server that is starting or restarting.
+const START_WAIT: Duration = Duration::from_secs(30);
+
+pub(crate) fn routes() -> Router {
+    Router::new().route(
+        "/managed/{server}",
+        post(handle_post).get(handle_get).delete(handle_delete),
+    )
+}
+
+async fn running(server_id: &str) -> Option<Arc<Upstream>> {
+    let deadline = Instant::now() + START_WAIT;
+    loop {
+        if let Some(upstream) = super::upstream(server_id) {
+            return Some(upstream);
+        }
+        if !super::known(server_id) || Instant::now() >= deadline {
+            return None;
+        }
+        sleep(Duration::from_millis(200)).await;
+    }
+}
+
+async fn handle_post(Path(server_id): Path<String>, headers: HeaderMap, body: String) -> Response {
+    if !gateway::allowed_origin(&headers) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    if !super::known(&server_id) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let message: Value = match serde_json::from_str(&body) {
+        Ok(message) => message,
+        Err(e) => {
+            let error = gateway::error_response(
+                &Value::Null,
+                gateway::rpc_error(-32700, &format!("Parse error: {}", e)),
+            );
+            return (StatusCode::BAD_REQUEST, axum::Json(error)).into_response();
+        }
+    };
+
+    let is_initialize = message.get("method").and_then(|m| m.as_str()) == Some("initialize");
+    let (replies, is_batch) = match message {
+        Value::Array(batch) => (
+            futures_util::future::join_all(
+                batch
+                    .into_iter()
+                    .map(|message| handle_message(&server_id, message)),
+            )
+            .await
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>(),
+            true,
+        ),
+        message => (
+            handle_message(&server_id, message)
+                .await
+                .into_iter()
+                .collect(),
+            false,
+        ),
+    };
+
+    if replies.is_empty() {
+        return StatusCode::ACCEPTED.into_response();
+    }
+    let body = if is_batch {
+        Value::Array(replies)
+    } else {
+        replies.into_iter().next().unwrap_or(Value::Null)
+    };
+    let mut response = axum::Json(body).into_response();
+    // Sessions only let clients follow the protocol; every client shares
+    // the process, so nothing is kept per session.
+    if is_initialize {
+        if let Ok(value) = HeaderValue::from_str(&uuid::Uuid::new_v4().to_string()) {
+            response.headers_mut().insert(SESSION_HEADER, value);
+        }
+    }
+    response
+}
+
+/// Answers one client message. Client notifications and responses aren't
+/// forwarded: the process was initialized once, by Conductor, and requests
+/// from the server are answered upstream.
+async fn handle_message(server_id: &str, message: Value) -> Option<Value> {
+    let method = message.get("method")?.as_str()?.to_string();
+    let id = message.get("id")?.clone();
+    let params = message.get("params").cloned().unwrap_or(json!({}));
+    if method == "ping" {
+        return Some(json!({ "jsonrpc": "2.0", "id": id, "result": {} }));
+    }
+
+    let Some(upstream) = running(server_id).await else {
+        return Some(gateway::error_response(
+            &id,
+            gateway::rpc_error(
+                -32000,
+                "The server is not running; Conductor is restarting it",
+            ),
+        ));
+    };
+    // The version the process agreed with Conductor is the one it speaks.
+    if method == "initialize" {
+        let result = upstream.initialize_result().cloned().unwrap_or(json!({}));
+        return Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }));
+    }
+    Some(
+        match upstream
+            .request(&method, params, gateway::CALL_TIMEOUT)
+            .await
+        {
+            Ok(mut response) => {
+                response["id"] = id;
+                response
+            }
+            Err(e) => gateway::error_response(&id, gateway::rpc_error(-32603, &format!("{:#}", e))),
+        },
+    )
+}
+
+/// Server-to-client stream carrying the server's notifications.
+async fn handle_get(Path(server_id): Path<String>, headers: HeaderMap) -> Response {
+    if !gateway::allowed_origin(&headers) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    let Some(rx) = super::subscribe(&server_id) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let stream = futures_util::stream::unfold(rx, |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(message) => {
+                    let event = Event::default().event("message").data(message.to_string());
+                    return Some((Ok::<_, Infallible>(event), rx));
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+async fn handle_delete() -> StatusCode {
+    StatusCode::OK
+}
@@ -0,0 +1,112 @@
+//! Output logs of managed servers: `~/.conductor/logs/<server-id>.log`,
+//! rotated by size. Each line is stamped and tagged with where it came
+//! from (`stderr`, `stdout` or `conductor` for lifecycle messages).
+
+use crate::config;
+use anyhow::{Context, Result};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+const MAX_LOG_BYTES: u64 = 2 * 1024 * 1024;
+/// Rotated files kept besides the live one: `<id>.1.log` is the newest.
+const ROTATED_LOGS: usize = 2;
+
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+fn log_path(server_id: &str, generation: usize) -> Result<PathBuf> {
+    let stem: String = server_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let name = match generation {
+        0 => format!("{}.log", stem),
+        n => format!("{}.{}.log", stem, n),
+    };
+    Ok(config::master_config_path()?
+        .with_file_name("logs")
+        .join(name))
+}
+
+/// Appends one line to a server's log and emits it as
+/// `managed-server-log` for live tails. Failures are dropped; a full disk
+/// shouldn't take the server down.
+pub fn log_line(server_id: &str, source: &str, text: &str) {
+    let line = format!(
+        "{} [{}] {}",
+        chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        source,
+        text
+    );
+    if let Err(e) = append(server_id, &line) {
+        eprintln!("Supervisor: log for {}: {:#}", server_id, e);
+    }
+    super::emit(
+        "managed-server-log",
+        serde_json::json!({ "serverId": server_id, "line": line }),
+    );
+}
+
+fn append(server_id: &str, line: &str) -> Result<()> {
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = log_path(server_id, 0)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if std::fs::metadata(&path).is_ok_and(|m| m.len() + line.len() as u64 >= MAX_LOG_BYTES) {
+        for generation in (1..=ROTATED_LOGS).rev() {
+            let from = log_path(server_id, generation - 1)?;
+            if from.exists() {
+                std::fs::rename(&from, log_path(server_id, generation)?)?;
+            }
+        }
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    writeln!(file, "{}", line)?;
+    Ok(())
+}
+
+/// The last `lines` lines of a server's log, oldest first, reaching into
+/// rotated files when the live one is short.
+pub fn tail(server_id: &str, lines: usize) -> Result<Vec<String>> {
+    let mut tail: Vec<String> = Vec::new();
+    for generation in 0..=ROTATED_LOGS {
+        if tail.len() >= lines {
+            break;
+        }
+        let Ok(file) = std::fs::File::open(log_path(server_id, generation)?) else {
+            continue;
+        };
+        let mut older: Vec<String> = BufReader::new(file)
+            .lines()
+            .map_while(|line| line.ok())
+            .collect();
+        let skip = older.len().saturating_sub(lines - tail.len());
+        older.drain(..skip);
+        older.append(&mut tail);
+        tail = older;
+    }
+    Ok(tail)
+}
+
+pub fn clear(server_id: &str) -> Result<()> {
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    for generation in 0..=ROTATED_LOGS {
+        let path = log_path(server_id, generation)?;
+        if path.exists() {
+            std::fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+        }
+    }
+    Ok(())
+}
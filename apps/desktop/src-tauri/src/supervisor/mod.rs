@@ -0,0 +1,455 @@
+//! Supervisor for managed stdio servers. Instead of every client launching
+//! its own copy, Conductor runs one process per managed server, restarts it
+//! with backoff when it exits, logs its output (see `logs`) and samples its
+//! CPU and memory. Clients reach it through `conductor bridge` and the
+//! endpoint in `endpoint`; the gateway shares the same process.
+
+pub mod endpoint;
+mod logs;
+
+pub use logs::{clear as clear_logs, log_line, tail as tail_logs};
+
+use crate::config::{self, serializer, McpServerConfig, TransportType};
+use crate::gateway::{self, upstream::Upstream};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, LazyLock, Mutex, OnceLock};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{broadcast, Notify};
+use tokio::time::{sleep, Duration, Instant};
+
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+/// A process that stayed up this long starts the backoff over.
+const STABLE_AFTER: Duration = Duration::from_secs(60);
+const SAMPLE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ProcessState {
+    Starting,
+    Running,
+    /// Waiting to restart after an exit.
+    Backoff,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessExit {
+    pub at: String,
+    /// `None` when the process was killed by a signal or never started.
+    pub code: Option<i32>,
+    /// Why the server failed to start, if it did.
+    pub error: Option<String>,
+    pub uptime_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagedServerStatus {
+    pub server_id: String,
+    pub name: String,
+    pub state: ProcessState,
+    /// The endpoint clients reach through the bridge.
+    pub url: String,
+    pub pid: Option<u32>,
+    pub started_at: Option<String>,
+    pub uptime_secs: Option<u64>,
+    /// Restarts after exits since Conductor started.
+    pub restarts: u32,
+    pub last_exit: Option<ProcessExit>,
+    pub next_restart_at: Option<String>,
+    /// Percent of one core, summed over the server's process tree.
+    pub cpu_percent: Option<f32>,
+    pub memory_bytes: Option<u64>,
+}
+
+/// Payload of the `managed-server-crashed` event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashEvent {
+    pub server_id: String,
+    pub name: String,
+    pub exit: ProcessExit,
+    pub restarts: u32,
+    pub restart_in_secs: u64,
+}
+
+struct Managed {
+    name: String,
+    fingerprint: String,
+    task: JoinHandle<()>,
+    restart: Arc<Notify>,
+    upstream: Option<Arc<Upstream>>,
+    state: ProcessState,
+    started_at: Option<(Instant, chrono::DateTime<chrono::Utc>)>,
+    restarts: u32,
+    last_exit: Option<ProcessExit>,
+    next_restart_at: Option<String>,
+    /// `(cpu_percent, memory_bytes)` from the last sample.
+    usage: Option<(f32, u64)>,
+    /// Server notifications, relayed to connected clients.
+    notifications: broadcast::Sender<Value>,
+}
+
+impl Drop for Managed {
+    // Stopping the task drops its upstream, which kills the process.
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+static PROCESSES: LazyLock<Mutex<HashMap<String, Managed>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static CONFIG_CHANGED: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn emit<S: Serialize + Clone>(event: &str, payload: S) {
+    if let Some(app) = APP.get() {
+        let _ = app.emit(event, payload);
+    }
+}
+
+/// Whether the supervisor runs `server`.
+pub fn is_managed(server: &McpServerConfig) -> bool {
+    server.enabled && server.managed && server.transport == TransportType::Stdio
+}
+
+/// Where clients reach a managed server; served on the gateway port.
+pub fn endpoint_url(port: u16, server_id: &str) -> String {
+    format!("http://127.0.0.1:{}/managed/{}", port, server_id)
+}
+
+/// Starts the supervisor. It follows the managed servers in the config for
+/// the lifetime of the app.
+pub fn spawn(app: AppHandle) {
+    let _ = APP.set(app);
+    tauri::async_runtime::spawn(async {
+        loop {
+            if let Ok(cfg) = config::read_config() {
+                reconcile(&cfg.servers);
+            }
+            CONFIG_CHANGED.notified().await;
+        }
+    });
+    std::thread::spawn(sample_usage);
+}
+
+/// Called after every master config write.
+pub fn config_changed() {
+    CONFIG_CHANGED.notify_one();
+}
+
+/// Starts and stops processes so they match the managed servers. A changed
+/// launch config restarts the process; toggling inspection doesn't.
+fn reconcile(servers: &[McpServerConfig]) {
+    let desired: Vec<&McpServerConfig> = servers.iter().filter(|s| is_managed(s)).collect();
+    let mut processes = lock(&PROCESSES);
+    processes.retain(|id, managed| {
+        desired
+            .iter()
+            .any(|s| &s.id == id && gateway::fingerprint(s) == managed.fingerprint)
+    });
+    for server in desired {
+        match processes.get(&server.id) {
+            Some(managed) => {
+                if let Some(upstream) = &managed.upstream {
+                    upstream.recorder.set_enabled(server.inspect);
+                }
+            }
+            None => {
+                let fingerprint = gateway::fingerprint(server);
+                let restart = Arc::new(Notify::new());
+                let task = tauri::async_runtime::spawn(run(
+                    server.clone(),
+                    fingerprint.clone(),
+                    restart.clone(),
+                ));
+                processes.insert(
+                    server.id.clone(),
+                    Managed {
+                        name: server.name.clone(),
+                        fingerprint,
+                        task,
+                        restart,
+                        upstream: None,
+                        state: ProcessState::Starting,
+                        started_at: None,
+                        restarts: 0,
+                        last_exit: None,
+                        next_restart_at: None,
+                        usage: None,
+                        notifications: broadcast::channel(64).0,
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// Runs `f` on the server's entry if it still belongs to this launch config;
+/// a replaced process's task may still be winding down.
+fn update<T>(server_id: &str, fingerprint: &str, f: impl FnOnce(&mut Managed) -> T) -> Option<T> {
+    lock(&PROCESSES)
+        .get_mut(server_id)
+        .filter(|m| m.fingerprint == fingerprint)
+        .map(f)
+}
+
+fn backoff(failures: u32) -> Duration {
+    MIN_BACKOFF
+        .saturating_mul(1 << failures.min(16))
+        .min(MAX_BACKOFF)
+}
+
+/// Keeps one server running until the task is aborted.
+async fn run(server: McpServerConfig, fingerprint: String, restart: Arc<Notify>) {
+    let id = server.id.as_str();
+    // Consecutive short-lived runs, for the backoff.
+    let mut failures: u32 = 0;
+    loop {
+        update(id, &fingerprint, |m| {
+            m.state = ProcessState::Starting;
+            m.next_restart_at = None;
+        });
+        let mut launch = server.clone();
+        if let Err(e) = crate::commands::sync::inject_secrets(&mut launch).await {
+            log_line(id, "conductor", &format!("Secrets: {}", e));
+        }
+
+        let started = Instant::now();
+        let exit = match Upstream::start_managed(&launch, fingerprint.clone()).await {
+            Ok(upstream) => {
+                let pid = upstream.pid.map_or("?".to_string(), |p| p.to_string());
+                log_line(id, "conductor", &format!("Started (pid {})", pid));
+                update(id, &fingerprint, |m| {
+                    m.upstream = Some(upstream.clone());
+                    m.state = ProcessState::Running;
+                    m.started_at = Some((started, chrono::Utc::now()));
+                });
+                gateway::announce_all_changed();
+
+                let status = tokio::select! {
+                    status = upstream.wait() => Some(status),
+                    _ = restart.notified() => None,
+                };
+                update(id, &fingerprint, |m| {
+                    m.upstream = None;
+                    m.started_at = None;
+                    m.usage = None;
+                });
+                let Some(status) = status else {
+                    upstream.kill().await;
+                    log_line(id, "conductor", "Restarting on request");
+                    failures = 0;
+                    continue;
+                };
+                ProcessExit {
+                    at: chrono::Utc::now().to_rfc3339(),
+                    code: status.and_then(|s| s.code()),
+                    error: None,
+                    uptime_secs: started.elapsed().as_secs(),
+                }
+            }
+            Err(e) => ProcessExit {
+                at: chrono::Utc::now().to_rfc3339(),
+                code: None,
+                error: Some(format!("{:#}", e)),
+                uptime_secs: 0,
+            },
+        };
+        gateway::announce_all_changed();
+
+        if started.elapsed() >= STABLE_AFTER {
+            failures = 0;
+        }
+        let delay = backoff(failures);
+        failures += 1;
+        let reason = match (&exit.error, exit.code) {
+            (Some(error), _) => format!("Failed to start: {}", error),
+            (None, Some(code)) => format!("Exited with code {}", code),
+            (None, None) => "Killed by a signal".to_string(),
+        };
+        log_line(
+            id,
+            "conductor",
+            &format!("{}; restarting in {}s", reason, delay.as_secs()),
+        );
+        let restarts = update(id, &fingerprint, |m| {
+            m.restarts += 1;
+            m.state = ProcessState::Backoff;
+            m.last_exit = Some(exit.clone());
+            m.next_restart_at = Some(
+                (chrono::Utc::now() + chrono::Duration::from_std(delay).unwrap_or_default())
+                    .to_rfc3339(),
+            );
+            m.restarts
+        })
+        .unwrap_or_default();
+        emit(
+            "managed-server-crashed",
+            CrashEvent {
+                server_id: server.id.clone(),
+                name: server.name.clone(),
+                exit,
+                restarts,
+                restart_in_secs: delay.as_secs(),
+            },
+        );
+
+        tokio::select! {
+            _ = sleep(delay) => {}
+            _ = restart.notified() => failures = 0,
+        }
+    }
+}
+
+/// Restarts a managed server now, skipping any backoff.
+pub fn restart(server_id: &str) -> bool {
+    match lock(&PROCESSES).get(server_id) {
+        Some(managed) => {
+            managed.restart.notify_one();
+            true
+        }
+        None => false,
+    }
+}
+
+/// The running process of a managed server.
+pub fn upstream(server_id: &str) -> Option<Arc<Upstream>> {
+    lock(&PROCESSES).get(server_id)?.upstream.clone()
+}
+
+/// Every running managed process.
+pub fn upstreams() -> Vec<Arc<Upstream>> {
+    lock(&PROCESSES)
+        .values()
+        .filter_map(|m| m.upstream.clone())
+        .collect()
+}
+
+/// Relays a notification from a managed server to its connected clients.
+pub(crate) fn forward_notification(server_id: &str, message: &Value) {
+    if let Some(managed) = lock(&PROCESSES).get(server_id) {
+        let _ = managed.notifications.send(message.clone());
+    }
+}
+
+fn known(server_id: &str) -> bool {
+    lock(&PROCESSES).contains_key(server_id)
+}
+
+fn subscribe(server_id: &str) -> Option<broadcast::Receiver<Value>> {
+    Some(lock(&PROCESSES).get(server_id)?.notifications.subscribe())
+}
+
+pub fn status() -> Vec<ManagedServerStatus> {
+    let port = config::read_config()
+        .map(|c| c.settings.gateway_port)
+        .unwrap_or_default();
+    let mut statuses: Vec<ManagedServerStatus> = lock(&PROCESSES)
+        .iter()
+        .map(|(id, m)| ManagedServerStatus {
+            server_id: id.clone(),
+            name: m.name.clone(),
+            state: m.state,
+            url: endpoint_url(port, id),
+            pid: m.upstream.as_ref().and_then(|u| u.pid),
+            started_at: m.started_at.map(|(_, at)| at.to_rfc3339()),
+            uptime_secs: m.started_at.map(|(started, _)| started.elapsed().as_secs()),
+            restarts: m.restarts,
+            last_exit: m.last_exit.clone(),
+            next_restart_at: m.next_restart_at.clone(),
+            cpu_percent: m.usage.map(|(cpu, _)| cpu),
+            memory_bytes: m.usage.map(|(_, memory)| memory),
+        })
+        .collect();
+    statuses.sort_by(|a, b| a.name.cmp(&b.name));
+    statuses
+}
+
+/// Samples CPU and memory of every running process tree. Servers started
+/// through `npx`, `uvx` and the like do their work in child processes.
+fn sample_usage() {
+    use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+    let mut system = System::new();
+    loop {
+        std::thread::sleep(SAMPLE_INTERVAL);
+        let pids: Vec<(String, u32)> = lock(&PROCESSES)
+            .iter()
+            .filter_map(|(id, m)| Some((id.clone(), m.upstream.as_ref()?.pid?)))
+            .collect();
+        if pids.is_empty() {
+            continue;
+        }
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing().with_cpu().with_memory(),
+        );
+        let mut children: HashMap<Pid, Vec<Pid>> = HashMap::new();
+        for (pid, process) in system.processes() {
+            if let Some(parent) = process.parent() {
+                children.entry(parent).or_default().push(*pid);
+            }
+        }
+
+        let mut usage: HashMap<String, (f32, u64)> = HashMap::new();
+        for (id, pid) in pids {
+            let (mut cpu, mut memory) = (0.0, 0);
+            let mut seen = HashSet::new();
+            let mut stack = vec![Pid::from_u32(pid)];
+            while let Some(pid) = stack.pop() {
+                if !seen.insert(pid) {
+                    continue;
+                }
+                if let Some(process) = system.process(pid) {
+                    cpu += process.cpu_usage();
+                    memory += process.memory();
+                }
+                stack.extend(children.get(&pid).into_iter().flatten());
+            }
+            usage.insert(id, (cpu, memory));
+        }
+
+        for (id, managed) in lock(&PROCESSES).iter_mut() {
+            if managed.upstream.is_some() {
+                managed.usage = usage.remove(id);
+            }
+        }
+    }
+}
+
+/// Points managed servers' entries at their endpoint through `conductor
+/// bridge`. Runs on the copies about to be written, after secrets are
+/// injected: the env stays with Conductor, which runs the process.
+pub fn apply_managed(servers: &mut [McpServerConfig], port: u16, warnings: &mut Vec<String>) {
+    for server in servers.iter_mut().filter(|s| is_managed(s)) {
+        let mut remote = server.clone();
+        remote.transport = TransportType::StreamableHttp;
+        remote.url = Some(endpoint_url(port, &server.id));
+        match serializer::bridge_command(&remote) {
+            Ok((program, args)) => {
+                server.command = Some(program);
+                server.args = args;
+                server.env.clear();
+                server.secret_env_keys.clear();
+                // The supervisor records its traffic itself.
+                server.inspect = false;
+            }
+            Err(e) => warnings.push(format!(
+                "Server '{}': written unmanaged, the bridge is unavailable: {}",
+                server.name, e
+            )),
+        }
+    }
+}
@@ -0,0 +1,46 @@
+//! Helpers shared by unit tests across modules.
+
+use std::ffi::{OsStr, OsString};
+use std::sync::{Mutex, MutexGuard};
+
+/// Environment variables are process-wide and the test runner is parallel,
+/// so every test that changes one holds this lock for its whole run.
+static ENV: Mutex<()> = Mutex::new(());
+
+/// Holds the environment lock and puts back every variable it changed when
+/// dropped.
+pub(crate) struct EnvGuard {
+    saved: Vec<(&'static str, Option<OsString>)>,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl EnvGuard {
+    pub(crate) fn lock() -> Self {
+        Self {
+            saved: Vec::new(),
+            _lock: ENV.lock().unwrap_or_else(|e| e.into_inner()),
+        }
+    }
+
+    /// Sets (`Some`) or removes (`None`) a variable until the guard drops.
+    pub(crate) fn set(&mut self, name: &'static str, value: Option<impl AsRef<OsStr>>) {
+        if !self.saved.iter().any(|(saved, _)| *saved == name) {
+            self.saved.push((name, std::env::var_os(name)));
+        }
+        match value {
+            Some(value) => std::env::set_var(name, value),
+            None => std::env::remove_var(name),
+        }
+    }
+}
+
+impl Drop for EnvGuard {
+    fn drop(&mut self) {
+        for (name, value) in self.saved.drain(..).rev() {
+            match value {
+                Some(value) => std::env::set_var(name, value),
+                None => std::env::remove_var(name),
+            }
+        }
+    }
+}
@@ -0,0 +1,328 @@
+//! Tool discovery and per-client tool filters. Each server's tools are
+//! probed with `tools/list` and cached in `~/.conductor/tools.json`. Filters
+//! are written into a client's own config where it can express them and
+//! enforced by `conductor filter` (see `proxy`) everywhere else.
+
+pub mod proxy;
+
+use crate::clients::{ClientAdapter, NativeToolFilter};
+use crate::config::{self, serializer, McpServerConfig, ToolFilter};
+use crate::gateway;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolInfo {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// The tools one server exposed the last time it was probed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolCatalog {
+    pub server_id: String,
+    /// Launch fingerprint at discovery; a config change makes the catalog stale.
+    pub fingerprint: String,
+    pub discovered_at: String,
+    pub tools: Vec<ToolInfo>,
+}
+
+// Serializes read-modify-write of the cache file.
+static CACHE_LOCK: Mutex<()> = Mutex::new(());
+
+fn cache_path() -> Result<PathBuf> {
+    Ok(config::master_config_path()?.with_file_name("tools.json"))
+}
+
+fn read_cache() -> HashMap<String, ToolCatalog> {
+    cache_path()
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// The cached catalog for `server`, unless its launch config changed since.
+pub fn cached(server: &McpServerConfig) -> Option<ToolCatalog> {
+    cached_all(std::slice::from_ref(server)).remove(&server.id)
+}
+
+/// Fresh cached catalogs of `servers`, keyed by server ID. Must be given the
+/// servers as configured, before secrets are injected.
+pub fn cached_all(servers: &[McpServerConfig]) -> HashMap<String, ToolCatalog> {
+    let mut cache = read_cache();
+    servers
+        .iter()
+        .filter_map(|server| {
+            let catalog = cache.remove(&server.id)?;
+            (catalog.fingerprint == gateway::fingerprint(server))
+                .then(|| (server.id.clone(), catalog))
+        })
+        .collect()
+}
+
+/// Caches the raw `tools/list` items of a server. Unchanged catalogs aren't
+/// rewritten, so the gateway can record on every list.
+pub fn record(server_id: &str, fingerprint: &str, items: &[Value]) -> Result<ToolCatalog> {
+    let tools: Vec<ToolInfo> = items
+        .iter()
+        .filter_map(|item| {
+            Some(ToolInfo {
+                name: item.get("name")?.as_str()?.to_string(),
+                description: item
+                    .get("description")
+                    .and_then(|d| d.as_str())
+                    .map(|d| d.to_string()),
+            })
+        })
+        .collect();
+
+    let _guard = CACHE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut cache = read_cache();
+    if let Some(existing) = cache
+        .get(server_id)
+        .filter(|c| c.fingerprint == fingerprint && c.tools == tools)
+    {
+        return Ok(existing.clone());
+    }
+    let catalog = ToolCatalog {
+        server_id: server_id.to_string(),
+        fingerprint: fingerprint.to_string(),
+        discovered_at: chrono::Utc::now().to_rfc3339(),
+        tools,
+    };
+    cache.insert(server_id.to_string(), catalog.clone());
+
+    let path = cache_path()?;
+    let temp = path.with_extension("json.tmp");
+    std::fs::write(&temp, serde_json::to_string_pretty(&cache)?)
+        .with_context(|| format!("Failed to write {}", temp.display()))?;
+    std::fs::rename(&temp, &path).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(catalog)
+}
+
+/// Drops a deleted server's catalog.
+pub fn forget(server_id: &str) {
+    let _guard = CACHE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut cache = read_cache();
+    if cache.remove(server_id).is_some() {
+        if let (Ok(path), Ok(content)) = (cache_path(), serde_json::to_string_pretty(&cache)) {
+            let _ = std::fs::write(path, content);
+        }
+    }
+}
+
+/// Probes `server` with `tools/list`. Reuses the gateway's upstream when it
+/// is running the same config; otherwise starts the server just for the probe.
+pub async fn discover(server: &McpServerConfig) -> Result<ToolCatalog> {
+    let fingerprint = gateway::fingerprint(server);
+    let upstream = gateway::upstream_for(server).await?;
+    let items = if upstream.supports("tools") {
+        gateway::collect_pages(&upstream, "tools/list", "tools").await?
+    } else {
+        Vec::new()
+    };
+    record(&server.id, &fingerprint, &items)
+}
+
+/// Applies each server's tool filter for `adapter`'s client: natively when
+/// the client can express it, else by wrapping the entry in `conductor filter`.
+/// Runs on the copies about to be written, after secrets are injected;
+/// `catalogs` come from `cached_all` on the configured servers.
+pub fn apply_filters(
+    adapter: &dyn ClientAdapter,
+    servers: &mut [McpServerConfig],
+    catalogs: &HashMap<String, ToolCatalog>,
+    warnings: &mut Vec<String>,
+) {
+    let native = adapter.native_tool_filter();
+    for server in servers {
+        let Some(filter) = server.tool_filter(adapter.id()).cloned() else {
+            continue;
+        };
+        let tools = catalogs.get(&server.id).map(|c| c.tools.as_slice());
+
+        let applied = match &native {
+            NativeToolFilter::EnabledDisabledLists => match enabled_disabled(&filter, tools) {
+                Some((enabled, disabled)) => {
+                    let options = server.codex.get_or_insert_with(Default::default);
+                    options.enabled_tools = enabled;
+                    options.disabled_tools = disabled;
+                    true
+                }
+                None => false,
+            },
+            NativeToolFilter::DisabledField(field) => match hidden(&filter, tools) {
+                Some(hidden) => {
+                    server
+                        .extra
+                        .entry(adapter.id().to_string())
+                        .or_default()
+                        .insert(field.clone(), serde_json::json!(hidden));
+                    true
+                }
+                None => false,
+            },
+            NativeToolFilter::Unsupported => false,
+        };
+
+        if !applied {
+            if let Err(e) = wrap_in_proxy(server, adapter.id()) {
+                warnings.push(format!(
+                    "Server '{}': tool filter not applied: {:#}",
+                    server.name, e
+                ));
+            }
+        }
+    }
+}
+
+fn is_literal(pattern: &str) -> bool {
+    !pattern.contains(['*', '?', '['])
+}
+
+/// Codex `(enabled_tools, disabled_tools)`.
+type ToolLists = (Option<Vec<String>>, Option<Vec<String>>);
+
+/// Globs need a discovered catalog to expand; plain names don't.
+fn enabled_disabled(filter: &ToolFilter, tools: Option<&[ToolInfo]>) -> Option<ToolLists> {
+    let non_empty = |names: Vec<String>| (!names.is_empty()).then_some(names);
+    match tools {
+        Some(tools) => {
+            let (visible, hidden): (Vec<String>, Vec<String>) = tools
+                .iter()
+                .map(|t| t.name.clone())
+                .partition(|name| filter.permits(name));
+            Some(if filter.allow.is_empty() {
+                (None, non_empty(hidden))
+            } else {
+                (Some(visible), None)
+            })
+        }
+        None if filter
+            .allow
+            .iter()
+            .chain(&filter.deny)
+            .all(|p| is_literal(p)) =>
+        {
+            let enabled = (!filter.allow.is_empty()).then(|| {
+                filter
+                    .allow
+                    .iter()
+                    .filter(|name| !filter.deny.contains(name))
+                    .cloned()
+                    .collect()
+            });
+            Some((enabled, non_empty(filter.deny.clone())))
+        }
+        None => None,
+    }
+}
+
+/// Every tool the filter hides, for clients that only take a deny list.
+fn hidden(filter: &ToolFilter, tools: Option<&[ToolInfo]>) -> Option<Vec<String>> {
+    match tools {
+        Some(tools) => Some(
+            tools
+                .iter()
+                .map(|t| t.name.clone())
+                .filter(|name| !filter.permits(name))
+                .collect(),
+        ),
+        None if filter.allow.is_empty() && filter.deny.iter().all(|p| is_literal(p)) => {
+            Some(filter.deny.clone())
+        }
+        None => None,
+    }
+}
+
+/// Rewrites `server` as `conductor filter --server <name> --client <id> --
+/// <original command>`.
+fn wrap_in_proxy(server: &mut McpServerConfig, client_id: &str) -> Result<()> {
+    let args = [
+        "filter".to_string(),
+        "--server".to_string(),
+        server.name.clone(),
+        "--client".to_string(),
+        client_id.to_string(),
+    ];
+    serializer::wrap_with_conductor(server, &args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(allow: &[&str], deny: &[&str]) -> ToolFilter {
+        ToolFilter {
+            allow: allow.iter().map(|s| s.to_string()).collect(),
+            deny: deny.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn tools(names: &[&str]) -> Vec<ToolInfo> {
+        names
+            .iter()
+            .map(|name| ToolInfo {
+                name: name.to_string(),
+                description: None,
+            })
+            .collect()
+    }
+
+    fn names(list: &[&str]) -> Option<Vec<String>> {
+        Some(list.iter().map(|s| s.to_string()).collect())
+    }
+
+    #[test]
+    fn codex_lists_expand_globs_against_the_catalog() {
+        let catalog = tools(&["get_issue", "create_issue", "delete_issue"]);
+        assert_eq!(
+            enabled_disabled(&filter(&[], &["delete_*"]), Some(&catalog)),
+            Some((None, names(&["delete_issue"])))
+        );
+        assert_eq!(
+            enabled_disabled(&filter(&["*_issue"], &["delete_*"]), Some(&catalog)),
+            Some((names(&["get_issue", "create_issue"]), None))
+        );
+        assert_eq!(
+            enabled_disabled(&filter(&[], &["missing"]), Some(&catalog)),
+            Some((None, None))
+        );
+    }
+
+    #[test]
+    fn codex_lists_take_plain_names_without_a_catalog() {
+        assert_eq!(
+            enabled_disabled(&filter(&["get_issue", "delete_issue"], &["delete_issue"]), None),
+            Some((names(&["get_issue"]), names(&["delete_issue"])))
+        );
+        assert_eq!(
+            enabled_disabled(&filter(&[], &["delete_issue"]), None),
+            Some((None, names(&["delete_issue"])))
+        );
+        assert_eq!(enabled_disabled(&filter(&["get_*"], &[]), None), None);
+    }
+
+    #[test]
+    fn deny_only_clients_get_every_hidden_tool() {
+        let catalog = tools(&["get_issue", "create_issue"]);
+        assert_eq!(
+            hidden(&filter(&["get_*"], &[]), Some(&catalog)),
+            names(&["create_issue"])
+        );
+        assert_eq!(
+            hidden(&filter(&[], &["create_issue"]), None),
+            names(&["create_issue"])
+        );
+        assert_eq!(hidden(&filter(&["get_issue"], &[]), None), None);
+        assert_eq!(hidden(&filter(&[], &["create_*"]), None), None);
+    }
+}
@@ -0,0 +1,323 @@
+//! `conductor filter`: runs a stdio MCP server and hides the tools the
+//! client's tool filter doesn't permit. Hidden tools are dropped from
+//! `tools/list` results and calls to them are refused without reaching the
+//! server. The filter is read from Conductor's config at startup, so edits
+//! apply the next time the client starts the server.
+
+use crate::config::{self, ToolFilter};
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+
+const USAGE: &str =
+    "Usage: conductor filter --server <name> --client <client-id> -- <command> [args...]";
+
+struct FilterArgs {
+    server: String,
+    client: String,
+    command: Vec<String>,
+}
+
+/// Runs the filter if `args` (without the program name) start with `filter`.
+/// Returns the process exit code, or `None` when this isn't a filter
+/// invocation.
+pub fn run_from_args(args: &[String]) -> Option<i32> {
+    if args.first().map(String::as_str) != Some("filter") {
+        return None;
+    }
+
+    let args = match parse_args(&args[1..]) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("conductor filter: {}", e);
+            eprintln!("{}", USAGE);
+            return Some(2);
+        }
+    };
+
+    let runtime = match tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("conductor filter: failed to start runtime: {}", e);
+            return Some(1);
+        }
+    };
+
+    let code = match runtime.block_on(run(args)) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("conductor filter: {:#}", e);
+            1
+        }
+    };
+    // The stdin reader may still be blocked; don't wait for it.
+    runtime.shutdown_background();
+    Some(code)
+}
+
+fn parse_args(args: &[String]) -> Result<FilterArgs> {
+    let mut server = None;
+    let mut client = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--server" => server = Some(iter.next().context("--server needs a value")?.clone()),
+            "--client" => client = Some(iter.next().context("--client needs a value")?.clone()),
+            "--" => break,
+            other => bail!("Unexpected argument '{}'", other),
+        }
+    }
+
+    let command: Vec<String> = iter.cloned().collect();
+    if command.is_empty() {
+        bail!("Missing the server command after '--'");
+    }
+    Ok(FilterArgs {
+        server: server.context("Missing --server")?,
+        client: client.context("Missing --client")?,
+        command,
+    })
+}
+
+async fn run(args: FilterArgs) -> Result<i32> {
+    let filter = config::read_config()
+        .ok()
+        .and_then(|cfg| {
+            cfg.servers
+                .into_iter()
+                .find(|s| s.name == args.server)
+                .and_then(|s| s.tool_filter(&args.client).cloned())
+        })
+        .unwrap_or_default();
+
+    let (program, program_args) = args.command.split_first().context("Missing command")?;
+    let mut child = Command::new(program)
+        .args(program_args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("Failed to start '{}'", program))?;
+    let mut child_stdin = child.stdin.take().context("No stdin")?;
+    let child_stdout = child.stdout.take().context("No stdout")?;
+
+    let (out_tx, out_rx) = mpsc::unbounded_channel::<String>();
+    let writer = tokio::spawn(write_stdout(out_rx));
+    // IDs of the client's `tools/list` requests awaiting a response.
+    let list_requests: Arc<Mutex<HashSet<String>>> = Arc::default();
+
+    {
+        let filter = filter.clone();
+        let list_requests = list_requests.clone();
+        let out_tx = out_tx.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(tokio::io::stdin()).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if let Some(refusal) = intercept(&filter, &line, &list_requests) {
+                    let _ = out_tx.send(refusal);
+                    continue;
+                }
+                let line = line + "\n";
+                if child_stdin.write_all(line.as_bytes()).await.is_err()
+                    || child_stdin.flush().await.is_err()
+                {
+                    break;
+                }
+            }
+            // Dropping stdin tells the server the client is gone.
+        });
+    }
+
+    let mut lines = BufReader::new(child_stdout).lines();
+    while let Some(line) = lines
+        .next_line()
+        .await
+        .context("Failed to read the server")?
+    {
+        let _ = out_tx.send(filter_response(&filter, line, &list_requests));
+    }
+    drop(out_tx);
+    let _ = writer.await;
+
+    let status = child.wait().await?;
+    Ok(status.code().unwrap_or(1))
+}
+
+/// Notes `tools/list` requests and answers calls to hidden tools directly.
+fn intercept(
+    filter: &ToolFilter,
+    line: &str,
+    list_requests: &Mutex<HashSet<String>>,
+) -> Option<String> {
+    let message: Value = serde_json::from_str(line).ok()?;
+    let id = message.get("id")?;
+    match message.get("method")?.as_str()? {
+        "tools/list" => {
+            list_requests
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(id.to_string());
+            None
+        }
+        "tools/call" => {
+            let name = message.pointer("/params/name")?.as_str()?;
+            (!filter.permits(name)).then(|| {
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": {
+                        "code": -32602,
+                        "message": format!("Tool '{}' is not available to this client", name)
+                    }
+                })
+                .to_string()
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Drops hidden tools from responses to the client's `tools/list` requests.
+/// Everything else passes through untouched.
+fn filter_response(
+    filter: &ToolFilter,
+    line: String,
+    list_requests: &Mutex<HashSet<String>>,
+) -> String {
+    let Ok(mut message) = serde_json::from_str::<Value>(&line) else {
+        return line;
+    };
+    let is_list_response = message.get("method").is_none()
+        && message.get("id").is_some_and(|id| {
+            list_requests
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&id.to_string())
+        });
+    if !is_list_response {
+        return line;
+    }
+    match message
+        .pointer_mut("/result/tools")
+        .and_then(|t| t.as_array_mut())
+    {
+        Some(tools) => {
+            tools.retain(|tool| {
+                tool.get("name")
+                    .and_then(|n| n.as_str())
+                    .is_none_or(|name| filter.permits(name))
+            });
+            message.to_string()
+        }
+        None => line,
+    }
+}
+
+async fn write_stdout(mut rx: mpsc::UnboundedReceiver<String>) {
+    let mut stdout = tokio::io::stdout();
+    while let Some(mut line) = rx.recv().await {
+        line.push('\n');
+        if stdout.write_all(line.as_bytes()).await.is_err() || stdout.flush().await.is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter() -> ToolFilter {
+        ToolFilter {
+            allow: vec!["*_issue".to_string()],
+            deny: vec!["delete_*".to_string()],
+        }
+    }
+
+    fn list_response(id: Value) -> String {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": {
+                "tools": [
+                    {"name": "get_issue"},
+                    {"name": "delete_issue"},
+                    {"name": "search_code"}
+                ],
+                "nextCursor": "2"
+            }
+        })
+        .to_string()
+    }
+
+    fn tool_names(line: &str) -> Vec<String> {
+        let message: Value = serde_json::from_str(line).unwrap();
+        message["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn drops_hidden_tools_from_list_responses() {
+        let pending = Mutex::new(HashSet::new());
+        let request = r#"{"jsonrpc":"2.0","id":7,"method":"tools/list","params":{}}"#;
+        assert_eq!(intercept(&filter(), request, &pending), None);
+
+        let rewritten = filter_response(&filter(), list_response(json!(7)), &pending);
+        assert_eq!(tool_names(&rewritten), ["get_issue"]);
+        let message: Value = serde_json::from_str(&rewritten).unwrap();
+        assert_eq!(message["result"]["nextCursor"], "2");
+        assert!(pending.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn passes_other_responses_through_untouched() {
+        let pending = Mutex::new(HashSet::new());
+        intercept(
+            &filter(),
+            r#"{"jsonrpc":"2.0","id":"a","method":"tools/list"}"#,
+            &pending,
+        );
+
+        // Same number as the string ID the client used: a different request.
+        let other = list_response(json!(1));
+        assert_eq!(filter_response(&filter(), other.clone(), &pending), other);
+        let not_json = "server log line".to_string();
+        assert_eq!(filter_response(&filter(), not_json.clone(), &pending), not_json);
+        assert_eq!(pending.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn refuses_calls_to_hidden_tools() {
+        let pending = Mutex::new(HashSet::new());
+        let call = |name: &str| {
+            json!({
+                "jsonrpc": "2.0",
+                "id": 3,
+                "method": "tools/call",
+                "params": {"name": name, "arguments": {}}
+            })
+            .to_string()
+        };
+
+        assert_eq!(intercept(&filter(), &call("get_issue"), &pending), None);
+        let refusal = intercept(&filter(), &call("delete_issue"), &pending).unwrap();
+        let refusal: Value = serde_json::from_str(&refusal).unwrap();
+        assert_eq!(refusal["id"], 3);
+        assert_eq!(refusal["error"]["code"], -32602);
+        assert!(intercept(&filter(), &call("search_code"), &pending).is_some());
+    }
+}
@@ -0,0 +1,410 @@
+//! The system tray: per-client sync status, "Sync All Now", server toggles,
+//! the profile switcher and recent activity. The menu is rebuilt whenever the
+//! master config is written or a client config changes on disk, and the icon
+//! gets a badge while a client is out of sync or its last sync failed.
+
+use crate::clients::ClientDetection;
+use crate::commands;
+use crate::config::{self, profiles, McpConfig};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex, OnceLock};
+use tauri::image::Image;
+use tauri::menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::{AppHandle, Emitter, Manager, Wry};
+use tokio::sync::Notify;
+use tokio::time::Duration;
+
+const TRAY_ID: &str = "main";
+/// Menu item ID prefixes. An empty profile ID stands for "None".
+const CLIENT_PREFIX: &str = "client:";
+const SERVER_PREFIX: &str = "server:";
+const PROFILE_PREFIX: &str = "profile:";
+const RECENT_ACTIVITY: usize = 8;
+/// A sync writes the master config several times; rebuild once it settles.
+const REBUILD_DELAY: Duration = Duration::from_millis(300);
+
+static CHANGED: LazyLock<Notify> = LazyLock::new(Notify::new);
+/// Error of each client's last sync, cleared when one succeeds.
+static SYNC_ERRORS: LazyLock<Mutex<HashMap<String, String>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+/// The icon from the app config, before any badge.
+static BASE_ICON: OnceLock<Image<'static>> = OnceLock::new();
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum ClientStatus {
+    Synced,
+    NeverSynced,
+    OutOfSync,
+    Failed(String),
+}
+
+pub fn setup(app: &AppHandle) -> tauri::Result<()> {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return Ok(());
+    };
+    if let Some(icon) = app.default_window_icon() {
+        let _ = BASE_ICON.set(Image::new_owned(
+            icon.rgba().to_vec(),
+            icon.width(),
+            icon.height(),
+        ));
+    }
+
+    let app_handle = app.clone();
+    tray.on_menu_event(move |_tray, event| {
+        let app = app_handle.clone();
+        match event.id().as_ref() {
+            "show" => {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
+            }
+            "quit" => {
+                std::process::exit(0);
+            }
+            "sync-all" => {
+                tauri::async_runtime::spawn(async {
+                    if let Err(e) = commands::sync::sync_to_all_clients().await {
+                        eprintln!("Tray: sync: {}", e);
+                    }
+                });
+            }
+            id => {
+                if let Some(client_id) = id.strip_prefix(CLIENT_PREFIX) {
+                    let client_id = client_id.to_string();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = commands::sync::sync_to_client(client_id, None).await {
+                            eprintln!("Tray: sync: {}", e);
+                        }
+                    });
+                } else if let Some(server_id) = id.strip_prefix(SERVER_PREFIX) {
+                    tauri::async_runtime::spawn(toggle_server(app, server_id.to_string()));
+                } else if let Some(profile_id) = id.strip_prefix(PROFILE_PREFIX) {
+                    let profile_id = (!profile_id.is_empty()).then(|| profile_id.to_string());
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = commands::profiles::switch_profile(app, profile_id).await {
+                            eprintln!("Tray: switching profile: {}", e);
+                            // Put the check marks back on the profile still active.
+                            refresh();
+                        }
+                    });
+                }
+            }
+        }
+    });
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            rebuild(&app).await;
+            CHANGED.notified().await;
+            tokio::time::sleep(REBUILD_DELAY).await;
+        }
+    });
+    Ok(())
+}
+
+/// Schedules a rebuild of the menu and icon. Called after every master
+/// config write and when a client config changes on disk.
+pub fn refresh() {
+    CHANGED.notify_one();
+}
+
+/// Records the outcome of a client's sync for its status line.
+pub fn sync_finished(client_id: &str, error: Option<&str>) {
+    let mut errors = lock(&SYNC_ERRORS);
+    match error {
+        Some(error) => errors.insert(client_id.to_string(), error.to_string()),
+        None => errors.remove(client_id),
+    };
+    drop(errors);
+    refresh();
+}
+
+/// Flips a server from the tray and syncs every client to the result.
+async fn toggle_server(app: AppHandle, server_id: String) {
+    let Some(server) = config::read_config()
+        .ok()
+        .and_then(|cfg| cfg.servers.into_iter().find(|s| s.id == server_id))
+    else {
+        return;
+    };
+    if let Err(e) = commands::servers::toggle_server(server_id, !server.enabled).await {
+        eprintln!("Tray: toggling {}: {}", server.name, e);
+        // The check mark flipped on click; put it back.
+        refresh();
+        return;
+    }
+    let _ = app.emit("servers-changed", ());
+    if let Err(e) = commands::sync::sync_to_all_clients().await {
+        eprintln!("Tray: sync: {}", e);
+    }
+}
+
+async fn rebuild(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let cfg = config::read_config().unwrap_or_default();
+    let detections = commands::detection::detect_clients()
+        .await
+        .unwrap_or_default();
+    let errors = lock(&SYNC_ERRORS).clone();
+    let clients: Vec<(&ClientDetection, ClientStatus)> = detections
+        .iter()
+        .filter(|c| c.detected)
+        .map(|c| match errors.get(&c.client_id) {
+            Some(error) => (c, ClientStatus::Failed(error.clone())),
+            None => (c, status(c)),
+        })
+        .collect();
+
+    match build_menu(app, &cfg, &clients) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => eprintln!("Tray: building menu: {}", e),
+    }
+
+    let failed = clients
+        .iter()
+        .filter(|(_, s)| matches!(s, ClientStatus::Failed(_)))
+        .count();
+    let stale = clients
+        .iter()
+        .filter(|(_, s)| matches!(s, ClientStatus::OutOfSync | ClientStatus::NeverSynced))
+        .count();
+    let tooltip = match (failed, stale) {
+        (0, 0) => "Conductor: all clients in sync".to_string(),
+        (0, n) => format!("Conductor: {} client{} out of sync", n, plural(n)),
+        (n, _) => format!("Conductor: sync failed for {} client{}", n, plural(n)),
+    };
+    let _ = tray.set_tooltip(Some(&tooltip));
+    if let Some(base) = BASE_ICON.get() {
+        let icon = if failed + stale > 0 {
+            badged(base)
+        } else {
+            base.clone()
+        };
+        let _ = tray.set_icon(Some(icon));
+        let _ = tray.set_icon_as_template(true);
+    }
+}
+
+/// Same rules as the Clients view: out of sync when a server changed since
+/// the last sync, when an expected server is missing from the client, or
+/// when one Conductor removed is still there.
+fn status(client: &ClientDetection) -> ClientStatus {
+    let Some(last_synced) = &client.last_synced_at else {
+        return ClientStatus::NeverSynced;
+    };
+    let changed = client
+        .config_updated_at
+        .as_ref()
+        .is_some_and(|updated| updated > last_synced);
+    let missing = client
+        .expected_server_names
+        .iter()
+        .any(|name| !client.server_names.contains(name));
+    let orphaned = client.previously_synced_names.iter().any(|name| {
+        client.server_names.contains(name) && !client.expected_server_names.contains(name)
+    });
+    if changed || missing || orphaned {
+        ClientStatus::OutOfSync
+    } else {
+        ClientStatus::Synced
+    }
+}
+
+fn build_menu(
+    app: &AppHandle,
+    cfg: &McpConfig,
+    clients: &[(&ClientDetection, ClientStatus)],
+) -> tauri::Result<Menu<Wry>> {
+    let menu = Menu::new(app)?;
+    menu.append(&MenuItem::with_id(
+        app,
+        "show",
+        "Show Conductor",
+        true,
+        None::<&str>,
+    )?)?;
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+
+    // Clicking a client syncs it.
+    for (client, status) in clients {
+        let state = match status {
+            ClientStatus::Synced => match &client.last_synced_at {
+                Some(at) => format!("synced {}", ago(at)),
+                None => "synced".to_string(),
+            },
+            ClientStatus::NeverSynced => "never synced".to_string(),
+            ClientStatus::OutOfSync => "out of sync".to_string(),
+            ClientStatus::Failed(error) => format!("sync failed: {}", truncate(error, 48)),
+        };
+        menu.append(&MenuItem::with_id(
+            app,
+            format!("{}{}", CLIENT_PREFIX, client.client_id),
+            format!("{} — {}", client.display_name, state),
+            true,
+            None::<&str>,
+        )?)?;
+    }
+    menu.append(&MenuItem::with_id(
+        app,
+        "sync-all",
+        "Sync All Now",
+        !clients.is_empty(),
+        None::<&str>,
+    )?)?;
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+
+    let server_items = cfg
+        .servers
+        .iter()
+        .map(|server| {
+            CheckMenuItem::with_id(
+                app,
+                format!("{}{}", SERVER_PREFIX, server.id),
+                server.display_name.as_deref().unwrap_or(&server.name),
+                true,
+                server.enabled,
+                None::<&str>,
+            )
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    menu.append(&submenu(app, "Servers", &server_items)?)?;
+
+    if !cfg.profiles.is_empty() {
+        let active = profiles::active(cfg);
+        let mut profile_items = cfg
+            .profiles
+            .iter()
+            .map(|profile| {
+                CheckMenuItem::with_id(
+                    app,
+                    format!("{}{}", PROFILE_PREFIX, profile.id),
+                    &profile.name,
+                    true,
+                    active.is_some_and(|p| p.id == profile.id),
+                    None::<&str>,
+                )
+            })
+            .collect::<tauri::Result<Vec<_>>>()?;
+        profile_items.push(CheckMenuItem::with_id(
+            app,
+            PROFILE_PREFIX,
+            "None",
+            true,
+            active.is_none(),
+            None::<&str>,
+        )?);
+        let label = match active {
+            Some(profile) => format!("Profile: {}", profile.name),
+            None => "Profile".to_string(),
+        };
+        menu.append(&submenu(app, &label, &profile_items)?)?;
+    }
+
+    // The activity log is oldest first.
+    let activity_items = cfg
+        .activity
+        .iter()
+        .rev()
+        .take(RECENT_ACTIVITY)
+        .map(|entry| {
+            MenuItem::with_id(
+                app,
+                format!("activity:{}", entry.id),
+                format!(
+                    "{} · {}",
+                    truncate(&entry.description, 48),
+                    ago(&entry.timestamp)
+                ),
+                false,
+                None::<&str>,
+            )
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    menu.append(&submenu(app, "Recent Activity", &activity_items)?)?;
+
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&MenuItem::with_id(
+        app,
+        "quit",
+        "Quit Conductor",
+        true,
+        None::<&str>,
+    )?)?;
+    Ok(menu)
+}
+
+/// A submenu of `items`, disabled when there are none.
+fn submenu<I: IsMenuItem<Wry>>(
+    app: &AppHandle,
+    label: &str,
+    items: &[I],
+) -> tauri::Result<Submenu<Wry>> {
+    let items: Vec<&dyn IsMenuItem<Wry>> = items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect();
+    Submenu::with_items(app, label, !items.is_empty(), &items)
+}
+
+/// The icon with a dot in its lower right corner.
+fn badged(base: &Image<'_>) -> Image<'static> {
+    let (width, height) = (base.width(), base.height());
+    let mut rgba = base.rgba().to_vec();
+    let radius = width.min(height) as f32 * 0.22;
+    let (cx, cy) = (width as f32 - radius, height as f32 - radius);
+    for y in 0..height {
+        for x in 0..width {
+            let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+            let distance = (dx * dx + dy * dy).sqrt();
+            let i = ((y * width + x) * 4) as usize;
+            if distance <= radius {
+                rgba[i..i + 4].copy_from_slice(&[0xF5, 0x9E, 0x0B, 0xFF]);
+            } else if distance <= radius * 1.3 {
+                // Clear a ring so the dot stands out as a template image.
+                rgba[i + 3] = 0;
+            }
+        }
+    }
+    Image::new_owned(rgba, width, height)
+}
+
+fn ago(timestamp: &str) -> String {
+    let Ok(at) = chrono::DateTime::parse_from_rfc3339(timestamp) else {
+        return timestamp.to_string();
+    };
+    let secs = (chrono::Utc::now() - at.with_timezone(&chrono::Utc))
+        .num_seconds()
+        .max(0);
+    match secs {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{}m ago", secs / 60),
+        3600..86400 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((i, _)) => format!("{}…", &text[..i]),
+        None => text.to_string(),
+    }
+}
+
+fn plural(n: usize) -> &'static str {
+    if n == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
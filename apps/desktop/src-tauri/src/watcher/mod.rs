@@ -1,46 +1,46 @@
 use crate::clients::get_all_adapters;
 use anyhow::Result;
 use notify::{Config, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
-use std::collections::HashSet;
-use std::path::PathBuf;
-use std::sync::Arc;
-use tauri::Emitter;
-use tokio::sync::Mutex;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
 use tokio::time::{Duration, Instant};
 
-/// Start watching all detected client config files for changes.
-/// Emits "client-config-changed" Tauri events with 500ms debounce.
-/// Only emits for actual MCP config files, not other files in the same directory.
-pub async fn start_watching(app_handle: tauri::AppHandle) -> Result<()> {
-    let adapters = get_all_adapters();
-
-    // Collect actual config file paths and their parent directories
-    let mut watch_dirs: HashSet<PathBuf> = HashSet::new();
-    let mut config_files: HashSet<PathBuf> = HashSet::new();
-    for adapter in &adapters {
-        if let Some(config_path) = adapter.config_path() {
-            if let Some(parent) = config_path.parent() {
-                if parent.exists() {
-                    watch_dirs.insert(parent.to_path_buf());
-                    config_files.insert(config_path);
-                }
-            }
-        }
-    }
-
-    if watch_dirs.is_empty() {
-        return Ok(());
-    }
-
-    // Track last event time for debouncing
-    let last_event: Arc<Mutex<Option<Instant>>> = Arc::new(Mutex::new(None));
+/// Quiet period after a config file's last event before it is reported.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+/// How often clients are re-detected and watches re-armed even without
+/// filesystem events (an install elsewhere on disk, a new custom adapter).
+const DETECT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Payload of "client-detected" events.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectionChange {
+    pub client_id: String,
+    pub display_name: String,
+    /// `false` when the client was uninstalled.
+    pub detected: bool,
+}
 
-    let app_handle_clone = app_handle.clone();
-    let last_event_clone = last_event.clone();
+/// What is watched: every client config file, through its directory or,
+/// until that exists, the nearest ancestor that does.
+#[derive(Default)]
+struct Watches {
+    config_files: HashSet<PathBuf>,
+    /// Config files that existed at the last re-arm.
+    existing: HashSet<PathBuf>,
+    dirs: HashSet<PathBuf>,
+    /// Detected client IDs and their display names.
+    detected: HashMap<String, String>,
+}
 
+/// Start watching every client's config files for changes.
+/// Emits "client-config-changed" with the changed paths once a file has been
+/// quiet for 500ms, and "client-detected" when a client is installed or
+/// removed. Directories that don't exist yet are picked up when they appear.
+pub async fn start_watching(app_handle: AppHandle) -> Result<()> {
     let (tx, mut rx) = tokio::sync::mpsc::channel::<notify::Event>(100);
-
-    // Create the file watcher
     let mut watcher = RecommendedWatcher::new(
         move |result: Result<notify::Event, notify::Error>| {
             if let Ok(event) = result {
@@ -50,54 +50,164 @@ pub async fn start_watching(app_handle: tauri::AppHandle) -> Result<()> {
         Config::default(),
     )?;
 
-    // Watch all parent directories
-    for dir in &watch_dirs {
-        if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
-            eprintln!("Warning: Failed to watch {}: {}", dir.display(), e);
-        }
-    }
+    // Clients and files present at startup aren't news.
+    let mut watches = Watches::default();
+    rearm(&mut watcher, &mut watches);
+    redetect(&mut watches);
 
-    // Spawn a task to handle events with debouncing
     tokio::spawn(async move {
-        // Keep the watcher alive by moving it into this task
-        let _watcher = watcher;
-
-        while let Some(event) = rx.recv().await {
-            match event.kind {
-                EventKind::Modify(_) | EventKind::Create(_) | EventKind::Remove(_) => {
-                    // Filter to only actual MCP config files
-                    let changed_paths: Vec<String> = event
-                        .paths
-                        .iter()
-                        .filter(|p| config_files.contains(p.as_path()))
-                        .filter(|p| !crate::file_guard::is_internal_write(p.as_path()))
-                        .map(|p| p.to_string_lossy().to_string())
-                        .collect();
-
-                    if changed_paths.is_empty() {
+        let mut pending: HashMap<PathBuf, Instant> = HashMap::new();
+        // Directory changes are coalesced the same way before re-arming.
+        let mut rearm_due: Option<Instant> = None;
+        let mut detect = tokio::time::interval(DETECT_INTERVAL);
+        detect.reset();
+
+        loop {
+            let deadline = pending.values().copied().chain(rearm_due).min();
+            tokio::select! {
+                event = rx.recv() => {
+                    let Some(event) = event else { break };
+                    if !matches!(
+                        event.kind,
+                        EventKind::Modify(_) | EventKind::Create(_) | EventKind::Remove(_)
+                    ) {
                         continue;
                     }
-
-                    let mut last = last_event_clone.lock().await;
+                    for path in &event.paths {
+                        if watches.config_files.contains(path) {
+                            if path.exists() {
+                                watches.existing.insert(path.clone());
+                            } else {
+                                watches.existing.remove(path);
+                            }
+                            if !crate::file_guard::is_internal_write(path) {
+                                pending.insert(path.clone(), Instant::now() + DEBOUNCE);
+                            }
+                        } else if watches.config_files.iter().any(|f| f.starts_with(path)) {
+                            // A directory on the way to a config file came or went.
+                            rearm_due = Some(Instant::now() + DEBOUNCE);
+                        }
+                    }
+                }
+                _ = detect.tick() => {
+                    rearm_due = Some(Instant::now());
+                }
+                _ = sleep_until(deadline) => {
                     let now = Instant::now();
-
-                    // Debounce: only emit if 500ms have passed since last event
-                    let should_emit = match *last {
-                        Some(prev) => now.duration_since(prev) > Duration::from_millis(500),
-                        None => true,
-                    };
-
-                    if should_emit {
-                        *last = Some(now);
-                        drop(last); // Release lock before emitting
-
-                        let _ = app_handle_clone.emit("client-config-changed", &changed_paths);
+                    if rearm_due.is_some_and(|due| due <= now) {
+                        rearm_due = None;
+                        for path in rearm(&mut watcher, &mut watches) {
+                            pending.insert(path, now);
+                        }
+                        for change in redetect(&mut watches) {
+                            let _ = app_handle.emit("client-detected", &change);
+                            crate::tray::refresh();
+                        }
+                    }
+                    let changed_paths: Vec<String> = pending
+                        .iter()
+                        .filter(|(_, due)| **due <= now)
+                        .map(|(path, _)| path.to_string_lossy().to_string())
+                        .collect();
+                    pending.retain(|_, due| *due > now);
+                    if !changed_paths.is_empty() {
+                        let _ = app_handle.emit("client-config-changed", &changed_paths);
+                        crate::tray::refresh();
+                        crate::notifications::external_edit(&changed_paths);
                     }
                 }
-                _ => {}
             }
         }
     });
 
     Ok(())
 }
+
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Re-reads the adapters, watches what is now needed and drops watches that
+/// aren't. Returns config files that have appeared since the last call.
+fn rearm(watcher: &mut RecommendedWatcher, watches: &mut Watches) -> Vec<PathBuf> {
+    let config_files: HashSet<PathBuf> = get_all_adapters()
+        .iter()
+        .flat_map(|a| a.targets())
+        .map(|t| t.config_path)
+        .collect();
+    let dirs: HashSet<PathBuf> = config_files
+        .iter()
+        .filter_map(|file| file.parent().and_then(nearest_existing))
+        .collect();
+
+    for dir in watches.dirs.difference(&dirs) {
+        // Fails when the directory is already gone, which drops the watch too.
+        let _ = watcher.unwatch(dir);
+    }
+    let mut armed = HashSet::new();
+    for dir in dirs {
+        if watches.dirs.contains(&dir) {
+            armed.insert(dir);
+            continue;
+        }
+        match watcher.watch(&dir, RecursiveMode::NonRecursive) {
+            Ok(()) => {
+                armed.insert(dir);
+            }
+            Err(e) => eprintln!("Warning: Failed to watch {}: {}", dir.display(), e),
+        }
+    }
+
+    // A config file created along with its directory raised no event of its
+    // own in the new watch.
+    let existing: HashSet<PathBuf> = config_files
+        .iter()
+        .filter(|f| f.exists())
+        .cloned()
+        .collect();
+    let appeared = existing
+        .iter()
+        .filter(|f| !watches.existing.contains(*f) && watches.config_files.contains(*f))
+        .filter(|f| !crate::file_guard::is_internal_write(f))
+        .cloned()
+        .collect();
+    watches.config_files = config_files;
+    watches.existing = existing;
+    watches.dirs = armed;
+    appeared
+}
+
+/// Re-runs detection and returns the clients that were installed or removed
+/// since the last call.
+fn redetect(watches: &mut Watches) -> Vec<DetectionChange> {
+    let detected: HashMap<String, String> = get_all_adapters()
+        .iter()
+        .filter(|a| a.detect())
+        .map(|a| (a.id().to_string(), a.display_name().to_string()))
+        .collect();
+    let previous = std::mem::replace(&mut watches.detected, detected);
+    let installed = watches
+        .detected
+        .iter()
+        .filter(|(id, _)| !previous.contains_key(*id))
+        .map(|(id, name)| (id, name, true));
+    let removed = previous
+        .iter()
+        .filter(|(id, _)| !watches.detected.contains_key(*id))
+        .map(|(id, name)| (id, name, false));
+    installed
+        .chain(removed)
+        .map(|(id, name, detected)| DetectionChange {
+            client_id: id.clone(),
+            display_name: name.clone(),
+            detected,
+        })
+        .collect()
+}
+
+fn nearest_existing(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors().find(|d| d.is_dir()).map(Path::to_path_buf)
+}
@@ -1,9 +1,11 @@
 import { useEffect } from "react";
+import { listen } from "@tauri-apps/api/event";
 import { Sidebar } from "@/components/layout/Sidebar";
 import { ServersView } from "@/views/ServersView";
 import { ClientsView } from "@/views/ClientsView";
 import { RegistryView } from "@/views/RegistryView";
 import { StacksView } from "@/views/StacksView";
+import { RunningView } from "@/views/RunningView";
 import { ActivityView } from "@/views/ActivityView";
 import { SettingsView } from "@/views/SettingsView";
 import { CommandPalette } from "@/components/CommandPalette";
@@ -12,6 +14,7 @@ import { useUIStore } from "@/stores/uiStore";
 import { useConfigStore } from "@/stores/configStore";
 import { useClientStore } from "@/stores/clientStore";
 import { useAutoSync } from "@/hooks/useAutoSync";
+import { useNotifications } from "@/hooks/useNotifications";
 
 export function App() {
   const activeView = useUIStore((s) => s.activeView);
@@ -20,12 +23,32 @@ export function App() {
   const fetchServers = useConfigStore((s) => s.fetchServers);
   const detectClients = useClientStore((s) => s.detectClients);
   const { triggerAutoSync } = useAutoSync();
+  useNotifications();
 
   useEffect(() => {
     fetchServers();
     detectClients();
   }, [fetchServers, detectClients]);
 
+  // Switching profiles (also from the tray) changes which servers are enabled.
+  useEffect(() => {
+    const unlisten = listen("profile-changed", () => {
+      fetchServers();
+      detectClients();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [fetchServers, detectClients]);
+
+  // Servers toggled from the tray.
+  useEffect(() => {
+    const unlisten = listen("servers-changed", () => fetchServers());
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [fetchServers]);
+
   // Expose triggerAutoSync globally so stores can call it
   useEffect(() => {
     window.__conductorAutoSync = triggerAutoSync;
@@ -53,6 +76,8 @@ export function App() {
         return <RegistryView />;
       case "stacks":
         return <StacksView />;
+      case "running":
+        return <RunningView />;
       case "activity":
         return <ActivityView />;
       case "settings":
@@ -11,6 +11,13 @@ const clientColors: Record<string, string> = {
   jetbrains: "#FC801D",
   codex: "#F97316",
   antigravity: "#4285F4",
+  cline: "#A855F7",
+  "roo-code": "#EAB308",
+  continue: "#6366F1",
+  gemini: "#4285F4",
+  goose: "#111827",
+  opencode: "#64748B",
+  "amazon-q": "#FF9900",
 };
 
 const clientInitials: Record<string, string> = {
@@ -23,6 +30,13 @@ const clientInitials: Record<string, string> = {
   jetbrains: "JB",
   codex: "OC",
   antigravity: "AG",
+  cline: "Cl",
+  "roo-code": "RC",
+  continue: "Co",
+  gemini: "G",
+  goose: "Go",
+  opencode: "oc",
+  "amazon-q": "Q",
 };
 
 // Bundled SVG/PNG logos for reliable fallback when the app isn't installed
@@ -11,6 +11,7 @@ import {
   Download,
   ArrowRight,
   X,
+  Cpu,
 } from "lucide-react";
 import { cn } from "@/lib/utils";
 import { useConfigStore } from "@/stores/configStore";
@@ -119,6 +120,7 @@ export function CommandPalette({ open, onOpenChange }: CommandPaletteProps) {
       { id: "clients", label: "Go to Clients", icon: Monitor },
       { id: "stacks", label: "Go to Stacks", icon: Layers },
       { id: "registry", label: "Go to Registry", icon: Search },
+      { id: "running", label: "Go to Running", icon: Cpu },
       { id: "activity", label: "Go to Activity", icon: Activity },
       { id: "settings", label: "Go to Settings", icon: Settings },
     ];
@@ -6,6 +6,7 @@ import {
   Search,
   Activity,
   Settings,
+  Cpu,
 } from "lucide-react";
 import { cn } from "@/lib/utils";
 import { useUIStore, type ActiveView } from "@/stores/uiStore";
@@ -22,6 +23,7 @@ const navItems: NavItem[] = [
   { id: "clients", label: "Clients", icon: Monitor },
   { id: "stacks", label: "Stacks", icon: Layers },
   { id: "registry", label: "Registry", icon: Search },
+  { id: "running", label: "Running", icon: Cpu },
   { id: "activity", label: "Activity", icon: Activity },
   { id: "settings", label: "Settings", icon: Settings },
 ];
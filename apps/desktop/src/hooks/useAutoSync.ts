@@ -3,11 +3,12 @@ import { listen } from "@tauri-apps/api/event";
 import * as tauri from "@/lib/tauri";
 import { useClientStore } from "@/stores/clientStore";
 import { toast } from "sonner";
-import type { AppSettings } from "@conductor/types";
+import type { AppSettings, DetectionChange } from "@conductor/types";
 
 /**
  * Listens for "client-config-changed" events from the file watcher
- * and triggers auto-sync when enabled in settings.
+ * and triggers auto-sync when enabled in settings. Clients installed or
+ * removed while Conductor runs ("client-detected") refresh detection.
  *
  * Also provides `triggerAutoSync()` for use after config mutations.
  */
@@ -34,20 +35,27 @@ export function useAutoSync() {
 
   // Listen for file watcher events from the Rust backend
   useEffect(() => {
-    const unlisten = listen<string[]>("client-config-changed", (event) => {
-      const settings = settingsRef.current;
-      if (!settings) return;
-
-      // Notify about external config changes
-      if (settings.notifyExternal) {
-        const paths = event.payload;
-        const fileNames = paths.map((p) => p.split("/").pop() || p);
-        toast.info("External config change detected", {
-          description: fileNames.join(", "),
+    const unlisten = listen<string[]>("client-config-changed", () => {
+      // The backend notifies about the change itself (see useNotifications);
+      // refresh client detection to pick it up.
+      detectClients();
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [detectClients]);
+
+  useEffect(() => {
+    const unlisten = listen<DetectionChange>("client-detected", (event) => {
+      const { displayName, detected } = event.payload;
+      if (detected) {
+        toast.info(`${displayName} detected`, {
+          description: "Sync to add your servers to it",
         });
+      } else {
+        toast.info(`${displayName} is no longer installed`);
       }
-
-      // Refresh client detection to pick up external changes
       detectClients();
     });
 
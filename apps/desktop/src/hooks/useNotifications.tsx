@@ -0,0 +1,90 @@
+import { useEffect } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import { toast } from "sonner";
+import { useUIStore } from "@/stores/uiStore";
+import { useClientStore } from "@/stores/clientStore";
+import { useConfigStore } from "@/stores/configStore";
+import type { AppNotification, NotificationAction } from "@conductor/types";
+
+/**
+ * Shows the backend's "notification" events (external edits, background
+ * sync outcomes, expired sign-ins) as toasts whose buttons carry out the
+ * notification's actions.
+ */
+export function useNotifications() {
+  const setActiveView = useUIStore((s) => s.setActiveView);
+  const setSelectedServerId = useUIStore((s) => s.setSelectedServerId);
+  const setServerDetailOpen = useUIStore((s) => s.setServerDetailOpen);
+  const syncToClient = useClientStore((s) => s.syncToClient);
+  const syncToAllClients = useClientStore((s) => s.syncToAllClients);
+  const importFromClient = useClientStore((s) => s.importFromClient);
+  const fetchServers = useConfigStore((s) => s.fetchServers);
+
+  useEffect(() => {
+    const run = async (action: NotificationAction) => {
+      const appWindow = getCurrentWindow();
+      await appWindow.show();
+      await appWindow.setFocus();
+      switch (action.kind) {
+        case "showChanges":
+          setActiveView("clients");
+          break;
+        case "resync":
+          if (action.clientId) await syncToClient(action.clientId);
+          else await syncToAllClients();
+          break;
+        case "adopt":
+          if (action.clientId && (await importFromClient(action.clientId))) {
+            fetchServers();
+          }
+          break;
+        case "reauthorize":
+          setActiveView("servers");
+          setSelectedServerId(action.serverId ?? null);
+          setServerDetailOpen(true);
+          break;
+      }
+    };
+
+    const unlisten = listen<AppNotification>("notification", (event) => {
+      const { title, body, actions } = event.payload;
+      const id = toast.info(title, {
+        duration: actions.length > 0 ? 15_000 : undefined,
+        description: (
+          <div>
+            <p>{body}</p>
+            {actions.length > 0 && (
+              <div className="flex flex-wrap gap-1.5 mt-2">
+                {actions.map((action) => (
+                  <button
+                    key={action.kind}
+                    onClick={() => {
+                      toast.dismiss(id);
+                      run(action);
+                    }}
+                    className="h-6 px-2 rounded-md border border-border text-[11px] font-medium
+                      text-text-primary hover:bg-surface-3 transition-colors"
+                  >
+                    {action.label}
+                  </button>
+                ))}
+              </div>
+            )}
+          </div>
+        ),
+      });
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [
+    setActiveView,
+    setSelectedServerId,
+    setServerDetailOpen,
+    syncToClient,
+    syncToAllClients,
+    importFromClient,
+    fetchServers,
+  ]);
+}
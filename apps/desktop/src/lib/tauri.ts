@@ -6,12 +6,27 @@ import type {
   UpdateServerRequest,
   ClientDetection,
   SyncResult,
+  TargetNameConflicts,
   ImportResult,
   RegistryServer,
   McpStack,
   OAuthStatus,
   ActivityEntry,
   AppSettings,
+  GatewayStatus,
+  ToolCatalog,
+  ToolFilter,
+  TrafficEntry,
+  TrafficQuery,
+  ReplayResult,
+  ManagedServerStatus,
+  VariableInfo,
+  ServerPreview,
+  Profile,
+  ProfileList,
+  UpdateProfileRequest,
+  SecretBackend,
+  SecretMigration,
 } from "@conductor/types";
 
 // ── Server management ───────────────────────────────────────────────
@@ -88,6 +103,17 @@ export async function syncToAllClients(): Promise<SyncResult[]> {
   return invoke<SyncResult[]>("sync_to_all_clients");
 }
 
+export async function setSyncTargets(
+  clientId: string,
+  targetIds: string[]
+): Promise<void> {
+  return invoke("set_sync_targets", { clientId, targetIds });
+}
+
+export async function previewNameConflicts(): Promise<TargetNameConflicts[]> {
+  return invoke("preview_name_conflicts");
+}
+
 // ── Secrets ─────────────────────────────────────────────────────────
 
 export async function saveSecret(
@@ -116,6 +142,18 @@ export async function listSecretKeys(serverId: string): Promise<string[]> {
   return invoke<string[]>("list_secret_keys", { serverId });
 }
 
+export async function checkSecretBackend(backend: SecretBackend): Promise<void> {
+  return invoke<void>("check_secret_backend", { backend });
+}
+
+export async function unlockSecretFile(passphrase: string): Promise<void> {
+  return invoke<void>("unlock_secret_file", { passphrase });
+}
+
+export async function migrateSecrets(backend: SecretBackend): Promise<SecretMigration> {
+  return invoke<SecretMigration>("migrate_secrets", { backend });
+}
+
 // ── Registry ────────────────────────────────────────────────────────
 
 export async function getPopularServers(): Promise<RegistryServer[]> {
@@ -239,3 +277,122 @@ export async function openConfigFolder(): Promise<void> {
 export async function exportConfig(): Promise<string> {
   return invoke<string>("export_config");
 }
+
+// ── Gateway ─────────────────────────────────────────────────────────
+
+export async function getGatewayStatus(): Promise<GatewayStatus> {
+  return invoke<GatewayStatus>("get_gateway_status");
+}
+
+// ── Tools ───────────────────────────────────────────────────────────
+
+export async function getServerTools(
+  serverId: string,
+  refresh = false
+): Promise<ToolCatalog> {
+  return invoke<ToolCatalog>("get_server_tools", { serverId, refresh });
+}
+
+export async function setToolFilter(
+  serverId: string,
+  clientId: string,
+  filter: ToolFilter
+): Promise<void> {
+  return invoke<void>("set_tool_filter", { serverId, clientId, filter });
+}
+
+// ── Inspector ───────────────────────────────────────────────────────
+
+export async function queryTraffic(
+  serverId: string,
+  query?: TrafficQuery
+): Promise<TrafficEntry[]> {
+  return invoke<TrafficEntry[]>("query_traffic", { serverId, query });
+}
+
+/** Writes a HAR file to the Downloads folder and returns its path. */
+export async function exportTrafficHar(
+  serverId: string,
+  query?: TrafficQuery
+): Promise<string> {
+  return invoke<string>("export_traffic_har", { serverId, query });
+}
+
+export async function replayTraffic(
+  serverId: string,
+  entryId: string
+): Promise<ReplayResult> {
+  return invoke<ReplayResult>("replay_traffic", { serverId, entryId });
+}
+
+export async function clearTraffic(serverId: string): Promise<void> {
+  return invoke<void>("clear_traffic", { serverId });
+}
+
+// ── Supervisor ──────────────────────────────────────────────────────
+
+export async function listManagedServers(): Promise<ManagedServerStatus[]> {
+  return invoke<ManagedServerStatus[]>("list_managed_servers");
+}
+
+export async function getManagedServerLogs(
+  serverId: string,
+  lines?: number
+): Promise<string[]> {
+  return invoke<string[]>("get_managed_server_logs", { serverId, lines });
+}
+
+export async function restartManagedServer(serverId: string): Promise<void> {
+  return invoke<void>("restart_managed_server", { serverId });
+}
+
+// ── Variables ───────────────────────────────────────────────────────
+
+/** Global variables, plus `profileId`'s own when given. */
+export async function getVariables(profileId?: string): Promise<VariableInfo[]> {
+  return invoke<VariableInfo[]>("get_variables", { profileId });
+}
+
+export async function setVariable(
+  name: string,
+  value: string,
+  secret: boolean,
+  profileId?: string
+): Promise<void> {
+  return invoke<void>("set_variable", { name, value, secret, profileId });
+}
+
+export async function deleteVariable(name: string, profileId?: string): Promise<void> {
+  return invoke<void>("delete_variable", { name, profileId });
+}
+
+/** Resolves a server's `${NAME}` references, saved or not. */
+export async function previewServer(server: McpServer): Promise<ServerPreview> {
+  return invoke<ServerPreview>("preview_server", { server });
+}
+
+// ── Profiles ────────────────────────────────────────────────────────
+
+export async function listProfiles(): Promise<ProfileList> {
+  return invoke<ProfileList>("list_profiles");
+}
+
+export async function createProfile(name: string): Promise<Profile> {
+  return invoke<Profile>("create_profile", { name });
+}
+
+export async function updateProfile(
+  profileId: string,
+  request: UpdateProfileRequest
+): Promise<Profile> {
+  return invoke<Profile>("update_profile", { profileId, request });
+}
+
+export async function deleteProfile(profileId: string): Promise<void> {
+  return invoke<void>("delete_profile", { profileId });
+}
+
+/** Activates a profile (`null` for none) and re-syncs every client. */
+export async function switchProfile(profileId: string | null): Promise<SyncResult[]> {
+  return invoke<SyncResult[]>("switch_profile", { profileId });
+}
@@ -1,5 +1,6 @@
 import { type ClassValue, clsx } from "clsx";
 import { twMerge } from "tailwind-merge";
+import type { CommandError } from "@conductor/types";
 
 export function cn(...inputs: ClassValue[]) {
   return twMerge(clsx(inputs));
@@ -68,3 +69,24 @@ export function validateServerName(name: string): string | null {
   if (name.startsWith("-") || name.endsWith("-")) return "Cannot start or end with a hyphen";
   return null;
 }
+
+export function isCommandError(err: unknown): err is CommandError {
+  return (
+    typeof err === "object" &&
+    err !== null &&
+    "kind" in err &&
+    "message" in err &&
+    typeof (err as CommandError).message === "string"
+  );
+}
+
+/** User-facing text for anything thrown by a Tauri command or the UI itself. */
+export function errorMessage(err: unknown): string {
+  if (isCommandError(err)) {
+    return err.suggestedAction
+      ? `${err.message}. ${err.suggestedAction}`
+      : err.message;
+  }
+  if (err instanceof Error) return err.message;
+  return String(err);
+}
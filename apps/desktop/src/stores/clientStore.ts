@@ -2,6 +2,7 @@ import { create } from "zustand";
 import type { ClientDetection, ImportResult, SyncResult } from "@conductor/types";
 import * as tauri from "@/lib/tauri";
 import { toast } from "sonner";
+import { errorMessage } from "@/lib/utils";
 
 interface ClientState {
   clients: ClientDetection[];
@@ -11,6 +12,7 @@ interface ClientState {
   importFromClient: (clientId: string) => Promise<ImportResult | null>;
   syncToClient: (clientId: string) => Promise<SyncResult | null>;
   syncToAllClients: () => Promise<SyncResult[]>;
+  setSyncTargets: (clientId: string, targetIds: string[]) => Promise<void>;
 }
 
 export const useClientStore = create<ClientState>((set, get) => ({
@@ -24,7 +26,7 @@ export const useClientStore = create<ClientState>((set, get) => ({
       set({ clients, loading: false });
     } catch (err) {
       set({ loading: false });
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       toast.error("Failed to detect clients", { description: message });
     }
   },
@@ -38,7 +40,7 @@ export const useClientStore = create<ClientState>((set, get) => ({
       });
       return result;
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       toast.error(`Failed to import from ${client?.displayName || clientId}`, {
         description: message,
       });
@@ -59,12 +61,12 @@ export const useClientStore = create<ClientState>((set, get) => ({
         set({ clients });
       } else {
         toast.error(`Sync failed for ${client?.displayName || clientId}`, {
-          description: result.error,
+          description: result.error ? errorMessage(result.error) : undefined,
         });
       }
       return result;
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       toast.error(`Failed to sync to ${client?.displayName || clientId}`, {
         description: message,
       });
@@ -93,9 +95,20 @@ export const useClientStore = create<ClientState>((set, get) => ({
 
       return results;
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       toast.error("Failed to sync", { description: message });
       return [];
     }
   },
+
+  setSyncTargets: async (clientId, targetIds) => {
+    try {
+      await tauri.setSyncTargets(clientId, targetIds);
+      const clients = await tauri.detectClients();
+      set({ clients });
+    } catch (err) {
+      const message = errorMessage(err);
+      toast.error("Failed to update sync targets", { description: message });
+    }
+  },
 }));
@@ -6,6 +6,7 @@ import type {
 } from "@conductor/types";
 import * as tauri from "@/lib/tauri";
 import { toast } from "sonner";
+import { errorMessage } from "@/lib/utils";
 
 interface ConfigState {
   servers: McpServer[];
@@ -33,7 +34,7 @@ export const useConfigStore = create<ConfigState>((set, get) => ({
       const config = await tauri.readMasterConfig();
       set({ servers: config.servers, loading: false });
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       set({ error: message, loading: false });
       toast.error("Failed to load servers", { description: message });
     }
@@ -49,7 +50,7 @@ export const useConfigStore = create<ConfigState>((set, get) => ({
       window.__conductorAutoSync?.();
       return server;
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       toast.error("Failed to add server", { description: message });
       return null;
     }
@@ -65,7 +66,7 @@ export const useConfigStore = create<ConfigState>((set, get) => ({
       window.__conductorAutoSync?.();
       return updated;
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       toast.error("Failed to update server", { description: message });
       return null;
     }
@@ -84,7 +85,7 @@ export const useConfigStore = create<ConfigState>((set, get) => ({
       window.__conductorAutoSync?.();
       return true;
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       toast.error("Failed to delete server", { description: message });
       return false;
     }
@@ -115,7 +116,7 @@ export const useConfigStore = create<ConfigState>((set, get) => ({
           ),
         }));
       }
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       toast.error("Failed to toggle server", { description: message });
     }
   },
@@ -5,6 +5,7 @@ export type ActiveView =
   | "clients"
   | "registry"
   | "stacks"
+  | "running"
   | "activity"
   | "settings";
 
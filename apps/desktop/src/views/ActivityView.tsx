@@ -11,6 +11,7 @@ import {
   ChevronRight,
   Activity as ActivityIcon,
   Trash,
+  UserCog,
 } from "lucide-react";
 import { cn, formatRelativeTime } from "@/lib/utils";
 import * as tauri from "@/lib/tauri";
@@ -18,7 +19,15 @@ import type { ActivityEntry } from "@conductor/types";
 
 // ── Icon mapping ────────────────────────────────────────────────────
 
-type ActivityType = "sync" | "add" | "delete" | "import" | "auth" | "error" | "stack";
+type ActivityType =
+  | "sync"
+  | "add"
+  | "delete"
+  | "import"
+  | "auth"
+  | "error"
+  | "stack"
+  | "profile";
 
 const typeIcons: Record<
   ActivityType,
@@ -31,6 +40,7 @@ const typeIcons: Record<
   auth: Shield,
   error: AlertTriangle,
   stack: Layers,
+  profile: UserCog,
 };
 
 const typeColors: Record<ActivityType, string> = {
@@ -41,6 +51,7 @@ const typeColors: Record<ActivityType, string> = {
   auth: "text-warning bg-warning/10",
   error: "text-error bg-error/10",
   stack: "text-purple-400 bg-purple-500/10",
+  profile: "text-accent bg-accent/10",
 };
 
 // ── Day grouping ────────────────────────────────────────────────────
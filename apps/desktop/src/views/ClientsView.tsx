@@ -1,4 +1,4 @@
-import { useState, useCallback } from "react";
+import { useState, useCallback, useEffect } from "react";
 import {
   RefreshCw,
   ArrowDownToLine,
@@ -18,8 +18,14 @@ import { useConfigStore } from "@/stores/configStore";
 import { useSyncStore } from "@/stores/syncStore";
 import { useUIStore } from "@/stores/uiStore";
 import { ClientLogo } from "@/components/ClientLogo";
+import * as tauri from "@/lib/tauri";
 import { toast } from "sonner";
-import type { ClientDetection } from "@conductor/types";
+import type {
+  ClientDetection,
+  RemoteCapabilities,
+  NameResolution,
+  TargetNameConflicts,
+} from "@conductor/types";
 
 // ── Status Dot ──────────────────────────────────────────────────────
 
@@ -90,12 +96,20 @@ function StatusLabel({ status }: { status: SyncStatus }) {
 
 // ── Client Card ─────────────────────────────────────────────────────
 
+/** Which remote transports the client reads natively, for the card footer. */
+function remoteLabel(remote: RemoteCapabilities): string {
+  const native = [remote.sse && "SSE", remote.streamableHttp && "HTTP"].filter(Boolean);
+  if (native.length === 0) return "Remote via fallback";
+  return `Remote ${native.join(" + ")}${remote.headers ? "" : ", no headers"}`;
+}
+
 function ClientCard({ client }: { client: ClientDetection }) {
   const [syncing, setSyncing] = useState(false);
   const [importing, setImporting] = useState(false);
   const [flashGreen, setFlashGreen] = useState(false);
 
   const syncToClient = useClientStore((s) => s.syncToClient);
+  const setSyncTargets = useClientStore((s) => s.setSyncTargets);
   const importFromClient = useClientStore((s) => s.importFromClient);
   const fetchServers = useConfigStore((s) => s.fetchServers);
   const startSync = useSyncStore((s) => s.startSync);
@@ -162,6 +176,14 @@ function ClientCard({ client }: { client: ClientDetection }) {
       {/* Config path + stats */}
       <div className="flex items-center gap-3 mb-3 text-[11px] text-text-muted">
         <span>{client.serverCount} servers</span>
+        {client.detected && client.remote && (
+          <>
+            <span className="text-border">·</span>
+            <span title={client.version ? `Version ${client.version}` : undefined}>
+              {remoteLabel(client.remote)}
+            </span>
+          </>
+        )}
         {client.lastSyncedAt && (
           <>
             <span className="text-border">·</span>
@@ -169,10 +191,36 @@ function ClientCard({ client }: { client: ClientDetection }) {
           </>
         )}
       </div>
-      {client.configPath && (
-        <p className="text-[10px] text-text-muted/60 font-mono mb-3 truncate" title={client.configPath}>
-          {truncatePath(client.configPath)}
-        </p>
+      {client.targets && client.targets.length > 1 ? (
+        <div className="flex flex-col gap-0.5 mb-3">
+          {client.targets.map((target) => (
+            <label
+              key={target.id}
+              className="flex items-center gap-1.5 text-[10px] text-text-muted/60 font-mono truncate cursor-pointer"
+              title={target.configPath}
+            >
+              <input
+                type="checkbox"
+                className="accent-accent"
+                checked={target.selected}
+                onChange={() => {
+                  const selected = client.targets
+                    .filter((t) => (t.id === target.id ? !t.selected : t.selected))
+                    .map((t) => t.id);
+                  setSyncTargets(client.clientId, selected);
+                }}
+              />
+              <span className="font-sans font-medium text-text-muted">{target.label}</span>
+              <span className="truncate">{truncatePath(target.configPath)}</span>
+            </label>
+          ))}
+        </div>
+      ) : (
+        client.configPath && (
+          <p className="text-[10px] text-text-muted/60 font-mono mb-3 truncate" title={client.configPath}>
+            {truncatePath(client.configPath)}
+          </p>
+        )
       )}
 
       {/* Server chips */}
@@ -240,6 +288,17 @@ function ClientCard({ client }: { client: ClientDetection }) {
 
 // ── Main Clients View ───────────────────────────────────────────────
 
+function describeResolution(resolution: NameResolution): string {
+  switch (resolution.kind) {
+    case "renamed":
+      return `it will be written as "${resolution.name}"`;
+    case "skipped":
+      return "it will be skipped";
+    case "replaced":
+      return "your entry will be replaced";
+  }
+}
+
 export function ClientsView() {
   const clients = useClientStore((s) => s.clients);
   const loading = useClientStore((s) => s.loading);
@@ -248,6 +307,15 @@ export function ClientsView() {
   const servers = useConfigStore((s) => s.servers);
   const setActiveView = useUIStore((s) => s.setActiveView);
   const [syncingAll, setSyncingAll] = useState(false);
+  const [nameConflicts, setNameConflicts] = useState<TargetNameConflicts[]>([]);
+
+  // What the next push would do about servers named like the user's entries.
+  useEffect(() => {
+    tauri
+      .previewNameConflicts()
+      .then(setNameConflicts)
+      .catch(() => setNameConflicts([]));
+  }, [clients, servers]);
 
   const detectedCount = clients.filter((c) => c.detected).length;
   const detectedClients = clients.filter((c) => c.detected);
@@ -345,6 +413,37 @@ export function ClientsView() {
           </div>
         )}
 
+        {/* Name conflict banner */}
+        {nameConflicts.length > 0 && (
+          <div className="mb-4 flex items-start gap-3 rounded-xl border border-warning/30 bg-warning/5 p-3">
+            <AlertCircle className="w-4 h-4 text-warning shrink-0 mt-0.5" />
+            <div className="flex-1 min-w-0 space-y-1">
+              {nameConflicts.map((target) => (
+                <div key={`${target.clientId}:${target.targetId}`} className="text-xs text-text-secondary">
+                  <span className="font-medium text-text-primary">{target.targetLabel}</span>
+                  {target.conflicts.map((c) => (
+                    <p key={c.server}>
+                      "{c.server}" has the name of your entry "{c.existing}":{" "}
+                      {describeResolution(c.resolution)}
+                    </p>
+                  ))}
+                  {target.invalid.map((i) => (
+                    <p key={i.server}>
+                      "{i.server}" will be skipped: {i.reason}
+                    </p>
+                  ))}
+                </div>
+              ))}
+            </div>
+            <button
+              onClick={() => setActiveView("settings")}
+              className="text-xs text-accent hover:underline shrink-0"
+            >
+              Change policy
+            </button>
+          </div>
+        )}
+
         {loading && clients.length === 0 ? (
           <div className="grid grid-cols-1 md:grid-cols-2 gap-4">
             {[1, 2, 3, 4].map((i) => (
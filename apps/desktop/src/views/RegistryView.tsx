@@ -19,7 +19,7 @@ import {
   FolderOpen,
   Brain,
 } from "lucide-react";
-import { cn } from "@/lib/utils";
+import { cn, errorMessage } from "@/lib/utils";
 import * as tauri from "@/lib/tauri";
 import { useConfigStore } from "@/stores/configStore";
 import { ServerLogo } from "@/components/ServerLogo";
@@ -83,7 +83,7 @@ function InstallDialog({
       toast.success(`Installed ${server.displayName}`);
       setTimeout(() => onClose(), 1000);
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       toast.error("Installation failed", { description: message });
     }
     setInstalling(false);
@@ -448,7 +448,7 @@ export function RegistryView() {
       }
     } catch (err) {
       if (id === searchIdRef.current) {
-        const message = err instanceof Error ? err.message : String(err);
+        const message = errorMessage(err);
         toast.error("Search failed", { description: message });
         setResults([]);
       }
@@ -499,7 +499,7 @@ export function RegistryView() {
         })
         .catch((err) => {
           if (id === searchIdRef.current) {
-            const message = err instanceof Error ? err.message : String(err);
+            const message = errorMessage(err);
             toast.error("Search failed", { description: message });
             setResults([]);
           }
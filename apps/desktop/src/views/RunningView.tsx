@@ -0,0 +1,272 @@
+import { useState, useEffect, useCallback, useRef } from "react";
+import { RefreshCw, RotateCcw, Cpu, ScrollText, AlertTriangle } from "lucide-react";
+import { listen } from "@tauri-apps/api/event";
+import { toast } from "sonner";
+import { cn, formatRelativeTime, errorMessage } from "@/lib/utils";
+import * as tauri from "@/lib/tauri";
+import type {
+  ManagedServerStatus,
+  ProcessState,
+  CrashEvent,
+  ManagedServerLogLine,
+} from "@conductor/types";
+
+const POLL_INTERVAL_MS = 5000;
+const MAX_LOG_LINES = 1000;
+
+const stateStyles: Record<ProcessState, string> = {
+  running: "text-success bg-success/10",
+  starting: "text-accent bg-accent/10",
+  backoff: "text-warning bg-warning/10",
+};
+
+function formatUptime(secs?: number | null): string {
+  if (secs == null) return "—";
+  if (secs < 60) return `${secs}s`;
+  if (secs < 3600) return `${Math.floor(secs / 60)}m ${secs % 60}s`;
+  const hours = Math.floor(secs / 3600);
+  return hours < 24
+    ? `${hours}h ${Math.floor((secs % 3600) / 60)}m`
+    : `${Math.floor(hours / 24)}d ${hours % 24}h`;
+}
+
+function formatBytes(bytes?: number | null): string {
+  if (bytes == null) return "—";
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(0)} KB`;
+  if (bytes < 1024 * 1024 * 1024) return `${(bytes / 1024 / 1024).toFixed(1)} MB`;
+  return `${(bytes / 1024 / 1024 / 1024).toFixed(2)} GB`;
+}
+
+function exitSummary(status: ManagedServerStatus): string | null {
+  const exit = status.lastExit;
+  if (!exit) return null;
+  const reason = exit.error
+    ? `Failed to start: ${exit.error}`
+    : exit.code != null
+      ? `Exited with code ${exit.code}`
+      : "Killed by a signal";
+  return `${reason} ${formatRelativeTime(exit.at)}`;
+}
+
+// ── Process Row ─────────────────────────────────────────────────────
+
+function ProcessRow({
+  status,
+  selected,
+  onSelect,
+  onRestart,
+}: {
+  status: ManagedServerStatus;
+  selected: boolean;
+  onSelect: () => void;
+  onRestart: () => void;
+}) {
+  const exit = exitSummary(status);
+  return (
+    <div
+      onClick={onSelect}
+      className={cn(
+        "px-4 py-3 rounded-xl border cursor-pointer transition-colors",
+        selected ? "border-accent/50 bg-accent/5" : "border-border hover:bg-surface-3"
+      )}
+    >
+      <div className="flex items-center gap-3">
+        <span className="text-sm font-medium text-text-primary truncate flex-1">
+          {status.name}
+        </span>
+        <span
+          className={cn(
+            "text-[11px] font-medium px-2 py-0.5 rounded-full capitalize",
+            stateStyles[status.state]
+          )}
+        >
+          {status.state === "backoff" ? "restarting" : status.state}
+        </span>
+        <button
+          onClick={(e) => {
+            e.stopPropagation();
+            onRestart();
+          }}
+          title="Restart now"
+          className="text-text-muted hover:text-text-primary"
+        >
+          <RotateCcw className="w-4 h-4" />
+        </button>
+      </div>
+      <div className="grid grid-cols-5 gap-3 mt-2 text-[11px] text-text-muted">
+        <span>PID {status.pid ?? "—"}</span>
+        <span>Up {formatUptime(status.uptimeSecs)}</span>
+        <span>CPU {status.cpuPercent != null ? `${status.cpuPercent.toFixed(1)}%` : "—"}</span>
+        <span>RSS {formatBytes(status.memoryBytes)}</span>
+        <span>
+          {status.restarts} restart{status.restarts !== 1 && "s"}
+        </span>
+      </div>
+      {exit && (
+        <p className="flex items-center gap-1.5 mt-1.5 text-[11px] text-warning truncate">
+          <AlertTriangle className="w-3 h-3 shrink-0" />
+          {exit}
+          {status.nextRestartAt &&
+            status.state === "backoff" &&
+            ` · next try ${formatRelativeTime(status.nextRestartAt)}`}
+        </p>
+      )}
+    </div>
+  );
+}
+
+// ── Log Tail ────────────────────────────────────────────────────────
+
+function LogTail({ serverId }: { serverId: string }) {
+  const [lines, setLines] = useState<string[]>([]);
+  const bottomRef = useRef<HTMLDivElement>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    tauri
+      .getManagedServerLogs(serverId, 500)
+      .then((initial) => !cancelled && setLines(initial))
+      .catch(() => !cancelled && setLines([]));
+
+    const unlisten = listen<ManagedServerLogLine>("managed-server-log", (event) => {
+      if (event.payload.serverId !== serverId) return;
+      setLines((prev) => [...prev, event.payload.line].slice(-MAX_LOG_LINES));
+    });
+    return () => {
+      cancelled = true;
+      unlisten.then((fn) => fn());
+    };
+  }, [serverId]);
+
+  useEffect(() => {
+    bottomRef.current?.scrollIntoView({ block: "end" });
+  }, [lines]);
+
+  return (
+    <div className="h-full overflow-y-auto rounded-xl bg-surface-3 border border-border p-3 font-mono text-[11px] leading-relaxed">
+      {lines.length === 0 ? (
+        <p className="text-text-muted">No output yet.</p>
+      ) : (
+        lines.map((line, i) => (
+          <div
+            key={i}
+            className={cn(
+              "whitespace-pre-wrap break-all",
+              line.includes("[conductor]") ? "text-accent" : "text-text-secondary"
+            )}
+          >
+            {line}
+          </div>
+        ))
+      )}
+      <div ref={bottomRef} />
+    </div>
+  );
+}
+
+// ── Main Running View ───────────────────────────────────────────────
+
+export function RunningView() {
+  const [processes, setProcesses] = useState<ManagedServerStatus[]>([]);
+  const [selectedId, setSelectedId] = useState<string | null>(null);
+
+  const fetchProcesses = useCallback(async () => {
+    try {
+      setProcesses(await tauri.listManagedServers());
+    } catch (e) {
+      console.warn("Failed to fetch managed servers:", e);
+    }
+  }, []);
+
+  useEffect(() => {
+    fetchProcesses();
+    const interval = setInterval(fetchProcesses, POLL_INTERVAL_MS);
+    const unlisten = listen<CrashEvent>("managed-server-crashed", (event) => {
+      const { name, restartInSecs } = event.payload;
+      toast.error(`${name} stopped`, {
+        description: `Restarting in ${restartInSecs}s`,
+      });
+      fetchProcesses();
+    });
+    return () => {
+      clearInterval(interval);
+      unlisten.then((fn) => fn());
+    };
+  }, [fetchProcesses]);
+
+  const selected =
+    processes.find((p) => p.serverId === selectedId) ?? processes[0] ?? null;
+
+  const handleRestart = async (status: ManagedServerStatus) => {
+    try {
+      await tauri.restartManagedServer(status.serverId);
+      toast.success(`Restarting ${status.name}`);
+      setTimeout(fetchProcesses, 1000);
+    } catch (err) {
+      toast.error("Restart failed", { description: errorMessage(err) });
+    }
+  };
+
+  return (
+    <div className="h-full flex flex-col overflow-hidden">
+      {/* Top bar */}
+      <div className="shrink-0 px-6 py-5 border-b border-border">
+        <div className="flex items-center justify-between">
+          <div>
+            <h1 className="text-xl font-bold text-text-primary">Running</h1>
+            <p className="text-sm text-text-muted mt-0.5">
+              {processes.length} managed server{processes.length !== 1 && "s"}
+            </p>
+          </div>
+          <button
+            onClick={fetchProcesses}
+            className="flex items-center gap-2 h-9 px-3 rounded-lg border border-border text-sm
+              text-text-secondary hover:bg-surface-3 transition-colors"
+          >
+            <RefreshCw className="w-4 h-4" />
+          </button>
+        </div>
+      </div>
+
+      {processes.length === 0 ? (
+        <div className="flex flex-col items-center justify-center py-20">
+          <div className="w-20 h-20 rounded-2xl bg-surface-3 flex items-center justify-center mb-6">
+            <Cpu className="w-10 h-10 text-text-muted" />
+          </div>
+          <h3 className="text-lg font-semibold text-text-primary mb-2">
+            No managed servers
+          </h3>
+          <p className="text-sm text-text-muted text-center max-w-[320px]">
+            Turn on "Run as managed process" for a stdio server and Conductor
+            will run one copy that every client shares.
+          </p>
+        </div>
+      ) : (
+        <div className="flex-1 flex gap-4 overflow-hidden px-6 py-4">
+          <div className="w-[420px] shrink-0 overflow-y-auto space-y-2">
+            {processes.map((status) => (
+              <ProcessRow
+                key={status.serverId}
+                status={status}
+                selected={status.serverId === selected?.serverId}
+                onSelect={() => setSelectedId(status.serverId)}
+                onRestart={() => handleRestart(status)}
+              />
+            ))}
+          </div>
+          {selected && (
+            <div className="flex-1 flex flex-col min-w-0">
+              <h4 className="flex items-center gap-1.5 text-xs font-semibold text-text-muted uppercase tracking-wider mb-3">
+                <ScrollText className="w-3.5 h-3.5" />
+                {selected.name} output
+              </h4>
+              <div className="flex-1 min-h-0">
+                <LogTail serverId={selected.serverId} />
+              </div>
+            </div>
+          )}
+        </div>
+      )}
+    </div>
+  );
+}
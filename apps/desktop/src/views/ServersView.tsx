@@ -15,9 +15,16 @@ import {
   ShieldX,
   Loader2,
   AlertTriangle,
+  Wrench,
+  Activity,
+  ArrowRight,
+  ArrowLeft,
+  Play,
+  Cpu,
+  Variable,
 } from "lucide-react";
 import { listen } from "@tauri-apps/api/event";
-import { cn, formatRelativeTime } from "@/lib/utils";
+import { cn, formatRelativeTime, errorMessage } from "@/lib/utils";
 import { useConfigStore } from "@/stores/configStore";
 import { useClientStore } from "@/stores/clientStore";
 import { useUIStore } from "@/stores/uiStore";
@@ -25,7 +32,15 @@ import { ServerLogo } from "@/components/ServerLogo";
 import { AddServerModal } from "@/components/AddServerModal";
 import * as tauri from "@/lib/tauri";
 import { toast } from "sonner";
-import type { McpServer, TransportType, OAuthStatus } from "@conductor/types";
+import type {
+  McpServer,
+  TransportType,
+  OAuthStatus,
+  ToolCatalog,
+  ToolFilter,
+  ServerPreview,
+  TrafficEntry,
+} from "@conductor/types";
 
 // ── Transport Badge ─────────────────────────────────────────────────
 
@@ -375,7 +390,7 @@ function ServerDetailPanel({
         description: "Complete the sign-in in your browser, then return here.",
       });
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       toast.error("Authorization failed", { description: message });
     }
     setAuthLoading(false);
@@ -387,7 +402,7 @@ function ServerDetailPanel({
       setAuthStatus((prev) => prev ? { ...prev, authenticated: false, provider: undefined, expiresAt: undefined } : null);
       toast.success("Credentials revoked");
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       toast.error("Revoke failed", { description: message });
     }
   }, [server.id]);
@@ -685,6 +700,14 @@ function ServerDetailPanel({
             </section>
           )}
 
+          <ResolvedSection server={server} />
+
+          {server.transport === "stdio" && <ProcessSection server={server} />}
+
+          <ToolsSection server={server} />
+
+          <InspectorSection server={server} />
+
           {/* Metadata */}
           <section>
             <h4 className="text-xs font-semibold text-text-muted uppercase tracking-wider mb-3">
@@ -754,6 +777,457 @@ function ServerDetailPanel({
   );
 }
 
+// ── Resolved ────────────────────────────────────────────────────────
+
+/** What clients receive once `${NAME}` references are filled in. Only shown
+ * for servers that use variables. */
+function ResolvedSection({ server }: { server: McpServer }) {
+  const [preview, setPreview] = useState<ServerPreview | null>(null);
+  const usesVariables = JSON.stringify(server).includes("${");
+
+  useEffect(() => {
+    if (!usesVariables) return;
+    let cancelled = false;
+    tauri
+      .previewServer(server)
+      .then((p) => !cancelled && setPreview(p))
+      .catch(() => !cancelled && setPreview(null));
+    return () => {
+      cancelled = true;
+    };
+  }, [server, usesVariables]);
+
+  if (!usesVariables || !preview) return null;
+  const resolved = preview.server;
+  const commandLine = [resolved.command, ...(resolved.args ?? [])].filter(Boolean).join(" ");
+  const env = Object.entries(resolved.env ?? {});
+
+  return (
+    <section>
+      <h4 className="flex items-center gap-1.5 text-xs font-semibold text-text-muted uppercase tracking-wider mb-3">
+        <Variable className="w-3.5 h-3.5" />
+        Resolved
+      </h4>
+      {preview.undefined.length > 0 && (
+        <p className="flex items-center gap-1.5 mb-2 text-xs text-warning">
+          <AlertTriangle className="w-3.5 h-3.5 shrink-0" />
+          Undefined: {preview.undefined.join(", ")}. Define them in Settings
+          before syncing.
+        </p>
+      )}
+      <div className="rounded-lg bg-surface-3 p-3 space-y-1 text-xs font-mono text-text-secondary break-all">
+        {commandLine && <p>{commandLine}</p>}
+        {resolved.url && <p>{resolved.url}</p>}
+        {env.map(([key, value]) => (
+          <p key={key}>
+            <span className="text-text-muted">{key}=</span>
+            {value}
+          </p>
+        ))}
+      </div>
+    </section>
+  );
+}
+
+// ── Process ─────────────────────────────────────────────────────────
+
+function ProcessSection({ server }: { server: McpServer }) {
+  const fetchServers = useConfigStore((s) => s.fetchServers);
+  const setActiveView = useUIStore((s) => s.setActiveView);
+
+  const handleToggle = async (managed: boolean) => {
+    try {
+      await tauri.updateServer(server.id, { managed });
+      await fetchServers();
+      toast.success(managed ? "Server is now managed" : "Server is no longer managed", {
+        description: "Sync so clients pick up the change.",
+      });
+    } catch (err) {
+      toast.error("Failed to update server", { description: errorMessage(err) });
+    }
+  };
+
+  return (
+    <section>
+      <h4 className="text-xs font-semibold text-text-muted uppercase tracking-wider mb-3">
+        Process
+      </h4>
+      <div className="flex items-center justify-between">
+        <div>
+          <p className="text-sm text-text-primary">Run as managed process</p>
+          <p className="text-[11px] text-text-muted">
+            Conductor runs one copy for every client, restarts it when it
+            crashes and keeps its logs.
+          </p>
+        </div>
+        <ToggleSwitch enabled={!!server.managed} onChange={handleToggle} />
+      </div>
+      {server.managed && (
+        <button
+          onClick={() => setActiveView("running")}
+          className="flex items-center gap-1 mt-2 text-xs text-accent hover:text-accent/80"
+        >
+          <Cpu className="w-3 h-3" />
+          View process and logs
+        </button>
+      )}
+    </section>
+  );
+}
+
+// ── Tools ───────────────────────────────────────────────────────────
+
+/** Same matching as the backend: `*` and `?` wildcards, `[...]` classes. */
+function globMatches(pattern: string, name: string): boolean {
+  const source = pattern
+    .split("")
+    .map((c) => (c === "*" ? ".*" : c === "?" ? "." : /[\\^$.|+(){}]/.test(c) ? `\\${c}` : c))
+    .join("");
+  try {
+    return new RegExp(`^${source}$`).test(name);
+  } catch {
+    return pattern === name;
+  }
+}
+
+function permits(filter: ToolFilter, name: string): boolean {
+  const allow = filter.allow ?? [];
+  const deny = filter.deny ?? [];
+  return (
+    (allow.length === 0 || allow.some((p) => globMatches(p, name))) &&
+    !deny.some((p) => globMatches(p, name))
+  );
+}
+
+const splitPatterns = (value: string) =>
+  value.split(/[\s,]+/).filter(Boolean);
+
+function ToolsSection({ server }: { server: McpServer }) {
+  const clients = useClientStore((s) => s.clients);
+  const fetchServers = useConfigStore((s) => s.fetchServers);
+  const [catalog, setCatalog] = useState<ToolCatalog | null>(null);
+  const [discovering, setDiscovering] = useState(false);
+  const [clientId, setClientId] = useState("*");
+  const [allow, setAllow] = useState("");
+  const [deny, setDeny] = useState("");
+
+  // Reset the inputs when switching clients or after a save.
+  useEffect(() => {
+    const current = server.toolFilters?.[clientId];
+    setAllow((current?.allow ?? []).join(", "));
+    setDeny((current?.deny ?? []).join(", "));
+  }, [clientId, server.toolFilters]);
+
+  const discover = useCallback(
+    async (refresh: boolean) => {
+      setDiscovering(true);
+      try {
+        setCatalog(await tauri.getServerTools(server.id, refresh));
+      } catch (err) {
+        if (refresh) {
+          toast.error("Tool discovery failed", { description: errorMessage(err) });
+        }
+      }
+      setDiscovering(false);
+    },
+    [server.id]
+  );
+
+  // Show the cached catalog without starting the server.
+  useEffect(() => {
+    tauri
+      .getServerTools(server.id, false)
+      .then(setCatalog)
+      .catch(() => setCatalog(null));
+  }, [server.id]);
+
+  const draft: ToolFilter = { allow: splitPatterns(allow), deny: splitPatterns(deny) };
+
+  const handleSave = async () => {
+    try {
+      await tauri.setToolFilter(server.id, clientId, draft);
+      await fetchServers();
+      toast.success("Tool filter saved", {
+        description: "Sync to apply it to your clients.",
+      });
+    } catch (err) {
+      toast.error("Failed to save tool filter", { description: errorMessage(err) });
+    }
+  };
+
+  const filtered = Object.keys(server.toolFilters ?? {});
+
+  return (
+    <section>
+      <div className="flex items-center justify-between mb-3">
+        <h4 className="text-xs font-semibold text-text-muted uppercase tracking-wider">
+          Tools
+        </h4>
+        <button
+          onClick={() => discover(true)}
+          disabled={discovering}
+          className="flex items-center gap-1 text-xs text-accent hover:text-accent/80 disabled:opacity-60"
+        >
+          {discovering ? (
+            <Loader2 className="w-3 h-3 animate-spin" />
+          ) : (
+            <RefreshCw className="w-3 h-3" />
+          )}
+          {catalog ? "Rediscover" : "Discover"}
+        </button>
+      </div>
+      <div className="space-y-3">
+        <div className="flex items-center gap-2">
+          <label className="text-[11px] text-text-muted shrink-0">Client</label>
+          <select
+            value={clientId}
+            onChange={(e) => setClientId(e.target.value)}
+            className="h-8 flex-1 px-2 rounded-lg bg-surface-3 border border-border text-sm text-text-primary
+              outline-none focus:ring-1 focus:ring-accent/50"
+          >
+            <option value="*">All clients{filtered.includes("*") ? " •" : ""}</option>
+            {clients.map((c) => (
+              <option key={c.clientId} value={c.clientId}>
+                {c.displayName}
+                {filtered.includes(c.clientId) ? " •" : ""}
+              </option>
+            ))}
+          </select>
+        </div>
+        {(["allow", "deny"] as const).map((kind) => (
+          <div key={kind}>
+            <label className="text-[11px] text-text-muted">
+              {kind === "allow" ? "Allow (empty allows all)" : "Deny"}
+            </label>
+            <input
+              type="text"
+              value={kind === "allow" ? allow : deny}
+              onChange={(e) => (kind === "allow" ? setAllow : setDeny)(e.target.value)}
+              placeholder={kind === "allow" ? "get_*, list_*" : "delete_*"}
+              className="w-full h-8 px-2 rounded-lg bg-surface-3 border border-border text-sm text-text-primary font-mono
+                placeholder:text-text-muted outline-none focus:ring-1 focus:ring-accent/50"
+            />
+          </div>
+        ))}
+        <button
+          onClick={handleSave}
+          className="h-8 px-3 rounded-lg bg-accent text-white text-xs font-medium hover:bg-accent/90 transition-colors"
+        >
+          Save filter
+        </button>
+
+        {catalog ? (
+          <div className="space-y-1">
+            <p className="text-[11px] text-text-muted">
+              {catalog.tools.filter((t) => permits(draft, t.name)).length} of{" "}
+              {catalog.tools.length} visible · discovered{" "}
+              {formatRelativeTime(catalog.discoveredAt)}
+            </p>
+            {catalog.tools.map((tool) => (
+              <div
+                key={tool.name}
+                title={tool.description}
+                className={cn(
+                  "flex items-center gap-2 text-sm font-mono bg-surface-3 px-3 py-1.5 rounded",
+                  !permits(draft, tool.name) && "opacity-40 line-through"
+                )}
+              >
+                <Wrench className="w-3 h-3 text-text-muted shrink-0" />
+                <span className="truncate">{tool.name}</span>
+              </div>
+            ))}
+          </div>
+        ) : (
+          <p className="text-[11px] text-text-muted">
+            Discover to list this server's tools. Patterns can still be set without it.
+          </p>
+        )}
+      </div>
+    </section>
+  );
+}
+
+// ── Inspector ───────────────────────────────────────────────────────
+
+function InspectorSection({ server }: { server: McpServer }) {
+  const fetchServers = useConfigStore((s) => s.fetchServers);
+  const [entries, setEntries] = useState<TrafficEntry[]>([]);
+  const [errorsOnly, setErrorsOnly] = useState(false);
+  const [loading, setLoading] = useState(false);
+  const [replaying, setReplaying] = useState<string | null>(null);
+
+  const refresh = useCallback(async () => {
+    setLoading(true);
+    try {
+      setEntries(
+        await tauri.queryTraffic(server.id, { errorsOnly, limit: 100 })
+      );
+    } catch {
+      setEntries([]);
+    }
+    setLoading(false);
+  }, [server.id, errorsOnly]);
+
+  useEffect(() => {
+    refresh();
+  }, [refresh]);
+
+  const handleToggle = async (inspect: boolean) => {
+    try {
+      await tauri.updateServer(server.id, { inspect });
+      await fetchServers();
+      toast.success(inspect ? "Recording traffic" : "Recording stopped", {
+        description: "Sync so clients pick up the change.",
+      });
+    } catch (err) {
+      toast.error("Failed to update server", { description: errorMessage(err) });
+    }
+  };
+
+  const handleExport = async () => {
+    try {
+      const path = await tauri.exportTrafficHar(server.id, { errorsOnly });
+      toast.success("Traffic exported", { description: path });
+    } catch (err) {
+      toast.error("Export failed", { description: errorMessage(err) });
+    }
+  };
+
+  const handleClear = async () => {
+    try {
+      await tauri.clearTraffic(server.id);
+      setEntries([]);
+    } catch (err) {
+      toast.error("Failed to clear traffic", { description: errorMessage(err) });
+    }
+  };
+
+  const handleReplay = async (entry: TrafficEntry) => {
+    setReplaying(entry.id);
+    try {
+      const result = await tauri.replayTraffic(server.id, entry.id);
+      toast.success(`Replayed ${entry.method} in ${result.durationMs.toFixed(0)} ms`, {
+        description: JSON.stringify(result.response).slice(0, 200),
+      });
+      await refresh();
+    } catch (err) {
+      toast.error("Replay failed", { description: errorMessage(err) });
+    }
+    setReplaying(null);
+  };
+
+  return (
+    <section>
+      <div className="flex items-center justify-between mb-3">
+        <h4 className="text-xs font-semibold text-text-muted uppercase tracking-wider">
+          Inspector
+        </h4>
+        <button
+          onClick={refresh}
+          disabled={loading}
+          className="flex items-center gap-1 text-xs text-accent hover:text-accent/80 disabled:opacity-60"
+        >
+          <RefreshCw className={cn("w-3 h-3", loading && "animate-spin")} />
+          Refresh
+        </button>
+      </div>
+      <div className="space-y-3">
+        <div className="flex items-center justify-between">
+          <div>
+            <p className="text-sm text-text-primary">Record traffic</p>
+            <p className="text-[11px] text-text-muted">
+              Logs every message with secrets redacted.
+            </p>
+          </div>
+          <ToggleSwitch enabled={!!server.inspect} onChange={handleToggle} />
+        </div>
+        <div className="flex items-center gap-3">
+          <label className="flex items-center gap-1.5 text-[11px] text-text-muted">
+            <input
+              type="checkbox"
+              checked={errorsOnly}
+              onChange={(e) => setErrorsOnly(e.target.checked)}
+            />
+            Errors only
+          </label>
+          <div className="flex-1" />
+          <button
+            onClick={handleExport}
+            disabled={entries.length === 0}
+            className="flex items-center gap-1 text-xs text-accent hover:text-accent/80 disabled:opacity-60"
+          >
+            <Download className="w-3 h-3" />
+            Export HAR
+          </button>
+          <button
+            onClick={handleClear}
+            disabled={entries.length === 0}
+            className="flex items-center gap-1 text-xs text-error hover:text-error/80 disabled:opacity-60"
+          >
+            <Trash2 className="w-3 h-3" />
+            Clear
+          </button>
+        </div>
+
+        {entries.length > 0 ? (
+          <div className="space-y-1 max-h-72 overflow-y-auto">
+            {entries
+              .filter((e) => e.kind !== "session")
+              .map((entry) => (
+                <div
+                  key={entry.id}
+                  title={JSON.stringify(entry.message, null, 2)}
+                  className={cn(
+                    "flex items-center gap-2 text-xs font-mono bg-surface-3 px-3 py-1.5 rounded",
+                    entry.isError && "text-error"
+                  )}
+                >
+                  {entry.direction === "clientToServer" ? (
+                    <ArrowRight className="w-3 h-3 text-text-muted shrink-0" />
+                  ) : (
+                    <ArrowLeft className="w-3 h-3 text-text-muted shrink-0" />
+                  )}
+                  <span className="truncate flex-1">
+                    {entry.method ?? entry.kind}
+                    {entry.clientId ? ` · ${entry.clientId}` : ""}
+                  </span>
+                  {entry.durationMs != null && (
+                    <span className="text-text-muted shrink-0">
+                      {entry.durationMs.toFixed(0)} ms
+                    </span>
+                  )}
+                  {entry.kind === "request" &&
+                    entry.direction === "clientToServer" &&
+                    entry.method !== "initialize" && (
+                      <button
+                        onClick={() => handleReplay(entry)}
+                        disabled={replaying !== null}
+                        title="Replay"
+                        className="text-accent hover:text-accent/80 disabled:opacity-60 shrink-0"
+                      >
+                        {replaying === entry.id ? (
+                          <Loader2 className="w-3 h-3 animate-spin" />
+                        ) : (
+                          <Play className="w-3 h-3" />
+                        )}
+                      </button>
+                    )}
+                </div>
+              ))}
+          </div>
+        ) : (
+          <p className="flex items-center gap-1.5 text-[11px] text-text-muted">
+            <Activity className="w-3 h-3" />
+            {server.inspect
+              ? "No traffic recorded yet."
+              : "Turn on recording and sync to capture this server's traffic."}
+          </p>
+        )}
+      </div>
+    </section>
+  );
+}
+
 // ── Empty State ─────────────────────────────────────────────────────
 
 function EmptyState() {
@@ -13,12 +13,29 @@ import {
   Loader2,
   ArrowUpCircle,
   CheckCircle2,
+  Variable,
+  KeyRound,
+  Plus,
+  Layers,
+  Lock,
 } from "lucide-react";
-import { cn } from "@/lib/utils";
+import { cn, errorMessage } from "@/lib/utils";
 import * as tauri from "@/lib/tauri";
 import { open } from "@tauri-apps/plugin-shell";
+import { listen } from "@tauri-apps/api/event";
+import { useConfigStore } from "@/stores/configStore";
 import { toast } from "sonner";
-import type { AppSettings } from "@conductor/types";
+import type {
+  AppSettings,
+  GatewayStatus,
+  RemoteFallback,
+  NameConflictPolicy,
+  SecretBackend,
+  VariableInfo,
+  Profile,
+  ProfileList,
+  UpdateProfileRequest,
+} from "@conductor/types";
 
 // ── Toggle Component ────────────────────────────────────────────────
 
@@ -94,6 +111,557 @@ function SettingRow({
   );
 }
 
+// ── Variables Section ───────────────────────────────────────────────
+
+const VARIABLE_NAME = /^[A-Za-z_][A-Za-z0-9_]*$/;
+
+/** Global variables, or a profile's own when `profileId` is set. */
+function VariablesSection({
+  profileId,
+  envAllowlist,
+  onEnvAllowlistChange,
+}: {
+  profileId?: string;
+  envAllowlist?: string[];
+  onEnvAllowlistChange?: (patterns: string[]) => void;
+}) {
+  const [variables, setVariables] = useState<VariableInfo[]>([]);
+  const [name, setName] = useState("");
+  const [value, setValue] = useState("");
+  const [secret, setSecret] = useState(false);
+
+  const refresh = useCallback(() => {
+    tauri.getVariables(profileId).then(setVariables).catch(() => {});
+  }, [profileId]);
+
+  useEffect(refresh, [refresh, envAllowlist]);
+
+  const handleAdd = async () => {
+    try {
+      await tauri.setVariable(name.trim(), value, secret, profileId);
+      setName("");
+      setValue("");
+      setSecret(false);
+      refresh();
+    } catch (err) {
+      toast.error("Failed to save variable", { description: errorMessage(err) });
+    }
+  };
+
+  const handleDelete = async (variable: VariableInfo) => {
+    try {
+      await tauri.deleteVariable(variable.name, profileId);
+      refresh();
+    } catch (err) {
+      toast.error(`Failed to delete ${variable.name}`, { description: errorMessage(err) });
+    }
+  };
+
+  const defined = variables.filter((v) =>
+    profileId ? v.source === "profile" : v.source !== "environment"
+  );
+  const environment = variables.filter((v) => v.source === "environment");
+
+  return (
+    <div className="space-y-4">
+      {!profileId && (
+        <p className="text-xs text-text-muted">
+          Reference these as <code className="font-mono">{"${NAME}"}</code> in a
+          server's command, arguments, env, URL or headers. Write{" "}
+          <code className="font-mono">{"$${NAME}"}</code> to pass the text through
+          unchanged. A profile's own variables take precedence.
+        </p>
+      )}
+
+      {defined.length > 0 && (
+        <div className="divide-y divide-border rounded-lg border border-border">
+          {defined.map((v) => (
+            <div key={v.name} className="flex items-center gap-3 px-3 py-2">
+              {v.secret ? (
+                <KeyRound className="w-3.5 h-3.5 text-warning shrink-0" />
+              ) : (
+                <Variable className="w-3.5 h-3.5 text-text-muted shrink-0" />
+              )}
+              <span className="text-sm font-mono text-text-primary">{v.name}</span>
+              <span className="flex-1 text-xs font-mono text-text-muted truncate">
+                {v.secret ? "stored in keychain" : v.value}
+              </span>
+              {v.usedBy.length > 0 && (
+                <span className="text-[11px] text-text-muted" title={v.usedBy.join(", ")}>
+                  {v.usedBy.length} server{v.usedBy.length !== 1 && "s"}
+                </span>
+              )}
+              <button
+                onClick={() => handleDelete(v)}
+                title="Delete"
+                className="text-text-muted hover:text-error"
+              >
+                <Trash2 className="w-3.5 h-3.5" />
+              </button>
+            </div>
+          ))}
+        </div>
+      )}
+
+      <div className="flex items-center gap-2">
+        <input
+          value={name}
+          onChange={(e) => setName(e.target.value)}
+          placeholder="NAME"
+          className="h-8 w-40 px-2 rounded-lg bg-surface-3 border border-border text-sm text-text-primary font-mono
+            placeholder:text-text-muted outline-none focus:ring-1 focus:ring-accent/50"
+        />
+        <input
+          type={secret ? "password" : "text"}
+          value={value}
+          onChange={(e) => setValue(e.target.value)}
+          placeholder="value"
+          className="h-8 flex-1 px-2 rounded-lg bg-surface-3 border border-border text-sm text-text-primary font-mono
+            placeholder:text-text-muted outline-none focus:ring-1 focus:ring-accent/50"
+        />
+        <label className="flex items-center gap-1.5 text-xs text-text-secondary">
+          <input type="checkbox" checked={secret} onChange={(e) => setSecret(e.target.checked)} />
+          Secret
+        </label>
+        <button
+          onClick={handleAdd}
+          disabled={!VARIABLE_NAME.test(name.trim())}
+          className="flex items-center gap-1.5 h-8 px-3 rounded-lg bg-accent text-white text-xs font-medium
+            hover:bg-accent/90 disabled:opacity-50 transition-colors"
+        >
+          <Plus className="w-3.5 h-3.5" />
+          Set
+        </button>
+      </div>
+
+      {envAllowlist && onEnvAllowlistChange && (
+        <SettingRow
+          label="Environment variables"
+          description={
+            environment.length > 0
+              ? `Readable: ${environment.map((v) => v.name).join(", ")}`
+              : "Glob patterns of environment variables servers may reference"
+          }
+        >
+          <input
+            type="text"
+            key={envAllowlist.join(" ")}
+            defaultValue={envAllowlist.join(" ")}
+            onBlur={(e) => onEnvAllowlistChange(e.target.value.split(/\s+/).filter(Boolean))}
+            placeholder="HOME GITHUB_*"
+            className="h-8 w-56 px-2 rounded-lg bg-surface-3 border border-border text-sm text-text-primary font-mono
+              placeholder:text-text-muted outline-none focus:ring-1 focus:ring-accent/50"
+          />
+        </SettingRow>
+      )}
+    </div>
+  );
+}
+
+// ── Profiles Section ────────────────────────────────────────────────
+
+function ProfileEditor({
+  profile,
+  onChange,
+}: {
+  profile: Profile;
+  onChange: () => void;
+}) {
+  const servers = useConfigStore((s) => s.servers);
+
+  const update = async (request: UpdateProfileRequest) => {
+    try {
+      await tauri.updateProfile(profile.id, request);
+      onChange();
+    } catch (err) {
+      toast.error("Failed to update profile", { description: errorMessage(err) });
+    }
+  };
+
+  const toggleServer = (serverId: string, included: boolean) =>
+    update({
+      servers: included
+        ? [...profile.servers, serverId]
+        : profile.servers.filter((id) => id !== serverId),
+    });
+
+  return (
+    <div className="space-y-4 px-3 py-3 border-t border-border">
+      <div>
+        <p className="text-xs font-medium text-text-secondary mb-2">Servers</p>
+        <div className="grid grid-cols-2 gap-1.5">
+          {servers.map((server) => (
+            <label
+              key={server.id}
+              className="flex items-center gap-2 text-xs text-text-primary truncate"
+            >
+              <input
+                type="checkbox"
+                checked={profile.servers.includes(server.id)}
+                onChange={(e) => toggleServer(server.id, e.target.checked)}
+              />
+              {server.displayName || server.name}
+            </label>
+          ))}
+        </div>
+      </div>
+      <div>
+        <p className="text-xs font-medium text-text-secondary mb-1">Project directories</p>
+        <p className="text-[11px] text-text-muted mb-2">
+          Client configs inside these directories get this profile's servers,
+          whichever profile is active. One per line.
+        </p>
+        <textarea
+          key={(profile.projects ?? []).join("\n")}
+          defaultValue={(profile.projects ?? []).join("\n")}
+          onBlur={(e) => update({ projects: e.target.value.split("\n") })}
+          rows={2}
+          placeholder="/Users/me/code/client-x"
+          className="w-full px-2 py-1.5 rounded-lg bg-surface-3 border border-border text-xs text-text-primary font-mono
+            placeholder:text-text-muted outline-none focus:ring-1 focus:ring-accent/50 resize-none"
+        />
+      </div>
+      <div>
+        <p className="text-xs font-medium text-text-secondary mb-2">Variables</p>
+        <VariablesSection profileId={profile.id} />
+      </div>
+    </div>
+  );
+}
+
+function ProfilesSection() {
+  const [list, setList] = useState<ProfileList>({ profiles: [] });
+  const [expandedId, setExpandedId] = useState<string | null>(null);
+  const [newName, setNewName] = useState("");
+  const [switching, setSwitching] = useState(false);
+
+  const refresh = useCallback(() => {
+    tauri.listProfiles().then(setList).catch(() => {});
+  }, []);
+
+  useEffect(() => {
+    refresh();
+    const unlisten = listen("profile-changed", refresh);
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [refresh]);
+
+  const handleCreate = async () => {
+    try {
+      const profile = await tauri.createProfile(newName);
+      setNewName("");
+      setExpandedId(profile.id);
+      refresh();
+    } catch (err) {
+      toast.error("Failed to create profile", { description: errorMessage(err) });
+    }
+  };
+
+  const handleSwitch = async (profileId: string | null) => {
+    setSwitching(true);
+    try {
+      const results = await tauri.switchProfile(profileId);
+      const failed = results.filter((r) => !r.success).length;
+      if (failed > 0) {
+        toast.warning("Switched profile with sync errors", {
+          description: `${failed} client${failed !== 1 ? "s" : ""} failed to sync.`,
+        });
+      } else {
+        toast.success("Profile switched", { description: "All clients re-synced." });
+      }
+    } catch (err) {
+      toast.error("Failed to switch profile", { description: errorMessage(err) });
+    } finally {
+      setSwitching(false);
+      refresh();
+    }
+  };
+
+  const handleDelete = async (profile: Profile) => {
+    try {
+      await tauri.deleteProfile(profile.id);
+      refresh();
+    } catch (err) {
+      toast.error(`Failed to delete ${profile.name}`, { description: errorMessage(err) });
+    }
+  };
+
+  const active = list.activeProfile ?? null;
+
+  return (
+    <div className="space-y-4">
+      <p className="text-xs text-text-muted">
+        Each profile has its own set of enabled servers, variables and
+        keychain secrets. Switching re-syncs every client; the tray menu
+        switches too.
+      </p>
+
+      {list.profiles.length > 0 && (
+        <div className="divide-y divide-border rounded-lg border border-border">
+          {[...list.profiles, null].map((profile) => {
+            const id = profile?.id ?? null;
+            return (
+              <div key={id ?? "none"}>
+                <div className="flex items-center gap-3 px-3 py-2">
+                  <input
+                    type="radio"
+                    checked={active === id}
+                    disabled={switching}
+                    onChange={() => handleSwitch(id)}
+                  />
+                  <span className="flex-1 text-sm text-text-primary">
+                    {profile ? profile.name : "No profile"}
+                  </span>
+                  {profile && (
+                    <>
+                      <span className="text-[11px] text-text-muted">
+                        {profile.servers.length} server{profile.servers.length !== 1 && "s"}
+                        {(profile.projects?.length ?? 0) > 0 &&
+                          ` · ${profile.projects!.length} project${profile.projects!.length !== 1 ? "s" : ""}`}
+                      </span>
+                      <button
+                        onClick={() => setExpandedId(expandedId === id ? null : id)}
+                        className="text-xs text-accent hover:text-accent/80"
+                      >
+                        {expandedId === id ? "Done" : "Edit"}
+                      </button>
+                      <button
+                        onClick={() => handleDelete(profile)}
+                        title="Delete"
+                        className="text-text-muted hover:text-error"
+                      >
+                        <Trash2 className="w-3.5 h-3.5" />
+                      </button>
+                    </>
+                  )}
+                </div>
+                {profile && expandedId === id && (
+                  <ProfileEditor profile={profile} onChange={refresh} />
+                )}
+              </div>
+            );
+          })}
+        </div>
+      )}
+
+      <div className="flex items-center gap-2">
+        <input
+          value={newName}
+          onChange={(e) => setNewName(e.target.value)}
+          placeholder="Profile name"
+          className="h-8 flex-1 px-2 rounded-lg bg-surface-3 border border-border text-sm text-text-primary
+            placeholder:text-text-muted outline-none focus:ring-1 focus:ring-accent/50"
+        />
+        <button
+          onClick={handleCreate}
+          disabled={!newName.trim()}
+          className="flex items-center gap-1.5 h-8 px-3 rounded-lg bg-accent text-white text-xs font-medium
+            hover:bg-accent/90 disabled:opacity-50 transition-colors"
+        >
+          <Plus className="w-3.5 h-3.5" />
+          Create from enabled servers
+        </button>
+      </div>
+    </div>
+  );
+}
+
+// ── Secrets Section ─────────────────────────────────────────────────
+
+const SECRET_BACKENDS: { value: SecretBackend; label: string }[] = [
+  { value: "keyring", label: "System keychain" },
+  { value: "encryptedFile", label: "Encrypted file" },
+  { value: "pass", label: "pass" },
+  { value: "onePassword", label: "1Password (op)" },
+  { value: "bitwarden", label: "Bitwarden (bw)" },
+  { value: "vault", label: "HashiCorp Vault" },
+];
+
+function backendLabel(backend: SecretBackend) {
+  return SECRET_BACKENDS.find((b) => b.value === backend)?.label ?? backend;
+}
+
+/**
+ * Picks where secrets are kept. Choosing another backend checks it can be
+ * used, then moves every stored secret over before switching.
+ */
+function SecretsSection({
+  settings,
+  updateSetting,
+  onMigrated,
+}: {
+  settings: AppSettings;
+  updateSetting: <K extends keyof AppSettings>(key: K, value: AppSettings[K]) => void;
+  onMigrated: () => void;
+}) {
+  const [target, setTarget] = useState<SecretBackend>(settings.secretBackend);
+  const [problem, setProblem] = useState<string | null>(null);
+  const [recheck, setRecheck] = useState(0);
+  const [passphrase, setPassphrase] = useState("");
+  const [moving, setMoving] = useState(false);
+
+  useEffect(() => setTarget(settings.secretBackend), [settings.secretBackend]);
+
+  useEffect(() => {
+    let cancelled = false;
+    setProblem(null);
+    tauri.checkSecretBackend(target).catch((e) => {
+      if (!cancelled) setProblem(errorMessage(e));
+    });
+    return () => {
+      cancelled = true;
+    };
+  }, [target, recheck, settings.onepasswordVault, settings.vaultAddress, settings.vaultMount]);
+
+  const handleUnlock = async () => {
+    try {
+      await tauri.unlockSecretFile(passphrase);
+      setPassphrase("");
+      setRecheck((n) => n + 1);
+      toast.success("Secrets file unlocked");
+    } catch (err) {
+      toast.error("Failed to unlock secrets file", { description: errorMessage(err) });
+    }
+  };
+
+  const handleMove = async () => {
+    setMoving(true);
+    try {
+      const result = await tauri.migrateSecrets(target);
+      if (result.failed.length > 0) {
+        toast.error(`${result.failed.length} secrets couldn't be moved`, {
+          description: result.failed.map((f) => `${f.key}: ${f.error}`).join("\n"),
+        });
+      } else {
+        toast.success(`Moved ${result.moved} secrets to ${backendLabel(target)}`);
+        onMigrated();
+      }
+    } catch (err) {
+      toast.error("Failed to move secrets", { description: errorMessage(err) });
+    } finally {
+      setMoving(false);
+    }
+  };
+
+  const inputClass = `h-8 w-56 px-2 rounded-lg bg-surface-3 border border-border text-sm text-text-primary font-mono
+    placeholder:text-text-muted outline-none focus:ring-1 focus:ring-accent/50`;
+
+  return (
+    <div className="space-y-3">
+      <div className="divide-y divide-border">
+        <SettingRow
+          label="Secret backend"
+          description="Where API keys, OAuth tokens and secret variables are kept"
+        >
+          <select
+            value={target}
+            onChange={(e) => setTarget(e.target.value as SecretBackend)}
+            className="h-8 px-2 rounded-lg bg-surface-3 border border-border text-sm text-text-primary
+              outline-none focus:ring-1 focus:ring-accent/50"
+          >
+            {SECRET_BACKENDS.map((b) => (
+              <option key={b.value} value={b.value}>
+                {b.label}
+              </option>
+            ))}
+          </select>
+        </SettingRow>
+        {target === "onePassword" && (
+          <SettingRow label="1Password vault" description="Vault items are created in">
+            <input
+              type="text"
+              key={settings.onepasswordVault}
+              defaultValue={settings.onepasswordVault}
+              onBlur={(e) => {
+                const vault = e.target.value.trim();
+                if (vault && vault !== settings.onepasswordVault) updateSetting("onepasswordVault", vault);
+              }}
+              className={inputClass}
+            />
+          </SettingRow>
+        )}
+        {target === "vault" && (
+          <>
+            <SettingRow label="Vault address" description="Token from VAULT_TOKEN or vault login">
+              <input
+                type="text"
+                key={settings.vaultAddress ?? ""}
+                defaultValue={settings.vaultAddress ?? ""}
+                onBlur={(e) => {
+                  const address = e.target.value.trim() || null;
+                  if (address !== (settings.vaultAddress ?? null)) updateSetting("vaultAddress", address);
+                }}
+                placeholder="$VAULT_ADDR"
+                className={inputClass}
+              />
+            </SettingRow>
+            <SettingRow label="KV mount" description="A KV version 2 secrets engine">
+              <input
+                type="text"
+                key={settings.vaultMount}
+                defaultValue={settings.vaultMount}
+                onBlur={(e) => {
+                  const mount = e.target.value.trim();
+                  if (mount && mount !== settings.vaultMount) updateSetting("vaultMount", mount);
+                }}
+                className={inputClass}
+              />
+            </SettingRow>
+          </>
+        )}
+        {target === "encryptedFile" && (
+          <SettingRow
+            label="Passphrase"
+            description="Kept in memory until Conductor quits; without a desktop, set CONDUCTOR_SECRETS_PASSPHRASE"
+          >
+            <div className="flex gap-2">
+              <input
+                type="password"
+                value={passphrase}
+                onChange={(e) => setPassphrase(e.target.value)}
+                onKeyDown={(e) => e.key === "Enter" && passphrase && handleUnlock()}
+                className={cn(inputClass, "w-40")}
+              />
+              <button
+                onClick={handleUnlock}
+                disabled={!passphrase}
+                className="flex items-center gap-1.5 h-8 px-3 rounded-lg border border-border text-xs font-medium
+                  text-text-secondary hover:bg-surface-3 transition-colors disabled:opacity-50"
+              >
+                <Lock className="w-3.5 h-3.5" />
+                Unlock
+              </button>
+            </div>
+          </SettingRow>
+        )}
+      </div>
+
+      {problem && <p className="text-xs text-error">{problem}</p>}
+
+      {target !== settings.secretBackend && (
+        <button
+          onClick={handleMove}
+          disabled={moving || problem !== null}
+          className="flex items-center gap-2 h-9 px-4 rounded-lg bg-accent text-white text-sm font-medium
+            hover:bg-accent/90 transition-colors disabled:opacity-50"
+        >
+          {moving && <Loader2 className="w-4 h-4 animate-spin" />}
+          Move secrets to {backendLabel(target)}
+        </button>
+      )}
+
+      <p className="text-xs text-text-muted">
+        Env values can also point at a secret directly:{" "}
+        <code className="font-mono">op://vault/item/field</code>,{" "}
+        <code className="font-mono">pass://path</code>,{" "}
+        <code className="font-mono">bw://item/field</code> or{" "}
+        <code className="font-mono">vault://mount/path#field</code>.
+      </p>
+    </div>
+  );
+}
+
 // ── Danger Confirm Dialog ───────────────────────────────────────────
 
 function DangerConfirmDialog({
@@ -184,7 +752,19 @@ export function SettingsView() {
     backupRetention: 30,
     syncNotifications: true,
     errorNotifications: true,
+    vscodeSecretInputs: false,
+    remoteFallback: "conductor",
+    remoteProxyCommand: [],
+    gatewayEnabled: false,
+    gatewayPort: 7373,
+    envAllowlist: ["HOME", "USER", "USERPROFILE"],
+    nameConflictPolicy: "prefix",
+    secretBackend: "keyring",
+    onepasswordVault: "Conductor",
+    vaultAddress: null,
+    vaultMount: "secret",
   });
+  const [gatewayStatus, setGatewayStatus] = useState<GatewayStatus | null>(null);
   const [loaded, setLoaded] = useState(false);
   const [dangerDialog, setDangerDialog] = useState<null | "clear" | "reset">(null);
 
@@ -214,7 +794,7 @@ export function SettingsView() {
         toast.success("You're on the latest version");
       }
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       toast.error("Failed to check for updates", { description: message });
     } finally {
       setCheckingUpdate(false);
@@ -225,7 +805,7 @@ export function SettingsView() {
     setSettings((prev) => {
       const next = { ...prev, [key]: value };
       tauri.saveSettings(next).catch((e) => {
-        const message = e instanceof Error ? e.message : String(e);
+        const message = errorMessage(e);
         toast.error("Failed to save setting", { description: message });
         // Revert the optimistic update
         tauri.getSettings().then((s) => setSettings(s)).catch(() => {});
@@ -234,11 +814,47 @@ export function SettingsView() {
     });
   }, []);
 
+  // The gateway starts and stops in the background, so poll while it's on.
+  useEffect(() => {
+    if (!settings.gatewayEnabled) {
+      setGatewayStatus(null);
+      return;
+    }
+    const refresh = () => tauri.getGatewayStatus().then(setGatewayStatus).catch(() => {});
+    refresh();
+    const timer = setInterval(refresh, 3000);
+    return () => clearInterval(timer);
+  }, [settings.gatewayEnabled]);
+
+  // Clients switch between one gateway entry and one entry per server, so
+  // gateway changes are followed by a sync.
+  const applyGatewaySettings = async (next: AppSettings) => {
+    setSettings(next);
+    try {
+      await tauri.saveSettings(next);
+      await tauri.syncToAllClients();
+    } catch (err) {
+      const message = errorMessage(err);
+      toast.error("Failed to update the gateway", { description: message });
+      tauri.getSettings().then((s) => setSettings(s)).catch(() => {});
+    }
+  };
+
+  const gatewayDescription = () => {
+    if (!settings.gatewayEnabled) {
+      return "Give every client one entry that serves all enabled servers";
+    }
+    if (!gatewayStatus) return "Starting…";
+    if (gatewayStatus.error) return gatewayStatus.error;
+    const running = gatewayStatus.servers.filter((s) => s.running).length;
+    return `${gatewayStatus.url ?? "Starting…"} · ${running} of ${gatewayStatus.servers.length} servers running`;
+  };
+
   const handleOpenConfig = async () => {
     try {
       await tauri.openConfigFolder();
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       toast.error("Failed to open config folder", { description: message });
     }
   };
@@ -249,7 +865,7 @@ export function SettingsView() {
       await navigator.clipboard.writeText(json);
       toast.success("Config copied to clipboard");
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       toast.error("Failed to export config", { description: message });
     }
   };
@@ -262,7 +878,7 @@ export function SettingsView() {
       });
       toast.success("All configurations cleared");
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       toast.error("Failed to clear configs", { description: message });
     }
     setDangerDialog(null);
@@ -274,7 +890,7 @@ export function SettingsView() {
       setSettings(defaults);
       toast.success("Settings reset to defaults");
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       toast.error("Failed to reset settings", { description: message });
     }
     setDangerDialog(null);
@@ -398,6 +1014,88 @@ export function SettingsView() {
             >
               <Toggle checked={settings.notifyExternal} onChange={(v) => updateSetting("notifyExternal", v)} />
             </SettingRow>
+            <SettingRow
+              label="Prompt for secrets in VS Code"
+              description="Write API keys to VS Code as input prompts instead of plain text"
+            >
+              <Toggle checked={settings.vscodeSecretInputs} onChange={(v) => updateSetting("vscodeSecretInputs", v)} />
+            </SettingRow>
+            <SettingRow
+              label="Remote server fallback"
+              description="How remote servers reach clients that can't connect to a URL themselves"
+            >
+              <select
+                value={settings.remoteFallback}
+                onChange={(e) => updateSetting("remoteFallback", e.target.value as RemoteFallback)}
+                className="h-8 px-2 rounded-lg bg-surface-3 border border-border text-sm text-text-primary
+                  outline-none focus:ring-1 focus:ring-accent/50"
+              >
+                <option value="conductor">Conductor bridge</option>
+                <option value="mcpRemote">mcp-remote (Node.js)</option>
+                <option value="customProxy">Custom proxy</option>
+              </select>
+            </SettingRow>
+            <SettingRow
+              label="Name conflicts"
+              description="When a server has the name of an entry you added to a client yourself"
+            >
+              <select
+                value={settings.nameConflictPolicy}
+                onChange={(e) => updateSetting("nameConflictPolicy", e.target.value as NameConflictPolicy)}
+                className="h-8 px-2 rounded-lg bg-surface-3 border border-border text-sm text-text-primary
+                  outline-none focus:ring-1 focus:ring-accent/50"
+              >
+                <option value="prefix">Write as conductor-name</option>
+                <option value="skip">Skip the server</option>
+                <option value="takeOwnership">Replace my entry</option>
+              </select>
+            </SettingRow>
+            {settings.remoteFallback === "customProxy" && (
+              <SettingRow
+                label="Proxy command"
+                description="Space-separated; {url} and {token} are filled in per server"
+              >
+                <input
+                  type="text"
+                  key={settings.remoteProxyCommand.join(" ")}
+                  defaultValue={settings.remoteProxyCommand.join(" ")}
+                  onBlur={(e) =>
+                    updateSetting("remoteProxyCommand", e.target.value.split(/\s+/).filter(Boolean))
+                  }
+                  placeholder="mcp-proxy {url}"
+                  className="h-8 w-56 px-2 rounded-lg bg-surface-3 border border-border text-sm text-text-primary font-mono
+                    placeholder:text-text-muted outline-none focus:ring-1 focus:ring-accent/50"
+                />
+              </SettingRow>
+            )}
+            <SettingRow label="Gateway mode" description={gatewayDescription()}>
+              <Toggle
+                checked={settings.gatewayEnabled}
+                onChange={(v) => applyGatewaySettings({ ...settings, gatewayEnabled: v })}
+              />
+            </SettingRow>
+            {settings.gatewayEnabled && (
+              <SettingRow
+                label="Gateway port"
+                description="Local port clients connect to"
+              >
+                <input
+                  type="number"
+                  min={1024}
+                  max={65535}
+                  key={settings.gatewayPort}
+                  defaultValue={settings.gatewayPort}
+                  onBlur={(e) => {
+                    const port = Number(e.target.value);
+                    if (Number.isInteger(port) && port >= 1024 && port <= 65535 && port !== settings.gatewayPort) {
+                      applyGatewaySettings({ ...settings, gatewayPort: port });
+                    }
+                  }}
+                  className="h-8 w-24 px-2 rounded-lg bg-surface-3 border border-border text-sm text-text-primary font-mono
+                    outline-none focus:ring-1 focus:ring-accent/50"
+                />
+              </SettingRow>
+            )}
             <SettingRow
               label="Backup retention"
               description="How long to keep config backups"
@@ -418,6 +1116,28 @@ export function SettingsView() {
           </div>
         </Section>
 
+        {/* Profiles */}
+        <Section title="Profiles" icon={Layers}>
+          <ProfilesSection />
+        </Section>
+
+        {/* Variables */}
+        <Section title="Variables" icon={Variable}>
+          <VariablesSection
+            envAllowlist={settings.envAllowlist}
+            onEnvAllowlistChange={(patterns) => updateSetting("envAllowlist", patterns)}
+          />
+        </Section>
+
+        {/* Secrets */}
+        <Section title="Secrets" icon={KeyRound}>
+          <SecretsSection
+            settings={settings}
+            updateSetting={updateSetting}
+            onMigrated={() => tauri.getSettings().then(setSettings).catch(() => {})}
+          />
+        </Section>
+
         {/* Notifications */}
         <Section title="Notifications" icon={Bell}>
           <div className="divide-y divide-border">
@@ -15,7 +15,7 @@ import {
   Server,
   Share2,
 } from "lucide-react";
-import { cn, formatRelativeTime } from "@/lib/utils";
+import { cn, formatRelativeTime, errorMessage } from "@/lib/utils";
 import { useConfigStore } from "@/stores/configStore";
 import { ServerLogo } from "@/components/ServerLogo";
 import * as tauri from "@/lib/tauri";
@@ -79,7 +79,7 @@ function CreateStackDialog({
       });
       onClose();
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       toast.error("Failed to export stack", { description: message });
     }
     setExporting(false);
@@ -334,7 +334,7 @@ function ImportStackDialog({
       }
       setPreview(stack);
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       setError(message);
     }
     setLoading(false);
@@ -353,7 +353,7 @@ function ImportStackDialog({
       });
       setTimeout(() => onClose(), 1000);
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       toast.error("Import failed", { description: message });
     }
     setLoading(false);
@@ -684,7 +684,7 @@ export function StacksView() {
       setSavedStacks((prev) => [saved, ...prev]);
       navigator.clipboard.writeText(json);
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       toast.error("Failed to save stack", { description: message });
       navigator.clipboard.writeText(json);
     }
@@ -6,7 +6,14 @@ export type SupportedClient =
   | "windsurf"
   | "zed"
   | "jetbrains"
-  | "codex";
+  | "codex"
+  | "cline"
+  | "roo-code"
+  | "continue"
+  | "gemini"
+  | "goose"
+  | "opencode"
+  | "amazon-q";
 
 export interface ClientDetection {
   clientId: string;
@@ -22,6 +29,35 @@ export interface ClientDetection {
   previouslySyncedNames: string[];
   lastSyncedAt?: string;
   configUpdatedAt?: string;
+  targets: SyncTarget[];
+  /** Installed version, when Conductor could determine it. */
+  version?: string | null;
+  /** Remote transports written natively; the rest use the remote fallback. */
+  remote: RemoteCapabilities;
+}
+
+/** Payload of "client-detected": a client was installed or removed. */
+export interface DetectionChange {
+  clientId: string;
+  displayName: string;
+  /** `false` when the client was uninstalled. */
+  detected: boolean;
+}
+
+export interface RemoteCapabilities {
+  sse: boolean;
+  streamableHttp: boolean;
+  headers: boolean;
+}
+
+/** One config file a client reads servers from (e.g. one per JetBrains IDE). */
+export interface SyncTarget {
+  id: string;
+  label: string;
+  configPath: string;
+  exists: boolean;
+  /** Whether sync writes to this target. */
+  selected: boolean;
 }
 
 export interface ClientSync {
@@ -1,8 +1,14 @@
 import type { McpServer } from "./server";
+import type { Profile } from "./profiles";
 
 export interface McpConfig {
+  schemaVersion: number;
   servers: McpServer[];
   sync: ClientSyncConfig[];
+  variables?: Record<string, string>;
+  secretVariables?: string[];
+  profiles?: Profile[];
+  activeProfile?: string | null;
 }
 
 export interface ClientSyncConfig {
@@ -10,6 +16,18 @@ export interface ClientSyncConfig {
   enabled: boolean;
   serverIds: string[];
   lastSynced?: string;
+  targets?: TargetSyncState[];
+  /** Target IDs picked by the user; empty means the client's defaults. */
+  selectedTargets?: string[];
+  /** Servers the last sync wrote under another name, or skipped (`null`). */
+  resolvedNames?: Record<string, string | null>;
+}
+
+export interface TargetSyncState {
+  targetId: string;
+  syncedServerNames: string[];
+  previouslySyncedNames: string[];
+  lastSynced?: string;
 }
 
 export interface AppSettings {
@@ -21,4 +39,35 @@ export interface AppSettings {
   backupRetention: number;
   syncNotifications: boolean;
   errorNotifications: boolean;
+  vscodeSecretInputs: boolean;
+  remoteFallback: RemoteFallback;
+  /** Argv for the custom proxy; `{url}` and `{token}` are substituted. */
+  remoteProxyCommand: string[];
+  /** Serve all enabled servers through one local endpoint. */
+  gatewayEnabled: boolean;
+  gatewayPort: number;
+  /** Glob patterns of environment variables `${NAME}` may read. */
+  envAllowlist: string[];
+  /** What sync does with a server named like the user's own entry. */
+  nameConflictPolicy: NameConflictPolicy;
+  /** Where secrets are kept; changed with `migrateSecrets`. */
+  secretBackend: SecretBackend;
+  /** 1Password vault the `onePassword` backend writes items to. */
+  onepasswordVault: string;
+  /** Vault server address; `VAULT_ADDR` when unset. */
+  vaultAddress?: string | null;
+  /** KV version 2 mount the `vault` backend writes under. */
+  vaultMount: string;
 }
+
+export type RemoteFallback = "mcpRemote" | "customProxy" | "conductor";
+
+export type NameConflictPolicy = "prefix" | "skip" | "takeOwnership";
+
+export type SecretBackend =
+  | "keyring"
+  | "encryptedFile"
+  | "pass"
+  | "onePassword"
+  | "bitwarden"
+  | "vault";
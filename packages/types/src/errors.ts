@@ -0,0 +1,39 @@
+export type ErrorKind =
+  | "configRead"
+  | "configWrite"
+  | "configParse"
+  | "configTooNew"
+  | "clientConfigInvalid"
+  | "clientConfigWrite"
+  | "clientNotFound"
+  | "clientNotDetected"
+  | "syncVerification"
+  | "remoteFallbackUnavailable"
+  | "serverNotFound"
+  | "serverNameCollision"
+  | "invalidInput"
+  | "secretAccess"
+  | "keychainUnavailable"
+  | "secretBackendUnavailable"
+  | "oAuthFailed"
+  | "oAuthNotConfigured"
+  | "network"
+  | "registry"
+  | "toolDiscovery"
+  | "undefinedVariable"
+  | "watcher"
+  | "autostart"
+  | "io"
+  | "serialization"
+  | "internal";
+
+/** Error payload rejected by every Tauri command. */
+export interface CommandError {
+  kind: ErrorKind;
+  message: string;
+  clientId?: string | null;
+  serverId?: string | null;
+  path?: string | null;
+  retryable: boolean;
+  suggestedAction?: string | null;
+}
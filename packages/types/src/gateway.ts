@@ -0,0 +1,14 @@
+export interface GatewayStatus {
+  enabled: boolean;
+  /** Set while the gateway is listening. */
+  url?: string;
+  error?: string;
+  servers: GatewayServerStatus[];
+}
+
+export interface GatewayServerStatus {
+  serverId: string;
+  name: string;
+  running: boolean;
+  error?: string;
+}
@@ -3,19 +3,37 @@ export type {
   McpServer,
   AddServerRequest,
   UpdateServerRequest,
+  CodexServerOptions,
+  ToolFilter,
 } from "./server";
 
 export type {
   SupportedClient,
   ClientDetection,
+  DetectionChange,
   ClientSync,
+  SyncTarget,
+  RemoteCapabilities,
 } from "./client";
 
-export type { McpConfig, ClientSyncConfig, AppSettings } from "./config";
+export type {
+  McpConfig,
+  ClientSyncConfig,
+  TargetSyncState,
+  AppSettings,
+  RemoteFallback,
+  NameConflictPolicy,
+  SecretBackend,
+} from "./config";
 
 export type {
   SyncResult,
+  TargetSyncResult,
   ImportResult,
+  NameResolution,
+  NameConflict,
+  InvalidName,
+  TargetNameConflicts,
 } from "./sync";
 
 export type {
@@ -25,3 +43,37 @@ export type {
   OAuthStatus,
   ActivityEntry,
 } from "./stacks";
+
+export type { GatewayStatus, GatewayServerStatus } from "./gateway";
+
+export type { ToolCatalog, ToolInfo } from "./tools";
+
+export type {
+  TrafficEntry,
+  TrafficQuery,
+  ReplayResult,
+  Direction,
+  MessageKind,
+} from "./traffic";
+
+export type {
+  ManagedServerStatus,
+  ProcessState,
+  ProcessExit,
+  CrashEvent,
+  ManagedServerLogLine,
+} from "./supervisor";
+
+export type { VariableInfo, VariableSource, ServerPreview } from "./variables";
+
+export type { Profile, ProfileList, UpdateProfileRequest } from "./profiles";
+
+export type { SecretMigration, SecretMigrationFailure } from "./secrets";
+
+export type {
+  AppNotification,
+  NotificationAction,
+  NotificationActionKind,
+} from "./notifications";
+
+export type { ErrorKind, CommandError } from "./errors";
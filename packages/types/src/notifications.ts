@@ -0,0 +1,16 @@
+export type NotificationActionKind = "showChanges" | "resync" | "adopt" | "reauthorize";
+
+export interface NotificationAction {
+  kind: NotificationActionKind;
+  label: string;
+  /** The client to act on; every client when absent. */
+  clientId?: string;
+  serverId?: string;
+}
+
+/** Payload of "notification": the same notification shown by the OS. */
+export interface AppNotification {
+  title: string;
+  body: string;
+  actions: NotificationAction[];
+}
@@ -0,0 +1,22 @@
+/** A named server set with its own variables and keychain namespace. */
+export interface Profile {
+  id: string;
+  name: string;
+  /** IDs of the servers enabled while the profile is active. */
+  servers: string[];
+  variables?: Record<string, string>;
+  secretVariables?: string[];
+  /** Project directories whose client configs get this profile's servers. */
+  projects?: string[];
+}
+
+export interface ProfileList {
+  profiles: Profile[];
+  activeProfile?: string | null;
+}
+
+export interface UpdateProfileRequest {
+  name?: string;
+  servers?: string[];
+  projects?: string[];
+}
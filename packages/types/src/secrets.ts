@@ -0,0 +1,11 @@
+/** Outcome of moving secrets to another backend. */
+export interface SecretMigration {
+  moved: number;
+  /** Secrets that couldn't be copied; the backend only switches when empty. */
+  failed: SecretMigrationFailure[];
+}
+
+export interface SecretMigrationFailure {
+  key: string;
+  error: string;
+}
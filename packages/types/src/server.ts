@@ -12,12 +12,42 @@ export interface McpServer {
   env?: Record<string, string>;
   secretEnvKeys?: string[];
   url?: string;
+  /** HTTP headers for remote servers; secret values are `${NAME}` references. */
+  headers?: Record<string, string>;
   enabled: boolean;
   source?: string;
   tags?: string[];
   registryId?: string;
   createdAt?: string;
   updatedAt?: string;
+  codex?: CodexServerOptions;
+  /** Entry fields Conductor doesn't model, keyed by the client they came from. */
+  extra?: Record<string, Record<string, unknown>>;
+  /** Tool allow/deny lists keyed by client ID; `*` applies to every other client. */
+  toolFilters?: Record<string, ToolFilter>;
+  /** Record this server's JSON-RPC traffic in the inspector. */
+  inspect?: boolean;
+  /** Stdio only: Conductor runs one supervised copy that clients share. */
+  managed?: boolean;
+}
+
+/**
+ * Glob patterns over tool names. A tool is visible when `allow` is empty or
+ * matches it, and `deny` doesn't.
+ */
+export interface ToolFilter {
+  allow?: string[];
+  deny?: string[];
+}
+
+/** Codex-only `[mcp_servers.<name>]` fields, kept across import and sync. */
+export interface CodexServerOptions {
+  startupTimeoutSec?: number;
+  toolTimeoutSec?: number;
+  bearerTokenEnvVar?: string;
+  cwd?: string;
+  enabledTools?: string[];
+  disabledTools?: string[];
 }
 
 export interface AddServerRequest {
@@ -46,4 +76,6 @@ export interface UpdateServerRequest {
   url?: string;
   iconUrl?: string;
   enabled?: boolean;
+  inspect?: boolean;
+  managed?: boolean;
 }
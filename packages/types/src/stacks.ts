@@ -42,7 +42,7 @@ export interface OAuthStatus {
 
 export interface ActivityEntry {
   id: string;
-  type: "sync" | "add" | "delete" | "import" | "auth" | "error" | "stack";
+  type: "sync" | "add" | "delete" | "import" | "auth" | "error" | "stack" | "profile";
   description: string;
   timestamp: string;
   details?: string;
@@ -0,0 +1,44 @@
+export type ProcessState = "starting" | "running" | "backoff";
+
+export interface ProcessExit {
+  at: string;
+  /** Missing when the process was killed by a signal or never started. */
+  code?: number | null;
+  /** Why the server failed to start, if it did. */
+  error?: string | null;
+  uptimeSecs: number;
+}
+
+/** A managed server's process, as run by Conductor's supervisor. */
+export interface ManagedServerStatus {
+  serverId: string;
+  name: string;
+  state: ProcessState;
+  /** The endpoint clients reach through the bridge. */
+  url: string;
+  pid?: number | null;
+  startedAt?: string | null;
+  uptimeSecs?: number | null;
+  /** Restarts after exits since Conductor started. */
+  restarts: number;
+  lastExit?: ProcessExit | null;
+  nextRestartAt?: string | null;
+  /** Percent of one core, summed over the server's process tree. */
+  cpuPercent?: number | null;
+  memoryBytes?: number | null;
+}
+
+/** Payload of the `managed-server-crashed` event. */
+export interface CrashEvent {
+  serverId: string;
+  name: string;
+  exit: ProcessExit;
+  restarts: number;
+  restartInSecs: number;
+}
+
+/** Payload of the `managed-server-log` event. */
+export interface ManagedServerLogLine {
+  serverId: string;
+  line: string;
+}
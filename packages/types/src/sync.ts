@@ -1,11 +1,49 @@
+import type { CommandError } from "./errors";
 import type { McpServer } from "./server";
 
 export interface SyncResult {
   clientId: string;
   success: boolean;
   serversWritten: number;
-  error?: string;
+  /** Kind, file and suggested fix of the failure; several failed targets are named in its message. */
+  error?: CommandError | null;
   warnings?: string[];
+  targets?: TargetSyncResult[];
+}
+
+export interface TargetSyncResult {
+  targetId: string;
+  label: string;
+  success: boolean;
+  error?: CommandError | null;
+}
+
+/** What sync does about a name collision. */
+export type NameResolution =
+  | { kind: "renamed"; name: string }
+  | { kind: "skipped" }
+  | { kind: "replaced" };
+
+export interface NameConflict {
+  server: string;
+  /** The user's entry it collides with, as written in the client file. */
+  existing: string;
+  resolution: NameResolution;
+}
+
+export interface InvalidName {
+  server: string;
+  reason: string;
+}
+
+/** Name conflicts and invalid names one client file would see. */
+export interface TargetNameConflicts {
+  clientId: string;
+  displayName: string;
+  targetId: string;
+  targetLabel: string;
+  conflicts: NameConflict[];
+  invalid: InvalidName[];
 }
 
 export interface ImportResult {
@@ -0,0 +1,13 @@
+export interface ToolInfo {
+  name: string;
+  description?: string;
+}
+
+/** The tools one server exposed the last time it was probed. */
+export interface ToolCatalog {
+  serverId: string;
+  /** Launch config the tools were discovered with. */
+  fingerprint: string;
+  discoveredAt: string;
+  tools: ToolInfo[];
+}
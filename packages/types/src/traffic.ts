@@ -0,0 +1,37 @@
+export type Direction = "clientToServer" | "serverToClient";
+
+export type MessageKind = "session" | "request" | "response" | "notification";
+
+/** One logged JSON-RPC message, or the launch record of a session. */
+export interface TrafficEntry {
+  id: string;
+  sessionId: string;
+  serverId: string;
+  /** Missing when Conductor itself (the gateway or a tool probe) was the client. */
+  clientId?: string | null;
+  timestamp: string;
+  direction?: Direction | null;
+  kind: MessageKind;
+  method?: string | null;
+  rpcId?: string | number | null;
+  /** For responses: time since the matching request was sent. */
+  durationMs?: number | null;
+  isError: boolean;
+  message: unknown;
+}
+
+export interface TrafficQuery {
+  clientId?: string;
+  sessionId?: string;
+  /** Substring of the method name. */
+  method?: string;
+  errorsOnly?: boolean;
+  /** RFC 3339 timestamp; older entries are skipped. */
+  since?: string;
+  limit?: number;
+}
+
+export interface ReplayResult {
+  response: unknown;
+  durationMs: number;
+}
@@ -0,0 +1,21 @@
+import type { McpServer } from "./server";
+
+export type VariableSource = "profile" | "global" | "secret" | "environment";
+
+/** A value `${NAME}` references in server definitions resolve to. */
+export interface VariableInfo {
+  name: string;
+  source: VariableSource;
+  secret: boolean;
+  /** Missing for secrets, whose values stay in the secret store. */
+  value?: string | null;
+  /** Names of the servers referencing it. */
+  usedBy: string[];
+}
+
+/** A server as clients will receive it, with secrets masked. */
+export interface ServerPreview {
+  server: McpServer;
+  /** Referenced names with no value; syncing fails until they're defined. */
+  undefined: string[];
+}